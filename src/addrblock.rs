@@ -0,0 +1,481 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! IP address delegation extension (RFC 3779, section 2).  Address blocks
+//! are given as CIDR prefixes, single addresses or `from-to` ranges and are
+//! kept as sorted, merged ranges per address family, so that the encoding
+//! is the canonical minimal one required by RFC 3779, 2.2.3.6.
+
+use std::net::IpAddr;
+use crate::asn1::*;
+
+pub const AFI_IPV4: u16 = 1;
+pub const AFI_IPV6: u16 = 2;
+
+/// An inclusive range of IPv4 or IPv6 addresses
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct AddrRange {
+    /// first address of the range
+    pub from: u128,
+    /// last address of the range
+    pub to: u128,
+}
+
+/// The address blocks of one address family
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AddrFamily {
+    /// address family identifier
+    pub afi: u16,
+    /// optional subsequent address family identifier
+    pub safi: Option<u8>,
+    /// sorted and merged ranges, None if inherited from the issuer
+    pub ranges: Option<Vec<AddrRange>>,
+}
+
+/// The contents of an sbgp-ipAddrBlock extension
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AddrBlocks {
+    /// address families sorted by their addressFamily octets
+    pub families: Vec<AddrFamily>,
+}
+
+//
+// Number of address bits of an address family.
+//
+fn afi_bits(afi: u16) -> u32
+{
+    return if afi == AFI_IPV4 { 32 } else { 128 };
+}
+
+//
+// Convert an IP address into its address family and numeric value.
+//
+fn addr_value(addr: &IpAddr) -> (u16, u128)
+{
+    match addr {
+        IpAddr::V4(a) => { (AFI_IPV4, u32::from(*a) as u128) }
+        IpAddr::V6(a) => { (AFI_IPV6, u128::from(*a)) }
+    }
+}
+
+//
+// Format an address of the given family.
+//
+fn addr_string(afi: u16, value: u128) -> String
+{
+    if afi == AFI_IPV4 {
+        return std::net::Ipv4Addr::from(value as u32).to_string();
+    }
+    return std::net::Ipv6Addr::from(value).to_string();
+}
+
+//
+// Mask covering the host part of a prefix.
+//
+fn host_mask(bits: u32, prefix: u32) -> u128
+{
+    let host = bits - prefix;
+    return if host == 128 { u128::MAX } else { (1u128 << host) - 1 };
+}
+
+//
+// Get the length of the prefix exactly covering a range, if any.
+//
+fn prefix_len(bits: u32, range: &AddrRange) -> Option<u32>
+{
+    let diff = range.to - range.from;
+    if diff & diff.wrapping_add(1) != 0 || range.from & diff != 0 {
+        return None;
+    }
+    return Some(bits - (128 - diff.leading_zeros()));
+}
+
+//
+// Parse a single address of a block, returning its family and value.
+//
+fn parse_addr(block: &str, addr: &str) -> Result<(u16, u128), String>
+{
+    let addr = addr.trim().parse::<IpAddr>()
+                   .map_err(|_| format!("invalid address block '{}'", block))?;
+    return Ok(addr_value(&addr));
+}
+
+//
+// Parse a CIDR prefix, a from-to range or a single address.
+//
+pub fn parse_block(block: &str) -> Result<(u16, AddrRange), String>
+{
+    if let Some((addr, prefix)) = block.split_once('/') {
+        let (afi, from) = parse_addr(block, addr)?;
+        let bits = afi_bits(afi);
+        let prefix = match prefix.parse::<u32>() {
+            Ok(prefix) if prefix <= bits => { prefix }
+            _ => { return Err(format!("invalid prefix length in '{}'", block)); }
+        };
+        let mask = host_mask(bits, prefix);
+        if from & mask != 0 {
+            return Err(format!("address block '{}' has host bits set", block));
+        }
+        return Ok((afi, AddrRange { from, to: from | mask }));
+    }
+    if let Some((from, to)) = block.split_once('-') {
+        let (afi, from) = parse_addr(block, from)?;
+        let (to_afi, to) = parse_addr(block, to)?;
+        if afi != to_afi {
+            return Err(format!("address range '{}' mixes IPv4 and IPv6", block));
+        }
+        if from > to {
+            return Err(format!("address range '{}' is empty", block));
+        }
+        return Ok((afi, AddrRange { from, to }));
+    }
+    let (afi, addr) = parse_addr(block, block)?;
+    return Ok((afi, AddrRange { from: addr, to: addr }));
+}
+
+//
+// Sort ranges and merge overlapping or adjacent ones.
+//
+fn normalize(mut ranges: Vec<AddrRange>) -> Vec<AddrRange>
+{
+    ranges.sort();
+    let mut merged: Vec<AddrRange> = Vec::new();
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if last.to == u128::MAX || range.from <= last.to + 1 => {
+                last.to = last.to.max(range.to);
+            }
+            _ => { merged.push(range); }
+        }
+    }
+    return merged;
+}
+
+//
+// Encode the leading len bits of an address as BIT STRING.
+//
+fn encode_bits(bits: u32, value: u128, len: u32) -> Vec<u8>
+{
+    let octets = value.to_be_bytes();
+    let octets = &octets[16 - bits as usize / 8..];
+    let nbytes = len.div_ceil(8) as usize;
+    let mut content = octets[..nbytes].to_vec();
+    let unused = (nbytes * 8) as u32 - len;
+    if let Some(last) = content.last_mut() {
+        *last &= (0xFF00u16 >> (8 - unused)) as u8;
+    }
+    return asn1_wrap(ASN1_BIT_STRING, &[&[unused as u8], &content]);
+}
+
+//
+// Decode a BIT STRING address, filling the missing bits with ones or zeros.
+//
+fn decode_bits(bits: u32, obj: &Asn1Object, ones: bool) -> Result<(u128, u32), String>
+{
+    let (unused, content) = obj.to_bitstring()?;
+    let len = content.len() as u32 * 8 - unused as u32;
+    if len > bits {
+        return Err("address exceeds the address family length".to_string());
+    }
+    let mut value: u128 = 0;
+    for b in content {
+        value = (value << 8) | *b as u128;
+    }
+    value = value.checked_shl(bits - content.len() as u32 * 8).unwrap_or(0);
+    let mask = host_mask(bits, len);
+    value &= !mask;
+    if ones {
+        value |= mask;
+    }
+    return Ok((value, len));
+}
+
+//
+// Encode a range as addressPrefix if possible, else as addressRange with
+// trailing zero bits of the minimum and trailing one bits of the maximum
+// stripped (RFC 3779, 2.1.2).
+//
+fn encode_range(bits: u32, range: &AddrRange) -> Vec<u8>
+{
+    if let Some(prefix) = prefix_len(bits, range) {
+        return encode_bits(bits, range.from, prefix);
+    }
+    let min = bits - range.from.trailing_zeros().min(bits);
+    let max = bits - range.to.trailing_ones().min(bits);
+    return asn1_sequence(&[encode_bits(bits, range.from, min),
+                           encode_bits(bits, range.to, max)]);
+}
+
+impl AddrFamily {
+    //
+    // Get the addressFamily octets.
+    //
+    fn family_octets(&self) -> Vec<u8>
+    {
+        let mut octets = self.afi.to_be_bytes().to_vec();
+        octets.extend(self.safi);
+        return octets;
+    }
+
+    //
+    // Format the family name.
+    //
+    pub fn name(&self) -> String
+    {
+        let name = match self.afi {
+            AFI_IPV4 => { "IPv4".to_string() }
+            AFI_IPV6 => { "IPv6".to_string() }
+            afi => { format!("AFI {}", afi) }
+        };
+        match self.safi {
+            Some(safi) => { format!("{} SAFI {}", name, safi) }
+            None => { name }
+        }
+    }
+
+    //
+    // Format a range as prefix or from-to range.
+    //
+    pub fn range_string(&self, range: &AddrRange) -> String
+    {
+        let bits = afi_bits(self.afi);
+        match prefix_len(bits, range) {
+            Some(prefix) => { format!("{}/{}", addr_string(self.afi, range.from), prefix) }
+            None => {
+                format!("{}-{}", addr_string(self.afi, range.from), addr_string(self.afi, range.to))
+            }
+        }
+    }
+}
+
+impl AddrBlocks {
+    //
+    // Parse a list of address blocks.
+    //
+    pub fn parse(blocks: &[String]) -> Result<AddrBlocks, String>
+    {
+        let mut v4 = Vec::new();
+        let mut v6 = Vec::new();
+        for block in blocks {
+            match parse_block(block)? {
+                (AFI_IPV4, range) => { v4.push(range); }
+                (_, range) => { v6.push(range); }
+            }
+        }
+        let mut families = Vec::new();
+        for (afi, ranges) in [(AFI_IPV4, v4), (AFI_IPV6, v6)] {
+            if !ranges.is_empty() {
+                families.push(AddrFamily { afi, safi: None, ranges: Some(normalize(ranges)) });
+            }
+        }
+        return Ok(AddrBlocks { families });
+    }
+
+    //
+    // Encode as IPAddrBlocks.
+    //
+    pub fn encode(&self) -> Vec<u8>
+    {
+        let families: Vec<Vec<u8>> = self.families.iter().map(|family| {
+            let choice = match &family.ranges {
+                Some(ranges) => {
+                    let bits = afi_bits(family.afi);
+                    asn1_sequence(&ranges.iter().map(|r| encode_range(bits, r)).collect::<Vec<_>>())
+                }
+                None => { asn1_null() }
+            };
+            asn1_sequence(&[asn1_octet_string(&family.family_octets()), choice])
+        }).collect();
+        return asn1_sequence(&families);
+    }
+
+    //
+    // Decode the value of an sbgp-ipAddrBlock extension.
+    //
+    pub fn from_der(der: &[u8]) -> Result<AddrBlocks, String>
+    {
+        let mut families: Vec<AddrFamily> = Vec::new();
+        let mut parser = Asn1Parser::new(der).expect(ASN1_SEQUENCE)?.parser();
+        while let Some(obj) = parser.next_object()? {
+            let mut p = obj.parser();
+            let octets = p.expect(ASN1_OCTET_STRING)?.content;
+            if octets.len() < 2 || octets.len() > 3 {
+                return Err("invalid addressFamily length".to_string());
+            }
+            let afi = u16::from_be_bytes([octets[0], octets[1]]);
+            if afi != AFI_IPV4 && afi != AFI_IPV6 {
+                return Err(format!("unsupported address family {}", afi));
+            }
+            let bits = afi_bits(afi);
+            let choice = p.next_object()?.ok_or("missing ipAddressChoice")?;
+            p.finish()?;
+
+            let ranges = match choice.tag {
+                ASN1_NULL => { None }
+                ASN1_SEQUENCE => {
+                    let mut ranges = Vec::new();
+                    let mut r = choice.parser();
+                    while let Some(item) = r.next_object()? {
+                        if item.tag == ASN1_SEQUENCE {
+                            let mut minmax = item.parser();
+                            let (from, _) = decode_bits(bits, &minmax.expect(ASN1_BIT_STRING)?, false)?;
+                            let (to, _) = decode_bits(bits, &minmax.expect(ASN1_BIT_STRING)?, true)?;
+                            minmax.finish()?;
+                            if from > to {
+                                return Err("empty addressRange".to_string());
+                            }
+                            ranges.push(AddrRange { from, to });
+                        } else {
+                            let (from, len) = decode_bits(bits, &item, false)?;
+                            ranges.push(AddrRange { from, to: from | host_mask(bits, len) });
+                        }
+                    }
+                    Some(normalize(ranges))
+                }
+                tag => { return Err(format!("invalid ipAddressChoice {}", asn1_tag_name(tag))); }
+            };
+            let family = AddrFamily { afi, safi: octets.get(2).copied(), ranges };
+            if families.iter().any(|f| f.family_octets() == family.family_octets()) {
+                return Err(format!("duplicate {} address family", family.name()));
+            }
+            families.push(family);
+        }
+        return Ok(AddrBlocks { families });
+    }
+
+    //
+    // Check that all blocks are contained in the resources of the issuer and
+    // return the effective resources with inherited families resolved
+    // (RFC 3779, 2.3).
+    //
+    pub fn resolve(&self, issuer: Option<&AddrBlocks>) -> Result<AddrBlocks, String>
+    {
+        let mut families = Vec::new();
+        for family in &self.families {
+            let parent = issuer.and_then(|i| i.families.iter().find(|f| {
+                f.afi == family.afi && f.safi == family.safi
+            }));
+            let available = match parent.and_then(|p| p.ranges.as_ref()) {
+                Some(ranges) => { ranges }
+                None => {
+                    return Err(format!("issuer holds no {} address blocks", family.name()));
+                }
+            };
+            let ranges = match &family.ranges {
+                Some(ranges) => {
+                    for range in ranges {
+                        if !available.iter().any(|a| a.from <= range.from && range.to <= a.to) {
+                            return Err(format!("address block {} not contained in issuer's",
+                                               family.range_string(range)));
+                        }
+                    }
+                    ranges.clone()
+                }
+                None => { available.clone() }
+            };
+            families.push(AddrFamily { ranges: Some(ranges), ..family.clone() });
+        }
+        return Ok(AddrBlocks { families });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex;
+
+    fn blocks(blocks: &[&str]) -> AddrBlocks
+    {
+        let blocks: Vec<String> = blocks.iter().map(|b| b.to_string()).collect();
+        return AddrBlocks::parse(&blocks).unwrap();
+    }
+
+    #[test]
+    fn parse_blocks()
+    {
+        assert_eq!(parse_block("10.0.0.0/8").unwrap(),
+                   (AFI_IPV4, AddrRange { from: 0x0a000000, to: 0x0affffff }));
+        assert_eq!(parse_block("10.0.0.1 - 10.0.0.9").unwrap(),
+                   (AFI_IPV4, AddrRange { from: 0x0a000001, to: 0x0a000009 }));
+        assert_eq!(parse_block("::/0").unwrap(), (AFI_IPV6, AddrRange { from: 0, to: u128::MAX }));
+        assert_eq!(parse_block("fec1::1").unwrap().1.to, 0xfec1 << 112 | 1);
+
+        assert_eq!(parse_block("10.0.0.1/8").unwrap_err(),
+                   "address block '10.0.0.1/8' has host bits set");
+        assert_eq!(parse_block("10.0.0.0/33").unwrap_err(),
+                   "invalid prefix length in '10.0.0.0/33'");
+        assert_eq!(parse_block("10.0.0.9-10.0.0.1").unwrap_err(),
+                   "address range '10.0.0.9-10.0.0.1' is empty");
+        assert!(parse_block("10.0.0.0-::1").is_err());
+        assert!(parse_block("10.0.0").is_err());
+    }
+
+    #[test]
+    fn normalize_blocks()
+    {
+        let addrblocks = blocks(&["10.0.48.0/20", "2001:db8::/32", "10.0.32.0/20", "10.0.40.0/24",
+                                  "0.0.0.0-9.255.255.255"]);
+        let v4 = &addrblocks.families[0];
+        let ranges = v4.ranges.as_ref().unwrap();
+        let ranges: Vec<String> = ranges.iter().map(|r| v4.range_string(r)).collect();
+        assert_eq!(ranges, vec!["0.0.0.0-9.255.255.255", "10.0.32.0/19"]);
+        assert_eq!(addrblocks.families[1].name(), "IPv6");
+
+        let all = blocks(&["::/1", "8000::/1"]);
+        assert_eq!(all.families[0].ranges, Some(vec![AddrRange { from: 0, to: u128::MAX }]));
+    }
+
+    // encoded by OpenSSL 3.5
+    #[test]
+    fn encoding()
+    {
+        let der = hex("3052302304020001301d0304050a0020300e0305020a0500040305030a050010030500c0a8
+                       0101302b04020002302503050020010db8301c03070020010db9000103110020010db900
+                       0200000000000000000000");
+        let addrblocks = blocks(&["10.5.0.4-10.5.0.23", "10.0.32.0/20", "10.0.48.0/20",
+                                  "192.168.1.1", "2001:db8::/32", "2001:db9:1::-2001:db9:2::"]);
+        assert_eq!(addrblocks.encode(), der);
+        assert_eq!(AddrBlocks::from_der(&der).unwrap(), addrblocks);
+
+        let inherit = AddrBlocks { families: vec![AddrFamily { afi: AFI_IPV6, safi: Some(1),
+                                                               ranges: None }] };
+        assert_eq!(inherit.encode(), hex("3009300704030002010500"));
+        assert_eq!(AddrBlocks::from_der(&inherit.encode()).unwrap(), inherit);
+        assert_eq!(inherit.families[0].name(), "IPv6 SAFI 1");
+
+        let duplicate = hex("301030060402000105003006040200010500");
+        assert_eq!(AddrBlocks::from_der(&duplicate).unwrap_err(), "duplicate IPv4 address family");
+        assert!(AddrBlocks::from_der(&hex("30083006040200030500")).is_err());
+        let empty = hex("30183016040200013010300e0305000a0000090305000a000001");
+        assert_eq!(AddrBlocks::from_der(&empty).unwrap_err(), "empty addressRange");
+    }
+
+    #[test]
+    fn resolve_with_issuer()
+    {
+        let issuer = blocks(&["10.0.0.0/8", "2001:db8::/32"]);
+        let subject = blocks(&["10.1.0.0/16", "10.2.0.1-10.2.0.9"]);
+        assert_eq!(subject.resolve(Some(&issuer)).unwrap(), subject);
+
+        let outside = blocks(&["10.0.0.0/7"]);
+        assert_eq!(outside.resolve(Some(&issuer)).unwrap_err(),
+                   "address block 10.0.0.0/7 not contained in issuer's");
+        assert_eq!(subject.resolve(None).unwrap_err(), "issuer holds no IPv4 address blocks");
+
+        let inherit = AddrBlocks { families: vec![AddrFamily { afi: AFI_IPV6, safi: None,
+                                                               ranges: None }] };
+        let resolved = inherit.resolve(Some(&issuer)).unwrap();
+        assert_eq!(resolved.families[0].ranges, issuer.families[1].ranges);
+        let v4_only = blocks(&["10.0.0.0/8"]);
+        assert!(inherit.resolve(Some(&v4_only)).is_err());
+    }
+}
@@ -0,0 +1,1105 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! DER encoder and DER/BER decoder for the ASN.1 subset used by X.509,
+//! PKCS and OCSP structures.
+
+use std::borrow::Cow;
+use chrono::{DateTime, Datelike, NaiveDate};
+
+pub const ASN1_EOC: u8              = 0x00;
+pub const ASN1_BOOLEAN: u8          = 0x01;
+pub const ASN1_INTEGER: u8          = 0x02;
+pub const ASN1_BIT_STRING: u8       = 0x03;
+pub const ASN1_OCTET_STRING: u8     = 0x04;
+pub const ASN1_NULL: u8             = 0x05;
+pub const ASN1_OID: u8              = 0x06;
+pub const ASN1_ENUMERATED: u8       = 0x0A;
+pub const ASN1_UTF8STRING: u8       = 0x0C;
+pub const ASN1_NUMERICSTRING: u8    = 0x12;
+pub const ASN1_PRINTABLESTRING: u8  = 0x13;
+pub const ASN1_T61STRING: u8        = 0x14;
+pub const ASN1_VIDEOTEXSTRING: u8   = 0x15;
+pub const ASN1_IA5STRING: u8        = 0x16;
+pub const ASN1_UTCTIME: u8          = 0x17;
+pub const ASN1_GENERALIZEDTIME: u8  = 0x18;
+pub const ASN1_GRAPHICSTRING: u8    = 0x19;
+pub const ASN1_VISIBLESTRING: u8    = 0x1A;
+pub const ASN1_GENERALSTRING: u8    = 0x1B;
+pub const ASN1_UNIVERSALSTRING: u8  = 0x1C;
+pub const ASN1_BMPSTRING: u8        = 0x1E;
+
+pub const ASN1_CONSTRUCTED: u8      = 0x20;
+pub const ASN1_SEQUENCE: u8         = 0x30;
+pub const ASN1_SET: u8              = 0x31;
+
+pub const ASN1_CONTEXT_S_0: u8      = 0x80;
+pub const ASN1_CONTEXT_S_1: u8      = 0x81;
+pub const ASN1_CONTEXT_S_2: u8      = 0x82;
+pub const ASN1_CONTEXT_S_3: u8      = 0x83;
+pub const ASN1_CONTEXT_S_4: u8      = 0x84;
+pub const ASN1_CONTEXT_S_5: u8      = 0x85;
+pub const ASN1_CONTEXT_S_6: u8      = 0x86;
+pub const ASN1_CONTEXT_S_7: u8      = 0x87;
+pub const ASN1_CONTEXT_S_8: u8      = 0x88;
+
+pub const ASN1_CONTEXT_C_0: u8      = 0xA0;
+pub const ASN1_CONTEXT_C_1: u8      = 0xA1;
+pub const ASN1_CONTEXT_C_2: u8      = 0xA2;
+pub const ASN1_CONTEXT_C_3: u8      = 0xA3;
+pub const ASN1_CONTEXT_C_4: u8      = 0xA4;
+pub const ASN1_CONTEXT_C_5: u8      = 0xA5;
+pub const ASN1_CONTEXT_C_6: u8      = 0xA6;
+pub const ASN1_CONTEXT_C_7: u8      = 0xA7;
+pub const ASN1_CONTEXT_C_8: u8      = 0xA8;
+
+/// maximum nesting depth accepted by the decoder
+const ASN1_MAX_DEPTH: usize = 64;
+
+//
+// Encode the length octets of a DER object.
+//
+pub fn asn1_length(len: usize) -> Vec<u8>
+{
+    if len < 0x80 {
+        return vec![len as u8];
+    }
+    let bytes = len.to_be_bytes();
+    let skip = bytes.iter().take_while(|b| **b == 0).count();
+    let mut out = vec![0x80 | (bytes.len() - skip) as u8];
+    out.extend_from_slice(&bytes[skip..]);
+    return out;
+}
+
+//
+// Build a DER object with the given tag from the concatenation of parts.
+//
+pub fn asn1_wrap(tag: u8, parts: &[&[u8]]) -> Vec<u8>
+{
+    let len: usize = parts.iter().map(|p| p.len()).sum();
+    let mut out = Vec::with_capacity(len + 6);
+    out.push(tag);
+    out.extend_from_slice(&asn1_length(len));
+    for part in parts {
+        out.extend_from_slice(part);
+    }
+    return out;
+}
+
+//
+// Build a DER object with the given tag and content.
+//
+pub fn asn1_simple_object(tag: u8, content: &[u8]) -> Vec<u8>
+{
+    return asn1_wrap(tag, &[content]);
+}
+
+//
+// Build a DER SEQUENCE from a list of already encoded objects.
+//
+pub fn asn1_sequence(items: &[Vec<u8>]) -> Vec<u8>
+{
+    let parts: Vec<&[u8]> = items.iter().map(|i| i.as_slice()).collect();
+    return asn1_wrap(ASN1_SEQUENCE, &parts);
+}
+
+//
+// Build a DER SET OF, sorting the encoded elements as required by X.690.
+//
+pub fn asn1_set_of(items: &[Vec<u8>]) -> Vec<u8>
+{
+    let mut sorted: Vec<&[u8]> = items.iter().map(|i| i.as_slice()).collect();
+    sorted.sort();
+    return asn1_wrap(ASN1_SET, &sorted);
+}
+
+//
+// Build a DER BOOLEAN.
+//
+pub fn asn1_boolean(value: bool) -> Vec<u8>
+{
+    return vec![ASN1_BOOLEAN, 0x01, if value { 0xFF } else { 0x00 }];
+}
+
+//
+// Build a DER NULL.
+//
+pub fn asn1_null() -> Vec<u8>
+{
+    return vec![ASN1_NULL, 0x00];
+}
+
+//
+// Build a DER INTEGER from an unsigned big-endian magnitude.
+//
+pub fn asn1_integer(magnitude: &[u8]) -> Vec<u8>
+{
+    let skip = magnitude.iter().take_while(|b| **b == 0).count();
+    let value = &magnitude[skip..];
+
+    if value.is_empty() {
+        return vec![ASN1_INTEGER, 0x01, 0x00];
+    }
+    if value[0] & 0x80 != 0 {
+        return asn1_wrap(ASN1_INTEGER, &[&[0x00], value]);
+    }
+    return asn1_simple_object(ASN1_INTEGER, value);
+}
+
+//
+// Build a DER INTEGER from an unsigned 64 bit value.
+//
+pub fn asn1_integer_from_u64(value: u64) -> Vec<u8>
+{
+    return asn1_integer(&value.to_be_bytes());
+}
+
+//
+// Build a DER BIT STRING without unused bits.
+//
+pub fn asn1_bitstring(content: &[u8]) -> Vec<u8>
+{
+    return asn1_wrap(ASN1_BIT_STRING, &[&[0x00], content]);
+}
+
+//
+// Build a DER named bit list where bit i of flags corresponds to named
+// bit i, stripping trailing zero bits as required by X.690 11.2.2.
+//
+pub fn asn1_named_bits(flags: u32) -> Vec<u8>
+{
+    if flags == 0 {
+        return vec![ASN1_BIT_STRING, 0x01, 0x00];
+    }
+    let nbits = 32 - flags.leading_zeros() as usize;
+    let nbytes = nbits.div_ceil(8);
+    let mut content = vec![0u8; nbytes];
+
+    for i in 0..nbits {
+        if flags & (1 << i) != 0 {
+            content[i / 8] |= 0x80 >> (i % 8);
+        }
+    }
+    let unused = (nbytes * 8 - nbits) as u8;
+    return asn1_wrap(ASN1_BIT_STRING, &[&[unused], &content]);
+}
+
+//
+// Build a DER OCTET STRING.
+//
+pub fn asn1_octet_string(content: &[u8]) -> Vec<u8>
+{
+    return asn1_simple_object(ASN1_OCTET_STRING, content);
+}
+
+//
+// Encode the content octets of an OBJECT IDENTIFIER in dotted notation.
+//
+pub fn asn1_oid_from_string(oid: &str) -> Result<Vec<u8>, String>
+{
+    let mut arcs: Vec<u128> = Vec::new();
+    for arc in oid.split('.') {
+        if arc.is_empty() || !arc.bytes().all(|c| c.is_ascii_digit()) ||
+          (arc.len() > 1 && arc.starts_with('0')) {
+            return Err(format!("invalid OID '{}'", oid));
+        }
+        match arc.parse::<u128>() {
+            Ok(v)  => { arcs.push(v) }
+            Err(_) => { return Err(format!("OID arc too large in '{}'", oid)) }
+        }
+    }
+    if arcs.len() < 2 || arcs[0] > 2 || (arcs[0] < 2 && arcs[1] > 39) {
+        return Err(format!("invalid OID '{}'", oid));
+    }
+
+    let mut out = Vec::new();
+    let first = arcs[0] * 40 + arcs[1];
+    for arc in std::iter::once(first).chain(arcs[2..].iter().copied()) {
+        let mut groups = vec![(arc & 0x7F) as u8];
+        let mut rest = arc >> 7;
+        while rest > 0 {
+            groups.push(0x80 | (rest & 0x7F) as u8);
+            rest >>= 7;
+        }
+        groups.reverse();
+        out.extend_from_slice(&groups);
+    }
+    return Ok(out);
+}
+
+//
+// Convert the content octets of an OBJECT IDENTIFIER into dotted notation.
+//
+pub fn asn1_oid_to_string(content: &[u8]) -> Result<String, String>
+{
+    if content.is_empty() {
+        return Err("empty OID".to_string());
+    }
+    if content[content.len() - 1] & 0x80 != 0 {
+        return Err("truncated OID".to_string());
+    }
+
+    let mut arcs: Vec<u128> = Vec::new();
+    let mut value: u128 = 0;
+    let mut start = true;
+    for b in content {
+        if start && *b == 0x80 {
+            return Err("non-minimal OID subidentifier".to_string());
+        }
+        if value >> 121 != 0 {
+            return Err("OID subidentifier too large".to_string());
+        }
+        value = (value << 7) | (*b & 0x7F) as u128;
+        start = *b & 0x80 == 0;
+        if start {
+            if arcs.is_empty() {
+                let first = if value < 80 { value / 40 } else { 2 };
+                arcs.push(first);
+                arcs.push(value - 40 * first);
+            } else {
+                arcs.push(value);
+            }
+            value = 0;
+        }
+    }
+    let arcs: Vec<String> = arcs.iter().map(|a| a.to_string()).collect();
+    return Ok(arcs.join("."));
+}
+
+//
+// Build a DER OBJECT IDENTIFIER from a well-known OID in dotted notation.
+// User-supplied OIDs must be validated with asn1_oid_from_string() first.
+//
+pub fn asn1_oid(oid: &str) -> Vec<u8>
+{
+    let content = asn1_oid_from_string(oid).expect("valid built-in OID");
+    return asn1_simple_object(ASN1_OID, &content);
+}
+
+//
+// Build an AlgorithmIdentifier with an optional parameters field.
+//
+pub fn asn1_algorithm_identifier(oid: &str, params: Option<&[u8]>) -> Vec<u8>
+{
+    let oid = asn1_oid(oid);
+    match params {
+        Some(p) => { asn1_wrap(ASN1_SEQUENCE, &[&oid, p]) }
+        None    => { asn1_wrap(ASN1_SEQUENCE, &[&oid]) }
+    }
+}
+
+//
+// Build a DER character string of the given string type.
+//
+pub fn asn1_string(tag: u8, value: &str) -> Vec<u8>
+{
+    match tag {
+        ASN1_BMPSTRING => {
+            let ucs2: Vec<u8> = value.encode_utf16()
+                                     .flat_map(|c| c.to_be_bytes()).collect();
+            asn1_simple_object(tag, &ucs2)
+        }
+        ASN1_UNIVERSALSTRING => {
+            let ucs4: Vec<u8> = value.chars()
+                                     .flat_map(|c| (c as u32).to_be_bytes()).collect();
+            asn1_simple_object(tag, &ucs4)
+        }
+        _ => { asn1_simple_object(tag, value.as_bytes()) }
+    }
+}
+
+//
+// Check if a string consists of PrintableString characters only.
+//
+pub fn asn1_is_printablestring(value: &str) -> bool
+{
+    return value.bytes().all(|c| c.is_ascii_alphanumeric() || b" '()+,-./:=?".contains(&c));
+}
+
+//
+// Build a UTCTime or GeneralizedTime from a Unix timestamp, failing for
+// times outside 1950 through 2049 or the years 0000 through 9999.
+//
+pub fn asn1_from_time(time: i64, tag: u8) -> Result<Vec<u8>, String>
+{
+    let (format, years, name) = if tag == ASN1_UTCTIME {
+        ("%y%m%d%H%M%SZ", 1950..=2049, "UTCTime")
+    } else {
+        ("%Y%m%d%H%M%SZ", 0..=9999, "GeneralizedTime")
+    };
+    match DateTime::from_timestamp(time, 0) {
+        Some(dt) if years.contains(&dt.year()) => {
+            return Ok(asn1_simple_object(tag, dt.format(format).to_string().as_bytes()));
+        }
+        _ => { return Err(format!("time {} out of range for {}", time, name)); }
+    }
+}
+
+/// A decoded ASN.1 TLV object referencing the underlying encoding
+#[derive(Clone, Copy, Debug)]
+pub struct Asn1Object<'a> {
+    /// identifier octet
+    pub tag: u8,
+    /// content octets (without end-of-contents octets for BER indefinite form)
+    pub content: &'a [u8],
+    /// complete encoding including identifier and length octets
+    pub raw: &'a [u8],
+    /// BER relaxations allowed for nested objects
+    ber: bool,
+}
+
+/// Sequential decoder for concatenated ASN.1 objects
+pub struct Asn1Parser<'a> {
+    data: &'a [u8],
+    pos: usize,
+    ber: bool,
+}
+
+//
+// Map an ASN.1 tag to a human readable name for error messages.
+//
+pub fn asn1_tag_name(tag: u8) -> String
+{
+    let name = match tag {
+        ASN1_BOOLEAN         => { "BOOLEAN" }
+        ASN1_INTEGER         => { "INTEGER" }
+        ASN1_BIT_STRING      => { "BIT STRING" }
+        ASN1_OCTET_STRING    => { "OCTET STRING" }
+        ASN1_NULL            => { "NULL" }
+        ASN1_OID             => { "OBJECT IDENTIFIER" }
+        ASN1_ENUMERATED      => { "ENUMERATED" }
+        ASN1_UTF8STRING      => { "UTF8String" }
+        ASN1_NUMERICSTRING   => { "NumericString" }
+        ASN1_PRINTABLESTRING => { "PrintableString" }
+        ASN1_T61STRING       => { "T61String" }
+        ASN1_IA5STRING       => { "IA5String" }
+        ASN1_UTCTIME         => { "UTCTime" }
+        ASN1_GENERALIZEDTIME => { "GeneralizedTime" }
+        ASN1_VISIBLESTRING   => { "VisibleString" }
+        ASN1_UNIVERSALSTRING => { "UniversalString" }
+        ASN1_BMPSTRING       => { "BMPString" }
+        ASN1_SEQUENCE        => { "SEQUENCE" }
+        ASN1_SET             => { "SET" }
+        _ if tag & 0xC0 == 0x80 => {
+            return format!("[{}]{}", tag & 0x1F,
+                           if tag & ASN1_CONSTRUCTED != 0 { "" } else { " IMPLICIT" });
+        }
+        _ => { return format!("tag 0x{:02x}", tag); }
+    };
+    return name.to_string();
+}
+
+impl<'a> Asn1Parser<'a> {
+    //
+    // Create a strict DER decoder.
+    //
+    pub fn new(data: &'a [u8]) -> Self
+    {
+        Asn1Parser { data, pos: 0, ber: false }
+    }
+
+    //
+    // Create a decoder tolerating BER encodings (indefinite lengths,
+    // non-minimal lengths and constructed strings).
+    //
+    pub fn new_ber(data: &'a [u8]) -> Self
+    {
+        Asn1Parser { data, pos: 0, ber: true }
+    }
+
+    //
+    // Check if all objects have been consumed.
+    //
+    pub fn is_empty(&self) -> bool
+    {
+        return self.pos >= self.data.len();
+    }
+
+    //
+    // Peek at the tag of the next object.
+    //
+    pub fn peek_tag(&self) -> Option<u8>
+    {
+        return self.data.get(self.pos).copied();
+    }
+
+    //
+    // Decode the next object, returning None at the end of the data.
+    //
+    pub fn next_object(&mut self) -> Result<Option<Asn1Object<'a>>, String>
+    {
+        if self.is_empty() {
+            return Ok(None);
+        }
+        let (obj, len) = read_tlv(&self.data[self.pos..], self.ber, 0)?;
+        self.pos += len;
+        return Ok(Some(obj));
+    }
+
+    //
+    // Decode the next object which must carry the given tag.
+    //
+    pub fn expect(&mut self, tag: u8) -> Result<Asn1Object<'a>, String>
+    {
+        match self.next_object()? {
+            Some(obj) if obj.tag == tag => { Ok(obj) }
+            Some(obj) => {
+                Err(format!("expected {}, found {}", asn1_tag_name(tag),
+                            asn1_tag_name(obj.tag)))
+            }
+            None => { Err(format!("expected {}, found end of data", asn1_tag_name(tag))) }
+        }
+    }
+
+    //
+    // Decode the next object only if it carries the given tag.
+    //
+    pub fn optional(&mut self, tag: u8) -> Result<Option<Asn1Object<'a>>, String>
+    {
+        if self.peek_tag() == Some(tag) {
+            return self.next_object();
+        }
+        return Ok(None);
+    }
+
+    //
+    // Make sure that no trailing objects are left.
+    //
+    pub fn finish(&self) -> Result<(), String>
+    {
+        if !self.is_empty() {
+            return Err(format!("{} bytes of unexpected trailing data",
+                               self.data.len() - self.pos));
+        }
+        return Ok(());
+    }
+}
+
+//
+// Decode exactly one strict DER object spanning all of data.
+//
+pub fn asn1_parse(data: &[u8]) -> Result<Asn1Object<'_>, String>
+{
+    let mut parser = Asn1Parser::new(data);
+    let obj = parser.next_object()?.ok_or("empty ASN.1 data".to_string())?;
+    parser.finish()?;
+    return Ok(obj);
+}
+
+//
+// Decode exactly one BER object spanning all of data.
+//
+pub fn asn1_parse_ber(data: &[u8]) -> Result<Asn1Object<'_>, String>
+{
+    let mut parser = Asn1Parser::new_ber(data);
+    let obj = parser.next_object()?.ok_or("empty ASN.1 data".to_string())?;
+    parser.finish()?;
+    return Ok(obj);
+}
+
+//
+// Decode a single TLV at the start of data, returning the object and the
+// number of bytes it occupies.
+//
+fn read_tlv(data: &[u8], ber: bool, depth: usize) -> Result<(Asn1Object<'_>, usize), String>
+{
+    if depth > ASN1_MAX_DEPTH {
+        return Err("ASN.1 nesting too deep".to_string());
+    }
+    if data.len() < 2 {
+        return Err("truncated ASN.1 object".to_string());
+    }
+    let tag = data[0];
+    if tag & 0x1F == 0x1F {
+        return Err("high tag numbers are not supported".to_string());
+    }
+
+    let first = data[1];
+    let (len, hdr) = if first < 0x80 {
+        (first as usize, 2)
+    } else if first == 0x80 {
+        if !ber {
+            return Err("indefinite length is not allowed in DER".to_string());
+        }
+        if tag & ASN1_CONSTRUCTED == 0 {
+            return Err("indefinite length on primitive object".to_string());
+        }
+        // walk nested objects until the end-of-contents octets
+        let mut pos = 2;
+        loop {
+            if data.len() < pos + 2 {
+                return Err("missing end-of-contents octets".to_string());
+            }
+            if data[pos] == ASN1_EOC && data[pos + 1] == 0x00 {
+                break;
+            }
+            let (_, len) = read_tlv(&data[pos..], ber, depth + 1)?;
+            pos += len;
+        }
+        let obj = Asn1Object { tag, content: &data[2..pos], raw: &data[..pos + 2], ber };
+        return Ok((obj, pos + 2));
+    } else {
+        let count = (first & 0x7F) as usize;
+        if count > std::mem::size_of::<usize>() || data.len() < 2 + count {
+            return Err("invalid ASN.1 length".to_string());
+        }
+        let mut len: usize = 0;
+        for b in &data[2..2 + count] {
+            len = (len << 8) | *b as usize;
+        }
+        if !ber && (len < 0x80 || data[2] == 0) {
+            return Err("non-minimal length encoding".to_string());
+        }
+        (len, 2 + count)
+    };
+
+    if data.len() - hdr < len {
+        return Err(format!("ASN.1 length {} exceeds remaining {} bytes", len,
+                           data.len() - hdr));
+    }
+    let obj = Asn1Object { tag, content: &data[hdr..hdr + len], raw: &data[..hdr + len], ber };
+
+    if tag & ASN1_CONSTRUCTED != 0 {
+        // validate nested encodings eagerly so that errors surface early
+        let mut pos = 0;
+        while pos < len {
+            let (_, l) = read_tlv(&obj.content[pos..], ber, depth + 1)?;
+            pos += l;
+        }
+    } else if tag == ASN1_EOC {
+        return Err("unexpected end-of-contents octets".to_string());
+    }
+    return Ok((obj, hdr + len));
+}
+
+impl<'a> Asn1Object<'a> {
+    //
+    // Create a decoder for the nested objects of a constructed object.
+    //
+    pub fn parser(&self) -> Asn1Parser<'a>
+    {
+        Asn1Parser { data: self.content, pos: 0, ber: self.ber }
+    }
+
+    //
+    // Check if the object is constructed.
+    //
+    pub fn is_constructed(&self) -> bool
+    {
+        return self.tag & ASN1_CONSTRUCTED != 0;
+    }
+
+    //
+    // Interpret the object as a BOOLEAN.
+    //
+    pub fn to_bool(&self) -> Result<bool, String>
+    {
+        self.check_tag(ASN1_BOOLEAN)?;
+        if self.content.len() != 1 {
+            return Err("invalid BOOLEAN length".to_string());
+        }
+        match self.content[0] {
+            0x00 => { Ok(false) }
+            0xFF => { Ok(true) }
+            _ if self.ber => { Ok(true) }
+            _ => { Err("non-canonical BOOLEAN value".to_string()) }
+        }
+    }
+
+    //
+    // Return the big-endian content octets of an INTEGER or ENUMERATED
+    // after checking for minimal two's complement encoding.
+    //
+    pub fn integer_bytes(&self) -> Result<&'a [u8], String>
+    {
+        if self.tag != ASN1_INTEGER && self.tag != ASN1_ENUMERATED &&
+           self.tag & 0xE0 != ASN1_CONTEXT_S_0 {
+            return Err(format!("expected INTEGER, found {}", asn1_tag_name(self.tag)));
+        }
+        let c = self.content;
+        if c.is_empty() {
+            return Err("empty INTEGER".to_string());
+        }
+        if c.len() > 1 && ((c[0] == 0x00 && c[1] & 0x80 == 0) ||
+                           (c[0] == 0xFF && c[1] & 0x80 != 0)) {
+            return Err("non-minimal INTEGER encoding".to_string());
+        }
+        return Ok(c);
+    }
+
+    //
+    // Return the magnitude of a non-negative INTEGER without sign octet.
+    //
+    pub fn to_unsigned(&self) -> Result<&'a [u8], String>
+    {
+        let c = self.integer_bytes()?;
+        if c[0] & 0x80 != 0 {
+            return Err("negative INTEGER".to_string());
+        }
+        if c.len() > 1 && c[0] == 0x00 {
+            return Ok(&c[1..]);
+        }
+        return Ok(c);
+    }
+
+    //
+    // Interpret the object as a non-negative INTEGER fitting into 64 bits.
+    //
+    pub fn to_u64(&self) -> Result<u64, String>
+    {
+        let c = self.to_unsigned()?;
+        if c.len() > 8 {
+            return Err("INTEGER exceeds 64 bits".to_string());
+        }
+        return Ok(c.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64));
+    }
+
+    //
+    // Interpret the object as an OBJECT IDENTIFIER in dotted notation.
+    //
+    pub fn to_oid(&self) -> Result<String, String>
+    {
+        self.check_tag(ASN1_OID)?;
+        return asn1_oid_to_string(self.content);
+    }
+
+    //
+    // Interpret the object as a BIT STRING, returning the number of unused
+    // bits and the content octets.
+    //
+    pub fn to_bitstring(&self) -> Result<(u8, &'a [u8]), String>
+    {
+        if self.tag != ASN1_BIT_STRING && self.tag & 0xE0 != ASN1_CONTEXT_S_0 {
+            return Err(format!("expected BIT STRING, found {}", asn1_tag_name(self.tag)));
+        }
+        let content = self.content;
+        if content.is_empty() {
+            return Err("empty BIT STRING".to_string());
+        }
+        let unused = content[0];
+        if unused > 7 || (content.len() == 1 && unused != 0) {
+            return Err("invalid number of unused bits in BIT STRING".to_string());
+        }
+        if !self.ber && unused > 0 && content[content.len() - 1] & ((1 << unused) - 1) != 0 {
+            return Err("non-zero padding bits in BIT STRING".to_string());
+        }
+        return Ok((unused, &content[1..]));
+    }
+
+    //
+    // Interpret a BIT STRING without unused bits as an octet sequence.
+    //
+    pub fn to_bitstring_octets(&self) -> Result<&'a [u8], String>
+    {
+        let (unused, bits) = self.to_bitstring()?;
+        if unused != 0 {
+            return Err("BIT STRING with unused bits where octets expected".to_string());
+        }
+        return Ok(bits);
+    }
+
+    //
+    // Interpret a named bit list, bit i being returned as bit i of the result.
+    //
+    pub fn to_named_bits(&self) -> Result<u32, String>
+    {
+        let (_, bits) = self.to_bitstring()?;
+        if bits.len() > 4 {
+            return Err("named bit list too long".to_string());
+        }
+        let mut flags = 0;
+        for (i, b) in bits.iter().enumerate() {
+            for j in 0..8 {
+                if b & (0x80 >> j) != 0 {
+                    flags |= 1 << (8 * i + j);
+                }
+            }
+        }
+        return Ok(flags);
+    }
+
+    //
+    // Return the content octets of a primitive or (BER only) constructed
+    // string type, concatenating the segments of the latter.
+    //
+    pub fn octets(&self) -> Result<Cow<'a, [u8]>, String>
+    {
+        if !self.is_constructed() || self.tag & 0xC0 != 0x00 {
+            return Ok(Cow::Borrowed(self.content));
+        }
+        if !self.ber {
+            return Err(format!("constructed {} is not allowed in DER",
+                               asn1_tag_name(self.tag & !ASN1_CONSTRUCTED)));
+        }
+        let mut out = Vec::new();
+        let mut parser = self.parser();
+        while let Some(segment) = parser.next_object()? {
+            if segment.tag & !ASN1_CONSTRUCTED != self.tag & !ASN1_CONSTRUCTED {
+                return Err("inconsistent segment in constructed string".to_string());
+            }
+            out.extend_from_slice(&segment.octets()?);
+        }
+        return Ok(Cow::Owned(out));
+    }
+
+    //
+    // Interpret the object as one of the ASN.1 character string types.
+    //
+    pub fn to_string(&self) -> Result<String, String>
+    {
+        let tag = self.tag & !ASN1_CONSTRUCTED;
+        let content = self.octets()?;
+        let invalid = || format!("invalid {}", asn1_tag_name(tag));
+
+        match tag {
+            ASN1_UTF8STRING => {
+                String::from_utf8(content.to_vec()).map_err(|_| invalid())
+            }
+            ASN1_PRINTABLESTRING => {
+                let s = String::from_utf8(content.to_vec()).map_err(|_| invalid())?;
+                if !asn1_is_printablestring(&s) && !self.ber {
+                    return Err(invalid());
+                }
+                Ok(s)
+            }
+            ASN1_NUMERICSTRING => {
+                if !content.iter().all(|c| c.is_ascii_digit() || *c == b' ') {
+                    return Err(invalid());
+                }
+                Ok(String::from_utf8_lossy(&content).to_string())
+            }
+            ASN1_IA5STRING | ASN1_VISIBLESTRING | ASN1_GENERALSTRING | ASN1_GRAPHICSTRING => {
+                if !content.is_ascii() {
+                    return Err(invalid());
+                }
+                Ok(String::from_utf8_lossy(&content).to_string())
+            }
+            ASN1_T61STRING | ASN1_VIDEOTEXSTRING => {
+                // commonly used for ISO 8859-1 content
+                Ok(content.iter().map(|c| *c as char).collect())
+            }
+            ASN1_BMPSTRING => {
+                if content.len() % 2 != 0 {
+                    return Err(invalid());
+                }
+                let ucs2: Vec<u16> = content.chunks(2)
+                                            .map(|c| u16::from_be_bytes([c[0], c[1]])).collect();
+                String::from_utf16(&ucs2).map_err(|_| invalid())
+            }
+            ASN1_UNIVERSALSTRING => {
+                if content.len() % 4 != 0 {
+                    return Err(invalid());
+                }
+                content.chunks(4)
+                       .map(|c| char::from_u32(u32::from_be_bytes([c[0], c[1], c[2], c[3]])))
+                       .collect::<Option<String>>().ok_or_else(invalid)
+            }
+            _ => { Err(format!("{} is not a string type", asn1_tag_name(tag))) }
+        }
+    }
+
+    //
+    // Interpret a UTCTime or GeneralizedTime as Unix timestamp.
+    //
+    pub fn to_time(&self) -> Result<i64, String>
+    {
+        let s = std::str::from_utf8(self.content).map_err(|_| "invalid time".to_string())?;
+        return match self.tag {
+            ASN1_UTCTIME         => { parse_time(s, false, self.ber) }
+            ASN1_GENERALIZEDTIME => { parse_time(s, true, self.ber) }
+            _ => { Err(format!("expected time, found {}", asn1_tag_name(self.tag))) }
+        };
+    }
+
+    //
+    // Ensure the object carries the expected universal tag.
+    //
+    fn check_tag(&self, tag: u8) -> Result<(), String>
+    {
+        if self.tag != tag {
+            return Err(format!("expected {}, found {}", asn1_tag_name(tag),
+                               asn1_tag_name(self.tag)));
+        }
+        return Ok(());
+    }
+}
+
+//
+// Parse the textual content of a UTCTime or GeneralizedTime. DER demands
+// seconds, a 'Z' suffix and no trailing zeros in fractional seconds, BER
+// also accepts missing seconds and local time differentials.
+//
+fn parse_time(s: &str, generalized: bool, ber: bool) -> Result<i64, String>
+{
+    let invalid = || format!("invalid {} '{}'", if generalized { "GeneralizedTime" }
+                                                 else { "UTCTime" }, s);
+    if !s.is_ascii() {
+        return Err(invalid());
+    }
+    let digits = |from: usize, len: usize| -> Result<u32, String> {
+        let part = s.get(from..from + len).ok_or_else(invalid)?;
+        if !part.bytes().all(|c| c.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        return part.parse::<u32>().map_err(|_| invalid());
+    };
+
+    let (year, mut pos) = if generalized {
+        (digits(0, 4)? as i32, 4)
+    } else {
+        let yy = digits(0, 2)? as i32;
+        (if yy < 50 { 2000 + yy } else { 1900 + yy }, 2)
+    };
+    let month = digits(pos, 2)?;
+    let day = digits(pos + 2, 2)?;
+    let hour = digits(pos + 4, 2)?;
+    let minute = digits(pos + 6, 2)?;
+    pos += 8;
+
+    let mut second = 0;
+    if s.len() > pos && s.as_bytes()[pos].is_ascii_digit() {
+        second = digits(pos, 2)?;
+        pos += 2;
+    } else if !ber {
+        return Err(invalid());
+    }
+
+    if generalized && s.len() > pos && (s.as_bytes()[pos] == b'.' || s.as_bytes()[pos] == b',') {
+        let frac_start = pos + 1;
+        pos = frac_start;
+        while s.len() > pos && s.as_bytes()[pos].is_ascii_digit() {
+            pos += 1;
+        }
+        if pos == frac_start || (!ber && (s.as_bytes()[pos - 1] == b'0' ||
+                                           s.as_bytes()[frac_start - 1] != b'.')) {
+            return Err(invalid());
+        }
+    }
+
+    let mut offset: i64 = 0;
+    match &s[pos..] {
+        "Z" => {}
+        tz if ber && tz.len() == 5 && (tz.starts_with('+') || tz.starts_with('-')) => {
+            let h = digits(pos + 1, 2)? as i64;
+            let m = digits(pos + 3, 2)? as i64;
+            offset = (h * 60 + m) * 60;
+            if tz.starts_with('-') {
+                offset = -offset;
+            }
+        }
+        _ => { return Err(invalid()); }
+    }
+
+    let date = NaiveDate::from_ymd_opt(year, month, day).ok_or_else(invalid)?;
+    let time = date.and_hms_opt(hour, minute, second).ok_or_else(invalid)?;
+    return Ok(time.and_utc().timestamp() - offset);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex;
+
+    fn nested(depth: usize) -> Vec<u8>
+    {
+        let mut der = asn1_null();
+        for _ in 1..depth {
+            der = asn1_wrap(ASN1_SEQUENCE, &[&der]);
+        }
+        return der;
+    }
+
+    #[test]
+    fn length_encoding()
+    {
+        assert_eq!(asn1_length(0x7F), vec![0x7F]);
+        assert_eq!(asn1_length(0x80), vec![0x81, 0x80]);
+        assert_eq!(asn1_length(0x100), vec![0x82, 0x01, 0x00]);
+
+        let content = vec![0xAB; 0x100];
+        let der = asn1_octet_string(&content);
+        assert_eq!(&der[..4], &[ASN1_OCTET_STRING, 0x82, 0x01, 0x00]);
+        assert_eq!(asn1_parse(&der).unwrap().content, content.as_slice());
+    }
+
+    #[test]
+    fn non_minimal_length()
+    {
+        for encoding in ["04810548656c6c6f", "0482000548656c6c6f"] {
+            assert_eq!(asn1_parse_ber(&hex(encoding)).unwrap().content, b"Hello");
+            assert_eq!(asn1_parse(&hex(encoding)).unwrap_err(), "non-minimal length encoding");
+        }
+        // the long form is minimal from 128 octets on
+        let der = [hex("048180"), vec![0; 0x80]].concat();
+        assert_eq!(asn1_parse(&der).unwrap().content.len(), 0x80);
+        let der = [hex("04820080"), vec![0; 0x80]].concat();
+        assert!(asn1_parse(&der).is_err());
+    }
+
+    #[test]
+    fn invalid_length()
+    {
+        assert!(asn1_parse(&hex("0405414243")).is_err());
+        assert!(asn1_parse(&hex("04")).is_err());
+        assert!(asn1_parse(&hex("0489010000000000000000")).is_err());
+        assert_eq!(asn1_parse(&hex("0401410000")).unwrap_err(), "2 bytes of unexpected trailing data");
+        assert!(asn1_parse(&hex("1f0100")).is_err());
+    }
+
+    #[test]
+    fn indefinite_length()
+    {
+        let ber = hex("3080040141020101 0000");
+        assert_eq!(asn1_parse(&ber).unwrap_err(), "indefinite length is not allowed in DER");
+
+        let obj = asn1_parse_ber(&ber).unwrap();
+        let mut parser = obj.parser();
+        assert_eq!(parser.expect(ASN1_OCTET_STRING).unwrap().content, b"A");
+        assert_eq!(parser.expect(ASN1_INTEGER).unwrap().to_u64().unwrap(), 1);
+        parser.finish().unwrap();
+        assert_eq!(obj.raw, ber.as_slice());
+
+        // nested indefinite lengths and constructed strings
+        let ber = hex("3080 2480 0402 4142 0401 43 0000 0000");
+        let obj = asn1_parse_ber(&ber).unwrap();
+        assert_eq!(obj.parser().next_object().unwrap().unwrap().octets().unwrap().as_ref(), b"ABC");
+
+        assert_eq!(asn1_parse_ber(&hex("0480 41 0000")).unwrap_err(),
+                   "indefinite length on primitive object");
+        assert_eq!(asn1_parse_ber(&hex("3080 020101")).unwrap_err(), "missing end-of-contents octets");
+        assert_eq!(asn1_parse(&hex("3003 0000 00")).unwrap_err(), "unexpected end-of-contents octets");
+        assert!(asn1_parse(&hex("2403 040141")).unwrap().octets().is_err());
+    }
+
+    #[test]
+    fn depth_limit()
+    {
+        assert!(asn1_parse(&nested(ASN1_MAX_DEPTH + 1)).is_ok());
+        assert_eq!(asn1_parse(&nested(ASN1_MAX_DEPTH + 2)).unwrap_err(), "ASN.1 nesting too deep");
+        assert!(asn1_parse_ber(&nested(1000)).is_err());
+    }
+
+    #[test]
+    fn integers()
+    {
+        assert_eq!(asn1_integer(&[0x80]), hex("02020080"));
+        assert_eq!(asn1_integer(&[0x00, 0x00, 0x7F]), hex("02017f"));
+        assert_eq!(asn1_integer(&[]), hex("020100"));
+        assert_eq!(asn1_integer_from_u64(0x8000), hex("0203008000"));
+
+        assert_eq!(asn1_parse(&hex("02020080")).unwrap().to_unsigned().unwrap(), &[0x80]);
+        assert_eq!(asn1_parse(&hex("0202007f")).unwrap().integer_bytes().unwrap_err(),
+                   "non-minimal INTEGER encoding");
+        assert!(asn1_parse(&hex("0202ff80")).unwrap().integer_bytes().is_err());
+        assert_eq!(asn1_parse(&hex("020180")).unwrap().to_unsigned().unwrap_err(), "negative INTEGER");
+        assert!(asn1_parse(&hex("0200")).unwrap().to_u64().is_err());
+        assert!(asn1_parse(&hex("0209010000000000000000")).unwrap().to_u64().is_err());
+        assert_eq!(asn1_parse(&hex("020900ffffffffffffffff")).unwrap().to_u64().unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn booleans_and_bit_strings()
+    {
+        assert!(asn1_parse(&hex("0101ff")).unwrap().to_bool().unwrap());
+        assert!(!asn1_parse(&hex("010100")).unwrap().to_bool().unwrap());
+        assert!(asn1_parse(&hex("010101")).unwrap().to_bool().is_err());
+        assert!(asn1_parse_ber(&hex("010101")).unwrap().to_bool().unwrap());
+
+        assert_eq!(asn1_named_bits(0x01 | 0x20), hex("03020284"));
+        assert_eq!(asn1_named_bits(0x100), hex("0303070080"));
+        assert_eq!(asn1_parse(&hex("03020284")).unwrap().to_named_bits().unwrap(), 0x21);
+        assert!(asn1_parse(&hex("03020285")).unwrap().to_bitstring().is_err());
+        assert!(asn1_parse(&hex("030108")).unwrap().to_bitstring().is_err());
+        assert!(asn1_parse(&hex("03020100")).unwrap().to_bitstring_octets().is_err());
+    }
+
+    #[test]
+    fn object_identifiers()
+    {
+        for oid in ["2.5.29.19", "1.2.840.113549.1.1.11", "2.999.3", "0.39",
+                    "1.3.6.1.4.1.311.21.20"] {
+            let content = asn1_oid_from_string(oid).unwrap();
+            assert_eq!(asn1_oid_to_string(&content).unwrap(), oid);
+        }
+        assert_eq!(asn1_oid("2.999.3"), hex("0603883703"));
+        for oid in ["1", "3.1", "1.40", "1.2.", "1.02", "1.2.a"] {
+            assert!(asn1_oid_from_string(oid).is_err(), "{}", oid);
+        }
+        assert_eq!(asn1_oid_to_string(&hex("2a864886f70d")).unwrap(), "1.2.840.113549");
+        assert!(asn1_oid_to_string(&hex("2a8648ce")).is_err());
+        assert!(asn1_oid_to_string(&hex("2a80860d")).is_err());
+        assert!(asn1_oid_to_string(&[]).is_err());
+    }
+
+    #[test]
+    fn time_boundaries()
+    {
+        let utc = |s: &str| asn1_simple_object(ASN1_UTCTIME, s.as_bytes());
+        let gtime = |s: &str| asn1_simple_object(ASN1_GENERALIZEDTIME, s.as_bytes());
+        let time = |der: Vec<u8>| asn1_parse(&der).unwrap().to_time();
+
+        // UTCTime years 50..99 map to 19xx, 00..49 to 20xx
+        assert_eq!(time(utc("500101000000Z")), Ok(-631152000));
+        assert_eq!(time(utc("491231235959Z")), Ok(2524607999));
+        assert_eq!(time(utc("700101000000Z")), Ok(0));
+        assert_eq!(time(gtime("20500101000000Z")), Ok(2524608000));
+        assert_eq!(time(gtime("19491231235959Z")), Ok(-631152001));
+        assert_eq!(time(gtime("99991231235959Z")), Ok(253402300799));
+        assert_eq!(time(gtime("20240229120000Z")), Ok(1709208000));
+        assert_eq!(time(gtime("20240229120000.5Z")), Ok(1709208000));
+
+        for invalid in [utc("4912312359Z"), utc("491231235959+0100"), utc("491231235959"),
+                        utc("491331235959Z"), gtime("20230229000000Z"), gtime("20240229240000Z"),
+                        gtime("20240229120000.50Z"), gtime("20240229120000,5Z"),
+                        gtime("20240229120000.Z"), gtime("2024022912000Z")] {
+            assert!(asn1_parse(&invalid).unwrap().to_time().is_err(),
+                    "{}", String::from_utf8_lossy(&invalid[2..]));
+        }
+
+        // BER accepts missing seconds and time differentials
+        let ber = |der: Vec<u8>| asn1_parse_ber(&der).unwrap().to_time();
+        assert_eq!(ber(utc("4912312359Z")), Ok(2524607940));
+        assert_eq!(ber(utc("500101010000+0100")), Ok(-631152000));
+        assert_eq!(ber(gtime("20240229120000-0130")), Ok(1709213400));
+
+        for t in [-631152000, 0, 1709208000, 2524607999] {
+            assert_eq!(time(asn1_from_time(t, ASN1_UTCTIME).unwrap()), Ok(t));
+        }
+        assert_eq!(asn1_from_time(2524608000, ASN1_GENERALIZEDTIME), Ok(gtime("20500101000000Z")));
+        assert_eq!(asn1_from_time(253402300799, ASN1_GENERALIZEDTIME),
+                   Ok(gtime("99991231235959Z")));
+        assert_eq!(asn1_from_time(-62167219200, ASN1_GENERALIZEDTIME),
+                   Ok(gtime("00000101000000Z")));
+
+        // out of range times must not silently turn into 1970 or five-digit years
+        for (t, tag) in [(2524608000, ASN1_UTCTIME), (-631152001, ASN1_UTCTIME),
+                         (253402300800, ASN1_GENERALIZEDTIME),
+                         (-62167219201, ASN1_GENERALIZEDTIME),
+                         (i64::MAX, ASN1_GENERALIZEDTIME), (i64::MIN, ASN1_UTCTIME)] {
+            assert!(asn1_from_time(t, tag).is_err(), "{}", t);
+        }
+    }
+
+    #[test]
+    fn strings()
+    {
+        assert_eq!(asn1_parse(&hex("0c03e282ac")).unwrap().to_string().unwrap(), "\u{20ac}");
+        assert!(asn1_parse(&hex("0c02c328")).unwrap().to_string().is_err());
+        assert!(asn1_parse(&hex("130141")).unwrap().to_string().is_ok());
+        assert!(asn1_parse(&hex("130140")).unwrap().to_string().is_err());
+        assert!(asn1_parse(&hex("160180")).unwrap().to_string().is_err());
+        assert_eq!(asn1_parse(&hex("1e0400e400f6")).unwrap().to_string().unwrap(), "\u{e4}\u{f6}");
+        assert!(asn1_parse(&hex("1e03004100")).unwrap().to_string().is_err());
+        assert_eq!(asn1_parse(&hex("1c080001f60000000041")).unwrap().to_string().unwrap(), "\u{1f600}A");
+        assert_eq!(asn1_string(ASN1_UTF8STRING, "abc"), hex("0c03616263"));
+    }
+}
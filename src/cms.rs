@@ -0,0 +1,394 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! CMS enveloped-data (RFC 5652) with AES-CBC content encryption. RSA
+//! recipients use KeyTransRecipientInfo, ML-KEM recipients the
+//! KEMRecipientInfo of RFC 9629 with HKDF and AES key wrap (RFC 9936):
+//!
+//! ```text
+//! KEMRecipientInfo ::= SEQUENCE {
+//!     version       CMSVersion,  -- always set to 0
+//!     rid           RecipientIdentifier,
+//!     kem           KEMAlgorithmIdentifier,
+//!     kemct         OCTET STRING,
+//!     kdf           KeyDerivationAlgorithmIdentifier,
+//!     kekLength     INTEGER (1..65535),
+//!     ukm           [0] EXPLICIT UserKeyingMaterial OPTIONAL,
+//!     wrap          KeyEncryptionAlgorithmIdentifier,
+//!     encryptedKey  EncryptedKey
+//! }
+//!
+//! CMSORIforKEMOtherInfo ::= SEQUENCE {
+//!     wrap          KeyEncryptionAlgorithmIdentifier,
+//!     kekLength     INTEGER (1..65535),
+//!     ukm           [0] EXPLICIT UserKeyingMaterial OPTIONAL
+//! }
+//! ```
+
+use crate::asn1::*;
+use crate::oid::*;
+use crate::keys::{PrivateKey, PublicKey, parse_algorithm_identifier};
+use crate::x509::Certificate;
+use crate::crypto::aes::*;
+use crate::crypto::hash::HashAlgorithm;
+use crate::crypto::kdf::hkdf;
+use crate::crypto::mlkem::MlKemParams;
+use crate::crypto::rng::rng_bytes;
+
+// content encryption key length of the generated AES-256-CBC envelopes
+const CEK_LEN: usize = 32;
+
+//
+// Key wrap algorithm and KEK length for an ML-KEM parameter set.
+//
+fn kem_wrap_algorithm(params: MlKemParams) -> (&'static str, usize)
+{
+    match params {
+        MlKemParams::MlKem512 => { (OID_AES128_WRAP, 16) }
+        _                     => { (OID_AES256_WRAP, 32) }
+    }
+}
+
+//
+// Encode CMSORIforKEMOtherInfo used as HKDF info.
+//
+fn kem_other_info(wrap: &[u8], kek_len: u64, ukm: Option<&[u8]>) -> Vec<u8>
+{
+    let mut items = vec![wrap.to_vec(), asn1_integer_from_u64(kek_len)];
+    if let Some(ukm) = ukm {
+        items.push(ukm.to_vec());
+    }
+    return asn1_sequence(&items);
+}
+
+//
+// Build a RecipientInfo transporting the content encryption key to the
+// holder of a certificate.
+//
+fn recipient_info(cert: &Certificate, cek: &[u8]) -> Result<Vec<u8>, String>
+{
+    match &cert.public_key {
+        PublicKey::Rsa(key) => {
+            Ok(asn1_sequence(&[
+                asn1_integer_from_u64(0),
+                cert.issuer_and_serial(),
+                asn1_algorithm_identifier(OID_RSA_ENCRYPTION, Some(&asn1_null())),
+                asn1_octet_string(&key.encrypt_pkcs1(cek)?),
+            ]))
+        }
+        PublicKey::MlKem(key) => {
+            let (wrap_oid, kek_len) = kem_wrap_algorithm(key.params);
+            let wrap = asn1_algorithm_identifier(wrap_oid, None);
+            let (secret, ct) = key.encapsulate();
+            let info = kem_other_info(&wrap, kek_len as u64, None);
+            let kek = hkdf(HashAlgorithm::Sha256, &[], &secret, &info, kek_len)?;
+
+            let kemri = asn1_sequence(&[
+                asn1_integer_from_u64(0),
+                cert.issuer_and_serial(),
+                asn1_algorithm_identifier(key.params.oid(), None),
+                asn1_octet_string(&ct),
+                asn1_algorithm_identifier(OID_HKDF_SHA256, None),
+                asn1_integer_from_u64(kek_len as u64),
+                wrap,
+                asn1_octet_string(&aes_key_wrap(&kek, cek)?),
+            ]);
+            Ok(asn1_wrap(ASN1_CONTEXT_C_4, &[&asn1_oid(OID_ORI_KEM), &kemri]))
+        }
+        key => {
+            Err(format!("{} keys are not supported for enveloped-data recipients",
+                        key.key_type()))
+        }
+    }
+}
+
+//
+// Encrypt data for a list of recipients, returning a ContentInfo with
+// enveloped-data.
+//
+pub fn cms_encrypt(data: &[u8], recipients: &[Certificate]) -> Result<Vec<u8>, String>
+{
+    if recipients.is_empty() {
+        return Err("no recipients".to_string());
+    }
+    let cek = rng_bytes(CEK_LEN);
+    let iv = rng_bytes(AES_BLOCK_SIZE);
+
+    let mut infos = Vec::new();
+    for cert in recipients {
+        infos.push(recipient_info(cert, &cek)?);
+    }
+    // version 3 is required if any OtherRecipientInfo is present
+    let version = if infos.iter().any(|i| i[0] == ASN1_CONTEXT_C_4) { 3 } else { 0 };
+
+    let encrypted_content_info = asn1_sequence(&[
+        asn1_oid(OID_PKCS7_DATA),
+        asn1_algorithm_identifier(OID_AES256_CBC, Some(&asn1_octet_string(&iv))),
+        asn1_simple_object(ASN1_CONTEXT_S_0, &aes_cbc_encrypt(&cek, &iv, data)?),
+    ]);
+    let enveloped_data = asn1_sequence(&[
+        asn1_integer_from_u64(version),
+        asn1_set_of(&infos),
+        encrypted_content_info,
+    ]);
+    return Ok(asn1_sequence(&[
+        asn1_oid(OID_PKCS7_ENVELOPED_DATA),
+        asn1_wrap(ASN1_CONTEXT_C_0, &[&enveloped_data]),
+    ]));
+}
+
+//
+// Recover the content encryption key from a KeyTransRecipientInfo.
+//
+fn decrypt_ktri(ri: &Asn1Object, key: &PrivateKey) -> Result<Vec<u8>, String>
+{
+    let PrivateKey::Rsa(key) = key else {
+        return Err("not an RSA recipient".to_string());
+    };
+    let mut parser = ri.parser();
+    parser.expect(ASN1_INTEGER)?;
+    parser.next_object()?.ok_or("missing recipient identifier")?;
+    let (oid, _) = parse_algorithm_identifier(&parser.expect(ASN1_SEQUENCE)?)?;
+    if oid != OID_RSA_ENCRYPTION {
+        return Err(format!("unsupported key encryption algorithm {}", oid_display(&oid)));
+    }
+    let encrypted_key = parser.expect(ASN1_OCTET_STRING)?;
+    parser.finish()?;
+    return key.decrypt_pkcs1(&encrypted_key.octets()?);
+}
+
+//
+// Recover the content encryption key from a KEMRecipientInfo.
+//
+fn decrypt_kemri(ri: &Asn1Object, key: &PrivateKey) -> Result<Vec<u8>, String>
+{
+    let PrivateKey::MlKem(key) = key else {
+        return Err("not an ML-KEM recipient".to_string());
+    };
+    let mut parser = ri.parser();
+    if parser.expect(ASN1_INTEGER)?.to_u64()? != 0 {
+        return Err("unsupported KEMRecipientInfo version".to_string());
+    }
+    parser.next_object()?.ok_or("missing recipient identifier")?;
+    let (kem, _) = parse_algorithm_identifier(&parser.expect(ASN1_SEQUENCE)?)?;
+    if kem != key.params.oid() {
+        return Err(format!("KEM algorithm {} does not match key", oid_display(&kem)));
+    }
+    let kemct = parser.expect(ASN1_OCTET_STRING)?.octets()?;
+    let (kdf, params) = parse_algorithm_identifier(&parser.expect(ASN1_SEQUENCE)?)?;
+    let hash = match kdf.as_str() {
+        OID_HKDF_SHA256 => { HashAlgorithm::Sha256 }
+        OID_HKDF_SHA384 => { HashAlgorithm::Sha384 }
+        OID_HKDF_SHA512 => { HashAlgorithm::Sha512 }
+        _ => { return Err(format!("unsupported key derivation function {}", oid_display(&kdf))); }
+    };
+    if params.is_some() {
+        return Err("invalid HKDF parameters".to_string());
+    }
+    let kek_len = parser.expect(ASN1_INTEGER)?.to_u64()?;
+    let ukm = parser.optional(ASN1_CONTEXT_C_0)?;
+    let wrap = parser.expect(ASN1_SEQUENCE)?;
+    let (wrap_oid, _) = parse_algorithm_identifier(&wrap)?;
+    let expected_len = match wrap_oid.as_str() {
+        OID_AES128_WRAP => { 16 }
+        OID_AES192_WRAP => { 24 }
+        OID_AES256_WRAP => { 32 }
+        _ => { return Err(format!("unsupported key wrap algorithm {}", oid_display(&wrap_oid))); }
+    };
+    if kek_len != expected_len {
+        return Err(format!("invalid KEK length {} for {}", kek_len, oid_display(&wrap_oid)));
+    }
+    let encrypted_key = parser.expect(ASN1_OCTET_STRING)?.octets()?;
+    parser.finish()?;
+
+    let secret = key.decapsulate(&kemct)?;
+    let info = kem_other_info(wrap.raw, kek_len, ukm.map(|u| u.raw));
+    let kek = hkdf(hash, &[], &secret, &info, kek_len as usize)?;
+    return aes_key_unwrap(&kek, &encrypted_key);
+}
+
+//
+// Get the content octets of an IMPLICIT OCTET STRING, concatenating the
+// segments of a BER constructed encoding.
+//
+fn implicit_octets(obj: &Asn1Object) -> Result<Vec<u8>, String>
+{
+    if !obj.is_constructed() {
+        return Ok(obj.content.to_vec());
+    }
+    let mut out = Vec::new();
+    let mut parser = obj.parser();
+    while let Some(segment) = parser.next_object()? {
+        out.extend_from_slice(&segment.octets()?);
+    }
+    return Ok(out);
+}
+
+//
+// Decrypt a ContentInfo with enveloped-data using the private key of one
+// of the recipients.
+//
+pub fn cms_decrypt(der: &[u8], key: &PrivateKey) -> Result<Vec<u8>, String>
+{
+    let obj = asn1_parse_ber(der)?;
+    if obj.tag != ASN1_SEQUENCE {
+        return Err("not a ContentInfo".to_string());
+    }
+    let mut parser = obj.parser();
+    let content_type = parser.expect(ASN1_OID)?.to_oid()?;
+    if content_type != OID_PKCS7_ENVELOPED_DATA {
+        return Err(format!("expected envelopedData, found {}", oid_display(&content_type)));
+    }
+    let enveloped_data = parser.expect(ASN1_CONTEXT_C_0)?.parser().expect(ASN1_SEQUENCE)?;
+
+    let mut parser = enveloped_data.parser();
+    parser.expect(ASN1_INTEGER)?;
+    parser.optional(ASN1_CONTEXT_C_0)?;
+    let infos = parser.expect(ASN1_SET)?;
+    let encrypted_content_info = parser.expect(ASN1_SEQUENCE)?;
+
+    let mut cek = None;
+    let mut errors = Vec::new();
+    let mut infos = infos.parser();
+    while let Some(ri) = infos.next_object()? {
+        let result = match ri.tag {
+            ASN1_SEQUENCE if matches!(key, PrivateKey::Rsa(_)) => { decrypt_ktri(&ri, key) }
+            ASN1_CONTEXT_C_4 if matches!(key, PrivateKey::MlKem(_)) => {
+                let mut ori = ri.parser();
+                if ori.expect(ASN1_OID)?.to_oid()? != OID_ORI_KEM {
+                    continue;
+                }
+                decrypt_kemri(&ori.expect(ASN1_SEQUENCE)?, key)
+            }
+            _ => { continue; }
+        };
+        match result {
+            Ok(key) => {
+                cek = Some(key);
+                break;
+            }
+            Err(e) => { errors.push(e); }
+        }
+    }
+    let cek = match cek {
+        Some(cek) => { cek }
+        None if errors.is_empty() => {
+            return Err(format!("no {} recipient found", key.key_type()));
+        }
+        None => { return Err(errors.join(", ")); }
+    };
+
+    let mut parser = encrypted_content_info.parser();
+    parser.expect(ASN1_OID)?;
+    let (alg, params) = parse_algorithm_identifier(&parser.expect(ASN1_SEQUENCE)?)?;
+    let key_len = match alg.as_str() {
+        OID_AES128_CBC => { 16 }
+        OID_AES192_CBC => { 24 }
+        OID_AES256_CBC => { 32 }
+        _ => { return Err(format!("unsupported content encryption {}", oid_display(&alg))); }
+    };
+    let iv = match params {
+        Some(p) if p.tag == ASN1_OCTET_STRING => { p.octets()? }
+        _ => { return Err("invalid AES-CBC parameters".to_string()); }
+    };
+    if cek.len() != key_len {
+        return Err("content encryption key length does not match algorithm".to_string());
+    }
+    let encrypted = match parser.next_object()? {
+        Some(obj) if obj.tag & !ASN1_CONSTRUCTED == ASN1_CONTEXT_S_0 => { implicit_octets(&obj)? }
+        _ => { return Err("detached encrypted content is not supported".to_string()); }
+    };
+    return aes_cbc_decrypt(&cek, &iv, &encrypted);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::eddsa::{EdCurve, EdPrivateKey};
+    use crate::crypto::mlkem::MlKemPrivateKey;
+    use crate::dn::Dn;
+    use crate::hex;
+    use crate::signature::SignatureScheme;
+    use crate::x509::TbsCertificate;
+
+    // Enveloped-data for the ML-KEM-768 key derived from the seed 00..3f,
+    // generated independently with pyca/cryptography: BER indefinite
+    // lengths, a subjectKeyIdentifier rid, ukm, HKDF-SHA256, AES-256 key wrap
+    // and AES-128-CBC content encryption.
+    const KEMRI_MLKEM768: &str = "
+        308006092a864886f70d010703a08030820525020103318204d0a48204cc060b2a864886f70d0109100d03308204bb02
+        01008014000102030405060708090a0b0c0d0e0f10111213300b06096086480165030404020482044074bd096dd186cc
+        8b20190ef3a1ec6baaab550ab347b86eb901cc39e013eb3ac83271d98d2ab7ac23c10c7290e4c9727b9a2a34ee45fc44
+        927ae78dd99190b2018416b4ed8d7b980d657c22220808b8f4da1b39832c13c08e9214b18f5e15f0e94460dd5817c564
+        cabcb1856428677145c0a86e22c504e6e3cdc46779ff9acee4e78facd7bec429ec59e10b3638c185267638754537a2d2
+        f55550901d563d42a2022988ff38b348159c36409edb54922ef0363ae7e347890371a7aa14b128d461c9cabfe0c370c5
+        668f19b4eadd2673c0db3bb84cc12afd11fd1d5d2f38166f14ab8ccf358c382a05ec43359f17da797e44d74dec7637d2
+        3ab24e61adb40a8d526eb8ccd6c7e05ec09821bb087e2b6878ec95e5bae89b4b38918b552fde6e1b100fc0fbe956f1b5
+        c3de168f56a0c2c3628d31c364e1d6598cc1a9eed4e12be3e8807953655f2576637ded11f7d2a33c9a62a9592c2c4036
+        09e9293540dced26725eafb29a851a50c8f067539a6f99db65d821c74f88239157f3d3a237d39026fa4ef827851f9065
+        baf9c06a900fa87e0049fee5dd4f24996d33a3c5c49b920889b0b8da0d5d629c217a48e14943292278fe06fd8e442c29
+        0882d82b1889b71ef9529bf4cc8c369b736c1811c66d7fce643f20d71fd8f4877ba2f3e6b4c635d10a7107e6c05bf7f0
+        c6f6d759c9337e6880e7a52a7de17a5cd1ccac76227d240aa026c6012b57d5b24b1c66b8335d01cc5f7eaa80f89857db
+        c64dacd1d7c2422ea5623d51f47e3625641db6ce9772fcd680e75a105bf6a4a98d9a4287d302598ae734c71f2a232927
+        dc3bbeaa712e3199f15573ef4d1a95a524ca642c883a37182674281f93c14e47067f8ef5c55a588faed61f6f25d90b2d
+        747cb5a802b51d5ecdcd59f2112faf866a2721299cef54bf84522d0700f91191cf67539e4b2e2286112bc0325738af59
+        296caa28fbdb07843430bab888984d0fa16cd7f15f31805085c365a14e7bcbfe72e4106a119f3c41217fbb374013a6b2
+        5de2868582539d525af5b32bc95db36bab1500ae1902cd6c25b8362ce026003ac90bdce382c824a54df86706a6d2d227
+        abfb04423e867fd8ce94c952419afbf43851bbec6b8ed3991b93f654108b179e93942e7db5cbfe90b61fc9e458b2e239
+        a317d179165397fd54e6e4d56295e544fab89bd0caf5b378b473ea9d123cd194acbf6d0e2ae8d3fdb0c3086de87e7008
+        216d6e9ff6db8e655e93321095e1bbe2bd0370bc2015e197002dc8cf293f098d9b041167c27865602e27153afb76b153
+        56376feb0b2cef4148fd31057f4f8b04fa9bb4e8505857b630ddfdfe5abafc9a254bd7578832da91cec9dd8953ded975
+        3d44ab213b6652eb825d94dec5c0645b1dc6691d03e9092383e0b5e56799326ae15ed910dc83bd4fb77c6b8a57ebfd4a
+        ddbe093012f7c86498ce3b9a5283e08066e1b43cdf16a44d803ee8c97377c6e55f631f93f41af59ea553a98fedfd7f5c
+        926637777806dbd7c155498d69395733994bb1fedc5abfbf91300d060b2a864886f70d010910031c020120a016041475
+        736572206b6579696e67206d6174657269616c300b060960864801650304012d041876d06aa3e484b7df79a465b83907
+        fb3aced1fd33924d2a42304c06092a864886f70d010701301d06096086480165030401020410a0a1a2a3a4a5a6a7a8a9
+        aaabacadaeaf80204e3ba74691f1dcfd5cf290c65da3d4110a522c26c076dfb73790e20b5265a53c00000000";
+
+    fn recipient(key: &PrivateKey) -> Certificate
+    {
+        let ca = PrivateKey::Eddsa(EdPrivateKey::generate(EdCurve::Ed25519));
+        let name = Dn::parse("C=CH, CN=recipient").unwrap().to_der();
+        let tbs = TbsCertificate { serial: vec![0x01], issuer: name.clone(), not_before: 1760000000,
+                                   not_after: 1790000000, subject: name, public_key: key.public_key(),
+                                   extensions: vec![] };
+        return tbs.sign(&ca, SignatureScheme::Ed25519).unwrap();
+    }
+
+    #[test]
+    fn kemri_test_vector()
+    {
+        let seed: Vec<u8> = (0..64).collect();
+        let key = PrivateKey::MlKem(MlKemPrivateKey::from_seed(MlKemParams::MlKem768, &seed).unwrap());
+        let content = cms_decrypt(&hex(KEMRI_MLKEM768), &key).unwrap();
+        assert_eq!(content, b"KEMRecipientInfo test vector\n");
+
+        let other = PrivateKey::MlKem(MlKemPrivateKey::generate(MlKemParams::MlKem768));
+        assert!(cms_decrypt(&hex(KEMRI_MLKEM768), &other).is_err());
+    }
+
+    #[test]
+    fn kemri_round_trip()
+    {
+        let data = b"enveloped data for multiple recipients";
+        let keys: Vec<PrivateKey> = [MlKemParams::MlKem512, MlKemParams::MlKem1024].iter()
+                                        .map(|p| PrivateKey::MlKem(MlKemPrivateKey::generate(*p)))
+                                        .collect();
+        let certs: Vec<Certificate> = keys.iter().map(recipient).collect();
+        let der = cms_encrypt(data, &certs).unwrap();
+        for key in &keys {
+            assert_eq!(cms_decrypt(&der, key).unwrap(), data);
+        }
+        let other = PrivateKey::MlKem(MlKemPrivateKey::generate(MlKemParams::MlKem768));
+        assert!(cms_decrypt(&der, &other).is_err());
+    }
+}
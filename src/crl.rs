@@ -0,0 +1,480 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! X.509v2 certificate revocation lists (RFC 5280, section 5).
+
+use crate::asn1::*;
+use crate::keys::{PrivateKey, PublicKey};
+use crate::oid::*;
+use crate::signature::SignatureScheme;
+use crate::validity::encode_time;
+use crate::x509::{Extension, MAX_SERIAL_LEN, parse_extensions};
+
+/// CRLReason codes with the names accepted by --reason (RFC 5280, 5.3.1)
+const REASONS: &[(&str, u8)] = &[
+    ("unspecified",            0),
+    ("key-compromise",         1),
+    ("ca-compromise",          2),
+    ("affiliation-changed",    3),
+    ("superseded",             4),
+    ("cessation-of-operation", 5),
+    ("certificate-hold",       6),
+    ("remove-from-crl",        8),
+    ("privilege-withdrawn",    9),
+    ("aa-compromise",         10),
+];
+
+/// A revokedCertificates entry
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RevokedCert {
+    /// userCertificate serial number magnitude
+    pub serial: Vec<u8>,
+    /// revocationDate
+    pub date: i64,
+    /// CRLReason code, None if unspecified
+    pub reason: Option<u8>,
+}
+
+/// The fields of a CRL to be signed
+#[derive(Clone, Debug)]
+pub struct TbsCertList {
+    /// DER encoded issuer Name
+    pub issuer: Vec<u8>,
+    /// thisUpdate
+    pub this_update: i64,
+    /// nextUpdate
+    pub next_update: i64,
+    /// revoked certificates
+    pub revoked: Vec<RevokedCert>,
+    /// crlExtensions
+    pub extensions: Vec<Extension>,
+}
+
+/// A decoded X.509 CRL
+#[derive(Clone, Debug)]
+pub struct Crl {
+    /// complete DER encoding
+    pub der: Vec<u8>,
+    /// DER encoded tbsCertList
+    pub tbs: Vec<u8>,
+    /// DER encoded issuer Name
+    pub issuer: Vec<u8>,
+    /// thisUpdate
+    pub this_update: i64,
+    /// nextUpdate, if any
+    pub next_update: Option<i64>,
+    /// revoked certificates
+    pub revoked: Vec<RevokedCert>,
+    /// crlExtensions
+    pub extensions: Vec<Extension>,
+    /// outer signature algorithm
+    pub scheme: SignatureScheme,
+    /// signature value
+    pub signature: Vec<u8>,
+}
+
+//
+// Parse a revocation reason given by name.
+//
+pub fn parse_reason(name: &str) -> Result<u8, String>
+{
+    return REASONS.iter().find(|(n, _)| *n == name)
+                  .map(|(_, code)| *code)
+                  .ok_or(format!("invalid revocation reason '{}'", name));
+}
+
+//
+// Get the name of a CRLReason code.
+//
+pub fn reason_name(code: u8) -> String
+{
+    match REASONS.iter().find(|(_, c)| *c == code) {
+        Some((name, _)) => { name.to_string() }
+        None => { format!("reason{}", code) }
+    }
+}
+
+//
+// Strip the leading zero octets of INTEGER content octets.
+//
+fn magnitude(content: &[u8]) -> Vec<u8>
+{
+    let skip = content.iter().take_while(|b| **b == 0).count().min(content.len().saturating_sub(1));
+    return content[skip..].to_vec();
+}
+
+//
+// Increment a CRLNumber, given as magnitude, by one.
+//
+pub fn next_crl_number(number: &[u8]) -> Result<Vec<u8>, String>
+{
+    let mut next = magnitude(number);
+    let mut carry = true;
+    for octet in next.iter_mut().rev() {
+        let (sum, overflow) = octet.overflowing_add(carry as u8);
+        *octet = sum;
+        carry = overflow;
+    }
+    if carry || next.is_empty() {
+        next.insert(0, 1);
+    }
+    if next.len() > MAX_SERIAL_LEN || (next.len() == MAX_SERIAL_LEN && next[0] & 0x80 != 0) {
+        return Err("CRL number exceeds 20 octets".to_string());
+    }
+    return Ok(next);
+}
+
+//
+// Build a cRLNumber extension (RFC 5280, 5.2.3).
+//
+pub fn crl_number(number: &[u8]) -> Extension
+{
+    return Extension { oid: OID_CRL_NUMBER.to_string(), critical: false,
+                       value: asn1_integer(number) };
+}
+
+//
+// Build a critical deltaCRLIndicator extension referencing the cRLNumber
+// of the base CRL (RFC 5280, 5.2.4).
+//
+pub fn delta_crl_indicator(base_number: &[u8]) -> Extension
+{
+    return Extension { oid: OID_DELTA_CRL_INDICATOR.to_string(), critical: true,
+                       value: asn1_integer(base_number) };
+}
+
+//
+// Decode the INTEGER value of a cRLNumber or deltaCRLIndicator extension.
+//
+fn parse_number(ext: &Extension) -> Result<Vec<u8>, String>
+{
+    let mut parser = Asn1Parser::new(&ext.value);
+    let number = parser.expect(ASN1_INTEGER)?.to_unsigned()?.to_vec();
+    parser.finish()?;
+    return Ok(magnitude(&number));
+}
+
+impl RevokedCert {
+    //
+    // Encode as a revokedCertificates entry with a reasonCode entry
+    // extension, omitted if unspecified (RFC 5280, 5.3.1).
+    //
+    fn encode(&self) -> Result<Vec<u8>, String>
+    {
+        let mut items = vec![asn1_integer(&self.serial), encode_time(self.date)?];
+        if let Some(code) = self.reason && code != 0 {
+            let ext = Extension { oid: OID_CRL_REASON_CODE.to_string(), critical: false,
+                                  value: asn1_simple_object(ASN1_ENUMERATED, &[code]) };
+            items.push(asn1_sequence(&[ext.encode()?]));
+        }
+        return Ok(asn1_sequence(&items));
+    }
+
+    //
+    // Decode a revokedCertificates entry.
+    //
+    fn from_asn1(obj: &Asn1Object) -> Result<RevokedCert, String>
+    {
+        if obj.tag != ASN1_SEQUENCE {
+            return Err("invalid revokedCertificates entry".to_string());
+        }
+        let mut p = obj.parser();
+        let serial = magnitude(p.expect(ASN1_INTEGER)?.integer_bytes()?);
+        let date = p.next_object()?.ok_or("missing revocationDate")?.to_time()?;
+        let mut reason = None;
+        if let Some(exts) = p.optional(ASN1_SEQUENCE)? {
+            for ext in parse_extensions(&exts)? {
+                if ext.oid == OID_CRL_REASON_CODE {
+                    let mut parser = Asn1Parser::new(&ext.value);
+                    let code = parser.expect(ASN1_ENUMERATED)?.to_u64()?;
+                    parser.finish()?;
+                    reason = Some(u8::try_from(code).map_err(|_| "invalid reasonCode")?);
+                } else if ext.critical {
+                    return Err(format!("unsupported critical CRL entry extension {}",
+                                       oid_display(&ext.oid)));
+                }
+            }
+        }
+        p.finish()?;
+        return Ok(RevokedCert { serial, date, reason });
+    }
+}
+
+impl TbsCertList {
+    //
+    // Encode as an X.509v2 tbsCertList, omitting an empty revocation list.
+    //
+    pub fn encode(&self, scheme: SignatureScheme) -> Result<Vec<u8>, String>
+    {
+        let mut items = vec![asn1_integer_from_u64(1), scheme.to_algorithm_identifier(),
+                             self.issuer.clone(), encode_time(self.this_update)?,
+                             encode_time(self.next_update)?];
+        if !self.revoked.is_empty() {
+            let revoked = self.revoked.iter().map(|r| r.encode()).collect::<Result<Vec<_>, _>>()?;
+            items.push(asn1_sequence(&revoked));
+        }
+        if !self.extensions.is_empty() {
+            let exts = self.extensions.iter().map(|e| e.encode()).collect::<Result<Vec<_>, _>>()?;
+            items.push(asn1_wrap(ASN1_CONTEXT_C_0, &[&asn1_sequence(&exts)]));
+        }
+        return Ok(asn1_sequence(&items));
+    }
+
+    //
+    // Sign with the issuer's private key and return the decoded CRL.
+    //
+    pub fn sign(&self, key: &PrivateKey, scheme: SignatureScheme) -> Result<Crl, String>
+    {
+        let tbs = self.encode(scheme)?;
+        let signature = key.sign(scheme, &tbs)?;
+        let der = asn1_sequence(&[tbs, scheme.to_algorithm_identifier(), asn1_bitstring(&signature)]);
+        return Crl::from_der(&der);
+    }
+}
+
+impl Crl {
+    //
+    // Decode a DER encoded CRL.
+    //
+    pub fn from_der(der: &[u8]) -> Result<Crl, String>
+    {
+        let obj = asn1_parse(der)?;
+        if obj.tag != ASN1_SEQUENCE {
+            return Err("not a CertificateList".to_string());
+        }
+        let mut parser = obj.parser();
+        let tbs = parser.expect(ASN1_SEQUENCE)?;
+        let scheme = SignatureScheme::from_algorithm_identifier(&parser.expect(ASN1_SEQUENCE)?)?;
+        let signature = parser.expect(ASN1_BIT_STRING)?.to_bitstring_octets()?.to_vec();
+        parser.finish()?;
+
+        let mut p = tbs.parser();
+        let version = match p.optional(ASN1_INTEGER)? {
+            Some(v) => { v.to_u64()? + 1 }
+            None => { 1 }
+        };
+        if version > 2 {
+            return Err(format!("unsupported CRL version {}", version));
+        }
+        let inner = SignatureScheme::from_algorithm_identifier(&p.expect(ASN1_SEQUENCE)?)?;
+        if inner != scheme {
+            return Err("inconsistent signature algorithms".to_string());
+        }
+        let issuer = p.expect(ASN1_SEQUENCE)?.raw.to_vec();
+        let this_update = p.next_object()?.ok_or("missing thisUpdate")?.to_time()?;
+        let next_update = match p.peek_tag() {
+            Some(ASN1_UTCTIME) | Some(ASN1_GENERALIZEDTIME) => {
+                Some(p.next_object()?.ok_or("missing nextUpdate")?.to_time()?)
+            }
+            _ => { None }
+        };
+        let mut revoked = Vec::new();
+        if let Some(list) = p.optional(ASN1_SEQUENCE)? {
+            let mut entries = list.parser();
+            while let Some(entry) = entries.next_object()? {
+                revoked.push(RevokedCert::from_asn1(&entry)?);
+            }
+        }
+        let extensions = match p.optional(ASN1_CONTEXT_C_0)? {
+            Some(exts) => {
+                if version != 2 {
+                    return Err("extensions require an X.509v2 CRL".to_string());
+                }
+                parse_extensions(&exts.parser().expect(ASN1_SEQUENCE)?)?
+            }
+            None => { Vec::new() }
+        };
+        p.finish()?;
+
+        return Ok(Crl {
+            der: der.to_vec(), tbs: tbs.raw.to_vec(), issuer, this_update, next_update,
+            revoked, extensions, scheme, signature,
+        });
+    }
+
+    //
+    // Look up an extension by its OID.
+    //
+    pub fn extension(&self, oid: &str) -> Option<&Extension>
+    {
+        return self.extensions.iter().find(|e| e.oid == oid);
+    }
+
+    //
+    // Get the cRLNumber of the CRL, if any.
+    //
+    pub fn number(&self) -> Result<Option<Vec<u8>>, String>
+    {
+        return self.extension(OID_CRL_NUMBER).map(parse_number).transpose();
+    }
+
+    //
+    // Get the cRLNumber of the base CRL if this is a delta CRL.
+    //
+    pub fn base_number(&self) -> Result<Option<Vec<u8>>, String>
+    {
+        return self.extension(OID_DELTA_CRL_INDICATOR).map(parse_number).transpose();
+    }
+
+    //
+    // Check the CRL signature with the issuer's public key.
+    //
+    pub fn verify_signature(&self, issuer: &PublicKey) -> bool
+    {
+        return issuer.verify(self.scheme, &self.tbs, &self.signature);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::composite::{CompositeParams, CompositePrivateKey, TradPublicKey};
+    use crate::crypto::eddsa::{EdCurve, EdPrivateKey};
+    use crate::crypto::hash::HashAlgorithm;
+    use crate::dn::Dn;
+    use crate::extensions::{custom_extensions, merge_extensions};
+
+    fn tbs_cert_list(extensions: Vec<Extension>) -> TbsCertList
+    {
+        let revoked = vec![
+            RevokedCert { serial: vec![0x01, 0x02], date: 1760000000, reason: Some(1) },
+            RevokedCert { serial: vec![0x7f], date: 1760000001, reason: None },
+        ];
+        return TbsCertList { issuer: Dn::parse("C=CH, CN=Test CA").unwrap().to_der(),
+                             this_update: 1760000100, next_update: 1761296100, revoked,
+                             extensions };
+    }
+
+    #[test]
+    fn sign_and_parse()
+    {
+        let key = PrivateKey::Eddsa(EdPrivateKey::generate(EdCurve::Ed25519));
+        let tbs = tbs_cert_list(vec![crl_number(&[0x05]), delta_crl_indicator(&[0x04])]);
+        let crl = tbs.sign(&key, SignatureScheme::Ed25519).unwrap();
+
+        assert!(crl.verify_signature(&key.public_key()));
+        assert_eq!(crl.issuer, tbs.issuer);
+        assert_eq!(crl.this_update, tbs.this_update);
+        assert_eq!(crl.next_update, Some(tbs.next_update));
+        assert_eq!(crl.revoked, tbs.revoked);
+        assert_eq!(crl.number().unwrap(), Some(vec![0x05]));
+        assert_eq!(crl.base_number().unwrap(), Some(vec![0x04]));
+        assert!(crl.extension(OID_DELTA_CRL_INDICATOR).unwrap().critical);
+
+        let mut tampered = crl.clone();
+        tampered.tbs[20] ^= 0x01;
+        assert!(!tampered.verify_signature(&key.public_key()));
+    }
+
+    // Each component of a composite CRL signature must verify on its own over
+    // M' = Prefix || Label || len(ctx) || ctx || SHA512(tbsCertList).
+    #[test]
+    fn composite_signature_components()
+    {
+        let composites = [
+            (CompositeParams::MlDsa44Ed25519, "COMPSIG-MLDSA44-Ed25519-SHA512"),
+            (CompositeParams::MlDsa65EcdsaP256, "COMPSIG-MLDSA65-ECDSA-P256-SHA512"),
+        ];
+        for (params, label) in composites {
+            let key = CompositePrivateKey::generate(params).unwrap();
+            let public = key.public_key();
+            let key = PrivateKey::Composite(key);
+            let crl = tbs_cert_list(vec![crl_number(&[0x01])])
+                          .sign(&key, SignatureScheme::Composite(params)).unwrap();
+            assert!(crl.verify_signature(&key.public_key()));
+
+            let mut m = b"CompositeAlgorithmSignatures2025".to_vec();
+            m.extend_from_slice(label.as_bytes());
+            m.push(0);
+            m.extend_from_slice(&HashAlgorithm::Sha512.hash(&crl.tbs));
+            let (mldsa_sig, trad_sig) = crl.signature.split_at(params.mldsa().signature_len());
+            assert!(public.mldsa.verify_with_context(&m, label.as_bytes(), mldsa_sig));
+            match &public.trad {
+                TradPublicKey::Eddsa(trad) => { assert!(trad.verify(&m, trad_sig)); }
+                TradPublicKey::Ecdsa(trad) => {
+                    assert!(trad.verify(HashAlgorithm::Sha256, &m, trad_sig));
+                }
+                TradPublicKey::Rsa(_) => { unreachable!(); }
+            }
+
+            // a broken traditional component invalidates the composite
+            let mut forged = crl.clone();
+            let last = forged.signature.len() - 1;
+            forged.signature[last] ^= 0x01;
+            assert!(!forged.verify_signature(&key.public_key()));
+        }
+    }
+
+    #[test]
+    fn critical_custom_extension()
+    {
+        let key = PrivateKey::Eddsa(EdPrivateKey::generate(EdCurve::Ed25519));
+        let mut extensions = vec![crl_number(&[0x01])];
+        merge_extensions(&mut extensions,
+                         custom_extensions(&[], Some("1.3.6.1.4.1.36906.99")).unwrap()).unwrap();
+        let crl = tbs_cert_list(extensions).sign(&key, SignatureScheme::Ed25519).unwrap();
+
+        let ext = crl.extension("1.3.6.1.4.1.36906.99").unwrap();
+        assert!(ext.critical);
+        assert_eq!(ext.value, asn1_null());
+    }
+
+    #[test]
+    fn unspecified_reason_omitted()
+    {
+        let entry = RevokedCert { serial: vec![0x03], date: 1760000000, reason: Some(0) };
+        let der = entry.encode().unwrap();
+        assert_eq!(der.len(), 2 + 3 + 15);
+        let parsed = RevokedCert::from_asn1(&asn1_parse(&der).unwrap()).unwrap();
+        assert_eq!(parsed.reason, None);
+    }
+
+    #[test]
+    fn empty_revocation_list()
+    {
+        let mut tbs = tbs_cert_list(Vec::new());
+        tbs.revoked.clear();
+        let der = tbs.encode(SignatureScheme::Ed25519).unwrap();
+        let obj = asn1_parse(&der).unwrap();
+        let mut parser = obj.parser();
+        let mut count = 0;
+        while parser.next_object().unwrap().is_some() {
+            count += 1;
+        }
+        // version, signature, issuer, thisUpdate and nextUpdate only
+        assert_eq!(count, 5);
+    }
+
+    #[test]
+    fn crl_numbers()
+    {
+        assert_eq!(next_crl_number(&[]).unwrap(), vec![0x01]);
+        assert_eq!(next_crl_number(&[0x00, 0x41]).unwrap(), vec![0x42]);
+        assert_eq!(next_crl_number(&[0x7f]).unwrap(), vec![0x80]);
+        assert_eq!(next_crl_number(&[0x01, 0xff, 0xff]).unwrap(), vec![0x02, 0x00, 0x00]);
+        assert_eq!(next_crl_number(&[0xff]).unwrap(), vec![0x01, 0x00]);
+
+        let mut max = vec![0xff; MAX_SERIAL_LEN];
+        max[0] = 0x7f;
+        assert!(next_crl_number(&max).is_err());
+    }
+
+    #[test]
+    fn reasons()
+    {
+        assert_eq!(parse_reason("key-compromise").unwrap(), 1);
+        assert_eq!(parse_reason("certificate-hold").unwrap(), 6);
+        assert!(parse_reason("keyCompromise").is_err());
+        assert_eq!(reason_name(5), "cessation-of-operation");
+        assert_eq!(reason_name(7), "reason7");
+    }
+}
@@ -0,0 +1,291 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! AES block cipher (FIPS 197) in CBC mode with PKCS#7 padding and the AES
+//! key wrap algorithm (RFC 3394).
+
+use std::sync::OnceLock;
+
+/// AES block size in octets
+pub const AES_BLOCK_SIZE: usize = 16;
+
+// default initial value of the AES key wrap
+const KEY_WRAP_IV: [u8; 8] = [0xa6; 8];
+
+/// AES cipher with expanded round keys
+pub struct Aes {
+    round_keys: Vec<[u8; 16]>,
+}
+
+struct SBoxes {
+    sbox: [u8; 256],
+    inv_sbox: [u8; 256],
+}
+
+//
+// Multiply in GF(2^8) modulo x^8 + x^4 + x^3 + x + 1.
+//
+fn gmul(mut a: u8, mut b: u8) -> u8
+{
+    let mut p = 0;
+    while b != 0 {
+        if b & 1 != 0 {
+            p ^= a;
+        }
+        a = (a << 1) ^ if a & 0x80 != 0 { 0x1b } else { 0 };
+        b >>= 1;
+    }
+    return p;
+}
+
+//
+// Compute the S-box from the multiplicative inverse and affine transform.
+//
+fn sboxes() -> &'static SBoxes
+{
+    static SBOXES: OnceLock<SBoxes> = OnceLock::new();
+    return SBOXES.get_or_init(|| {
+        let mut sbox = [0u8; 256];
+        let mut inv_sbox = [0u8; 256];
+        for x in 0..256usize {
+            let inv = (1..256usize).find(|y| gmul(x as u8, *y as u8) == 1).unwrap_or(0) as u8;
+            let s = inv ^ inv.rotate_left(1) ^ inv.rotate_left(2) ^ inv.rotate_left(3) ^
+                    inv.rotate_left(4) ^ 0x63;
+            sbox[x] = s;
+            inv_sbox[s as usize] = x as u8;
+        }
+        SBoxes { sbox, inv_sbox }
+    });
+}
+
+fn add_round_key(state: &mut [u8; 16], key: &[u8; 16])
+{
+    for (s, k) in state.iter_mut().zip(key) {
+        *s ^= k;
+    }
+}
+
+//
+// Combined ShiftRows on the column-major state.
+//
+fn shift_rows(state: &mut [u8; 16], inverse: bool)
+{
+    let old = *state;
+    for c in 0..4 {
+        for r in 1..4 {
+            let src = if inverse { (c + 4 - r) % 4 } else { (c + r) % 4 };
+            state[4 * c + r] = old[4 * src + r];
+        }
+    }
+}
+
+fn mix_columns(state: &mut [u8; 16], inverse: bool)
+{
+    let m: [u8; 4] = if inverse { [14, 11, 13, 9] } else { [2, 3, 1, 1] };
+    for c in 0..4 {
+        let col = [state[4 * c], state[4 * c + 1], state[4 * c + 2], state[4 * c + 3]];
+        for r in 0..4 {
+            state[4 * c + r] = gmul(col[r], m[0]) ^ gmul(col[(r + 1) % 4], m[1]) ^
+                               gmul(col[(r + 2) % 4], m[2]) ^ gmul(col[(r + 3) % 4], m[3]);
+        }
+    }
+}
+
+impl Aes {
+    //
+    // Expand a 128, 192 or 256 bit key.
+    //
+    pub fn new(key: &[u8]) -> Result<Aes, String>
+    {
+        let nk = match key.len() {
+            16 | 24 | 32 => { key.len() / 4 }
+            _ => { return Err(format!("invalid AES key length of {} octets", key.len())); }
+        };
+        let rounds = nk + 6;
+        let sbox = &sboxes().sbox;
+        let mut w: Vec<[u8; 4]> = key.chunks(4).map(|c| [c[0], c[1], c[2], c[3]]).collect();
+        let mut rcon = 1u8;
+        for i in nk..4 * (rounds + 1) {
+            let mut t = w[i - 1];
+            if i % nk == 0 {
+                t = [sbox[t[1] as usize] ^ rcon, sbox[t[2] as usize], sbox[t[3] as usize],
+                     sbox[t[0] as usize]];
+                rcon = gmul(rcon, 2);
+            } else if nk > 6 && i % nk == 4 {
+                t = t.map(|b| sbox[b as usize]);
+            }
+            let prev = w[i - nk];
+            w.push([prev[0] ^ t[0], prev[1] ^ t[1], prev[2] ^ t[2], prev[3] ^ t[3]]);
+        }
+        let round_keys = w.chunks(4).map(|c| {
+            let mut k = [0u8; 16];
+            for (j, word) in c.iter().enumerate() {
+                k[4 * j..4 * j + 4].copy_from_slice(word);
+            }
+            k
+        }).collect();
+        Ok(Aes { round_keys })
+    }
+
+    pub fn encrypt_block(&self, block: &mut [u8; 16])
+    {
+        let sbox = &sboxes().sbox;
+        let rounds = self.round_keys.len() - 1;
+        add_round_key(block, &self.round_keys[0]);
+        for round in 1..=rounds {
+            for b in block.iter_mut() {
+                *b = sbox[*b as usize];
+            }
+            shift_rows(block, false);
+            if round != rounds {
+                mix_columns(block, false);
+            }
+            add_round_key(block, &self.round_keys[round]);
+        }
+    }
+
+    pub fn decrypt_block(&self, block: &mut [u8; 16])
+    {
+        let inv_sbox = &sboxes().inv_sbox;
+        let rounds = self.round_keys.len() - 1;
+        add_round_key(block, &self.round_keys[rounds]);
+        for round in (0..rounds).rev() {
+            shift_rows(block, true);
+            for b in block.iter_mut() {
+                *b = inv_sbox[*b as usize];
+            }
+            add_round_key(block, &self.round_keys[round]);
+            if round != 0 {
+                mix_columns(block, true);
+            }
+        }
+    }
+}
+
+//
+// Encrypt data in CBC mode after applying PKCS#7 padding.
+//
+pub fn aes_cbc_encrypt(key: &[u8], iv: &[u8], data: &[u8]) -> Result<Vec<u8>, String>
+{
+    let aes = Aes::new(key)?;
+    if iv.len() != AES_BLOCK_SIZE {
+        return Err("invalid AES-CBC IV length".to_string());
+    }
+    let pad = AES_BLOCK_SIZE - data.len() % AES_BLOCK_SIZE;
+    let mut padded = data.to_vec();
+    padded.resize(data.len() + pad, pad as u8);
+
+    let mut out = Vec::with_capacity(padded.len());
+    let mut prev: [u8; 16] = iv.try_into().unwrap();
+    for chunk in padded.chunks(AES_BLOCK_SIZE) {
+        for (p, c) in prev.iter_mut().zip(chunk) {
+            *p ^= c;
+        }
+        aes.encrypt_block(&mut prev);
+        out.extend_from_slice(&prev);
+    }
+    return Ok(out);
+}
+
+//
+// Decrypt data in CBC mode and remove the PKCS#7 padding.
+//
+pub fn aes_cbc_decrypt(key: &[u8], iv: &[u8], data: &[u8]) -> Result<Vec<u8>, String>
+{
+    let aes = Aes::new(key)?;
+    if iv.len() != AES_BLOCK_SIZE {
+        return Err("invalid AES-CBC IV length".to_string());
+    }
+    if data.is_empty() || !data.len().is_multiple_of(AES_BLOCK_SIZE) {
+        return Err("invalid AES-CBC ciphertext length".to_string());
+    }
+    let mut out = Vec::with_capacity(data.len());
+    let mut prev: [u8; 16] = iv.try_into().unwrap();
+    for chunk in data.chunks(AES_BLOCK_SIZE) {
+        let mut block: [u8; 16] = chunk.try_into().unwrap();
+        aes.decrypt_block(&mut block);
+        for (b, p) in block.iter_mut().zip(&prev) {
+            *b ^= p;
+        }
+        out.extend_from_slice(&block);
+        prev = chunk.try_into().unwrap();
+    }
+    let pad = out[out.len() - 1] as usize;
+    if pad == 0 || pad > AES_BLOCK_SIZE || out[out.len() - pad..].iter().any(|b| *b as usize != pad) {
+        return Err("invalid padding".to_string());
+    }
+    out.truncate(out.len() - pad);
+    return Ok(out);
+}
+
+//
+// Wrap a key being a multiple of 64 bits with a key encryption key.
+//
+pub fn aes_key_wrap(kek: &[u8], key: &[u8]) -> Result<Vec<u8>, String>
+{
+    let aes = Aes::new(kek)?;
+    if key.len() < 16 || !key.len().is_multiple_of(8) {
+        return Err("invalid length of key to wrap".to_string());
+    }
+    let n = key.len() / 8;
+    let mut a = KEY_WRAP_IV;
+    let mut r: Vec<[u8; 8]> = key.chunks(8).map(|c| c.try_into().unwrap()).collect();
+    for j in 0..6 {
+        for i in 0..n {
+            let mut block = [0u8; 16];
+            block[..8].copy_from_slice(&a);
+            block[8..].copy_from_slice(&r[i]);
+            aes.encrypt_block(&mut block);
+            let t = ((n * j + i + 1) as u64).to_be_bytes();
+            for k in 0..8 {
+                a[k] = block[k] ^ t[k];
+            }
+            r[i].copy_from_slice(&block[8..]);
+        }
+    }
+    let mut out = a.to_vec();
+    for block in &r {
+        out.extend_from_slice(block);
+    }
+    return Ok(out);
+}
+
+//
+// Unwrap a key and check its integrity.
+//
+pub fn aes_key_unwrap(kek: &[u8], wrapped: &[u8]) -> Result<Vec<u8>, String>
+{
+    let aes = Aes::new(kek)?;
+    if wrapped.len() < 24 || !wrapped.len().is_multiple_of(8) {
+        return Err("invalid length of wrapped key".to_string());
+    }
+    let n = wrapped.len() / 8 - 1;
+    let mut a: [u8; 8] = wrapped[..8].try_into().unwrap();
+    let mut r: Vec<[u8; 8]> = wrapped[8..].chunks(8).map(|c| c.try_into().unwrap()).collect();
+    for j in (0..6).rev() {
+        for i in (0..n).rev() {
+            let t = ((n * j + i + 1) as u64).to_be_bytes();
+            let mut block = [0u8; 16];
+            for k in 0..8 {
+                block[k] = a[k] ^ t[k];
+            }
+            block[8..].copy_from_slice(&r[i]);
+            aes.decrypt_block(&mut block);
+            a.copy_from_slice(&block[..8]);
+            r[i].copy_from_slice(&block[8..]);
+        }
+    }
+    if a != KEY_WRAP_IV {
+        return Err("key unwrap integrity check failed".to_string());
+    }
+    return Ok(r.concat());
+}
@@ -0,0 +1,999 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! Arbitrary precision unsigned integers with a Montgomery arithmetic
+//! context. Montgomery multiplication and exponentiation run in time
+//! depending only on the limb lengths of their operands, so that private
+//! exponents and secret field elements are not leaked through timing.
+
+use std::cmp::Ordering;
+use std::sync::OnceLock;
+use crate::crypto::rng::rng_fill;
+
+const LIMB_BITS: usize = 64;
+const B: u128 = 1 << 64;
+
+/// Unsigned integer stored as little-endian 64 bit limbs
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct BigUint {
+    limbs: Vec<u64>,
+}
+
+//
+// Constant-time selection of a or b depending on a 0/1 choice bit.
+//
+#[inline]
+fn ct_select(choice: u64, a: u64, b: u64) -> u64
+{
+    let mask = 0u64.wrapping_sub(choice);
+    return (a & !mask) | (b & mask);
+}
+
+impl BigUint {
+    pub fn zero() -> BigUint
+    {
+        BigUint { limbs: Vec::new() }
+    }
+
+    pub fn one() -> BigUint
+    {
+        BigUint::from_u64(1)
+    }
+
+    pub fn from_u64(v: u64) -> BigUint
+    {
+        let mut n = BigUint { limbs: vec![v] };
+        n.normalize();
+        return n;
+    }
+
+    //
+    // Create an integer from little-endian limbs.
+    //
+    pub fn from_limbs(limbs: &[u64]) -> BigUint
+    {
+        let mut n = BigUint { limbs: limbs.to_vec() };
+        n.normalize();
+        return n;
+    }
+
+    //
+    // Access the little-endian limbs of the integer.
+    //
+    pub fn limbs(&self) -> &[u64]
+    {
+        return &self.limbs;
+    }
+
+    //
+    // Return the limbs zero-extended to the given length.
+    //
+    pub fn limbs_padded(&self, len: usize) -> Vec<u64>
+    {
+        let mut limbs = self.limbs.clone();
+        limbs.resize(len.max(limbs.len()), 0);
+        return limbs;
+    }
+
+    //
+    // Create an integer from big-endian octets.
+    //
+    pub fn from_bytes_be(bytes: &[u8]) -> BigUint
+    {
+        let mut limbs = Vec::with_capacity(bytes.len().div_ceil(8));
+        for chunk in bytes.rchunks(8) {
+            let mut limb = 0u64;
+            for b in chunk {
+                limb = (limb << 8) | *b as u64;
+            }
+            limbs.push(limb);
+        }
+        let mut n = BigUint { limbs };
+        n.normalize();
+        return n;
+    }
+
+    //
+    // Create an integer from little-endian octets.
+    //
+    pub fn from_bytes_le(bytes: &[u8]) -> BigUint
+    {
+        let be: Vec<u8> = bytes.iter().rev().copied().collect();
+        return BigUint::from_bytes_be(&be);
+    }
+
+    //
+    // Minimal big-endian octet representation, empty for zero.
+    //
+    pub fn to_bytes_be(&self) -> Vec<u8>
+    {
+        let len = self.bits().div_ceil(8);
+        return self.to_bytes_be_padded(len);
+    }
+
+    //
+    // Big-endian octet representation left-padded with zeros to len octets.
+    //
+    pub fn to_bytes_be_padded(&self, len: usize) -> Vec<u8>
+    {
+        let mut out = vec![0u8; len];
+        for i in 0..len {
+            let limb = i / 8;
+            if limb < self.limbs.len() {
+                out[len - 1 - i] = (self.limbs[limb] >> (8 * (i % 8))) as u8;
+            }
+        }
+        return out;
+    }
+
+    //
+    // Little-endian octet representation padded with zeros to len octets.
+    //
+    pub fn to_bytes_le_padded(&self, len: usize) -> Vec<u8>
+    {
+        let mut out = self.to_bytes_be_padded(len);
+        out.reverse();
+        return out;
+    }
+
+    fn normalize(&mut self)
+    {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+
+    pub fn is_zero(&self) -> bool
+    {
+        return self.limbs.is_empty();
+    }
+
+    pub fn is_odd(&self) -> bool
+    {
+        return self.limbs.first().is_some_and(|l| l & 1 == 1);
+    }
+
+    pub fn is_one(&self) -> bool
+    {
+        return self.limbs.len() == 1 && self.limbs[0] == 1;
+    }
+
+    //
+    // Number of significant bits.
+    //
+    pub fn bits(&self) -> usize
+    {
+        match self.limbs.last() {
+            Some(top) => { self.limbs.len() * LIMB_BITS - top.leading_zeros() as usize }
+            None      => { 0 }
+        }
+    }
+
+    //
+    // Value of bit i.
+    //
+    pub fn bit(&self, i: usize) -> bool
+    {
+        let limb = i / LIMB_BITS;
+        return limb < self.limbs.len() && (self.limbs[limb] >> (i % LIMB_BITS)) & 1 == 1;
+    }
+
+    //
+    // Set bit i.
+    //
+    pub fn set_bit(&mut self, i: usize)
+    {
+        let limb = i / LIMB_BITS;
+        if limb >= self.limbs.len() {
+            self.limbs.resize(limb + 1, 0);
+        }
+        self.limbs[limb] |= 1 << (i % LIMB_BITS);
+    }
+
+    //
+    // Value of the lowest 64 bits.
+    //
+    pub fn low_u64(&self) -> u64
+    {
+        return self.limbs.first().copied().unwrap_or(0);
+    }
+
+    pub fn add(&self, other: &BigUint) -> BigUint
+    {
+        let len = self.limbs.len().max(other.limbs.len());
+        let mut limbs = Vec::with_capacity(len + 1);
+        let mut carry = 0u128;
+
+        for i in 0..len {
+            let a = *self.limbs.get(i).unwrap_or(&0) as u128;
+            let b = *other.limbs.get(i).unwrap_or(&0) as u128;
+            let s = a + b + carry;
+            limbs.push(s as u64);
+            carry = s >> 64;
+        }
+        limbs.push(carry as u64);
+        let mut n = BigUint { limbs };
+        n.normalize();
+        return n;
+    }
+
+    pub fn add_u64(&self, v: u64) -> BigUint
+    {
+        return self.add(&BigUint::from_u64(v));
+    }
+
+    //
+    // Subtraction, other must not be larger than self.
+    //
+    pub fn sub(&self, other: &BigUint) -> BigUint
+    {
+        assert!(*self >= *other, "BigUint subtraction underflow");
+        let mut limbs = self.limbs.clone();
+        let mut borrow = 0u64;
+
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let b = *other.limbs.get(i).unwrap_or(&0);
+            let (d1, o1) = limb.overflowing_sub(b);
+            let (d2, o2) = d1.overflowing_sub(borrow);
+            *limb = d2;
+            borrow = (o1 | o2) as u64;
+        }
+        let mut n = BigUint { limbs };
+        n.normalize();
+        return n;
+    }
+
+    pub fn sub_u64(&self, v: u64) -> BigUint
+    {
+        return self.sub(&BigUint::from_u64(v));
+    }
+
+    pub fn mul(&self, other: &BigUint) -> BigUint
+    {
+        if self.is_zero() || other.is_zero() {
+            return BigUint::zero();
+        }
+        let mut limbs = vec![0u64; self.limbs.len() + other.limbs.len()];
+
+        for (i, a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u128;
+            for (j, b) in other.limbs.iter().enumerate() {
+                let t = (*a as u128) * (*b as u128) + limbs[i + j] as u128 + carry;
+                limbs[i + j] = t as u64;
+                carry = t >> 64;
+            }
+            limbs[i + other.limbs.len()] = carry as u64;
+        }
+        let mut n = BigUint { limbs };
+        n.normalize();
+        return n;
+    }
+
+    pub fn mul_u64(&self, v: u64) -> BigUint
+    {
+        return self.mul(&BigUint::from_u64(v));
+    }
+
+    pub fn shl(&self, n: usize) -> BigUint
+    {
+        if self.is_zero() {
+            return BigUint::zero();
+        }
+        let (words, bits) = (n / LIMB_BITS, n % LIMB_BITS);
+        let mut limbs = vec![0u64; words];
+        let mut carry = 0u64;
+
+        for limb in &self.limbs {
+            if bits == 0 {
+                limbs.push(*limb);
+            } else {
+                limbs.push((limb << bits) | carry);
+                carry = limb >> (LIMB_BITS - bits);
+            }
+        }
+        limbs.push(carry);
+        let mut n = BigUint { limbs };
+        n.normalize();
+        return n;
+    }
+
+    pub fn shr(&self, n: usize) -> BigUint
+    {
+        let (words, bits) = (n / LIMB_BITS, n % LIMB_BITS);
+        if words >= self.limbs.len() {
+            return BigUint::zero();
+        }
+        let src = &self.limbs[words..];
+        let mut limbs = Vec::with_capacity(src.len());
+
+        for i in 0..src.len() {
+            if bits == 0 {
+                limbs.push(src[i]);
+            } else {
+                let high = if i + 1 < src.len() { src[i + 1] << (LIMB_BITS - bits) } else { 0 };
+                limbs.push((src[i] >> bits) | high);
+            }
+        }
+        let mut n = BigUint { limbs };
+        n.normalize();
+        return n;
+    }
+
+    //
+    // Division by a single limb, returning quotient and remainder.
+    //
+    pub fn divrem_u64(&self, d: u64) -> (BigUint, u64)
+    {
+        assert!(d != 0, "division by zero");
+        let mut limbs = vec![0u64; self.limbs.len()];
+        let mut rem = 0u128;
+
+        for i in (0..self.limbs.len()).rev() {
+            let cur = (rem << 64) | self.limbs[i] as u128;
+            limbs[i] = (cur / d as u128) as u64;
+            rem = cur % d as u128;
+        }
+        let mut q = BigUint { limbs };
+        q.normalize();
+        return (q, rem as u64);
+    }
+
+    pub fn rem_u64(&self, d: u64) -> u64
+    {
+        return self.divrem_u64(d).1;
+    }
+
+    //
+    // Long division (Knuth, TAOCP Vol. 2, Algorithm D).
+    //
+    pub fn divrem(&self, divisor: &BigUint) -> (BigUint, BigUint)
+    {
+        assert!(!divisor.is_zero(), "division by zero");
+        if *self < *divisor {
+            return (BigUint::zero(), self.clone());
+        }
+        if divisor.limbs.len() == 1 {
+            let (q, r) = self.divrem_u64(divisor.limbs[0]);
+            return (q, BigUint::from_u64(r));
+        }
+
+        let n = divisor.limbs.len();
+        let m = self.limbs.len() - n;
+        let s = divisor.limbs[n - 1].leading_zeros() as usize;
+        let vn = divisor.shl(s).limbs;
+        let mut un = self.shl(s).limbs;
+        un.resize(self.limbs.len() + 1, 0);
+        let mut q = vec![0u64; m + 1];
+
+        for j in (0..=m).rev() {
+            let num = ((un[j + n] as u128) << 64) | un[j + n - 1] as u128;
+            let mut qhat = num / vn[n - 1] as u128;
+            let mut rhat = num % vn[n - 1] as u128;
+
+            while qhat >= B ||
+                  qhat * vn[n - 2] as u128 > ((rhat << 64) | un[j + n - 2] as u128) {
+                qhat -= 1;
+                rhat += vn[n - 1] as u128;
+                if rhat >= B {
+                    break;
+                }
+            }
+
+            // multiply and subtract
+            let mut k: i128 = 0;
+            for i in 0..n {
+                let p = qhat * vn[i] as u128;
+                let t = un[i + j] as i128 - k - (p as u64) as i128;
+                un[i + j] = t as u64;
+                k = (p >> 64) as i128 - (t >> 64);
+            }
+            let t = un[j + n] as i128 - k;
+            un[j + n] = t as u64;
+
+            q[j] = qhat as u64;
+            if t < 0 {
+                // add back
+                q[j] = q[j].wrapping_sub(1);
+                let mut carry = 0u128;
+                for i in 0..n {
+                    let s = un[i + j] as u128 + vn[i] as u128 + carry;
+                    un[i + j] = s as u64;
+                    carry = s >> 64;
+                }
+                un[j + n] = un[j + n].wrapping_add(carry as u64);
+            }
+        }
+
+        un.truncate(n);
+        let mut r = BigUint { limbs: un };
+        r.normalize();
+        let mut q = BigUint { limbs: q };
+        q.normalize();
+        return (q, r.shr(s));
+    }
+
+    pub fn rem(&self, m: &BigUint) -> BigUint
+    {
+        return self.divrem(m).1;
+    }
+
+    //
+    // Greatest common divisor.
+    //
+    pub fn gcd(&self, other: &BigUint) -> BigUint
+    {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while !b.is_zero() {
+            let r = a.rem(&b);
+            a = b;
+            b = r;
+        }
+        return a;
+    }
+
+    //
+    // Modular inverse using the extended Euclidean algorithm.
+    //
+    pub fn modinv(&self, m: &BigUint) -> Option<BigUint>
+    {
+        let (mut old_r, mut r) = (self.rem(m), m.clone());
+        let (mut old_s, mut s) = (BigUint::one(), BigUint::zero());
+
+        while !r.is_zero() {
+            let (q, rem) = old_r.divrem(&r);
+            old_r = r;
+            r = rem;
+            let qs = q.mul(&s).rem(m);
+            let new_s = old_s.add(m).sub(&qs).rem(m);
+            old_s = s;
+            s = new_s;
+        }
+        if !old_r.is_one() {
+            return None;
+        }
+        return Some(old_s);
+    }
+
+    //
+    // Modular addition of reduced operands.
+    //
+    pub fn mod_add(&self, other: &BigUint, m: &BigUint) -> BigUint
+    {
+        return self.add(other).rem(m);
+    }
+
+    //
+    // Modular subtraction of reduced operands.
+    //
+    pub fn mod_sub(&self, other: &BigUint, m: &BigUint) -> BigUint
+    {
+        return self.add(m).sub(other).rem(m);
+    }
+
+    //
+    // Modular multiplication.
+    //
+    pub fn mod_mul(&self, other: &BigUint, m: &BigUint) -> BigUint
+    {
+        return self.mul(other).rem(m);
+    }
+
+    //
+    // Modular exponentiation with an odd modulus.
+    //
+    pub fn modpow(&self, exp: &BigUint, m: &BigUint) -> BigUint
+    {
+        return Monty::new(m).pow(self, exp);
+    }
+
+    //
+    // Uniformly distributed random integer with the given number of bits,
+    // the top bit not necessarily set.
+    //
+    pub fn random_bits(bits: usize) -> BigUint
+    {
+        let mut bytes = vec![0u8; bits.div_ceil(8)];
+        rng_fill(&mut bytes);
+        let excess = bytes.len() * 8 - bits;
+        if excess > 0 {
+            bytes[0] &= 0xFF >> excess;
+        }
+        return BigUint::from_bytes_be(&bytes);
+    }
+
+    //
+    // Uniformly distributed random integer in the range [1, max).
+    //
+    pub fn random_below(max: &BigUint) -> BigUint
+    {
+        loop {
+            let r = BigUint::random_bits(max.bits());
+            if !r.is_zero() && r < *max {
+                return r;
+            }
+        }
+    }
+
+    //
+    // Parse a hexadecimal string of arbitrary length.
+    //
+    pub fn from_hex(hex: &str) -> Option<BigUint>
+    {
+        if hex.is_empty() || !hex.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let padded = if hex.len() % 2 == 1 { format!("0{}", hex) } else { hex.to_string() };
+        let bytes: Vec<u8> = (0..padded.len()).step_by(2)
+            .map(|i| u8::from_str_radix(&padded[i..i + 2], 16).unwrap())
+            .collect();
+        return Some(BigUint::from_bytes_be(&bytes));
+    }
+
+    //
+    // Hexadecimal representation without leading zeros.
+    //
+    pub fn to_hex(&self) -> String
+    {
+        if self.is_zero() {
+            return "0".to_string();
+        }
+        let s: String = self.to_bytes_be().iter().map(|b| format!("{:02x}", b)).collect();
+        return s.trim_start_matches('0').to_string();
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &BigUint) -> Ordering
+    {
+        if self.limbs.len() != other.limbs.len() {
+            return self.limbs.len().cmp(&other.limbs.len());
+        }
+        for i in (0..self.limbs.len()).rev() {
+            if self.limbs[i] != other.limbs[i] {
+                return self.limbs[i].cmp(&other.limbs[i]);
+            }
+        }
+        return Ordering::Equal;
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &BigUint) -> Option<Ordering>
+    {
+        Some(self.cmp(other))
+    }
+}
+
+/// Montgomery arithmetic context for a fixed odd modulus
+#[derive(Clone, Debug)]
+pub struct Monty {
+    /// modulus as BigUint
+    modulus: BigUint,
+    /// modulus limbs
+    m: Vec<u64>,
+    /// -m^-1 mod 2^64
+    minv: u64,
+    /// R^2 mod m with R = 2^(64 * len)
+    r2: Vec<u64>,
+    /// number of limbs
+    len: usize,
+}
+
+impl Monty {
+    //
+    // Precompute the Montgomery constants for an odd modulus.  R^2 mod m is
+    // derived by modular doublings instead of a division, as the modulus
+    // may be secret.
+    //
+    pub fn new(modulus: &BigUint) -> Monty
+    {
+        assert!(modulus.is_odd(), "Montgomery modulus must be odd");
+        let len = modulus.limbs.len();
+        let m = modulus.limbs.clone();
+
+        // Newton iteration for m^-1 mod 2^64
+        let mut inv: u64 = 1;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(m[0].wrapping_mul(inv)));
+        }
+        let mut monty = Monty { modulus: modulus.clone(), m, minv: inv.wrapping_neg(),
+                                r2: Vec::new(), len };
+        let mut r2 = BigUint::one().limbs_padded(len);
+        for _ in 0..2 * LIMB_BITS * len {
+            r2 = monty.add(&r2, &r2);
+        }
+        monty.r2 = r2;
+        return monty;
+    }
+
+    pub fn modulus(&self) -> &BigUint
+    {
+        return &self.modulus;
+    }
+
+    //
+    // Number of limbs of the Montgomery representation.
+    //
+    pub fn len(&self) -> usize
+    {
+        return self.len;
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        return self.len == 0;
+    }
+
+    //
+    // Montgomery product a * b * R^-1 mod m of reduced operands (CIOS).
+    //
+    pub fn mul(&self, a: &[u64], b: &[u64]) -> Vec<u64>
+    {
+        let n = self.len;
+        let mut t = vec![0u64; n + 2];
+
+        for &ai in a.iter().take(n) {
+            let mut c = 0u128;
+            for j in 0..n {
+                let s = t[j] as u128 + (ai as u128) * (b[j] as u128) + c;
+                t[j] = s as u64;
+                c = s >> 64;
+            }
+            let s = t[n] as u128 + c;
+            t[n] = s as u64;
+            t[n + 1] = (s >> 64) as u64;
+
+            let q = t[0].wrapping_mul(self.minv);
+            let s = t[0] as u128 + (q as u128) * (self.m[0] as u128);
+            let mut c = s >> 64;
+            for j in 1..n {
+                let s = t[j] as u128 + (q as u128) * (self.m[j] as u128) + c;
+                t[j - 1] = s as u64;
+                c = s >> 64;
+            }
+            let s = t[n] as u128 + c;
+            t[n - 1] = s as u64;
+            t[n] = t[n + 1] + (s >> 64) as u64;
+        }
+        return self.reduce_once(&t[..n], t[n]);
+    }
+
+    //
+    // Montgomery reduction a * R^-1 mod m of a value a < m * R given with
+    // at most twice the number of limbs of the modulus.  The running time
+    // depends on the number of limbs only.
+    //
+    pub fn reduce(&self, a: &[u64]) -> Vec<u64>
+    {
+        let n = self.len;
+        assert!(a.len() <= 2 * n, "value too large for Montgomery reduction");
+        let mut t = vec![0u64; 2 * n + 1];
+        t[..a.len()].copy_from_slice(a);
+
+        for i in 0..n {
+            let q = t[i].wrapping_mul(self.minv);
+            let mut c = 0u128;
+            for j in 0..n {
+                let s = t[i + j] as u128 + (q as u128) * (self.m[j] as u128) + c;
+                t[i + j] = s as u64;
+                c = s >> 64;
+            }
+            // propagate the carry through all remaining limbs
+            for limb in t.iter_mut().skip(i + n) {
+                let s = *limb as u128 + c;
+                *limb = s as u64;
+                c = s >> 64;
+            }
+        }
+        return self.reduce_once(&t[n..2 * n], t[2 * n]);
+    }
+
+    //
+    // Convert an integer of any size into Montgomery form without
+    // data-dependent branches or divisions, processing it in chunks of the
+    // modulus length, most significant first: a R = sum(a_k R^k) R.
+    //
+    pub fn to_mont_ct(&self, a: &BigUint) -> Vec<u64>
+    {
+        let n = self.len;
+        let limbs = a.limbs_padded(n);
+        let chunks = limbs.len().div_ceil(n);
+        let mut acc = vec![0u64; n];
+
+        for k in (0..chunks).rev() {
+            let mut chunk = limbs[k * n..limbs.len().min((k + 1) * n)].to_vec();
+            chunk.resize(n, 0);
+            let chunk = self.mul(&self.mul(&self.reduce(&chunk), &self.r2), &self.r2);
+            acc = self.add(&self.mul(&acc, &self.r2), &chunk);
+        }
+        return acc;
+    }
+
+    //
+    // Subtract the modulus if t (with an extra high limb) is not smaller,
+    // selecting the result without branching.
+    //
+    fn reduce_once(&self, t: &[u64], high: u64) -> Vec<u64>
+    {
+        let n = self.len;
+        let mut u = vec![0u64; n];
+        let mut borrow = 0u64;
+
+        for j in 0..n {
+            let (d1, o1) = t[j].overflowing_sub(self.m[j]);
+            let (d2, o2) = d1.overflowing_sub(borrow);
+            u[j] = d2;
+            borrow = (o1 | o2) as u64;
+        }
+        // keep t only if there was a borrow out of the full width
+        let high_set = (high | high.wrapping_neg()) >> 63;
+        let keep_t = borrow & (1 ^ high_set);
+        for j in 0..n {
+            u[j] = ct_select(keep_t, u[j], t[j]);
+        }
+        return u;
+    }
+
+    //
+    // Modular addition of two values in Montgomery (or plain) form.
+    //
+    pub fn add(&self, a: &[u64], b: &[u64]) -> Vec<u64>
+    {
+        let n = self.len;
+        let mut t = vec![0u64; n];
+        let mut carry = 0u64;
+
+        for j in 0..n {
+            let (s1, o1) = a[j].overflowing_add(b[j]);
+            let (s2, o2) = s1.overflowing_add(carry);
+            t[j] = s2;
+            carry = (o1 | o2) as u64;
+        }
+        return self.reduce_once(&t, carry);
+    }
+
+    //
+    // Modular subtraction of two values in Montgomery (or plain) form.
+    //
+    pub fn sub(&self, a: &[u64], b: &[u64]) -> Vec<u64>
+    {
+        let n = self.len;
+        let mut t = vec![0u64; n];
+        let mut borrow = 0u64;
+
+        for j in 0..n {
+            let (d1, o1) = a[j].overflowing_sub(b[j]);
+            let (d2, o2) = d1.overflowing_sub(borrow);
+            t[j] = d2;
+            borrow = (o1 | o2) as u64;
+        }
+        // add back the modulus if the subtraction wrapped around
+        let mask = 0u64.wrapping_sub(borrow);
+        let mut carry = 0u64;
+        for j in 0..n {
+            let (s1, o1) = t[j].overflowing_add(self.m[j] & mask);
+            let (s2, o2) = s1.overflowing_add(carry);
+            t[j] = s2;
+            carry = (o1 | o2) as u64;
+        }
+        return t;
+    }
+
+    //
+    // Modular negation.
+    //
+    pub fn neg(&self, a: &[u64]) -> Vec<u64>
+    {
+        return self.sub(&vec![0u64; self.len], a);
+    }
+
+    //
+    // Convert an integer into Montgomery form, reducing it first.
+    //
+    pub fn to_mont(&self, a: &BigUint) -> Vec<u64>
+    {
+        let a = if *a >= self.modulus { a.rem(&self.modulus) } else { a.clone() };
+        return self.mul(&a.limbs_padded(self.len), &self.r2);
+    }
+
+    //
+    // Convert a value in Montgomery form back into an integer.
+    //
+    pub fn from_mont(&self, a: &[u64]) -> BigUint
+    {
+        let mut one = vec![0u64; self.len];
+        one[0] = 1;
+        return BigUint::from_limbs(&self.mul(a, &one));
+    }
+
+    //
+    // Montgomery representation of one.
+    //
+    pub fn one(&self) -> Vec<u64>
+    {
+        return self.to_mont(&BigUint::one());
+    }
+
+    //
+    // Check if a value in Montgomery or plain form is zero.
+    //
+    pub fn is_zero(&self, a: &[u64]) -> bool
+    {
+        return a.iter().fold(0, |acc, l| acc | l) == 0;
+    }
+
+    //
+    // Exponentiation of a value in Montgomery form using a fixed 4 bit
+    // window and constant-time table lookups. The running time depends
+    // on the number of exponent limbs only.
+    //
+    pub fn pow_mont(&self, base: &[u64], exp: &BigUint) -> Vec<u64>
+    {
+        let mut table = Vec::with_capacity(16);
+        table.push(self.one());
+        table.push(base.to_vec());
+        for i in 2..16 {
+            let next = self.mul(&table[i - 1], base);
+            table.push(next);
+        }
+
+        let mut acc = self.one();
+        for i in (0..exp.limbs.len() * 16).rev() {
+            for _ in 0..4 {
+                acc = self.mul(&acc, &acc);
+            }
+            let window = (exp.limbs[i / 16] >> (4 * (i % 16))) & 0x0F;
+
+            let mut entry = vec![0u64; self.len];
+            for (k, t) in table.iter().enumerate() {
+                let hit = ((k as u64 ^ window).wrapping_sub(1) >> 63) & 1;
+                for j in 0..self.len {
+                    entry[j] = ct_select(hit, entry[j], t[j]);
+                }
+            }
+            acc = self.mul(&acc, &entry);
+        }
+        return acc;
+    }
+
+    //
+    // Modular exponentiation base^exp mod m.
+    //
+    pub fn pow(&self, base: &BigUint, exp: &BigUint) -> BigUint
+    {
+        let b = self.to_mont(base);
+        return self.from_mont(&self.pow_mont(&b, exp));
+    }
+
+    //
+    // Inverse of a value in Montgomery form modulo a prime using Fermat's
+    // little theorem.
+    //
+    pub fn inv_prime(&self, a: &[u64]) -> Vec<u64>
+    {
+        return self.pow_mont(a, &self.modulus.sub_u64(2));
+    }
+}
+
+//
+// Constant-time conditional swap of two limb vectors if choice is 1.
+//
+pub fn ct_swap(choice: u64, a: &mut [u64], b: &mut [u64])
+{
+    let mask = 0u64.wrapping_sub(choice);
+    for (x, y) in a.iter_mut().zip(b.iter_mut()) {
+        let t = (*x ^ *y) & mask;
+        *x ^= t;
+        *y ^= t;
+    }
+}
+
+//
+// Odd primes below 2^14 for trial division and sieving.
+//
+pub fn small_primes() -> &'static [u64]
+{
+    static PRIMES: OnceLock<Vec<u64>> = OnceLock::new();
+    return PRIMES.get_or_init(|| {
+        const LIMIT: usize = 1 << 14;
+        let mut composite = vec![false; LIMIT];
+        let mut primes = Vec::new();
+        for i in 3..LIMIT {
+            if !composite[i] && i % 2 == 1 {
+                primes.push(i as u64);
+                let mut j = i * i;
+                while j < LIMIT {
+                    composite[j] = true;
+                    j += i;
+                }
+            }
+        }
+        primes
+    });
+}
+
+//
+// Miller-Rabin probabilistic primality test with random bases.
+//
+pub fn is_probable_prime(n: &BigUint, rounds: usize) -> bool
+{
+    if n.bits() <= 14 {
+        let v = n.low_u64();
+        return v == 2 || (v > 2 && small_primes().contains(&v));
+    }
+    if !n.is_odd() {
+        return false;
+    }
+    for p in small_primes().iter().take(256) {
+        if n.rem_u64(*p) == 0 {
+            return false;
+        }
+    }
+
+    let monty = Monty::new(n);
+    let n1 = n.sub_u64(1);
+    let mut s = 0;
+    while !n1.bit(s) {
+        s += 1;
+    }
+    let d = n1.shr(s);
+    let one = monty.one();
+    let minus_one = monty.to_mont(&n1);
+
+    'witness: for _ in 0..rounds {
+        let a = BigUint::random_below(&n1.sub_u64(1)).add_u64(1);
+        let mut x = monty.pow_mont(&monty.to_mont(&a), &d);
+        if x == one || x == minus_one {
+            continue;
+        }
+        for _ in 1..s {
+            x = monty.mul(&x, &x);
+            if x == minus_one {
+                continue 'witness;
+            }
+            if x == one {
+                return false;
+            }
+        }
+        return false;
+    }
+    return true;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn montgomery_reduction()
+    {
+        let moduli = ["3", "fffffffffffffffffffffffffffffffeffffffffffffffff",
+                      "f7e75fdc469067ffdc4e847c51f452df0000000000000000000000000000000000000001"];
+        for modulus in moduli {
+            let m = BigUint::from_hex(modulus).unwrap();
+            let monty = Monty::new(&m);
+            let r = BigUint::one().shl(64 * monty.len());
+            assert_eq!(BigUint::from_limbs(&monty.r2), r.mul(&r).rem(&m));
+
+            for a in [BigUint::zero(), m.sub_u64(1), m.clone(), m.mul(&r).sub_u64(1),
+                      BigUint::random_bits(64 * monty.len() * 5), BigUint::random_below(&m)] {
+                assert_eq!(monty.from_mont(&monty.to_mont_ct(&a)), a.rem(&m));
+                assert_eq!(monty.to_mont_ct(&a), monty.to_mont(&a));
+                if a < m.mul(&r) {
+                    let reduced = BigUint::from_limbs(&monty.reduce(a.limbs()));
+                    assert_eq!(reduced.mul(&r).rem(&m), a.rem(&m));
+                    assert!(reduced < m);
+                }
+            }
+        }
+    }
+}
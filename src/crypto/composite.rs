@@ -0,0 +1,433 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! Composite ML-DSA signatures combining ML-DSA with an RSA, ECDSA or
+//! EdDSA signature (draft-ietf-lamps-pq-composite-sigs).
+//!
+//! Public keys, private keys and signatures are the concatenation of the
+//! fixed-length ML-DSA component and the traditional component, the latter
+//! being an RSAPublicKey/RSAPrivateKey, an uncompressed EC point/ECPrivateKey
+//! or the raw EdDSA key, respectively. The ML-DSA private key is stored as
+//! its 32 octet seed. Both components sign the same message representative
+//!
+//! ```text
+//! M' = Prefix || Label || len(ctx) || ctx || PH(M)
+//! ```
+//!
+//! with the ML-DSA signature using the Label as context string.
+
+use crate::oid::*;
+use crate::crypto::hash::{HashAlgorithm, shake256};
+use crate::crypto::rsa::{RsaPrivateKey, RsaPublicKey};
+use crate::crypto::ec::{Curve, EcPrivateKey, EcPublicKey};
+use crate::crypto::eddsa::{EdCurve, EdPrivateKey, EdPublicKey};
+use crate::crypto::mldsa::{MlDsaParams, MlDsaPrivateKey, MlDsaPublicKey};
+
+// prefix of the message representative M'
+const PREFIX: &[u8] = b"CompositeAlgorithmSignatures2025";
+
+/// Composite ML-DSA algorithms
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompositeParams {
+    MlDsa44Rsa2048Pss,
+    MlDsa44Rsa2048Pkcs15,
+    MlDsa44Ed25519,
+    MlDsa44EcdsaP256,
+    MlDsa65Rsa3072Pss,
+    MlDsa65Rsa3072Pkcs15,
+    MlDsa65Rsa4096Pss,
+    MlDsa65Rsa4096Pkcs15,
+    MlDsa65EcdsaP256,
+    MlDsa65EcdsaP384,
+    MlDsa65Ed25519,
+    MlDsa87EcdsaP384,
+    MlDsa87Ed448,
+    MlDsa87Rsa3072Pss,
+    MlDsa87Rsa4096Pss,
+    MlDsa87EcdsaP521,
+}
+
+/// Traditional component algorithm of a composite
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Traditional {
+    /// RSASSA-PSS with modulus size and hash
+    RsaPss(usize, HashAlgorithm),
+    /// RSASSA-PKCS1-v1_5 with modulus size and hash
+    RsaPkcs1(usize, HashAlgorithm),
+    /// ECDSA on a curve with hash
+    Ecdsa(Curve, HashAlgorithm),
+    /// pure EdDSA
+    Eddsa(EdCurve),
+}
+
+/// Properties of a composite algorithm
+struct CompositeInfo {
+    params: CompositeParams,
+    /// key type name used with --type
+    name: &'static str,
+    oid: &'static str,
+    /// domain separation label
+    label: &'static str,
+    mldsa: MlDsaParams,
+    trad: Traditional,
+    /// pre-hash of the message, SHAKE256 with 64 octets output if None
+    prehash: Option<HashAlgorithm>,
+}
+
+const COMPOSITES: &[CompositeInfo] = &[
+    CompositeInfo {
+        params: CompositeParams::MlDsa44Rsa2048Pss, name: "mldsa44-rsa2048-pss",
+        oid: OID_MLDSA44_RSA2048_PSS, label: "COMPSIG-MLDSA44-RSA2048-PSS-SHA256",
+        mldsa: MlDsaParams::MlDsa44, trad: Traditional::RsaPss(2048, HashAlgorithm::Sha256),
+        prehash: Some(HashAlgorithm::Sha256),
+    },
+    CompositeInfo {
+        params: CompositeParams::MlDsa44Rsa2048Pkcs15, name: "mldsa44-rsa2048-pkcs15",
+        oid: OID_MLDSA44_RSA2048_PKCS15, label: "COMPSIG-MLDSA44-RSA2048-PKCS15-SHA256",
+        mldsa: MlDsaParams::MlDsa44, trad: Traditional::RsaPkcs1(2048, HashAlgorithm::Sha256),
+        prehash: Some(HashAlgorithm::Sha256),
+    },
+    CompositeInfo {
+        params: CompositeParams::MlDsa44Ed25519, name: "mldsa44-ed25519",
+        oid: OID_MLDSA44_ED25519, label: "COMPSIG-MLDSA44-Ed25519-SHA512",
+        mldsa: MlDsaParams::MlDsa44, trad: Traditional::Eddsa(EdCurve::Ed25519),
+        prehash: Some(HashAlgorithm::Sha512),
+    },
+    CompositeInfo {
+        params: CompositeParams::MlDsa44EcdsaP256, name: "mldsa44-ecdsa-p256",
+        oid: OID_MLDSA44_ECDSA_P256, label: "COMPSIG-MLDSA44-ECDSA-P256-SHA256",
+        mldsa: MlDsaParams::MlDsa44, trad: Traditional::Ecdsa(Curve::P256, HashAlgorithm::Sha256),
+        prehash: Some(HashAlgorithm::Sha256),
+    },
+    CompositeInfo {
+        params: CompositeParams::MlDsa65Rsa3072Pss, name: "mldsa65-rsa3072-pss",
+        oid: OID_MLDSA65_RSA3072_PSS, label: "COMPSIG-MLDSA65-RSA3072-PSS-SHA512",
+        mldsa: MlDsaParams::MlDsa65, trad: Traditional::RsaPss(3072, HashAlgorithm::Sha256),
+        prehash: Some(HashAlgorithm::Sha512),
+    },
+    CompositeInfo {
+        params: CompositeParams::MlDsa65Rsa3072Pkcs15, name: "mldsa65-rsa3072-pkcs15",
+        oid: OID_MLDSA65_RSA3072_PKCS15, label: "COMPSIG-MLDSA65-RSA3072-PKCS15-SHA512",
+        mldsa: MlDsaParams::MlDsa65, trad: Traditional::RsaPkcs1(3072, HashAlgorithm::Sha256),
+        prehash: Some(HashAlgorithm::Sha512),
+    },
+    CompositeInfo {
+        params: CompositeParams::MlDsa65Rsa4096Pss, name: "mldsa65-rsa4096-pss",
+        oid: OID_MLDSA65_RSA4096_PSS, label: "COMPSIG-MLDSA65-RSA4096-PSS-SHA512",
+        mldsa: MlDsaParams::MlDsa65, trad: Traditional::RsaPss(4096, HashAlgorithm::Sha384),
+        prehash: Some(HashAlgorithm::Sha512),
+    },
+    CompositeInfo {
+        params: CompositeParams::MlDsa65Rsa4096Pkcs15, name: "mldsa65-rsa4096-pkcs15",
+        oid: OID_MLDSA65_RSA4096_PKCS15, label: "COMPSIG-MLDSA65-RSA4096-PKCS15-SHA512",
+        mldsa: MlDsaParams::MlDsa65, trad: Traditional::RsaPkcs1(4096, HashAlgorithm::Sha384),
+        prehash: Some(HashAlgorithm::Sha512),
+    },
+    CompositeInfo {
+        params: CompositeParams::MlDsa65EcdsaP256, name: "mldsa65-ecdsa-p256",
+        oid: OID_MLDSA65_ECDSA_P256, label: "COMPSIG-MLDSA65-ECDSA-P256-SHA512",
+        mldsa: MlDsaParams::MlDsa65, trad: Traditional::Ecdsa(Curve::P256, HashAlgorithm::Sha256),
+        prehash: Some(HashAlgorithm::Sha512),
+    },
+    CompositeInfo {
+        params: CompositeParams::MlDsa65EcdsaP384, name: "mldsa65-ecdsa-p384",
+        oid: OID_MLDSA65_ECDSA_P384, label: "COMPSIG-MLDSA65-ECDSA-P384-SHA512",
+        mldsa: MlDsaParams::MlDsa65, trad: Traditional::Ecdsa(Curve::P384, HashAlgorithm::Sha384),
+        prehash: Some(HashAlgorithm::Sha512),
+    },
+    CompositeInfo {
+        params: CompositeParams::MlDsa65Ed25519, name: "mldsa65-ed25519",
+        oid: OID_MLDSA65_ED25519, label: "COMPSIG-MLDSA65-Ed25519-SHA512",
+        mldsa: MlDsaParams::MlDsa65, trad: Traditional::Eddsa(EdCurve::Ed25519),
+        prehash: Some(HashAlgorithm::Sha512),
+    },
+    CompositeInfo {
+        params: CompositeParams::MlDsa87EcdsaP384, name: "mldsa87-ecdsa-p384",
+        oid: OID_MLDSA87_ECDSA_P384, label: "COMPSIG-MLDSA87-ECDSA-P384-SHA512",
+        mldsa: MlDsaParams::MlDsa87, trad: Traditional::Ecdsa(Curve::P384, HashAlgorithm::Sha384),
+        prehash: Some(HashAlgorithm::Sha512),
+    },
+    CompositeInfo {
+        params: CompositeParams::MlDsa87Ed448, name: "mldsa87-ed448",
+        oid: OID_MLDSA87_ED448, label: "COMPSIG-MLDSA87-Ed448-SHAKE256",
+        mldsa: MlDsaParams::MlDsa87, trad: Traditional::Eddsa(EdCurve::Ed448),
+        prehash: None,
+    },
+    CompositeInfo {
+        params: CompositeParams::MlDsa87Rsa3072Pss, name: "mldsa87-rsa3072-pss",
+        oid: OID_MLDSA87_RSA3072_PSS, label: "COMPSIG-MLDSA87-RSA3072-PSS-SHA512",
+        mldsa: MlDsaParams::MlDsa87, trad: Traditional::RsaPss(3072, HashAlgorithm::Sha256),
+        prehash: Some(HashAlgorithm::Sha512),
+    },
+    CompositeInfo {
+        params: CompositeParams::MlDsa87Rsa4096Pss, name: "mldsa87-rsa4096-pss",
+        oid: OID_MLDSA87_RSA4096_PSS, label: "COMPSIG-MLDSA87-RSA4096-PSS-SHA512",
+        mldsa: MlDsaParams::MlDsa87, trad: Traditional::RsaPss(4096, HashAlgorithm::Sha384),
+        prehash: Some(HashAlgorithm::Sha512),
+    },
+    CompositeInfo {
+        params: CompositeParams::MlDsa87EcdsaP521, name: "mldsa87-ecdsa-p521",
+        oid: OID_MLDSA87_ECDSA_P521, label: "COMPSIG-MLDSA87-ECDSA-P521-SHA512",
+        mldsa: MlDsaParams::MlDsa87, trad: Traditional::Ecdsa(Curve::P521, HashAlgorithm::Sha512),
+        prehash: Some(HashAlgorithm::Sha512),
+    },
+];
+
+impl CompositeParams {
+    fn info(&self) -> &'static CompositeInfo
+    {
+        return COMPOSITES.iter().find(|c| c.params == *self).expect("composite info");
+    }
+
+    //
+    // Parse a composite key type given with --type.
+    //
+    pub fn parse(name: &str) -> Option<CompositeParams>
+    {
+        return COMPOSITES.iter().find(|c| c.name == name).map(|c| c.params);
+    }
+
+    pub fn from_oid(oid: &str) -> Option<CompositeParams>
+    {
+        return COMPOSITES.iter().find(|c| c.oid == oid).map(|c| c.params);
+    }
+
+    pub fn oid(&self) -> &'static str
+    {
+        return self.info().oid;
+    }
+
+    pub fn mldsa(&self) -> MlDsaParams
+    {
+        return self.info().mldsa;
+    }
+
+    pub fn traditional(&self) -> Traditional
+    {
+        return self.info().trad;
+    }
+
+    //
+    // Build the message representative M' with an empty context.
+    //
+    fn message(&self, msg: &[u8]) -> Vec<u8>
+    {
+        let info = self.info();
+        let mut m = PREFIX.to_vec();
+        m.extend_from_slice(info.label.as_bytes());
+        m.push(0);
+        match info.prehash {
+            Some(hash) => { m.extend_from_slice(&hash.hash(msg)); }
+            None       => { m.extend_from_slice(&shake256(msg, 64)); }
+        }
+        return m;
+    }
+}
+
+/// Traditional component of a composite private key
+#[derive(Clone, Debug)]
+pub enum TradPrivateKey {
+    Rsa(RsaPrivateKey),
+    Ecdsa(EcPrivateKey),
+    Eddsa(EdPrivateKey),
+}
+
+/// Traditional component of a composite public key
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TradPublicKey {
+    Rsa(RsaPublicKey),
+    Ecdsa(EcPublicKey),
+    Eddsa(EdPublicKey),
+}
+
+/// Composite ML-DSA private key
+#[derive(Clone, Debug)]
+pub struct CompositePrivateKey {
+    /// composite algorithm
+    pub params: CompositeParams,
+    /// ML-DSA component, always derived from a seed
+    pub mldsa: MlDsaPrivateKey,
+    /// traditional component
+    pub trad: TradPrivateKey,
+}
+
+/// Composite ML-DSA public key
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompositePublicKey {
+    /// composite algorithm
+    pub params: CompositeParams,
+    /// ML-DSA component
+    pub mldsa: MlDsaPublicKey,
+    /// traditional component
+    pub trad: TradPublicKey,
+}
+
+impl CompositePrivateKey {
+    pub fn generate(params: CompositeParams) -> Result<CompositePrivateKey, String>
+    {
+        let mldsa = MlDsaPrivateKey::generate(params.mldsa());
+        let trad = match params.traditional() {
+            Traditional::RsaPss(bits, _) |
+            Traditional::RsaPkcs1(bits, _) => {
+                TradPrivateKey::Rsa(RsaPrivateKey::generate(bits, false)?)
+            }
+            Traditional::Ecdsa(curve, _) => { TradPrivateKey::Ecdsa(EcPrivateKey::generate(curve)) }
+            Traditional::Eddsa(curve)    => { TradPrivateKey::Eddsa(EdPrivateKey::generate(curve)) }
+        };
+        return Ok(CompositePrivateKey { params, mldsa, trad });
+    }
+
+    //
+    // Decode the ML-DSA seed followed by the traditional private key.
+    //
+    pub fn from_bytes(params: CompositeParams, data: &[u8]) -> Result<CompositePrivateKey, String>
+    {
+        if data.len() < 32 {
+            return Err("invalid composite private key length".to_string());
+        }
+        let (seed, key) = data.split_at(32);
+        let mldsa = MlDsaPrivateKey::from_seed(params.mldsa(), seed)?;
+        let trad = match params.traditional() {
+            Traditional::RsaPss(bits, _) |
+            Traditional::RsaPkcs1(bits, _) => {
+                let key = RsaPrivateKey::from_pkcs1(key)?;
+                if key.bits() != bits {
+                    return Err(format!("composite requires a {} bit RSA key", bits));
+                }
+                TradPrivateKey::Rsa(key)
+            }
+            Traditional::Ecdsa(curve, _) => {
+                TradPrivateKey::Ecdsa(EcPrivateKey::from_sec1(key, Some(curve))?)
+            }
+            Traditional::Eddsa(curve) => {
+                TradPrivateKey::Eddsa(EdPrivateKey::from_seed(curve, key)?)
+            }
+        };
+        return Ok(CompositePrivateKey { params, mldsa, trad });
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8>
+    {
+        let mut out = self.mldsa.seed.clone().expect("ML-DSA seed");
+        match &self.trad {
+            TradPrivateKey::Rsa(key)   => { out.extend_from_slice(&key.to_pkcs1()); }
+            TradPrivateKey::Ecdsa(key) => { out.extend_from_slice(&key.to_sec1(false)); }
+            TradPrivateKey::Eddsa(key) => { out.extend_from_slice(&key.seed); }
+        }
+        return out;
+    }
+
+    pub fn public_key(&self) -> CompositePublicKey
+    {
+        let trad = match &self.trad {
+            TradPrivateKey::Rsa(key)   => { TradPublicKey::Rsa(key.public_key()) }
+            TradPrivateKey::Ecdsa(key) => { TradPublicKey::Ecdsa(key.public_key()) }
+            TradPrivateKey::Eddsa(key) => { TradPublicKey::Eddsa(key.public_key()) }
+        };
+        CompositePublicKey { params: self.params, mldsa: self.mldsa.public_key(), trad }
+    }
+
+    //
+    // Create a composite signature, the ML-DSA signature followed by the
+    // traditional one.
+    //
+    pub fn sign(&self, msg: &[u8]) -> Result<Vec<u8>, String>
+    {
+        let m = self.params.message(msg);
+        let mut sig = self.mldsa.sign_with_context(&m, self.params.info().label.as_bytes())?;
+        let trad_sig = match (&self.trad, self.params.traditional()) {
+            (TradPrivateKey::Rsa(key), Traditional::RsaPss(_, hash)) => {
+                key.sign_pss(hash, &m)?
+            }
+            (TradPrivateKey::Rsa(key), Traditional::RsaPkcs1(_, hash)) => {
+                key.sign_pkcs1(hash, &m)?
+            }
+            (TradPrivateKey::Ecdsa(key), Traditional::Ecdsa(_, hash)) => { key.sign(hash, &m) }
+            (TradPrivateKey::Eddsa(key), Traditional::Eddsa(_)) => { key.sign(&m) }
+            _ => { return Err("inconsistent composite private key".to_string()); }
+        };
+        sig.extend_from_slice(&trad_sig);
+        return Ok(sig);
+    }
+}
+
+impl CompositePublicKey {
+    //
+    // Decode the ML-DSA public key followed by the traditional public key.
+    //
+    pub fn from_bytes(params: CompositeParams, data: &[u8]) -> Result<CompositePublicKey, String>
+    {
+        let len = params.mldsa().public_key_len();
+        if data.len() <= len {
+            return Err("invalid composite public key length".to_string());
+        }
+        let (mldsa, key) = data.split_at(len);
+        let mldsa = MlDsaPublicKey::from_bytes(params.mldsa(), mldsa)?;
+        let trad = match params.traditional() {
+            Traditional::RsaPss(bits, _) |
+            Traditional::RsaPkcs1(bits, _) => {
+                let key = RsaPublicKey::from_pkcs1(key)?;
+                if key.bits() != bits {
+                    return Err(format!("composite requires a {} bit RSA key", bits));
+                }
+                TradPublicKey::Rsa(key)
+            }
+            Traditional::Ecdsa(curve, _) => {
+                TradPublicKey::Ecdsa(EcPublicKey::from_point(curve, key)?)
+            }
+            Traditional::Eddsa(curve) => {
+                TradPublicKey::Eddsa(EdPublicKey::from_bytes(curve, key)?)
+            }
+        };
+        return Ok(CompositePublicKey { params, mldsa, trad });
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8>
+    {
+        let mut out = self.mldsa.key.clone();
+        match &self.trad {
+            TradPublicKey::Rsa(key)   => { out.extend_from_slice(&key.to_pkcs1()); }
+            TradPublicKey::Ecdsa(key) => { out.extend_from_slice(&key.point); }
+            TradPublicKey::Eddsa(key) => { out.extend_from_slice(&key.key); }
+        }
+        return out;
+    }
+
+    //
+    // Verify a composite signature, requiring both components to be valid.
+    //
+    pub fn verify(&self, msg: &[u8], sig: &[u8]) -> bool
+    {
+        let len = self.params.mldsa().signature_len();
+        if sig.len() <= len {
+            return false;
+        }
+        let (mldsa_sig, trad_sig) = sig.split_at(len);
+        let m = self.params.message(msg);
+        if !self.mldsa.verify_with_context(&m, self.params.info().label.as_bytes(), mldsa_sig) {
+            return false;
+        }
+        match (&self.trad, self.params.traditional()) {
+            (TradPublicKey::Rsa(key), Traditional::RsaPss(_, hash)) => {
+                key.verify_pss(hash, &m, trad_sig)
+            }
+            (TradPublicKey::Rsa(key), Traditional::RsaPkcs1(_, hash)) => {
+                key.verify_pkcs1(hash, &m, trad_sig)
+            }
+            (TradPublicKey::Ecdsa(key), Traditional::Ecdsa(_, hash)) => {
+                key.verify(hash, &m, trad_sig)
+            }
+            (TradPublicKey::Eddsa(key), Traditional::Eddsa(_)) => { key.verify(&m, trad_sig) }
+            _ => { false }
+        }
+    }
+}
@@ -0,0 +1,724 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! Arithmetic on the NIST prime curves P-256, P-384 and P-521, the SEC1
+//! encodings of ECDSA keys and ECDSA signatures (FIPS 186-5).
+
+use std::sync::OnceLock;
+use crate::asn1::*;
+use crate::oid::*;
+use crate::crypto::bigint::{BigUint, Monty, ct_swap};
+use crate::crypto::hash::HashAlgorithm;
+
+/// NIST prime curves
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Curve {
+    P256,
+    P384,
+    P521,
+}
+
+/// Domain parameters of a short Weierstrass curve with a = -3
+struct CurveParams {
+    p: &'static str,
+    b: &'static str,
+    n: &'static str,
+    gx: &'static str,
+    gy: &'static str,
+}
+
+const P256_PARAMS: CurveParams = CurveParams {
+    p:  "ffffffff00000001000000000000000000000000ffffffffffffffffffffffff",
+    b:  "5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b",
+    n:  "ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551",
+    gx: "6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296",
+    gy: "4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5",
+};
+
+const P384_PARAMS: CurveParams = CurveParams {
+    p:  "fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffe\
+         ffffffff0000000000000000ffffffff",
+    b:  "b3312fa7e23ee7e4988e056be3f82d19181d9c6efe8141120314088f5013875a\
+         c656398d8a2ed19d2a85c8edd3ec2aef",
+    n:  "ffffffffffffffffffffffffffffffffffffffffffffffffc7634d81f4372ddf\
+         581a0db248b0a77aecec196accc52973",
+    gx: "aa87ca22be8b05378eb1c71ef320ad746e1d3b628ba79b9859f741e082542a38\
+         5502f25dbf55296c3a545e3872760ab7",
+    gy: "3617de4a96262c6f5d9e98bf9292dc29f8f41dbd289a147ce9da3113b5f0b8c0\
+         0a60b1ce1d7e819d7a431d7c90ea0e5f",
+};
+
+const P521_PARAMS: CurveParams = CurveParams {
+    p:  "01ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff\
+         ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff\
+         ffff",
+    b:  "0051953eb9618e1c9a1f929a21a0b68540eea2da725b99b315f3b8b489918ef1\
+         09e156193951ec7e937b1652c0bd3bb1bf073573df883d2c34f1ef451fd46b50\
+         3f00",
+    n:  "01ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff\
+         fffa51868783bf2f966b7fcc0148f709a5d03bb5c9b8899c47aebb6fb71e9138\
+         6409",
+    gx: "00c6858e06b70404e9cd9e3ecb662395b4429c648139053fb521f828af606b4d\
+         3dbaa14b5e77efe75928fe1dc127a2ffa8de3348b3c1856a429bf97e7e31c2e5\
+         bd66",
+    gy: "011839296a789a3bc0045c8a5fb42c7d1bd998f54449579b446817afbd17273e\
+         662c97ee72995ef42640c550b9013fad0761353c7086a272c24088be94769fd1\
+         6650",
+};
+
+/// Precomputed arithmetic context of a curve
+pub struct EcGroup {
+    /// curve identifier
+    pub curve: Curve,
+    /// Montgomery context of the field prime
+    pub field: Monty,
+    /// Montgomery context of the group order
+    pub scalar: Monty,
+    /// curve coefficient b in Montgomery form
+    b: Vec<u64>,
+    /// base point
+    g: EcPoint,
+}
+
+/// Point in homogeneous projective coordinates, field elements in
+/// Montgomery form
+#[derive(Clone, Debug)]
+pub struct EcPoint {
+    x: Vec<u64>,
+    y: Vec<u64>,
+    z: Vec<u64>,
+}
+
+//
+// Parse a hexadecimal domain parameter.
+//
+fn param(hex: &str) -> BigUint
+{
+    return BigUint::from_hex(hex).expect("valid curve parameter");
+}
+
+impl Curve {
+    //
+    // Select a curve by its key size in bits.
+    //
+    pub fn from_bits(bits: usize) -> Option<Curve>
+    {
+        match bits {
+            256 => { Some(Curve::P256) }
+            384 => { Some(Curve::P384) }
+            521 => { Some(Curve::P521) }
+            _   => { None }
+        }
+    }
+
+    //
+    // Select a curve by its namedCurve OID.
+    //
+    pub fn from_oid(oid: &str) -> Option<Curve>
+    {
+        match oid {
+            OID_PRIME256V1 => { Some(Curve::P256) }
+            OID_SECP384R1  => { Some(Curve::P384) }
+            OID_SECP521R1  => { Some(Curve::P521) }
+            _              => { None }
+        }
+    }
+
+    pub fn oid(&self) -> &'static str
+    {
+        match self {
+            Curve::P256 => { OID_PRIME256V1 }
+            Curve::P384 => { OID_SECP384R1 }
+            Curve::P521 => { OID_SECP521R1 }
+        }
+    }
+
+    pub fn bits(&self) -> usize
+    {
+        match self {
+            Curve::P256 => { 256 }
+            Curve::P384 => { 384 }
+            Curve::P521 => { 521 }
+        }
+    }
+
+    //
+    // Length of an encoded field element or scalar in octets.
+    //
+    pub fn field_len(&self) -> usize
+    {
+        return self.bits().div_ceil(8);
+    }
+
+    pub fn name(&self) -> &'static str
+    {
+        match self {
+            Curve::P256 => { "NIST P-256" }
+            Curve::P384 => { "NIST P-384" }
+            Curve::P521 => { "NIST P-521" }
+        }
+    }
+
+    //
+    // Get the shared arithmetic context of the curve.
+    //
+    pub fn group(&self) -> &'static EcGroup
+    {
+        static P256: OnceLock<EcGroup> = OnceLock::new();
+        static P384: OnceLock<EcGroup> = OnceLock::new();
+        static P521: OnceLock<EcGroup> = OnceLock::new();
+
+        match self {
+            Curve::P256 => { P256.get_or_init(|| EcGroup::new(*self, &P256_PARAMS)) }
+            Curve::P384 => { P384.get_or_init(|| EcGroup::new(*self, &P384_PARAMS)) }
+            Curve::P521 => { P521.get_or_init(|| EcGroup::new(*self, &P521_PARAMS)) }
+        }
+    }
+}
+
+impl EcGroup {
+    fn new(curve: Curve, params: &CurveParams) -> EcGroup
+    {
+        let field = Monty::new(&param(params.p));
+        let scalar = Monty::new(&param(params.n));
+        let b = field.to_mont(&param(params.b));
+        let g = EcPoint {
+            x: field.to_mont(&param(params.gx)),
+            y: field.to_mont(&param(params.gy)),
+            z: field.one(),
+        };
+        EcGroup { curve, field, scalar, b, g }
+    }
+
+    //
+    // Order of the base point.
+    //
+    pub fn order(&self) -> &BigUint
+    {
+        return self.scalar.modulus();
+    }
+
+    //
+    // Point at infinity (0:1:0).
+    //
+    pub fn identity(&self) -> EcPoint
+    {
+        let len = self.field.len();
+        EcPoint { x: vec![0; len], y: self.field.one(), z: vec![0; len] }
+    }
+
+    pub fn generator(&self) -> EcPoint
+    {
+        return self.g.clone();
+    }
+
+    //
+    // Complete addition formula for a = -3 (Renes, Costello, Batina 2016,
+    // Algorithm 4), also valid for doubling and the point at infinity.
+    //
+    pub fn add(&self, p: &EcPoint, q: &EcPoint) -> EcPoint
+    {
+        let f = &self.field;
+        let (x1, y1, z1) = (&p.x, &p.y, &p.z);
+        let (x2, y2, z2) = (&q.x, &q.y, &q.z);
+
+        let mut t0 = f.mul(x1, x2);
+        let mut t1 = f.mul(y1, y2);
+        let mut t2 = f.mul(z1, z2);
+        let mut t3 = f.add(x1, y1);
+        let mut t4 = f.add(x2, y2);
+        t3 = f.mul(&t3, &t4);
+        t4 = f.add(&t0, &t1);
+        t3 = f.sub(&t3, &t4);
+        t4 = f.add(y1, z1);
+        let mut x3 = f.add(y2, z2);
+        t4 = f.mul(&t4, &x3);
+        x3 = f.add(&t1, &t2);
+        t4 = f.sub(&t4, &x3);
+        x3 = f.add(x1, z1);
+        let mut y3 = f.add(x2, z2);
+        x3 = f.mul(&x3, &y3);
+        y3 = f.add(&t0, &t2);
+        y3 = f.sub(&x3, &y3);
+        let mut z3 = f.mul(&self.b, &t2);
+        x3 = f.sub(&y3, &z3);
+        z3 = f.add(&x3, &x3);
+        x3 = f.add(&x3, &z3);
+        z3 = f.sub(&t1, &x3);
+        x3 = f.add(&t1, &x3);
+        y3 = f.mul(&self.b, &y3);
+        t1 = f.add(&t2, &t2);
+        t2 = f.add(&t1, &t2);
+        y3 = f.sub(&y3, &t2);
+        y3 = f.sub(&y3, &t0);
+        t1 = f.add(&y3, &y3);
+        y3 = f.add(&t1, &y3);
+        t1 = f.add(&t0, &t0);
+        t0 = f.add(&t1, &t0);
+        t0 = f.sub(&t0, &t2);
+        t1 = f.mul(&t4, &y3);
+        t2 = f.mul(&t0, &y3);
+        y3 = f.mul(&x3, &z3);
+        y3 = f.add(&y3, &t2);
+        x3 = f.mul(&t3, &x3);
+        x3 = f.sub(&x3, &t1);
+        z3 = f.mul(&t4, &z3);
+        t1 = f.mul(&t3, &t0);
+        z3 = f.add(&z3, &t1);
+
+        EcPoint { x: x3, y: y3, z: z3 }
+    }
+
+    //
+    // Scalar multiplication using a Montgomery ladder over all bits of the
+    // group order with constant-time conditional swaps.
+    //
+    pub fn mul(&self, p: &EcPoint, k: &BigUint) -> EcPoint
+    {
+        let mut r0 = self.identity();
+        let mut r1 = p.clone();
+
+        for i in (0..self.order().bits()).rev() {
+            let bit = k.bit(i) as u64;
+            ct_swap(bit, &mut r0.x, &mut r1.x);
+            ct_swap(bit, &mut r0.y, &mut r1.y);
+            ct_swap(bit, &mut r0.z, &mut r1.z);
+            r1 = self.add(&r0, &r1);
+            r0 = self.add(&r0, &r0);
+            ct_swap(bit, &mut r0.x, &mut r1.x);
+            ct_swap(bit, &mut r0.y, &mut r1.y);
+            ct_swap(bit, &mut r0.z, &mut r1.z);
+        }
+        return r0;
+    }
+
+    //
+    // Multiply the base point by a scalar.
+    //
+    pub fn mul_base(&self, k: &BigUint) -> EcPoint
+    {
+        return self.mul(&self.g, k);
+    }
+
+    pub fn is_identity(&self, p: &EcPoint) -> bool
+    {
+        return self.field.is_zero(&p.z);
+    }
+
+    //
+    // Convert to affine coordinates (x, y) as integers.
+    //
+    pub fn to_affine(&self, p: &EcPoint) -> Option<(BigUint, BigUint)>
+    {
+        if self.is_identity(p) {
+            return None;
+        }
+        let zinv = self.field.inv_prime(&p.z);
+        let x = self.field.from_mont(&self.field.mul(&p.x, &zinv));
+        let y = self.field.from_mont(&self.field.mul(&p.y, &zinv));
+        return Some((x, y));
+    }
+
+    //
+    // Create a point from affine coordinates after checking that it lies
+    // on the curve.
+    //
+    pub fn from_affine(&self, x: &BigUint, y: &BigUint) -> Result<EcPoint, String>
+    {
+        let f = &self.field;
+        if x >= f.modulus() || y >= f.modulus() {
+            return Err("EC point coordinate out of range".to_string());
+        }
+        let (xm, ym) = (f.to_mont(x), f.to_mont(y));
+
+        // y^2 = x^3 - 3x + b
+        let lhs = f.mul(&ym, &ym);
+        let x3 = f.mul(&f.mul(&xm, &xm), &xm);
+        let three_x = f.add(&f.add(&xm, &xm), &xm);
+        let rhs = f.add(&f.sub(&x3, &three_x), &self.b);
+        if lhs != rhs {
+            return Err("EC point is not on the curve".to_string());
+        }
+        Ok(EcPoint { x: xm, y: ym, z: f.one() })
+    }
+
+    //
+    // Encode a point in SEC1 uncompressed form 04 || x || y.
+    //
+    pub fn encode_point(&self, p: &EcPoint) -> Vec<u8>
+    {
+        let len = self.curve.field_len();
+        let (x, y) = self.to_affine(p).expect("finite point");
+        let mut out = vec![0x04];
+        out.extend_from_slice(&x.to_bytes_be_padded(len));
+        out.extend_from_slice(&y.to_bytes_be_padded(len));
+        return out;
+    }
+
+    //
+    // Decode a point in SEC1 uncompressed or compressed form.
+    //
+    pub fn decode_point(&self, data: &[u8]) -> Result<EcPoint, String>
+    {
+        let len = self.curve.field_len();
+        match data.first() {
+            Some(0x04) if data.len() == 1 + 2 * len => {
+                let x = BigUint::from_bytes_be(&data[1..1 + len]);
+                let y = BigUint::from_bytes_be(&data[1 + len..]);
+                self.from_affine(&x, &y)
+            }
+            Some(0x02) | Some(0x03) if data.len() == 1 + len => {
+                let f = &self.field;
+                let x = BigUint::from_bytes_be(&data[1..]);
+                if x >= *f.modulus() {
+                    return Err("EC point coordinate out of range".to_string());
+                }
+                let xm = f.to_mont(&x);
+                let x3 = f.mul(&f.mul(&xm, &xm), &xm);
+                let three_x = f.add(&f.add(&xm, &xm), &xm);
+                let rhs = f.add(&f.sub(&x3, &three_x), &self.b);
+
+                // all three field primes are congruent 3 mod 4
+                let exp = f.modulus().add_u64(1).shr(2);
+                let mut y = f.from_mont(&f.pow_mont(&rhs, &exp));
+                if y.is_odd() != (data[0] == 0x03) {
+                    y = f.modulus().sub(&y);
+                }
+                self.from_affine(&x, &y)
+            }
+            _ => { Err("invalid EC point encoding".to_string()) }
+        }
+    }
+}
+
+/// ECDSA public key
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EcPublicKey {
+    /// curve the key is defined on
+    pub curve: Curve,
+    /// SEC1 uncompressed point encoding
+    pub point: Vec<u8>,
+}
+
+/// ECDSA private key
+#[derive(Clone, Debug)]
+pub struct EcPrivateKey {
+    /// curve the key is defined on
+    pub curve: Curve,
+    /// private scalar
+    pub d: BigUint,
+    /// SEC1 uncompressed encoding of the public point
+    pub point: Vec<u8>,
+}
+
+//
+// Convert a message hash to an integer, keeping the leftmost bits up to
+// the size of the group order.
+//
+fn hash_to_scalar(group: &EcGroup, digest: &[u8]) -> BigUint
+{
+    let e = BigUint::from_bytes_be(digest);
+    let bits = group.order().bits();
+    if 8 * digest.len() > bits {
+        return e.shr(8 * digest.len() - bits);
+    }
+    return e;
+}
+
+impl EcPrivateKey {
+    //
+    // Generate a random private key on the given curve.
+    //
+    pub fn generate(curve: Curve) -> EcPrivateKey
+    {
+        let group = curve.group();
+        let d = BigUint::random_below(group.order());
+        let point = group.encode_point(&group.mul_base(&d));
+        EcPrivateKey { curve, d, point }
+    }
+
+    //
+    // Create a key from a private scalar.
+    //
+    pub fn from_scalar(curve: Curve, d: BigUint) -> Result<EcPrivateKey, String>
+    {
+        let group = curve.group();
+        if d.is_zero() || d >= *group.order() {
+            return Err("EC private key out of range".to_string());
+        }
+        let point = group.encode_point(&group.mul_base(&d));
+        Ok(EcPrivateKey { curve, d, point })
+    }
+
+    pub fn public_key(&self) -> EcPublicKey
+    {
+        EcPublicKey { curve: self.curve, point: self.point.clone() }
+    }
+
+    //
+    // Create an ECDSA signature encoded as Ecdsa-Sig-Value (RFC 3279).
+    //
+    pub fn sign(&self, hash: HashAlgorithm, data: &[u8]) -> Vec<u8>
+    {
+        let group = self.curve.group();
+        let n = &group.scalar;
+        let e = n.to_mont(&hash_to_scalar(group, &hash.hash(data)));
+        let d = n.to_mont(&self.d);
+
+        loop {
+            let k = BigUint::random_below(group.order());
+            let (x, _) = group.to_affine(&group.mul_base(&k)).expect("finite point");
+            let r = x.rem(group.order());
+            if r.is_zero() {
+                continue;
+            }
+            // s = k^-1 (e + r d) mod n
+            let kinv = n.inv_prime(&n.to_mont(&k));
+            let s = n.from_mont(&n.mul(&kinv, &n.add(&e, &n.mul(&n.to_mont(&r), &d))));
+            if s.is_zero() {
+                continue;
+            }
+            return asn1_sequence(&[
+                asn1_integer(&r.to_bytes_be()),
+                asn1_integer(&s.to_bytes_be()),
+            ]);
+        }
+    }
+
+    //
+    // Encode as SEC1 ECPrivateKey (RFC 5915), optionally including the
+    // namedCurve parameters.
+    //
+    pub fn to_sec1(&self, with_params: bool) -> Vec<u8>
+    {
+        let mut items = vec![
+            asn1_integer_from_u64(1),
+            asn1_octet_string(&self.d.to_bytes_be_padded(self.curve.field_len())),
+        ];
+        if with_params {
+            items.push(asn1_wrap(ASN1_CONTEXT_C_0, &[&asn1_oid(self.curve.oid())]));
+        }
+        items.push(asn1_wrap(ASN1_CONTEXT_C_1, &[&asn1_bitstring(&self.point)]));
+        return asn1_sequence(&items);
+    }
+
+    //
+    // Decode a SEC1 ECPrivateKey, the curve being given by the enclosing
+    // PKCS#8 structure or the embedded namedCurve parameters.
+    //
+    pub fn from_sec1(der: &[u8], curve: Option<Curve>) -> Result<EcPrivateKey, String>
+    {
+        let obj = asn1_parse(der)?;
+        if obj.tag != ASN1_SEQUENCE {
+            return Err("not an ECPrivateKey".to_string());
+        }
+        let mut parser = obj.parser();
+        if parser.expect(ASN1_INTEGER)?.to_u64()? != 1 {
+            return Err("unsupported ECPrivateKey version".to_string());
+        }
+        let d = parser.expect(ASN1_OCTET_STRING)?.content;
+        let mut named = None;
+        if let Some(params) = parser.optional(ASN1_CONTEXT_C_0)? {
+            let oid = params.parser().expect(ASN1_OID)?.to_oid()?;
+            named = Some(Curve::from_oid(&oid)
+                         .ok_or(format!("unsupported curve {}", oid_display(&oid)))?);
+        }
+        let point = match parser.optional(ASN1_CONTEXT_C_1)? {
+            Some(obj) => { Some(obj.parser().expect(ASN1_BIT_STRING)?
+                                   .to_bitstring_octets()?.to_vec()) }
+            None      => { None }
+        };
+        parser.finish()?;
+
+        let curve = match (curve, named) {
+            (Some(c), Some(n)) if c != n => {
+                return Err("conflicting EC curve parameters".to_string());
+            }
+            (Some(c), _) | (None, Some(c)) => { c }
+            (None, None) => { return Err("missing EC curve parameters".to_string()); }
+        };
+        if d.len() != curve.field_len() {
+            return Err("invalid EC private key length".to_string());
+        }
+        let key = EcPrivateKey::from_scalar(curve, BigUint::from_bytes_be(d))?;
+        if let Some(point) = point {
+            // the embedded public key may be compressed, compare re-encoded
+            let group = curve.group();
+            let point = group.decode_point(&point)
+                             .map_err(|_| "invalid EC public key in ECPrivateKey")?;
+            if group.encode_point(&point) != key.point {
+                return Err("EC public key in ECPrivateKey does not match the private key".to_string());
+            }
+        }
+        return Ok(key);
+    }
+}
+
+impl EcPublicKey {
+    //
+    // Create a public key from a SEC1 point encoding, validating the point.
+    //
+    pub fn from_point(curve: Curve, data: &[u8]) -> Result<EcPublicKey, String>
+    {
+        let group = curve.group();
+        let point = group.decode_point(data)?;
+        Ok(EcPublicKey { curve, point: group.encode_point(&point) })
+    }
+
+    //
+    // Verify an ECDSA signature encoded as Ecdsa-Sig-Value.
+    //
+    pub fn verify(&self, hash: HashAlgorithm, data: &[u8], sig: &[u8]) -> bool
+    {
+        let group = self.curve.group();
+        let n = &group.scalar;
+        let order = group.order();
+
+        let parse = || -> Result<(BigUint, BigUint), String> {
+            let obj = asn1_parse(sig)?;
+            if obj.tag != ASN1_SEQUENCE {
+                return Err("not an Ecdsa-Sig-Value".to_string());
+            }
+            let mut parser = obj.parser();
+            let r = BigUint::from_bytes_be(parser.expect(ASN1_INTEGER)?.to_unsigned()?);
+            let s = BigUint::from_bytes_be(parser.expect(ASN1_INTEGER)?.to_unsigned()?);
+            parser.finish()?;
+            Ok((r, s))
+        };
+        let (r, s) = match parse() {
+            Ok(rs) => { rs }
+            Err(_) => { return false; }
+        };
+        if r.is_zero() || s.is_zero() || r >= *order || s >= *order {
+            return false;
+        }
+        let q = match group.decode_point(&self.point) {
+            Ok(q) => { q }
+            Err(_) => { return false; }
+        };
+        let e = n.to_mont(&hash_to_scalar(group, &hash.hash(data)));
+        let w = n.inv_prime(&n.to_mont(&s));
+        let u1 = n.from_mont(&n.mul(&e, &w));
+        let u2 = n.from_mont(&n.mul(&n.to_mont(&r), &w));
+
+        let p = group.add(&group.mul_base(&u1), &group.mul(&q, &u2));
+        match group.to_affine(&p) {
+            Some((x, _)) => { x.rem(order) == r }
+            None => { false }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex;
+
+    fn signature(r: &str, s: &str) -> Vec<u8>
+    {
+        let r = BigUint::from_hex(r).unwrap();
+        let s = BigUint::from_hex(s).unwrap();
+        return asn1_sequence(&[asn1_integer(&r.to_bytes_be()), asn1_integer(&s.to_bytes_be())]);
+    }
+
+    // RFC 6979, A.2.5 and A.2.6
+    #[test]
+    fn rfc6979_vectors()
+    {
+        let key = EcPrivateKey::from_scalar(Curve::P256, BigUint::from_hex(
+            "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721").unwrap()).unwrap();
+        assert_eq!(key.point, hex("04
+            60fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb6
+            7903fe1008b8bc99a41ae9e95628bc64f2f1b20c2d7e9f5177a3c294d4462299"));
+        let public = key.public_key();
+        let sample = signature("efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716",
+                               "f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8");
+        assert!(public.verify(HashAlgorithm::Sha256, b"sample", &sample));
+        assert!(!public.verify(HashAlgorithm::Sha256, b"test", &sample));
+        let test = signature("f1abb023518351cd71d881567b1ea663ed3efcf6c5132b354f28d3b0b7d38367",
+                             "019f4113742a2b14bd25926b49c649155f267e60d3814b4c0cc84250e46f0083");
+        assert!(public.verify(HashAlgorithm::Sha256, b"test", &test));
+
+        let key = EcPrivateKey::from_scalar(Curve::P384, BigUint::from_hex(
+            "6b9d3dad2e1b8c1c05b19875b6659f4de23c3b667bf297ba9aa47740787137d8\
+             96d5724e4c70a825f872c9ea60d2edf5").unwrap()).unwrap();
+        assert_eq!(key.point, hex("04
+            ec3a4e415b4e19a4568618029f427fa5da9a8bc4ae92e02e06aae5286b300c64def8f0ea9055866064a254515480bc13
+            8015d9b72d7d57244ea8ef9ac0c621896708a59367f9dfb9f54ca84b3f1c9db1288b231c3ae0d4fe7344fd2533264720"));
+        let sample = signature("94edbb92a5ecb8aad4736e56c691916b3f88140666ce9fa73d64c4ea95ad133c\
+                                81a648152e44acf96e36dd1e80fabe46",
+                               "99ef4aeb15f178cea1fe40db2603138f130e740a19624526203b6351d0a3a94f\
+                                a329c145786e679e7b82c71a38628ac8");
+        assert!(key.public_key().verify(HashAlgorithm::Sha384, b"sample", &sample));
+        assert!(!key.public_key().verify(HashAlgorithm::Sha256, b"sample", &sample));
+    }
+
+    // P-521 with SHA-512, signed by pyca/cryptography
+    #[test]
+    fn p521_vector()
+    {
+        let key = EcPrivateKey::from_scalar(Curve::P521, BigUint::from_hex(
+            "003c4cb63ad61aa3fc7b1f5874fa0b7145354ae880eaf01c8ceb3d4211e777fb84d17e8180b20040c87c06e6a5\
+             4a90ca01fa1ebab7ec51c62788bf380183c173127d").unwrap()).unwrap();
+        let sample = signature("0111a67152283c5877eed6b50e4af0b0a3c66803deb06cf27b01baa865197cbdd9ac85312065\
+                                729666db08e9df5c045d5a2bf81d6ec751dbac9dd09682ed26d468c6",
+                               "009f76f7f6e5d80083d6acf5277dda6dba1cbc18e29323efcf45bbbd3fb5be1d7a2ff300f11e\
+                                9989d3473e6865e6456b3a2389be9272ae27284bb6032527f9c1bc00");
+        assert!(key.public_key().verify(HashAlgorithm::Sha512, b"sample", &sample));
+        assert!(!key.public_key().verify(HashAlgorithm::Sha512, b"other", &sample));
+    }
+
+    #[test]
+    fn sign_and_verify()
+    {
+        for curve in [Curve::P256, Curve::P384, Curve::P521] {
+            let key = EcPrivateKey::generate(curve);
+            let public = key.public_key();
+            let sig = key.sign(HashAlgorithm::Sha256, b"data");
+            assert!(public.verify(HashAlgorithm::Sha256, b"data", &sig));
+            assert!(!public.verify(HashAlgorithm::Sha256, b"Data", &sig));
+
+            let order = curve.group().order().to_bytes_be();
+            let one = asn1_integer(&[1]);
+            for (r, s) in [(asn1_integer(&[0]), one.clone()), (one.clone(), asn1_integer(&[0])),
+                           (asn1_integer(&order), one.clone())] {
+                assert!(!public.verify(HashAlgorithm::Sha256, b"data", &asn1_sequence(&[r, s])));
+            }
+
+            let decoded = EcPrivateKey::from_sec1(&key.to_sec1(true), None).unwrap();
+            assert_eq!((decoded.curve, &decoded.d, &decoded.point), (curve, &key.d, &key.point));
+            let decoded = EcPrivateKey::from_sec1(&key.to_sec1(false), Some(curve)).unwrap();
+            assert_eq!(decoded.d, key.d);
+            assert!(EcPublicKey::from_point(curve, &key.point[..key.point.len() - 1]).is_err());
+
+            // the embedded public key must be d*G, compressed or not
+            let len = curve.field_len();
+            let sec1 = |point: &[u8]| asn1_sequence(&[
+                asn1_integer(&[1]), asn1_octet_string(&key.d.to_bytes_be_padded(len)),
+                asn1_wrap(ASN1_CONTEXT_C_1, &[&asn1_bitstring(point)]),
+            ]);
+            let mut compressed = vec![0x02 | (key.point[2 * len] & 0x01)];
+            compressed.extend_from_slice(&key.point[1..=len]);
+            assert_eq!(EcPrivateKey::from_sec1(&sec1(&compressed), Some(curve)).unwrap().point,
+                       key.point);
+            let other = EcPrivateKey::generate(curve);
+            assert_eq!(EcPrivateKey::from_sec1(&sec1(&other.point), Some(curve)).unwrap_err(),
+                       "EC public key in ECPrivateKey does not match the private key");
+            compressed[0] ^= 0x01;
+            assert!(EcPrivateKey::from_sec1(&sec1(&compressed), Some(curve)).is_err());
+            assert!(EcPrivateKey::from_sec1(&sec1(&[0x04, 0x01]), Some(curve)).unwrap_err()
+                        .starts_with("invalid EC public key"));
+        }
+        assert!(EcPrivateKey::from_scalar(Curve::P256, BigUint::zero()).is_err());
+        assert!(EcPrivateKey::from_scalar(Curve::P256, Curve::P256.group().order().clone()).is_err());
+    }
+}
@@ -0,0 +1,526 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! Pure Ed25519 and Ed448 signatures (RFC 8032).
+
+use std::sync::OnceLock;
+use crate::oid::*;
+use crate::crypto::bigint::{BigUint, Monty, ct_swap};
+use crate::crypto::hash::{sha512, shake256};
+use crate::crypto::rng::rng_bytes;
+
+/// Edwards curves
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EdCurve {
+    Ed25519,
+    Ed448,
+}
+
+/// Precomputed arithmetic context of an Edwards curve
+struct EdGroup {
+    curve: EdCurve,
+    /// Montgomery context of the field prime
+    field: Monty,
+    /// order of the base point
+    order: BigUint,
+    /// curve coefficient d in Montgomery form
+    d: Vec<u64>,
+    /// square root of -1, only used for Ed25519
+    sqrt_m1: Vec<u64>,
+    /// base point
+    b: EdPoint,
+}
+
+/// Point in projective coordinates (X:Y:Z), field elements in Montgomery
+/// form
+#[derive(Clone, Debug)]
+struct EdPoint {
+    x: Vec<u64>,
+    y: Vec<u64>,
+    z: Vec<u64>,
+}
+
+// Ed448 base point y-coordinate, x being even
+const ED448_BASE_Y: &str =
+    "693f46716eb6bc248876203756c9c7624bea73736ca3984087789c1e05a0c2d7\
+     3ad3ff1ce67c39c4fdbd132c4ed7c8ad9808795bf230fa14";
+
+const ED25519_ORDER: &str =
+    "1000000000000000000000000000000014def9dea2f79cd65812631a5cf5d3ed";
+
+const ED448_ORDER: &str =
+    "3fffffffffffffffffffffffffffffffffffffffffffffffffffffff7cca23e9\
+     c44edb49aed63690216cc2728dc58f552378c292ab5844f3";
+
+impl EdCurve {
+    pub fn from_oid(oid: &str) -> Option<EdCurve>
+    {
+        match oid {
+            OID_ED25519 => { Some(EdCurve::Ed25519) }
+            OID_ED448   => { Some(EdCurve::Ed448) }
+            _           => { None }
+        }
+    }
+
+    pub fn oid(&self) -> &'static str
+    {
+        match self {
+            EdCurve::Ed25519 => { OID_ED25519 }
+            EdCurve::Ed448   => { OID_ED448 }
+        }
+    }
+
+    //
+    // Length of private and public keys in octets.
+    //
+    pub fn key_len(&self) -> usize
+    {
+        match self {
+            EdCurve::Ed25519 => { 32 }
+            EdCurve::Ed448   => { 57 }
+        }
+    }
+
+    //
+    // Length of a signature in octets.
+    //
+    pub fn signature_len(&self) -> usize
+    {
+        return 2 * self.key_len();
+    }
+
+    //
+    // Number of scalar bits processed by the Montgomery ladder.
+    //
+    fn scalar_bits(&self) -> usize
+    {
+        match self {
+            EdCurve::Ed25519 => { 255 }
+            EdCurve::Ed448   => { 448 }
+        }
+    }
+
+    fn group(&self) -> &'static EdGroup
+    {
+        static ED25519: OnceLock<EdGroup> = OnceLock::new();
+        static ED448: OnceLock<EdGroup> = OnceLock::new();
+
+        match self {
+            EdCurve::Ed25519 => { ED25519.get_or_init(|| EdGroup::new(*self)) }
+            EdCurve::Ed448   => { ED448.get_or_init(|| EdGroup::new(*self)) }
+        }
+    }
+
+    //
+    // Hash function H of the curve, including the dom4 prefix for Ed448
+    // with empty context.
+    //
+    fn hash(&self, parts: &[&[u8]]) -> Vec<u8>
+    {
+        let mut data = Vec::new();
+        if *self == EdCurve::Ed448 {
+            data.extend_from_slice(b"SigEd448\x00\x00");
+        }
+        for part in parts {
+            data.extend_from_slice(part);
+        }
+        match self {
+            EdCurve::Ed25519 => { sha512(&data).to_vec() }
+            EdCurve::Ed448   => { shake256(&data, 114) }
+        }
+    }
+
+    //
+    // Derive the secret scalar and the nonce prefix from a private key.
+    //
+    fn expand(&self, seed: &[u8]) -> (BigUint, Vec<u8>)
+    {
+        let len = self.key_len();
+        let mut h = match self {
+            EdCurve::Ed25519 => { sha512(seed).to_vec() }
+            EdCurve::Ed448   => { shake256(seed, 114) }
+        };
+        match self {
+            EdCurve::Ed25519 => {
+                h[0] &= 0xf8;
+                h[31] &= 0x7f;
+                h[31] |= 0x40;
+            }
+            EdCurve::Ed448 => {
+                h[0] &= 0xfc;
+                h[55] |= 0x80;
+                h[56] = 0;
+            }
+        }
+        return (BigUint::from_bytes_le(&h[..len]), h[len..].to_vec());
+    }
+}
+
+impl EdGroup {
+    fn new(curve: EdCurve) -> EdGroup
+    {
+        let (p, order) = match curve {
+            EdCurve::Ed25519 => {
+                (BigUint::one().shl(255).sub_u64(19),
+                 BigUint::from_hex(ED25519_ORDER).unwrap())
+            }
+            EdCurve::Ed448 => {
+                (BigUint::one().shl(448).sub(&BigUint::one().shl(224)).sub_u64(1),
+                 BigUint::from_hex(ED448_ORDER).unwrap())
+            }
+        };
+        let field = Monty::new(&p);
+        let (d, sqrt_m1, base_y) = match curve {
+            EdCurve::Ed25519 => {
+                // d = -121665/121666, sqrt(-1) = 2^((p-1)/4), y = 4/5
+                let num = field.neg(&field.to_mont(&BigUint::from_u64(121665)));
+                let den = field.to_mont(&BigUint::from_u64(121666));
+                let d = field.mul(&num, &field.inv_prime(&den));
+                let sqrt_m1 = field.pow_mont(&field.to_mont(&BigUint::from_u64(2)),
+                                             &p.sub_u64(1).shr(2));
+                let y = field.mul(&field.to_mont(&BigUint::from_u64(4)),
+                                  &field.inv_prime(&field.to_mont(&BigUint::from_u64(5))));
+                (d, sqrt_m1, field.from_mont(&y))
+            }
+            EdCurve::Ed448 => {
+                let d = field.neg(&field.to_mont(&BigUint::from_u64(39081)));
+                (d, field.one(), BigUint::from_hex(ED448_BASE_Y).unwrap())
+            }
+        };
+        let mut group = EdGroup {
+            curve, field, order, d, sqrt_m1,
+            b: EdPoint { x: Vec::new(), y: Vec::new(), z: Vec::new() },
+        };
+        let mut encoded = base_y.to_bytes_le_padded(curve.key_len());
+        encoded[curve.key_len() - 1] &= 0x7f;
+        group.b = group.decode(&encoded).expect("valid base point");
+        return group;
+    }
+
+    fn identity(&self) -> EdPoint
+    {
+        let len = self.field.len();
+        EdPoint { x: vec![0; len], y: self.field.one(), z: self.field.one() }
+    }
+
+    //
+    // Unified addition in projective coordinates, complete for both curves
+    // since a is a square and d a non-square.
+    //
+    fn add(&self, p: &EdPoint, q: &EdPoint) -> EdPoint
+    {
+        let f = &self.field;
+        let a = f.mul(&p.z, &q.z);
+        let b = f.mul(&a, &a);
+        let c = f.mul(&p.x, &q.x);
+        let d = f.mul(&p.y, &q.y);
+        let e = f.mul(&f.mul(&self.d, &c), &d);
+        let ff = f.sub(&b, &e);
+        let g = f.add(&b, &e);
+        let h = f.mul(&f.add(&p.x, &p.y), &f.add(&q.x, &q.y));
+        let x3 = f.mul(&f.mul(&a, &ff), &f.sub(&f.sub(&h, &c), &d));
+        let dc = match self.curve {
+            EdCurve::Ed25519 => { f.add(&d, &c) }  // a = -1
+            EdCurve::Ed448   => { f.sub(&d, &c) }  // a = 1
+        };
+        let y3 = f.mul(&f.mul(&a, &g), &dc);
+        let z3 = f.mul(&ff, &g);
+        EdPoint { x: x3, y: y3, z: z3 }
+    }
+
+    //
+    // Constant-time scalar multiplication with a Montgomery ladder.
+    //
+    fn mul(&self, p: &EdPoint, k: &BigUint) -> EdPoint
+    {
+        let mut r0 = self.identity();
+        let mut r1 = p.clone();
+
+        for i in (0..self.curve.scalar_bits()).rev() {
+            let bit = k.bit(i) as u64;
+            ct_swap(bit, &mut r0.x, &mut r1.x);
+            ct_swap(bit, &mut r0.y, &mut r1.y);
+            ct_swap(bit, &mut r0.z, &mut r1.z);
+            r1 = self.add(&r0, &r1);
+            r0 = self.add(&r0, &r0);
+            ct_swap(bit, &mut r0.x, &mut r1.x);
+            ct_swap(bit, &mut r0.y, &mut r1.y);
+            ct_swap(bit, &mut r0.z, &mut r1.z);
+        }
+        return r0;
+    }
+
+    //
+    // Encode the y-coordinate in little-endian order with the sign of x
+    // in the most significant bit.
+    //
+    fn encode(&self, p: &EdPoint) -> Vec<u8>
+    {
+        let f = &self.field;
+        let zinv = f.inv_prime(&p.z);
+        let x = f.from_mont(&f.mul(&p.x, &zinv));
+        let y = f.from_mont(&f.mul(&p.y, &zinv));
+        let len = self.curve.key_len();
+        let mut out = y.to_bytes_le_padded(len);
+        if x.is_odd() {
+            out[len - 1] |= 0x80;
+        }
+        return out;
+    }
+
+    //
+    // Decode and validate a point (RFC 8032, 5.1.3 and 5.2.3).
+    //
+    fn decode(&self, data: &[u8]) -> Result<EdPoint, String>
+    {
+        let f = &self.field;
+        let len = self.curve.key_len();
+        if data.len() != len {
+            return Err("invalid EdDSA point length".to_string());
+        }
+        let mut bytes = data.to_vec();
+        let sign = bytes[len - 1] >> 7;
+        bytes[len - 1] &= 0x7f;
+        let y = BigUint::from_bytes_le(&bytes);
+        if y >= *f.modulus() {
+            return Err("EdDSA point coordinate out of range".to_string());
+        }
+        let ym = f.to_mont(&y);
+        let yy = f.mul(&ym, &ym);
+        let u = f.sub(&yy, &f.one());
+        let dyy = f.mul(&self.d, &yy);
+        let p = f.modulus();
+
+        let x = match self.curve {
+            EdCurve::Ed25519 => {
+                // v = d y^2 + 1, x = u v^3 (u v^7)^((p-5)/8)
+                let v = f.add(&dyy, &f.one());
+                let v3 = f.mul(&f.mul(&v, &v), &v);
+                let v7 = f.mul(&f.mul(&v3, &v3), &v);
+                let t = f.pow_mont(&f.mul(&u, &v7), &p.sub_u64(5).shr(3));
+                let mut x = f.mul(&f.mul(&u, &v3), &t);
+                let vxx = f.mul(&v, &f.mul(&x, &x));
+                if vxx == f.neg(&u) {
+                    x = f.mul(&x, &self.sqrt_m1);
+                } else if vxx != u {
+                    return Err("EdDSA point is not on the curve".to_string());
+                }
+                x
+            }
+            EdCurve::Ed448 => {
+                // v = d y^2 - 1, x = u^3 v (u^5 v^3)^((p-3)/4)
+                let v = f.sub(&dyy, &f.one());
+                let u2 = f.mul(&u, &u);
+                let u3 = f.mul(&u2, &u);
+                let u5 = f.mul(&u3, &u2);
+                let v3 = f.mul(&f.mul(&v, &v), &v);
+                let t = f.pow_mont(&f.mul(&u5, &v3), &p.sub_u64(3).shr(2));
+                let x = f.mul(&f.mul(&u3, &v), &t);
+                if f.mul(&v, &f.mul(&x, &x)) != u {
+                    return Err("EdDSA point is not on the curve".to_string());
+                }
+                x
+            }
+        };
+        let mut xi = f.from_mont(&x);
+        if xi.is_zero() && sign == 1 {
+            return Err("invalid EdDSA point encoding".to_string());
+        }
+        if xi.is_odd() != (sign == 1) {
+            xi = p.sub(&xi);
+        }
+        Ok(EdPoint { x: f.to_mont(&xi), y: ym, z: f.one() })
+    }
+
+    //
+    // Compare two points in projective coordinates.
+    //
+    fn equal(&self, p: &EdPoint, q: &EdPoint) -> bool
+    {
+        let f = &self.field;
+        return f.mul(&p.x, &q.z) == f.mul(&q.x, &p.z) &&
+               f.mul(&p.y, &q.z) == f.mul(&q.y, &p.z);
+    }
+}
+
+/// EdDSA public key
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EdPublicKey {
+    /// curve the key is defined on
+    pub curve: EdCurve,
+    /// encoded public point
+    pub key: Vec<u8>,
+}
+
+/// EdDSA private key
+#[derive(Clone, Debug)]
+pub struct EdPrivateKey {
+    /// curve the key is defined on
+    pub curve: EdCurve,
+    /// random private key octets
+    pub seed: Vec<u8>,
+    /// encoded public point
+    pub public: Vec<u8>,
+}
+
+impl EdPrivateKey {
+    pub fn generate(curve: EdCurve) -> EdPrivateKey
+    {
+        let seed = rng_bytes(curve.key_len());
+        return EdPrivateKey::from_seed(curve, &seed).expect("valid seed length");
+    }
+
+    //
+    // Create a key from its private key octets, deriving the public key.
+    //
+    pub fn from_seed(curve: EdCurve, seed: &[u8]) -> Result<EdPrivateKey, String>
+    {
+        if seed.len() != curve.key_len() {
+            return Err("invalid EdDSA private key length".to_string());
+        }
+        let group = curve.group();
+        let (s, _) = curve.expand(seed);
+        let public = group.encode(&group.mul(&group.b, &s));
+        Ok(EdPrivateKey { curve, seed: seed.to_vec(), public })
+    }
+
+    pub fn public_key(&self) -> EdPublicKey
+    {
+        EdPublicKey { curve: self.curve, key: self.public.clone() }
+    }
+
+    //
+    // Create a pure EdDSA signature R || S.
+    //
+    pub fn sign(&self, msg: &[u8]) -> Vec<u8>
+    {
+        let curve = self.curve;
+        let group = curve.group();
+        let (s, prefix) = curve.expand(&self.seed);
+
+        let r = BigUint::from_bytes_le(&curve.hash(&[&prefix, msg])).rem(&group.order);
+        let rp = group.encode(&group.mul(&group.b, &r));
+        let k = BigUint::from_bytes_le(&curve.hash(&[&rp, &self.public, msg])).rem(&group.order);
+        let sig_s = r.mod_add(&k.mod_mul(&s.rem(&group.order), &group.order), &group.order);
+
+        let mut sig = rp;
+        sig.extend_from_slice(&sig_s.to_bytes_le_padded(curve.key_len()));
+        return sig;
+    }
+}
+
+impl EdPublicKey {
+    //
+    // Create a public key from its encoding, validating the point.
+    //
+    pub fn from_bytes(curve: EdCurve, data: &[u8]) -> Result<EdPublicKey, String>
+    {
+        curve.group().decode(data)?;
+        Ok(EdPublicKey { curve, key: data.to_vec() })
+    }
+
+    //
+    // Verify a pure EdDSA signature, checking [S]B = R + [k]A.
+    //
+    pub fn verify(&self, msg: &[u8], sig: &[u8]) -> bool
+    {
+        let curve = self.curve;
+        let group = curve.group();
+        let len = curve.key_len();
+        if sig.len() != curve.signature_len() {
+            return false;
+        }
+        let s = BigUint::from_bytes_le(&sig[len..]);
+        if s >= group.order {
+            return false;
+        }
+        let (a, r) = match (group.decode(&self.key), group.decode(&sig[..len])) {
+            (Ok(a), Ok(r)) => { (a, r) }
+            _ => { return false; }
+        };
+        let k = BigUint::from_bytes_le(&curve.hash(&[&sig[..len], &self.key, msg]))
+                .rem(&group.order);
+
+        let lhs = group.mul(&group.b, &s);
+        let rhs = group.add(&r, &group.mul(&a, &k));
+        return group.equal(&lhs, &rhs);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex;
+
+    // RFC 8032, section 7.1 TEST 1 and TEST 2, and section 7.4 "1 octet"
+    const VECTORS: &[(EdCurve, &str, &str, &str, &str)] = &[
+        (EdCurve::Ed25519,
+         "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+         "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+         "",
+         "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e06522490155
+          5fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b"),
+        (EdCurve::Ed25519,
+         "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+         "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+         "72",
+         "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da
+          085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00"),
+        (EdCurve::Ed448,
+         "c4eab05d357007c632f3dbb48489924d552b08fe0c353a0d4a1f00acda2c463a
+          fbea67c5e8d2877c5e3bc397a659949ef8021e954e0a12274e",
+         "43ba28f430cdff456ae531545f7ecd0ac834a55d9358c0372bfa0c6c6798c086
+          6aea01eb00742802b8438ea4cb82169c235160627b4c3a9480",
+         "03",
+         "26b8f91727bd62897af15e41eb43c377efb9c610d48f2335cb0bd0087810f435
+          2541b143c4b981b7e18f62de8ccdf633fc1bf037ab7cd779805e0dbcc0aae1cb
+          cee1afb2e027df36bc04dcecbf154336c19f0af7e0a6472905e799f1953d2a0f
+          f3348ab21aa4adafd1d234441cf807c03a00"),
+    ];
+
+    #[test]
+    fn rfc8032_vectors()
+    {
+        for (curve, seed, public, msg, sig) in VECTORS {
+            let key = EdPrivateKey::from_seed(*curve, &hex(seed)).unwrap();
+            assert_eq!(key.public, hex(public));
+            assert_eq!(key.sign(&hex(msg)), hex(sig));
+
+            let public = EdPublicKey::from_bytes(*curve, &hex(public)).unwrap();
+            assert!(public.verify(&hex(msg), &hex(sig)));
+            assert!(!public.verify(b"other", &hex(sig)));
+            let mut tampered = hex(sig);
+            tampered[1] ^= 1;
+            assert!(!public.verify(&hex(msg), &tampered));
+        }
+    }
+
+    #[test]
+    fn invalid_signatures()
+    {
+        for curve in [EdCurve::Ed25519, EdCurve::Ed448] {
+            let key = EdPrivateKey::generate(curve);
+            let public = key.public_key();
+            let sig = key.sign(b"data");
+            assert!(public.verify(b"data", &sig));
+            assert!(!public.verify(b"data", &sig[..sig.len() - 1]));
+
+            // S must be below the group order
+            let len = curve.key_len();
+            let mut high = sig.clone();
+            high[len..].copy_from_slice(&curve.group().order.to_bytes_le_padded(len));
+            assert!(!public.verify(b"data", &high));
+
+            assert!(EdPrivateKey::from_seed(curve, &[0; 31]).is_err());
+        }
+    }
+}
@@ -0,0 +1,623 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! MD5 (RFC 1321), SHA-1 (FIPS 180-4), SHA-2 (FIPS 180-4) and SHA-3/SHAKE (FIPS 202) hash
+//! functions.
+
+use crate::oid::*;
+
+/// Hash algorithms usable for signatures and key identifiers
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    Md5,
+    Sha1,
+    Sha224,
+    Sha256,
+    Sha384,
+    Sha512,
+    Sha3_224,
+    Sha3_256,
+    Sha3_384,
+    Sha3_512,
+}
+
+impl HashAlgorithm {
+    //
+    // Parse a hash algorithm given with --digest.
+    //
+    pub fn parse(name: &str) -> Option<HashAlgorithm>
+    {
+        match name {
+            "md5"      => { Some(HashAlgorithm::Md5) }
+            "sha1"     => { Some(HashAlgorithm::Sha1) }
+            "sha224"   => { Some(HashAlgorithm::Sha224) }
+            "sha256"   => { Some(HashAlgorithm::Sha256) }
+            "sha384"   => { Some(HashAlgorithm::Sha384) }
+            "sha512"   => { Some(HashAlgorithm::Sha512) }
+            "sha3_224" => { Some(HashAlgorithm::Sha3_224) }
+            "sha3_256" => { Some(HashAlgorithm::Sha3_256) }
+            "sha3_384" => { Some(HashAlgorithm::Sha3_384) }
+            "sha3_512" => { Some(HashAlgorithm::Sha3_512) }
+            _          => { None }
+        }
+    }
+
+    pub fn from_oid(oid: &str) -> Option<HashAlgorithm>
+    {
+        match oid {
+            OID_MD5      => { Some(HashAlgorithm::Md5) }
+            OID_SHA1     => { Some(HashAlgorithm::Sha1) }
+            OID_SHA224   => { Some(HashAlgorithm::Sha224) }
+            OID_SHA256   => { Some(HashAlgorithm::Sha256) }
+            OID_SHA384   => { Some(HashAlgorithm::Sha384) }
+            OID_SHA512   => { Some(HashAlgorithm::Sha512) }
+            OID_SHA3_224 => { Some(HashAlgorithm::Sha3_224) }
+            OID_SHA3_256 => { Some(HashAlgorithm::Sha3_256) }
+            OID_SHA3_384 => { Some(HashAlgorithm::Sha3_384) }
+            OID_SHA3_512 => { Some(HashAlgorithm::Sha3_512) }
+            _            => { None }
+        }
+    }
+
+    pub fn oid(&self) -> &'static str
+    {
+        match self {
+            HashAlgorithm::Md5      => { OID_MD5 }
+            HashAlgorithm::Sha1     => { OID_SHA1 }
+            HashAlgorithm::Sha224   => { OID_SHA224 }
+            HashAlgorithm::Sha256   => { OID_SHA256 }
+            HashAlgorithm::Sha384   => { OID_SHA384 }
+            HashAlgorithm::Sha512   => { OID_SHA512 }
+            HashAlgorithm::Sha3_224 => { OID_SHA3_224 }
+            HashAlgorithm::Sha3_256 => { OID_SHA3_256 }
+            HashAlgorithm::Sha3_384 => { OID_SHA3_384 }
+            HashAlgorithm::Sha3_512 => { OID_SHA3_512 }
+        }
+    }
+
+    //
+    // Size of the hash output in octets.
+    //
+    pub fn digest_len(&self) -> usize
+    {
+        match self {
+            HashAlgorithm::Md5      => { 16 }
+            HashAlgorithm::Sha1     => { 20 }
+            HashAlgorithm::Sha224   |
+            HashAlgorithm::Sha3_224 => { 28 }
+            HashAlgorithm::Sha256   |
+            HashAlgorithm::Sha3_256 => { 32 }
+            HashAlgorithm::Sha384   |
+            HashAlgorithm::Sha3_384 => { 48 }
+            HashAlgorithm::Sha512   |
+            HashAlgorithm::Sha3_512 => { 64 }
+        }
+    }
+
+    //
+    // Size of the input block in octets, the rate for SHA-3.
+    //
+    pub fn block_len(&self) -> usize
+    {
+        match self {
+            HashAlgorithm::Md5      |
+            HashAlgorithm::Sha1     |
+            HashAlgorithm::Sha224   |
+            HashAlgorithm::Sha256   => { 64 }
+            HashAlgorithm::Sha384   |
+            HashAlgorithm::Sha512   => { 128 }
+            HashAlgorithm::Sha3_224 => { 144 }
+            HashAlgorithm::Sha3_256 => { 136 }
+            HashAlgorithm::Sha3_384 => { 104 }
+            HashAlgorithm::Sha3_512 => { 72 }
+        }
+    }
+
+    pub fn name(&self) -> &'static str
+    {
+        match self {
+            HashAlgorithm::Md5      => { "md5" }
+            HashAlgorithm::Sha1     => { "sha1" }
+            HashAlgorithm::Sha224   => { "sha224" }
+            HashAlgorithm::Sha256   => { "sha256" }
+            HashAlgorithm::Sha384   => { "sha384" }
+            HashAlgorithm::Sha512   => { "sha512" }
+            HashAlgorithm::Sha3_224 => { "sha3_224" }
+            HashAlgorithm::Sha3_256 => { "sha3_256" }
+            HashAlgorithm::Sha3_384 => { "sha3_384" }
+            HashAlgorithm::Sha3_512 => { "sha3_512" }
+        }
+    }
+
+    //
+    // Hash a message.
+    //
+    pub fn hash(&self, data: &[u8]) -> Vec<u8>
+    {
+        match self {
+            HashAlgorithm::Md5      => { md5(data).to_vec() }
+            HashAlgorithm::Sha1     => { sha1(data).to_vec() }
+            HashAlgorithm::Sha224   => { sha256_core(&SHA224_IV, data)[..28].to_vec() }
+            HashAlgorithm::Sha256   => { sha256(data).to_vec() }
+            HashAlgorithm::Sha384   => { sha512_core(&SHA384_IV, data)[..48].to_vec() }
+            HashAlgorithm::Sha512   => { sha512(data).to_vec() }
+            HashAlgorithm::Sha3_224 => { Keccak::sha3(28, data) }
+            HashAlgorithm::Sha3_256 => { Keccak::sha3(32, data) }
+            HashAlgorithm::Sha3_384 => { Keccak::sha3(48, data) }
+            HashAlgorithm::Sha3_512 => { Keccak::sha3(64, data) }
+        }
+    }
+}
+
+//
+// Append the Merkle-Damgard padding with a length field of the given size.
+//
+fn md_pad(data: &[u8], block: usize, len_bytes: usize) -> Vec<u8>
+{
+    let mut msg = data.to_vec();
+    msg.push(0x80);
+    while !(msg.len() + len_bytes).is_multiple_of(block) {
+        msg.push(0);
+    }
+    let bits = (data.len() as u128) * 8;
+    msg.extend_from_slice(&bits.to_be_bytes()[16 - len_bytes..]);
+    return msg;
+}
+
+const MD5_K: [u32; 64] = [
+    0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee,
+    0xf57c0faf, 0x4787c62a, 0xa8304613, 0xfd469501,
+    0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be,
+    0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821,
+    0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa,
+    0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8,
+    0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed,
+    0xa9e3e905, 0xfcefa3f8, 0x676f02d9, 0x8d2a4c8a,
+    0xfffa3942, 0x8771f681, 0x6d9d6122, 0xfde5380c,
+    0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70,
+    0x289b7ec6, 0xeaa127fa, 0xd4ef3085, 0x04881d05,
+    0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665,
+    0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039,
+    0x655b59c3, 0x8f0ccc92, 0xffeff47d, 0x85845dd1,
+    0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1,
+    0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391,
+];
+
+//
+// MD5 hash, only to be used for legacy signatures.
+//
+pub fn md5(data: &[u8]) -> [u8; 16]
+{
+    const S: [u32; 16] = [7, 12, 17, 22, 5, 9, 14, 20, 4, 11, 16, 23, 6, 10, 15, 21];
+    let mut h: [u32; 4] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476];
+
+    // MD5 encodes the length in little-endian order
+    let mut msg = md_pad(data, 64, 8);
+    let n = msg.len();
+    msg[n - 8..].reverse();
+
+    for chunk in msg.chunks(64) {
+        let mut m = [0u32; 16];
+        for i in 0..16 {
+            m[i] = u32::from_le_bytes(chunk[4 * i..4 * i + 4].try_into().unwrap());
+        }
+        let [mut a, mut b, mut c, mut d] = h;
+        for i in 0..64 {
+            let (f, g) = match i / 16 {
+                0 => { ((b & c) | (!b & d), i) }
+                1 => { ((d & b) | (!d & c), (5 * i + 1) % 16) }
+                2 => { (b ^ c ^ d, (3 * i + 5) % 16) }
+                _ => { (c ^ (b | !d), (7 * i) % 16) }
+            };
+            let t = d;
+            d = c;
+            c = b;
+            b = b.wrapping_add(a.wrapping_add(f).wrapping_add(MD5_K[i]).wrapping_add(m[g])
+                               .rotate_left(S[4 * (i / 16) + i % 4]));
+            a = t;
+        }
+        for (x, y) in h.iter_mut().zip([a, b, c, d]) {
+            *x = x.wrapping_add(y);
+        }
+    }
+    let mut out = [0u8; 16];
+    for (i, x) in h.iter().enumerate() {
+        out[4 * i..4 * i + 4].copy_from_slice(&x.to_le_bytes());
+    }
+    return out;
+}
+
+//
+// SHA-1 hash, only to be used for key identifiers and legacy signatures.
+//
+pub fn sha1(data: &[u8]) -> [u8; 20]
+{
+    let mut h: [u32; 5] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+
+    for chunk in md_pad(data, 64, 8).chunks(64) {
+        let mut w = [0u32; 80];
+        for i in 0..16 {
+            w[i] = u32::from_be_bytes(chunk[4 * i..4 * i + 4].try_into().unwrap());
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e] = h;
+        for i in 0..80 {
+            let (f, k) = match i {
+                0..=19  => { ((b & c) | (!b & d), 0x5a827999) }
+                20..=39 => { (b ^ c ^ d, 0x6ed9eba1) }
+                40..=59 => { ((b & c) | (b & d) | (c & d), 0x8f1bbcdc) }
+                _       => { (b ^ c ^ d, 0xca62c1d6) }
+            };
+            let t = a.rotate_left(5).wrapping_add(f).wrapping_add(e)
+                     .wrapping_add(k).wrapping_add(w[i]);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = t;
+        }
+        for (x, y) in h.iter_mut().zip([a, b, c, d, e]) {
+            *x = x.wrapping_add(y);
+        }
+    }
+    let mut out = [0u8; 20];
+    for (i, x) in h.iter().enumerate() {
+        out[4 * i..4 * i + 4].copy_from_slice(&x.to_be_bytes());
+    }
+    return out;
+}
+
+const SHA256_K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5,
+    0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3,
+    0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc,
+    0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7,
+    0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13,
+    0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3,
+    0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5,
+    0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208,
+    0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+const SHA256_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a,
+    0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const SHA224_IV: [u32; 8] = [
+    0xc1059ed8, 0x367cd507, 0x3070dd17, 0xf70e5939,
+    0xffc00b31, 0x68581511, 0x64f98fa7, 0xbefa4fa4,
+];
+
+const SHA512_K: [u64; 80] = [
+    0x428a2f98d728ae22, 0x7137449123ef65cd,
+    0xb5c0fbcfec4d3b2f, 0xe9b5dba58189dbbc,
+    0x3956c25bf348b538, 0x59f111f1b605d019,
+    0x923f82a4af194f9b, 0xab1c5ed5da6d8118,
+    0xd807aa98a3030242, 0x12835b0145706fbe,
+    0x243185be4ee4b28c, 0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f, 0x80deb1fe3b1696b1,
+    0x9bdc06a725c71235, 0xc19bf174cf692694,
+    0xe49b69c19ef14ad2, 0xefbe4786384f25e3,
+    0x0fc19dc68b8cd5b5, 0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275, 0x4a7484aa6ea6e483,
+    0x5cb0a9dcbd41fbd4, 0x76f988da831153b5,
+    0x983e5152ee66dfab, 0xa831c66d2db43210,
+    0xb00327c898fb213f, 0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2, 0xd5a79147930aa725,
+    0x06ca6351e003826f, 0x142929670a0e6e70,
+    0x27b70a8546d22ffc, 0x2e1b21385c26c926,
+    0x4d2c6dfc5ac42aed, 0x53380d139d95b3df,
+    0x650a73548baf63de, 0x766a0abb3c77b2a8,
+    0x81c2c92e47edaee6, 0x92722c851482353b,
+    0xa2bfe8a14cf10364, 0xa81a664bbc423001,
+    0xc24b8b70d0f89791, 0xc76c51a30654be30,
+    0xd192e819d6ef5218, 0xd69906245565a910,
+    0xf40e35855771202a, 0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8, 0x1e376c085141ab53,
+    0x2748774cdf8eeb99, 0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63, 0x4ed8aa4ae3418acb,
+    0x5b9cca4f7763e373, 0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc, 0x78a5636f43172f60,
+    0x84c87814a1f0ab72, 0x8cc702081a6439ec,
+    0x90befffa23631e28, 0xa4506cebde82bde9,
+    0xbef9a3f7b2c67915, 0xc67178f2e372532b,
+    0xca273eceea26619c, 0xd186b8c721c0c207,
+    0xeada7dd6cde0eb1e, 0xf57d4f7fee6ed178,
+    0x06f067aa72176fba, 0x0a637dc5a2c898a6,
+    0x113f9804bef90dae, 0x1b710b35131c471b,
+    0x28db77f523047d84, 0x32caab7b40c72493,
+    0x3c9ebe0a15c9bebc, 0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6, 0x597f299cfc657e2a,
+    0x5fcb6fab3ad6faec, 0x6c44198c4a475817,
+];
+
+const SHA512_IV: [u64; 8] = [
+    0x6a09e667f3bcc908, 0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b, 0xa54ff53a5f1d36f1,
+    0x510e527fade682d1, 0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b, 0x5be0cd19137e2179,
+];
+
+const SHA384_IV: [u64; 8] = [
+    0xcbbb9d5dc1059ed8, 0x629a292a367cd507,
+    0x9159015a3070dd17, 0x152fecd8f70e5939,
+    0x67332667ffc00b31, 0x8eb44a8768581511,
+    0xdb0c2e0d64f98fa7, 0x47b5481dbefa4fa4,
+];
+
+//
+// SHA-256 compression over the padded message with a given IV.
+//
+fn sha256_core(iv: &[u32; 8], data: &[u8]) -> [u8; 32]
+{
+    let mut h = *iv;
+
+    for chunk in md_pad(data, 64, 8).chunks(64) {
+        let mut w = [0u32; 64];
+        for i in 0..16 {
+            w[i] = u32::from_be_bytes(chunk[4 * i..4 * i + 4].try_into().unwrap());
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = h;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = hh.wrapping_add(s1).wrapping_add(ch).wrapping_add(SHA256_K[i])
+                       .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            hh = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (x, y) in h.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+            *x = x.wrapping_add(y);
+        }
+    }
+    let mut out = [0u8; 32];
+    for (i, x) in h.iter().enumerate() {
+        out[4 * i..4 * i + 4].copy_from_slice(&x.to_be_bytes());
+    }
+    return out;
+}
+
+//
+// SHA-512 compression over the padded message with a given IV.
+//
+fn sha512_core(iv: &[u64; 8], data: &[u8]) -> [u8; 64]
+{
+    let mut h = *iv;
+
+    for chunk in md_pad(data, 128, 16).chunks(128) {
+        let mut w = [0u64; 80];
+        for i in 0..16 {
+            w[i] = u64::from_be_bytes(chunk[8 * i..8 * i + 8].try_into().unwrap());
+        }
+        for i in 16..80 {
+            let s0 = w[i - 15].rotate_right(1) ^ w[i - 15].rotate_right(8) ^ (w[i - 15] >> 7);
+            let s1 = w[i - 2].rotate_right(19) ^ w[i - 2].rotate_right(61) ^ (w[i - 2] >> 6);
+            w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = h;
+        for i in 0..80 {
+            let s1 = e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = hh.wrapping_add(s1).wrapping_add(ch).wrapping_add(SHA512_K[i])
+                       .wrapping_add(w[i]);
+            let s0 = a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            hh = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (x, y) in h.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+            *x = x.wrapping_add(y);
+        }
+    }
+    let mut out = [0u8; 64];
+    for (i, x) in h.iter().enumerate() {
+        out[8 * i..8 * i + 8].copy_from_slice(&x.to_be_bytes());
+    }
+    return out;
+}
+
+pub fn sha256(data: &[u8]) -> [u8; 32]
+{
+    return sha256_core(&SHA256_IV, data);
+}
+
+pub fn sha512(data: &[u8]) -> [u8; 64]
+{
+    return sha512_core(&SHA512_IV, data);
+}
+
+const KECCAK_RC: [u64; 24] = [
+    0x0000000000000001, 0x0000000000008082,
+    0x800000000000808a, 0x8000000080008000,
+    0x000000000000808b, 0x0000000080000001,
+    0x8000000080008081, 0x8000000000008009,
+    0x000000000000008a, 0x0000000000000088,
+    0x0000000080008009, 0x000000008000000a,
+    0x000000008000808b, 0x800000000000008b,
+    0x8000000000008089, 0x8000000000008003,
+    0x8000000000008002, 0x8000000000000080,
+    0x000000000000800a, 0x800000008000000a,
+    0x8000000080008081, 0x8000000000008080,
+    0x0000000080000001, 0x8000000080008008,
+];
+
+// rotation offsets indexed by x + 5y
+const KECCAK_ROT: [u32; 25] = [
+     0,  1, 62, 28, 27,
+    36, 44,  6, 55, 20,
+     3, 10, 43, 25, 39,
+    41, 45, 15, 21,  8,
+    18,  2, 61, 56, 14,
+];
+
+//
+// Keccak-f[1600] permutation.
+//
+fn keccak_f(a: &mut [u64; 25])
+{
+    for rc in KECCAK_RC {
+        // theta
+        let mut c = [0u64; 5];
+        for x in 0..5 {
+            c[x] = a[x] ^ a[x + 5] ^ a[x + 10] ^ a[x + 15] ^ a[x + 20];
+        }
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                a[x + 5 * y] ^= d;
+            }
+        }
+        // rho and pi
+        let mut b = [0u64; 25];
+        for x in 0..5 {
+            for y in 0..5 {
+                b[y + 5 * ((2 * x + 3 * y) % 5)] = a[x + 5 * y].rotate_left(KECCAK_ROT[x + 5 * y]);
+            }
+        }
+        // chi
+        for y in 0..5 {
+            for x in 0..5 {
+                a[x + 5 * y] = b[x + 5 * y] ^ (!b[(x + 1) % 5 + 5 * y] & b[(x + 2) % 5 + 5 * y]);
+            }
+        }
+        // iota
+        a[0] ^= rc;
+    }
+}
+
+/// Incremental Keccak sponge for SHA-3 and SHAKE
+#[derive(Clone)]
+pub struct Keccak {
+    state: [u64; 25],
+    /// rate in octets
+    rate: usize,
+    /// domain separation suffix
+    suffix: u8,
+    /// position within the current block
+    pos: usize,
+    /// set once squeezing has started
+    squeezing: bool,
+}
+
+impl Keccak {
+    fn new(rate: usize, suffix: u8) -> Keccak
+    {
+        Keccak { state: [0; 25], rate, suffix, pos: 0, squeezing: false }
+    }
+
+    pub fn shake128() -> Keccak
+    {
+        return Keccak::new(168, 0x1f);
+    }
+
+    pub fn shake256() -> Keccak
+    {
+        return Keccak::new(136, 0x1f);
+    }
+
+    //
+    // SHA-3 hash with an output of len octets.
+    //
+    pub fn sha3(len: usize, data: &[u8]) -> Vec<u8>
+    {
+        let mut keccak = Keccak::new(200 - 2 * len, 0x06);
+        keccak.absorb(data);
+        return keccak.squeeze(len);
+    }
+
+    fn xor_byte(&mut self, i: usize, b: u8)
+    {
+        self.state[i / 8] ^= (b as u64) << (8 * (i % 8));
+    }
+
+    pub fn absorb(&mut self, data: &[u8])
+    {
+        assert!(!self.squeezing, "absorb after squeeze");
+        for &b in data {
+            self.xor_byte(self.pos, b);
+            self.pos += 1;
+            if self.pos == self.rate {
+                keccak_f(&mut self.state);
+                self.pos = 0;
+            }
+        }
+    }
+
+    //
+    // Squeeze output, padding the input on the first call.
+    //
+    pub fn squeeze(&mut self, len: usize) -> Vec<u8>
+    {
+        if !self.squeezing {
+            self.xor_byte(self.pos, self.suffix);
+            self.xor_byte(self.rate - 1, 0x80);
+            keccak_f(&mut self.state);
+            self.pos = 0;
+            self.squeezing = true;
+        }
+        let mut out = Vec::with_capacity(len);
+        while out.len() < len {
+            if self.pos == self.rate {
+                keccak_f(&mut self.state);
+                self.pos = 0;
+            }
+            out.push((self.state[self.pos / 8] >> (8 * (self.pos % 8))) as u8);
+            self.pos += 1;
+        }
+        return out;
+    }
+}
+
+pub fn shake128(data: &[u8], len: usize) -> Vec<u8>
+{
+    let mut keccak = Keccak::shake128();
+    keccak.absorb(data);
+    return keccak.squeeze(len);
+}
+
+pub fn shake256(data: &[u8], len: usize) -> Vec<u8>
+{
+    let mut keccak = Keccak::shake256();
+    keccak.absorb(data);
+    return keccak.squeeze(len);
+}
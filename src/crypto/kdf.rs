@@ -0,0 +1,57 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! HMAC (RFC 2104) and the HKDF key derivation function (RFC 5869).
+
+use crate::crypto::hash::HashAlgorithm;
+
+//
+// Compute an HMAC over the given data.
+//
+pub fn hmac(hash: HashAlgorithm, key: &[u8], data: &[u8]) -> Vec<u8>
+{
+    let block_len = hash.block_len();
+    let mut k = if key.len() > block_len { hash.hash(key) } else { key.to_vec() };
+    k.resize(block_len, 0);
+
+    let mut inner: Vec<u8> = k.iter().map(|b| b ^ 0x36).collect();
+    inner.extend_from_slice(data);
+    let mut outer: Vec<u8> = k.iter().map(|b| b ^ 0x5c).collect();
+    outer.extend_from_slice(&hash.hash(&inner));
+    return hash.hash(&outer);
+}
+
+//
+// Derive len octets of keying material with HKDF-Extract and HKDF-Expand,
+// an empty salt being equivalent to a string of zeros.
+//
+pub fn hkdf(hash: HashAlgorithm, salt: &[u8], ikm: &[u8], info: &[u8], len: usize)
+            -> Result<Vec<u8>, String>
+{
+    if len > 255 * hash.digest_len() {
+        return Err(format!("HKDF output length {} too large", len));
+    }
+    let prk = hmac(hash, salt, ikm);
+    let mut okm = Vec::with_capacity(len);
+    let mut t = Vec::new();
+    let mut counter = 1u8;
+    while okm.len() < len {
+        let mut input = t;
+        input.extend_from_slice(info);
+        input.push(counter);
+        t = hmac(hash, &prk, &input);
+        okm.extend_from_slice(&t);
+        counter += 1;
+    }
+    okm.truncate(len);
+    return Ok(okm);
+}
@@ -0,0 +1,1028 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! Module-Lattice-Based Digital Signature Algorithm ML-DSA (FIPS 204),
+//! pure variant with an optional context string.
+
+use crate::oid::*;
+use crate::crypto::hash::{Keccak, shake256};
+use crate::crypto::rng::rng_bytes;
+
+const N: usize = 256;
+const Q: i64 = 8380417;
+const D: usize = 13;
+
+// 256^-1 mod q
+const N_INV: i64 = 8347681;
+
+// primitive 512th root of unity mod q
+const ZETA: i64 = 1753;
+
+/// ML-DSA parameter sets
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MlDsaParams {
+    MlDsa44,
+    MlDsa65,
+    MlDsa87,
+}
+
+/// Polynomial with coefficients in [0, q)
+type Poly = [i64; N];
+
+impl MlDsaParams {
+    pub fn from_oid(oid: &str) -> Option<MlDsaParams>
+    {
+        match oid {
+            OID_ML_DSA_44 => { Some(MlDsaParams::MlDsa44) }
+            OID_ML_DSA_65 => { Some(MlDsaParams::MlDsa65) }
+            OID_ML_DSA_87 => { Some(MlDsaParams::MlDsa87) }
+            _             => { None }
+        }
+    }
+
+    pub fn oid(&self) -> &'static str
+    {
+        match self {
+            MlDsaParams::MlDsa44 => { OID_ML_DSA_44 }
+            MlDsaParams::MlDsa65 => { OID_ML_DSA_65 }
+            MlDsaParams::MlDsa87 => { OID_ML_DSA_87 }
+        }
+    }
+
+    //
+    // Dimensions (k, l) of the matrix A.
+    //
+    fn dims(&self) -> (usize, usize)
+    {
+        match self {
+            MlDsaParams::MlDsa44 => { (4, 4) }
+            MlDsaParams::MlDsa65 => { (6, 5) }
+            MlDsaParams::MlDsa87 => { (8, 7) }
+        }
+    }
+
+    fn eta(&self) -> i64
+    {
+        match self {
+            MlDsaParams::MlDsa65 => { 4 }
+            _                    => { 2 }
+        }
+    }
+
+    fn tau(&self) -> usize
+    {
+        match self {
+            MlDsaParams::MlDsa44 => { 39 }
+            MlDsaParams::MlDsa65 => { 49 }
+            MlDsaParams::MlDsa87 => { 60 }
+        }
+    }
+
+    fn gamma1(&self) -> i64
+    {
+        match self {
+            MlDsaParams::MlDsa44 => { 1 << 17 }
+            _                    => { 1 << 19 }
+        }
+    }
+
+    fn gamma2(&self) -> i64
+    {
+        match self {
+            MlDsaParams::MlDsa44 => { (Q - 1) / 88 }
+            _                    => { (Q - 1) / 32 }
+        }
+    }
+
+    fn beta(&self) -> i64
+    {
+        return self.tau() as i64 * self.eta();
+    }
+
+    fn omega(&self) -> usize
+    {
+        match self {
+            MlDsaParams::MlDsa44 => { 80 }
+            MlDsaParams::MlDsa65 => { 55 }
+            MlDsaParams::MlDsa87 => { 75 }
+        }
+    }
+
+    //
+    // Collision strength lambda in bits.
+    //
+    pub fn lambda(&self) -> usize
+    {
+        match self {
+            MlDsaParams::MlDsa44 => { 128 }
+            MlDsaParams::MlDsa65 => { 192 }
+            MlDsaParams::MlDsa87 => { 256 }
+        }
+    }
+
+    fn eta_bits(&self) -> usize
+    {
+        return if self.eta() == 2 { 3 } else { 4 };
+    }
+
+    fn gamma1_bits(&self) -> usize
+    {
+        return if self.gamma1() == 1 << 17 { 18 } else { 20 };
+    }
+
+    fn w1_bits(&self) -> usize
+    {
+        return if self.gamma2() == (Q - 1) / 88 { 6 } else { 4 };
+    }
+
+    pub fn public_key_len(&self) -> usize
+    {
+        let (k, _) = self.dims();
+        return 32 + 320 * k;
+    }
+
+    pub fn private_key_len(&self) -> usize
+    {
+        let (k, l) = self.dims();
+        return 128 + 32 * ((k + l) * self.eta_bits() + D * k);
+    }
+
+    pub fn signature_len(&self) -> usize
+    {
+        let (k, l) = self.dims();
+        return self.lambda() / 4 + 32 * l * self.gamma1_bits() + self.omega() + k;
+    }
+}
+
+//
+// Zeta powers in bit-reversed order.
+//
+fn zetas() -> &'static [i64; N]
+{
+    static ZETAS: std::sync::OnceLock<[i64; N]> = std::sync::OnceLock::new();
+    return ZETAS.get_or_init(|| {
+        let mut zetas = [0i64; N];
+        for (k, z) in zetas.iter_mut().enumerate() {
+            let brv = (k as u8).reverse_bits() as u32;
+            let mut v = 1i64;
+            for _ in 0..brv {
+                v = v * ZETA % Q;
+            }
+            *z = v;
+        }
+        zetas
+    });
+}
+
+fn ntt(w: &mut Poly)
+{
+    let zetas = zetas();
+    let mut m = 0;
+    let mut len = 128;
+    while len >= 1 {
+        let mut start = 0;
+        while start < N {
+            m += 1;
+            let z = zetas[m];
+            for j in start..start + len {
+                let t = z * w[j + len] % Q;
+                w[j + len] = (w[j] - t).rem_euclid(Q);
+                w[j] = (w[j] + t) % Q;
+            }
+            start += 2 * len;
+        }
+        len /= 2;
+    }
+}
+
+fn ntt_inverse(w: &mut Poly)
+{
+    let zetas = zetas();
+    let mut m = N;
+    let mut len = 1;
+    while len < N {
+        let mut start = 0;
+        while start < N {
+            m -= 1;
+            let z = Q - zetas[m];
+            for j in start..start + len {
+                let t = w[j];
+                w[j] = (t + w[j + len]) % Q;
+                w[j + len] = z * (t - w[j + len]).rem_euclid(Q) % Q;
+            }
+            start += 2 * len;
+        }
+        len *= 2;
+    }
+    for c in w.iter_mut() {
+        *c = *c * N_INV % Q;
+    }
+}
+
+fn poly_add(a: &Poly, b: &Poly) -> Poly
+{
+    let mut r = [0i64; N];
+    for i in 0..N {
+        r[i] = (a[i] + b[i]) % Q;
+    }
+    return r;
+}
+
+fn poly_sub(a: &Poly, b: &Poly) -> Poly
+{
+    let mut r = [0i64; N];
+    for i in 0..N {
+        r[i] = (a[i] - b[i]).rem_euclid(Q);
+    }
+    return r;
+}
+
+fn poly_pointwise(a: &Poly, b: &Poly) -> Poly
+{
+    let mut r = [0i64; N];
+    for i in 0..N {
+        r[i] = a[i] * b[i] % Q;
+    }
+    return r;
+}
+
+//
+// Compute NTT^-1(A o v) for a matrix in NTT domain and a vector in NTT
+// domain.
+//
+fn matrix_mul(a: &[Vec<Poly>], v: &[Poly]) -> Vec<Poly>
+{
+    return a.iter().map(|row| {
+        let mut acc = [0i64; N];
+        for (aij, vj) in row.iter().zip(v) {
+            acc = poly_add(&acc, &poly_pointwise(aij, vj));
+        }
+        ntt_inverse(&mut acc);
+        acc
+    }).collect();
+}
+
+//
+// Centered representative of r mod q in (-q/2, q/2].
+//
+fn centered(r: i64) -> i64
+{
+    let r = r.rem_euclid(Q);
+    return if r > Q / 2 { r - Q } else { r };
+}
+
+fn inf_norm(v: &[Poly]) -> i64
+{
+    return v.iter().flat_map(|p| p.iter()).map(|c| centered(*c).abs()).max().unwrap_or(0);
+}
+
+//
+// Split r into (r1, r0) with r = r1 2^d + r0 (Algorithm 35).
+//
+fn power2round(r: i64) -> (i64, i64)
+{
+    let r0 = {
+        let m = r & ((1 << D) - 1);
+        if m > 1 << (D - 1) { m - (1 << D) } else { m }
+    };
+    return ((r - r0) >> D, r0);
+}
+
+//
+// Split r into high and low bits (Algorithm 36).
+//
+fn decompose(r: i64, gamma2: i64) -> (i64, i64)
+{
+    let r = r.rem_euclid(Q);
+    let mut r0 = r % (2 * gamma2);
+    if r0 > gamma2 {
+        r0 -= 2 * gamma2;
+    }
+    if r - r0 == Q - 1 {
+        return (0, r0 - 1);
+    }
+    return ((r - r0) / (2 * gamma2), r0);
+}
+
+fn make_hint(z: i64, r: i64, gamma2: i64) -> bool
+{
+    return decompose(r, gamma2).0 != decompose(r + z, gamma2).0;
+}
+
+fn use_hint(h: bool, r: i64, gamma2: i64) -> i64
+{
+    let m = (Q - 1) / (2 * gamma2);
+    let (r1, r0) = decompose(r, gamma2);
+    if h && r0 > 0 {
+        return (r1 + 1).rem_euclid(m);
+    }
+    if h {
+        return (r1 - 1).rem_euclid(m);
+    }
+    return r1;
+}
+
+//
+// Pack coefficients of bits width in little-endian bit order.
+//
+fn pack_bits(values: impl Iterator<Item = u32>, bits: usize, out: &mut Vec<u8>)
+{
+    let mut acc: u64 = 0;
+    let mut n = 0;
+    for v in values {
+        acc |= (v as u64) << n;
+        n += bits;
+        while n >= 8 {
+            out.push(acc as u8);
+            acc >>= 8;
+            n -= 8;
+        }
+    }
+}
+
+fn unpack_bits(data: &[u8], bits: usize) -> Vec<u32>
+{
+    let mut values = Vec::with_capacity(data.len() * 8 / bits);
+    let mut acc: u64 = 0;
+    let mut n = 0;
+    for b in data {
+        acc |= (*b as u64) << n;
+        n += 8;
+        while n >= bits {
+            values.push((acc & ((1 << bits) - 1)) as u32);
+            acc >>= bits;
+            n -= bits;
+        }
+    }
+    return values;
+}
+
+//
+// BitPack(w, a, b) storing b - w_i for coefficients in [-a, b].
+//
+fn bit_pack(p: &Poly, b: i64, bits: usize, out: &mut Vec<u8>)
+{
+    pack_bits(p.iter().map(|c| (b - centered(*c)) as u32), bits, out);
+}
+
+fn bit_unpack(data: &[u8], b: i64, bits: usize) -> Poly
+{
+    let mut p = [0i64; N];
+    for (c, v) in p.iter_mut().zip(unpack_bits(data, bits)) {
+        *c = (b - v as i64).rem_euclid(Q);
+    }
+    return p;
+}
+
+//
+// Sample a polynomial in NTT domain from SHAKE128 (Algorithm 30).
+//
+fn rej_ntt_poly(seed: &[u8]) -> Poly
+{
+    let mut xof = Keccak::shake128();
+    xof.absorb(seed);
+    let mut p = [0i64; N];
+    let mut j = 0;
+    while j < N {
+        let b = xof.squeeze(3);
+        let z = ((b[2] as i64 & 0x7f) << 16) | ((b[1] as i64) << 8) | b[0] as i64;
+        if z < Q {
+            p[j] = z;
+            j += 1;
+        }
+    }
+    return p;
+}
+
+//
+// Sample a polynomial with coefficients in [-eta, eta] (Algorithm 31).
+//
+fn rej_bounded_poly(seed: &[u8], eta: i64) -> Poly
+{
+    let mut xof = Keccak::shake256();
+    xof.absorb(seed);
+    let mut p = [0i64; N];
+    let mut j = 0;
+    while j < N {
+        let z = xof.squeeze(1)[0];
+        for half in [z & 0x0f, z >> 4] {
+            let half = half as i64;
+            let coeff = if eta == 2 && half < 15 {
+                Some(2 - half % 5)
+            } else if eta == 4 && half < 9 {
+                Some(4 - half)
+            } else {
+                None
+            };
+            if let Some(c) = coeff && j < N {
+                p[j] = c.rem_euclid(Q);
+                j += 1;
+            }
+        }
+    }
+    return p;
+}
+
+//
+// Expand the public matrix A in NTT domain (Algorithm 32).
+//
+fn expand_a(params: MlDsaParams, rho: &[u8]) -> Vec<Vec<Poly>>
+{
+    let (k, l) = params.dims();
+    return (0..k).map(|r| (0..l).map(|s| {
+        let mut seed = rho.to_vec();
+        seed.push(s as u8);
+        seed.push(r as u8);
+        rej_ntt_poly(&seed)
+    }).collect()).collect();
+}
+
+//
+// Expand the secret vectors s1 and s2 (Algorithm 33).
+//
+fn expand_s(params: MlDsaParams, rho: &[u8]) -> (Vec<Poly>, Vec<Poly>)
+{
+    let (k, l) = params.dims();
+    let sample = |r: usize| {
+        let mut seed = rho.to_vec();
+        seed.extend_from_slice(&(r as u16).to_le_bytes());
+        rej_bounded_poly(&seed, params.eta())
+    };
+    return ((0..l).map(sample).collect(), (l..l + k).map(sample).collect());
+}
+
+//
+// Expand the masking vector y (Algorithm 34).
+//
+fn expand_mask(params: MlDsaParams, rho: &[u8], kappa: usize) -> Vec<Poly>
+{
+    let (_, l) = params.dims();
+    let bits = params.gamma1_bits();
+    return (0..l).map(|r| {
+        let mut seed = rho.to_vec();
+        seed.extend_from_slice(&((kappa + r) as u16).to_le_bytes());
+        bit_unpack(&shake256(&seed, 32 * bits), params.gamma1(), bits)
+    }).collect();
+}
+
+//
+// Sample the challenge polynomial with tau coefficients +/-1
+// (Algorithm 29).
+//
+fn sample_in_ball(params: MlDsaParams, seed: &[u8]) -> Poly
+{
+    let mut xof = Keccak::shake256();
+    xof.absorb(seed);
+    let signs = u64::from_le_bytes(xof.squeeze(8).try_into().unwrap());
+    let tau = params.tau();
+    let mut c = [0i64; N];
+    for i in N - tau..N {
+        let j = loop {
+            let j = xof.squeeze(1)[0] as usize;
+            if j <= i {
+                break j;
+            }
+        };
+        c[i] = c[j];
+        c[j] = if (signs >> (i + tau - N)) & 1 == 1 { Q - 1 } else { 1 };
+    }
+    return c;
+}
+
+fn ntt_vec(v: &[Poly]) -> Vec<Poly>
+{
+    return v.iter().map(|p| {
+        let mut p = *p;
+        ntt(&mut p);
+        p
+    }).collect();
+}
+
+//
+// Compute NTT^-1(c o v) for a challenge and a vector in NTT domain.
+//
+fn scale_vec(c: &Poly, v: &[Poly]) -> Vec<Poly>
+{
+    return v.iter().map(|p| {
+        let mut r = poly_pointwise(c, p);
+        ntt_inverse(&mut r);
+        r
+    }).collect();
+}
+
+fn w1_encode(params: MlDsaParams, w1: &[Poly]) -> Vec<u8>
+{
+    let mut out = Vec::new();
+    for p in w1 {
+        pack_bits(p.iter().map(|c| *c as u32), params.w1_bits(), &mut out);
+    }
+    return out;
+}
+
+/// Decoded ML-DSA private key
+struct SecretKey {
+    rho: Vec<u8>,
+    k: Vec<u8>,
+    tr: Vec<u8>,
+    s1: Vec<Poly>,
+    s2: Vec<Poly>,
+    t0: Vec<Poly>,
+}
+
+//
+// Compute t = A s1 + s2 split into (t1, t0).
+//
+fn compute_t(params: MlDsaParams, rho: &[u8], s1: &[Poly], s2: &[Poly]) -> (Vec<Poly>, Vec<Poly>)
+{
+    let a = expand_a(params, rho);
+    let t: Vec<Poly> = matrix_mul(&a, &ntt_vec(s1)).iter().zip(s2)
+                          .map(|(as1, s2)| poly_add(as1, s2)).collect();
+    let mut t1 = Vec::new();
+    let mut t0 = Vec::new();
+    for p in &t {
+        let mut p1 = [0i64; N];
+        let mut p0 = [0i64; N];
+        for i in 0..N {
+            let (r1, r0) = power2round(p[i]);
+            p1[i] = r1;
+            p0[i] = r0.rem_euclid(Q);
+        }
+        t1.push(p1);
+        t0.push(p0);
+    }
+    return (t1, t0);
+}
+
+fn pk_encode(rho: &[u8], t1: &[Poly]) -> Vec<u8>
+{
+    let mut pk = rho.to_vec();
+    for p in t1 {
+        pack_bits(p.iter().map(|c| *c as u32), 10, &mut pk);
+    }
+    return pk;
+}
+
+fn sk_encode(params: MlDsaParams, sk: &SecretKey) -> Vec<u8>
+{
+    let mut out = Vec::with_capacity(params.private_key_len());
+    out.extend_from_slice(&sk.rho);
+    out.extend_from_slice(&sk.k);
+    out.extend_from_slice(&sk.tr);
+    for p in sk.s1.iter().chain(&sk.s2) {
+        bit_pack(p, params.eta(), params.eta_bits(), &mut out);
+    }
+    for p in &sk.t0 {
+        bit_pack(p, 1 << (D - 1), D, &mut out);
+    }
+    return out;
+}
+
+fn sk_decode(params: MlDsaParams, data: &[u8]) -> SecretKey
+{
+    let (k, l) = params.dims();
+    let eta_len = 32 * params.eta_bits();
+    let mut pos = 128;
+    let mut polys = |count: usize, len: usize, b: i64, bits: usize| -> Vec<Poly> {
+        (0..count).map(|_| {
+            let p = bit_unpack(&data[pos..pos + len], b, bits);
+            pos += len;
+            p
+        }).collect()
+    };
+    let s1 = polys(l, eta_len, params.eta(), params.eta_bits());
+    let s2 = polys(k, eta_len, params.eta(), params.eta_bits());
+    let t0 = polys(k, 32 * D, 1 << (D - 1), D);
+    SecretKey {
+        rho: data[..32].to_vec(), k: data[32..64].to_vec(), tr: data[64..128].to_vec(),
+        s1, s2, t0,
+    }
+}
+
+/// ML-DSA public key
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MlDsaPublicKey {
+    /// parameter set
+    pub params: MlDsaParams,
+    /// encoded public key
+    pub key: Vec<u8>,
+}
+
+/// ML-DSA private key
+#[derive(Clone, Debug)]
+pub struct MlDsaPrivateKey {
+    /// parameter set
+    pub params: MlDsaParams,
+    /// 32 octet seed the key was derived from, if known
+    pub seed: Option<Vec<u8>>,
+    /// encoded expanded private key
+    pub expanded: Vec<u8>,
+    /// encoded public key
+    pub public: Vec<u8>,
+}
+
+impl MlDsaPrivateKey {
+    pub fn generate(params: MlDsaParams) -> MlDsaPrivateKey
+    {
+        return MlDsaPrivateKey::from_seed(params, &rng_bytes(32)).expect("valid seed length");
+    }
+
+    //
+    // Derive a key pair from a 32 octet seed (Algorithm 6).
+    //
+    pub fn from_seed(params: MlDsaParams, seed: &[u8]) -> Result<MlDsaPrivateKey, String>
+    {
+        if seed.len() != 32 {
+            return Err("invalid ML-DSA seed length".to_string());
+        }
+        let (k, l) = params.dims();
+        let mut input = seed.to_vec();
+        input.push(k as u8);
+        input.push(l as u8);
+        let h = shake256(&input, 128);
+        let (rho, rho_prime, key) = (&h[..32], &h[32..96], &h[96..]);
+
+        let (s1, s2) = expand_s(params, rho_prime);
+        let (t1, t0) = compute_t(params, rho, &s1, &s2);
+        let public = pk_encode(rho, &t1);
+        let sk = SecretKey {
+            rho: rho.to_vec(), k: key.to_vec(), tr: shake256(&public, 64),
+            s1, s2, t0,
+        };
+        Ok(MlDsaPrivateKey {
+            params, seed: Some(seed.to_vec()), expanded: sk_encode(params, &sk), public,
+        })
+    }
+
+    //
+    // Load an expanded private key, recomputing and checking the public key.
+    //
+    pub fn from_expanded(params: MlDsaParams, data: &[u8]) -> Result<MlDsaPrivateKey, String>
+    {
+        if data.len() != params.private_key_len() {
+            return Err("invalid ML-DSA private key length".to_string());
+        }
+        let sk = sk_decode(params, data);
+        let eta = params.eta();
+        if inf_norm(&sk.s1) > eta || inf_norm(&sk.s2) > eta {
+            return Err("invalid ML-DSA private key".to_string());
+        }
+        let (t1, t0) = compute_t(params, &sk.rho, &sk.s1, &sk.s2);
+        let public = pk_encode(&sk.rho, &t1);
+        if t0 != sk.t0 || shake256(&public, 64) != sk.tr {
+            return Err("inconsistent ML-DSA private key".to_string());
+        }
+        Ok(MlDsaPrivateKey { params, seed: None, expanded: data.to_vec(), public })
+    }
+
+    pub fn public_key(&self) -> MlDsaPublicKey
+    {
+        MlDsaPublicKey { params: self.params, key: self.public.clone() }
+    }
+
+    //
+    // Create a hedged pure ML-DSA signature with an empty context.
+    //
+    pub fn sign(&self, msg: &[u8]) -> Vec<u8>
+    {
+        return self.sign_with_context(msg, &[]).expect("empty context");
+    }
+
+    //
+    // Create a hedged pure ML-DSA signature with a context string of at
+    // most 255 octets (Algorithms 2 and 7).
+    //
+    pub fn sign_with_context(&self, msg: &[u8], ctx: &[u8]) -> Result<Vec<u8>, String>
+    {
+        if ctx.len() > 255 {
+            return Err("ML-DSA context string too long".to_string());
+        }
+        let params = self.params;
+        let (k, l) = params.dims();
+        let gamma1 = params.gamma1();
+        let gamma2 = params.gamma2();
+        let beta = params.beta();
+        let sk = sk_decode(params, &self.expanded);
+
+        let s1_hat = ntt_vec(&sk.s1);
+        let s2_hat = ntt_vec(&sk.s2);
+        let t0_hat = ntt_vec(&sk.t0);
+        let a = expand_a(params, &sk.rho);
+
+        let mut mu_xof = Keccak::shake256();
+        mu_xof.absorb(&sk.tr);
+        mu_xof.absorb(&[0, ctx.len() as u8]);
+        mu_xof.absorb(ctx);
+        mu_xof.absorb(msg);
+        let mu = mu_xof.squeeze(64);
+
+        let mut input = sk.k.clone();
+        input.extend_from_slice(&rng_bytes(32));
+        input.extend_from_slice(&mu);
+        let rho2 = shake256(&input, 64);
+
+        let mut kappa = 0;
+        loop {
+            let y = expand_mask(params, &rho2, kappa);
+            kappa += l;
+            let w = matrix_mul(&a, &ntt_vec(&y));
+            let w1: Vec<Poly> = w.iter().map(|p| {
+                let mut r = [0i64; N];
+                for i in 0..N {
+                    r[i] = decompose(p[i], gamma2).0;
+                }
+                r
+            }).collect();
+
+            let mut xof = Keccak::shake256();
+            xof.absorb(&mu);
+            xof.absorb(&w1_encode(params, &w1));
+            let c_tilde = xof.squeeze(params.lambda() / 4);
+            let mut c_hat = sample_in_ball(params, &c_tilde);
+            ntt(&mut c_hat);
+
+            let cs1 = scale_vec(&c_hat, &s1_hat);
+            let cs2 = scale_vec(&c_hat, &s2_hat);
+            let z: Vec<Poly> = y.iter().zip(&cs1).map(|(y, c)| poly_add(y, c)).collect();
+            let w_cs2: Vec<Poly> = w.iter().zip(&cs2).map(|(w, c)| poly_sub(w, c)).collect();
+            let r0_norm = w_cs2.iter().flat_map(|p| p.iter())
+                               .map(|c| decompose(*c, gamma2).1.abs()).max().unwrap_or(0);
+            if inf_norm(&z) >= gamma1 - beta || r0_norm >= gamma2 - beta {
+                continue;
+            }
+
+            let ct0 = scale_vec(&c_hat, &t0_hat);
+            if inf_norm(&ct0) >= gamma2 {
+                continue;
+            }
+            let mut hints = vec![[false; N]; k];
+            let mut count = 0;
+            for i in 0..k {
+                for j in 0..N {
+                    let h = make_hint((Q - ct0[i][j]) % Q, w_cs2[i][j] + ct0[i][j], gamma2);
+                    hints[i][j] = h;
+                    count += h as usize;
+                }
+            }
+            if count > params.omega() {
+                continue;
+            }
+
+            let mut sig = c_tilde;
+            for p in &z {
+                bit_pack(p, gamma1, params.gamma1_bits(), &mut sig);
+            }
+            let omega = params.omega();
+            let mut packed = vec![0u8; omega + k];
+            let mut index = 0;
+            for i in 0..k {
+                for j in 0..N {
+                    if hints[i][j] {
+                        packed[index] = j as u8;
+                        index += 1;
+                    }
+                }
+                packed[omega + i] = index as u8;
+            }
+            sig.extend_from_slice(&packed);
+            return Ok(sig);
+        }
+    }
+}
+
+impl MlDsaPublicKey {
+    pub fn from_bytes(params: MlDsaParams, data: &[u8]) -> Result<MlDsaPublicKey, String>
+    {
+        if data.len() != params.public_key_len() {
+            return Err("invalid ML-DSA public key length".to_string());
+        }
+        Ok(MlDsaPublicKey { params, key: data.to_vec() })
+    }
+
+    //
+    // Verify a pure ML-DSA signature with an empty context.
+    //
+    pub fn verify(&self, msg: &[u8], sig: &[u8]) -> bool
+    {
+        return self.verify_with_context(msg, &[], sig);
+    }
+
+    //
+    // Verify a pure ML-DSA signature with a context string (Algorithms 3
+    // and 8).
+    //
+    pub fn verify_with_context(&self, msg: &[u8], ctx: &[u8], sig: &[u8]) -> bool
+    {
+        let params = self.params;
+        let (k, l) = params.dims();
+        let gamma1 = params.gamma1();
+        let gamma2 = params.gamma2();
+        let omega = params.omega();
+        if sig.len() != params.signature_len() || ctx.len() > 255 {
+            return false;
+        }
+
+        // decode the signature, rejecting malformed hints
+        let c_len = params.lambda() / 4;
+        let z_len = 32 * params.gamma1_bits();
+        let c_tilde = &sig[..c_len];
+        let z: Vec<Poly> = (0..l).map(|i| {
+            let start = c_len + i * z_len;
+            bit_unpack(&sig[start..start + z_len], gamma1, params.gamma1_bits())
+        }).collect();
+        let packed = &sig[c_len + l * z_len..];
+        let mut hints = vec![[false; N]; k];
+        let mut index = 0;
+        for i in 0..k {
+            let end = packed[omega + i] as usize;
+            if end < index || end > omega {
+                return false;
+            }
+            let first = index;
+            while index < end {
+                if index > first && packed[index - 1] >= packed[index] {
+                    return false;
+                }
+                hints[i][packed[index] as usize] = true;
+                index += 1;
+            }
+        }
+        if packed[index..omega].iter().any(|b| *b != 0) {
+            return false;
+        }
+        if inf_norm(&z) >= gamma1 - params.beta() {
+            return false;
+        }
+
+        let rho = &self.key[..32];
+        let t1: Vec<Poly> = (0..k).map(|i| {
+            let mut p = [0i64; N];
+            let start = 32 + 320 * i;
+            for (c, v) in p.iter_mut().zip(unpack_bits(&self.key[start..start + 320], 10)) {
+                *c = (v as i64) << D;
+            }
+            ntt(&mut p);
+            p
+        }).collect();
+        let a = expand_a(params, rho);
+
+        let mut mu_xof = Keccak::shake256();
+        mu_xof.absorb(&shake256(&self.key, 64));
+        mu_xof.absorb(&[0, ctx.len() as u8]);
+        mu_xof.absorb(ctx);
+        mu_xof.absorb(msg);
+        let mu = mu_xof.squeeze(64);
+
+        let mut c_hat = sample_in_ball(params, c_tilde);
+        ntt(&mut c_hat);
+        let az = matrix_mul(&a, &ntt_vec(&z));
+        let ct1 = scale_vec(&c_hat, &t1);
+        let w1: Vec<Poly> = (0..k).map(|i| {
+            let mut r = [0i64; N];
+            for j in 0..N {
+                r[j] = use_hint(hints[i][j], (az[i][j] - ct1[i][j]).rem_euclid(Q), gamma2);
+            }
+            r
+        }).collect();
+
+        let mut xof = Keccak::shake256();
+        xof.absorb(&mu);
+        xof.absorb(&w1_encode(params, &w1));
+        return xof.squeeze(c_len) == c_tilde;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::hash::sha256;
+    use crate::hex;
+
+    // SHA-256 of the public keys OpenSSL 3.5 derives from the seed 00..1f
+    const PUBLIC_KEYS: &[(MlDsaParams, &str)] = &[
+        (MlDsaParams::MlDsa44, "9f107644c1084526af3bc8098680b05499a2325a644e388fb4f970e058d19d46"),
+        (MlDsaParams::MlDsa65, "d666806e11cee19a7c989f7445f90dd419cf4d2d51db8c0fdb4c0f0a542238c9"),
+        (MlDsaParams::MlDsa87, "91dc389cfaa01470b7f66eee45a4ae9026d154817c754dfe22298b3fa241ffcd"),
+    ];
+
+    // deterministic OpenSSL 3.5 ML-DSA-44 signature by the seed 00..1f key
+    const SIGNATURE_44: &str = "
+     24fd9c58233194e534abb870643ea58750fb7a179587fe7c6083375a96170f145b1aaa55cf4586bf27a95da4f8635738
+     6c0514a623081d091a89681e4a0896ab6a28e772a0934d34eacff95e9221663728bdb528bb3333689290852f846d393d
+     fd9e48e8a30e6fbdf5501a3ccda1a071c210404f5b8f4dc886bd2cafc87ddb9a7fd6b7f830321439208bd6409eb9dba3
+     13b041d7808d993730f56a72ef9a3efbeb445e8a710067450faa3e95965990df7f5882b5c2b43c09a18e585884529c56
+     bf8ec696b14ea463cf6699d12d1873600a1c022378a1704c98555ba161aa5851a1f109be264db6f2c30def521ed6c01c
+     12b0d1098da096e010530e71c3204b73f78c18578b706df9c59e1950c4ba43f1d704371f7092f6d2299f47f756c5c040
+     23973ae2b8e74a3a17baca1c39f6119f07a842c9567db6e3ce0f3e1de56eaa145ff1851d3a521aad2e15768eee7a1f05
+     9c484a747e62551fdea139c9ddd0260e5315d2aada30b88da1e0f9b9b5dd0d2d6a22f6263ea0a58c34086c523be88955
+     4979c5867e0993bd8ad693202e49ecab13fbe92cd3c1df8a4752e7700987486ce7ba8572357a7eeadf8d6fc7dd6bb16e
+     73884030e79bf1b1c4e0782643f3db4e8c09f722f66f569760e5c99154e831a5fcfca01178c991c2c26b473ba8d34fa3
+     eff9f90cc9105943adc4b309b4fcb8086f5bd68928eaa49d69d6d8a8c128c4162a49dddca8482fd1212a9337d4abc91a
+     2171c792336c0396a3a514db2aaa9ceec8a7a3cd5adb9688c75b0a227d2b0e770857f95c1679f437616c69ea4598911f
+     8b06ad11828b329951ddb43287eb8691d5c80aaaa548a9a62102c204c1dfa78402a4368bb3903ba90748bc538b68f97a
+     69b4ad0b682069e5ba1147c86b4c6b5fceb0a5c2b4b65c9c59a05d23d3bdd2fbe9e003b6edf12e781facac219cff2eb4
+     f79a373cf5a3b5284ce7fe1a5777653c84df299440214357f434bfa1354578876018583d1799f8026eb9dd45f67bb431
+     dd079f06d32a7b31c8bf2b2fa87ee2d55e4a3a7f68f5c1f87509c440830f34e7503777d28aa00f84c18c1a96bdf7278e
+     b2cfa77f505cf1464daf480b57da86e2b2a241b2923db8140edc804baf8ef7d7c1221d67aefce51f9b75b9708bb2c5e4
+     07acb7b21fa6ed60f88b81c70327491e47342b96ea0403bb29c22dbccaa996ffc536f030bb3e5c98f6e4253b254221e2
+     428404f8e8a0dde59e7ad64a4678dd80d4169b8e25a3b21369b82588176f90e13b7cfe18f1b007e5bd073eda0e3bfca0
+     075ed589c5f4885b103d9c3674317c5b0c842df2e68be4c115ca511340054bc9e9b28319497bab95fcc47a482c42fe9e
+     a6f4d19362a2cd70e61d6cb9315edb87a72e8781d60a037db7232d1cea5696d637b84eab2822020d4a4a4c441c2e2be6
+     dcf58aaf3e714267eb6fda75140dd23955b7d6c3d79750ef9722059df0b23bfa75ca7d37239bc93b97caf75660adc66b
+     aac0f7e7e57fa3565d2c941d901089361ea79b17346af561a9780ba17736c76edd9533e4ab8ef9c26c045b4b20740f1e
+     7d8d7450e7841acb514c8b6b13ccb112f109967e48d646e44b4d125b78ecd2308ef2923b46c6609d172ed3296e75ddbc
+     30bbd5e9e3caabf05e003269045854d41270067fcc7ff9ea118541109fc2de89ea8a63330983c0647c5ba24bbdc5d8d3
+     1ebe9c5235d717a34afe2b0b2069f442765661dc70216d595eb0609c6c4e8392b8c480f136d80bfd5042c73837de0be5
+     094ea8d020083f2111ee1196d0c026450b975fd22896d15d1fdd07c838c9fc50cb62725a6d81af0fb4b66811df6c4856
+     8cdc5593b15cb3880d14f7e3924294b3cad83198e13564f33b8d9826abaadf15b10650f4171265fa887736fcc2aa4976
+     26e92ab8cda2d73158ea37b7d9f276abd0fdacd67478e38c648d28ad0e98e292d185667ece4aac78392c80a6068c7af5
+     e332a9d562c74f1485fcca84f11d80c6ad394f12a0bb36c4670ff23719fe869fd2f0a42841811438c1f59aab49e0f04a
+     3d4c4ab7f0355c6cb5f8de389b6413e8246605ccc801c3bb74caa32bba4b3c0094b6b362a7a5eeafe57657887c9fedf9
+     874af83ed28d808840338f4eb84bd7ee00febf21293fc330a415042f6f3dff0c2c13ef3f4340ece28131211f08be876e
+     7bb617e42fad9336664dc2e8eb9b3b5e1a917fffe02ede2b485c2f9ea0e749f2b5657292477027873047ae310c951c0e
+     c9467da7d7cf4a8f8ae20aeb983d0d4610517deb067fbaebef74910467cc3c4286a8a5d8dea84de022df4b44f18236a9
+     68e18cd47732530a94442a455e33759f19ea8605929e276041c4e5366fe930d396936d774c03a8a65b20e7bd1c68d983
+     3dbff665f1452e56900fb69b216055cf60c32d26817c784ec08f1fce633a03b824a41111fb79734f725ae24073996444
+     873b99d5a85f1c1aad3892c9bba7bd0e3645830559df4761e959e96e16cdbed388ad6c4a2f91e71026a1bc0611499585
+     00b6d6b4cbc2e634637898852b9f061fb2254a402709c63639bc642557fb43c8c2b690285bc75f83fb2a82f6f28b0dc8
+     053a8586b5e39ef8f888113b4ac48032cdd7d87fd2de3f227a22b6e3b7e146ec1d37dba7f44fb653fee72135a27e4796
+     614328ac4eb4d76fa7b10e28912b608aa4de6cafff0e3b2ff06dda5024df7175c0491ecb9a067a6c7d1a35cb934199b7
+     62b4bc48b97d430e68198413932d4feca2bf6a9165b52838791bdcc2336c357f30e864edeb041ec5831d6c6c5e117667
+     ca24e5dcf9e27d8dee76d0a52982b23b6b7606cef73f3f6db8772c645c73b438a4ed6c5fccf2f9ba652cf8b8933f6172
+     613bee8781211c13848efae65196350b0887551a980a69d191bfa2ccc4ededa00c1c33a24383c6c89e31670ad3a5be3d
+     52db84db027968219794175da3ac114e7cd3f644c881a8c23b01258f7c9c637b3a6f0b0bd891fe5893c7d123c561187e
+     d9e33885ccfc9f1b221b22f1fe0804dc21dc3f1a022222c0fe4c1a4c754db66fb2168e33693ab6f75e25626b5102597c
+     9139fb859eca06172d7a36def659b7f73f8aa5e1c8dceea1cc98d6d150be0206ff933feedd30f3055417b3f0a6c24a1e
+     daac3781afcce9737e67ca5b4e72255941767becf8a9d24f1307bf2cd185f604ccff2f2cc4686b0d362efdf0b63e1546
+     de818aa263d16375889e5f285ea36d41e234429bcaceec43e14a07fdfb3197c0cc84849dd99a59edbbbb51c8dadaa3f1
+     1f4ec6ab6e5a9dbb9c771f51c8a86b4f3db5f9ad67bdc9cf75718f69cf73145c0507161b252b404a646c787ca9bcccdc
+     e90d2831333b52545560728889a4c0c5fb07273a3d525c818b8dadb0dcddeef900012526333c4e636578819da3b8c0cc
+     d0ef000000000000000000000000000011213042";
+
+    fn seed() -> Vec<u8>
+    {
+        return (0..32).collect();
+    }
+
+    #[test]
+    fn key_derivation()
+    {
+        for (params, digest) in PUBLIC_KEYS {
+            let key = MlDsaPrivateKey::from_seed(*params, &seed()).unwrap();
+            assert_eq!(key.public.len(), params.public_key_len());
+            assert_eq!(sha256(&key.public).to_vec(), hex(digest));
+            assert_eq!(key.expanded.len(), params.private_key_len());
+
+            let expanded = MlDsaPrivateKey::from_expanded(*params, &key.expanded).unwrap();
+            assert_eq!(expanded.public, key.public);
+            let mut tampered = key.expanded.clone();
+            tampered[100] ^= 1;
+            assert!(MlDsaPrivateKey::from_expanded(*params, &tampered).is_err());
+        }
+        let key = MlDsaPrivateKey::from_seed(MlDsaParams::MlDsa44, &seed()).unwrap();
+        assert_eq!(sha256(&key.expanded).to_vec(),
+                   hex("04bf6b9f579166a627961dfc5c3bf9717df868db88863856356c4668c8b56b0b"));
+        assert!(MlDsaPrivateKey::from_seed(MlDsaParams::MlDsa44, &[0; 31]).is_err());
+    }
+
+    #[test]
+    fn signature_vector()
+    {
+        let key = MlDsaPrivateKey::from_seed(MlDsaParams::MlDsa44, &seed()).unwrap();
+        let public = key.public_key();
+        let sig = hex(SIGNATURE_44);
+        assert!(public.verify(b"ML-DSA known answer test", &sig));
+        assert!(!public.verify(b"ML-DSA known answer test.", &sig));
+        assert!(!public.verify_with_context(b"ML-DSA known answer test", b"ctx", &sig));
+        let mut tampered = sig.clone();
+        tampered[2000] ^= 1;
+        assert!(!public.verify(b"ML-DSA known answer test", &tampered));
+    }
+
+    #[test]
+    fn sign_and_verify()
+    {
+        for params in [MlDsaParams::MlDsa44, MlDsaParams::MlDsa65, MlDsaParams::MlDsa87] {
+            let key = MlDsaPrivateKey::generate(params);
+            let public = key.public_key();
+            let sig = key.sign(b"data");
+            assert_eq!(sig.len(), params.signature_len());
+            assert!(public.verify(b"data", &sig));
+            assert!(!public.verify(b"Data", &sig));
+
+            let sig = key.sign_with_context(b"data", b"context").unwrap();
+            assert!(public.verify_with_context(b"data", b"context", &sig));
+            assert!(!public.verify(b"data", &sig));
+        }
+        let key = MlDsaPrivateKey::generate(MlDsaParams::MlDsa44);
+        assert!(key.sign_with_context(b"data", &[0; 256]).is_err());
+    }
+}
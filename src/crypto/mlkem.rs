@@ -0,0 +1,634 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! Module-Lattice-Based Key-Encapsulation Mechanism ML-KEM (FIPS 203).
+
+use crate::oid::*;
+use crate::crypto::hash::{HashAlgorithm, Keccak, shake256};
+use crate::crypto::rng::rng_bytes;
+
+const N: usize = 256;
+const Q: u32 = 3329;
+
+// 128^-1 mod q
+const N_INV: u32 = 3303;
+
+// primitive 256th root of unity mod q
+const ZETA: u32 = 17;
+
+/// ML-KEM parameter sets
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MlKemParams {
+    MlKem512,
+    MlKem768,
+    MlKem1024,
+}
+
+/// Polynomial with coefficients in [0, q)
+type Poly = [u32; N];
+
+impl MlKemParams {
+    pub fn from_oid(oid: &str) -> Option<MlKemParams>
+    {
+        match oid {
+            OID_ML_KEM_512  => { Some(MlKemParams::MlKem512) }
+            OID_ML_KEM_768  => { Some(MlKemParams::MlKem768) }
+            OID_ML_KEM_1024 => { Some(MlKemParams::MlKem1024) }
+            _               => { None }
+        }
+    }
+
+    pub fn oid(&self) -> &'static str
+    {
+        match self {
+            MlKemParams::MlKem512  => { OID_ML_KEM_512 }
+            MlKemParams::MlKem768  => { OID_ML_KEM_768 }
+            MlKemParams::MlKem1024 => { OID_ML_KEM_1024 }
+        }
+    }
+
+    fn k(&self) -> usize
+    {
+        match self {
+            MlKemParams::MlKem512  => { 2 }
+            MlKemParams::MlKem768  => { 3 }
+            MlKemParams::MlKem1024 => { 4 }
+        }
+    }
+
+    fn eta1(&self) -> usize
+    {
+        return if *self == MlKemParams::MlKem512 { 3 } else { 2 };
+    }
+
+    fn du(&self) -> usize
+    {
+        return if *self == MlKemParams::MlKem1024 { 11 } else { 10 };
+    }
+
+    fn dv(&self) -> usize
+    {
+        return if *self == MlKemParams::MlKem1024 { 5 } else { 4 };
+    }
+
+    //
+    // Security strength in bits.
+    //
+    pub fn strength(&self) -> usize
+    {
+        match self {
+            MlKemParams::MlKem512  => { 128 }
+            MlKemParams::MlKem768  => { 192 }
+            MlKemParams::MlKem1024 => { 256 }
+        }
+    }
+
+    pub fn public_key_len(&self) -> usize
+    {
+        return 384 * self.k() + 32;
+    }
+
+    pub fn private_key_len(&self) -> usize
+    {
+        return 768 * self.k() + 96;
+    }
+
+    pub fn ciphertext_len(&self) -> usize
+    {
+        return 32 * (self.du() * self.k() + self.dv());
+    }
+}
+
+//
+// Zeta powers in bit-reversed order.
+//
+fn zetas() -> &'static [u32; 128]
+{
+    static ZETAS: std::sync::OnceLock<[u32; 128]> = std::sync::OnceLock::new();
+    return ZETAS.get_or_init(|| {
+        let mut zetas = [0u32; 128];
+        for (i, z) in zetas.iter_mut().enumerate() {
+            let brv = (i as u8).reverse_bits() >> 1;
+            let mut v = 1u32;
+            for _ in 0..brv {
+                v = v * ZETA % Q;
+            }
+            *z = v;
+        }
+        zetas
+    });
+}
+
+fn ntt(f: &mut Poly)
+{
+    let zetas = zetas();
+    let mut i = 1;
+    let mut len = 128;
+    while len >= 2 {
+        let mut start = 0;
+        while start < N {
+            let zeta = zetas[i];
+            i += 1;
+            for j in start..start + len {
+                let t = zeta * f[j + len] % Q;
+                f[j + len] = (f[j] + Q - t) % Q;
+                f[j] = (f[j] + t) % Q;
+            }
+            start += 2 * len;
+        }
+        len /= 2;
+    }
+}
+
+fn ntt_inverse(f: &mut Poly)
+{
+    let zetas = zetas();
+    let mut i = 127;
+    let mut len = 2;
+    while len <= 128 {
+        let mut start = 0;
+        while start < N {
+            let zeta = zetas[i];
+            i -= 1;
+            for j in start..start + len {
+                let t = f[j];
+                f[j] = (t + f[j + len]) % Q;
+                f[j + len] = zeta * ((f[j + len] + Q - t) % Q) % Q;
+            }
+            start += 2 * len;
+        }
+        len *= 2;
+    }
+    for c in f.iter_mut() {
+        *c = *c * N_INV % Q;
+    }
+}
+
+//
+// Multiply two polynomials in NTT domain (Algorithms 11 and 12).
+//
+fn multiply_ntts(f: &Poly, g: &Poly) -> Poly
+{
+    let zetas = zetas();
+    let mut h = [0u32; N];
+    for i in 0..N / 2 {
+        let gamma = zetas[64 + i / 2];
+        let gamma = if i % 2 == 0 { gamma } else { Q - gamma };
+        let (a0, a1, b0, b1) = (f[2 * i], f[2 * i + 1], g[2 * i], g[2 * i + 1]);
+        h[2 * i] = (a0 * b0 + a1 * b1 % Q * gamma) % Q;
+        h[2 * i + 1] = (a0 * b1 + a1 * b0) % Q;
+    }
+    return h;
+}
+
+fn poly_add(a: &Poly, b: &Poly) -> Poly
+{
+    let mut r = [0u32; N];
+    for i in 0..N {
+        r[i] = (a[i] + b[i]) % Q;
+    }
+    return r;
+}
+
+fn poly_sub(a: &Poly, b: &Poly) -> Poly
+{
+    let mut r = [0u32; N];
+    for i in 0..N {
+        r[i] = (a[i] + Q - b[i]) % Q;
+    }
+    return r;
+}
+
+//
+// Inner product of two vectors in NTT domain.
+//
+fn inner_product(a: &[Poly], b: &[Poly]) -> Poly
+{
+    return a.iter().zip(b).fold([0u32; N], |acc, (a, b)| poly_add(&acc, &multiply_ntts(a, b)));
+}
+
+fn byte_encode(p: &Poly, d: usize, out: &mut Vec<u8>)
+{
+    let mut acc: u64 = 0;
+    let mut n = 0;
+    for c in p {
+        acc |= (*c as u64) << n;
+        n += d;
+        while n >= 8 {
+            out.push(acc as u8);
+            acc >>= 8;
+            n -= 8;
+        }
+    }
+}
+
+fn byte_decode(data: &[u8], d: usize) -> Poly
+{
+    let mut p = [0u32; N];
+    let mut acc: u64 = 0;
+    let mut n = 0;
+    let mut i = 0;
+    for b in data {
+        acc |= (*b as u64) << n;
+        n += 8;
+        while n >= d && i < N {
+            p[i] = (acc & ((1 << d) - 1)) as u32;
+            acc >>= d;
+            n -= d;
+            i += 1;
+        }
+    }
+    return p;
+}
+
+fn compress(p: &Poly, d: usize) -> Poly
+{
+    let mut r = [0u32; N];
+    for i in 0..N {
+        r[i] = ((((p[i] as u64) << (d + 1)) + Q as u64) / (2 * Q as u64)) as u32 & ((1 << d) - 1);
+    }
+    return r;
+}
+
+fn decompress(p: &Poly, d: usize) -> Poly
+{
+    let mut r = [0u32; N];
+    for i in 0..N {
+        r[i] = ((2 * p[i] as u64 * Q as u64 + (1 << d)) >> (d + 1)) as u32;
+    }
+    return r;
+}
+
+//
+// Sample a polynomial in NTT domain from SHAKE128 (Algorithm 7).
+//
+fn sample_ntt(rho: &[u8], j: usize, i: usize) -> Poly
+{
+    let mut xof = Keccak::shake128();
+    xof.absorb(rho);
+    xof.absorb(&[j as u8, i as u8]);
+    let mut a = [0u32; N];
+    let mut n = 0;
+    while n < N {
+        let c = xof.squeeze(3);
+        let d1 = c[0] as u32 | ((c[1] as u32 & 0x0f) << 8);
+        let d2 = (c[1] as u32 >> 4) | ((c[2] as u32) << 4);
+        if d1 < Q {
+            a[n] = d1;
+            n += 1;
+        }
+        if d2 < Q && n < N {
+            a[n] = d2;
+            n += 1;
+        }
+    }
+    return a;
+}
+
+//
+// Sample a polynomial from the centered binomial distribution with
+// PRF_eta(s, b) as input (Algorithm 8).
+//
+fn sample_cbd(seed: &[u8], nonce: u8, eta: usize) -> Poly
+{
+    let mut input = seed.to_vec();
+    input.push(nonce);
+    let bytes = shake256(&input, 64 * eta);
+    let bit = |i: usize| ((bytes[i / 8] >> (i % 8)) & 1) as u32;
+    let mut f = [0u32; N];
+    for i in 0..N {
+        let x: u32 = (0..eta).map(|j| bit(2 * i * eta + j)).sum();
+        let y: u32 = (0..eta).map(|j| bit(2 * i * eta + eta + j)).sum();
+        f[i] = (x + Q - y) % Q;
+    }
+    return f;
+}
+
+fn expand_a(params: MlKemParams, rho: &[u8]) -> Vec<Vec<Poly>>
+{
+    let k = params.k();
+    return (0..k).map(|i| (0..k).map(|j| sample_ntt(rho, j, i)).collect()).collect();
+}
+
+fn sha3_256(data: &[u8]) -> Vec<u8>
+{
+    return HashAlgorithm::Sha3_256.hash(data);
+}
+
+fn sha3_512(data: &[u8]) -> Vec<u8>
+{
+    return HashAlgorithm::Sha3_512.hash(data);
+}
+
+//
+// K-PKE encryption of a 32 octet message with randomness r (Algorithm 14).
+//
+fn pke_encrypt(params: MlKemParams, ek: &[u8], m: &[u8], r: &[u8]) -> Vec<u8>
+{
+    let k = params.k();
+    let t_hat: Vec<Poly> = (0..k).map(|i| byte_decode(&ek[384 * i..384 * (i + 1)], 12)).collect();
+    let a = expand_a(params, &ek[384 * k..]);
+
+    let mut nonce = 0;
+    let mut y_hat = Vec::new();
+    for _ in 0..k {
+        let mut y = sample_cbd(r, nonce, params.eta1());
+        ntt(&mut y);
+        y_hat.push(y);
+        nonce += 1;
+    }
+    let mut e1 = Vec::new();
+    for _ in 0..k {
+        e1.push(sample_cbd(r, nonce, 2));
+        nonce += 1;
+    }
+    let e2 = sample_cbd(r, nonce, 2);
+
+    let mut c = Vec::with_capacity(params.ciphertext_len());
+    for i in 0..k {
+        // u = NTT^-1(A^T o y) + e1
+        let column: Vec<Poly> = (0..k).map(|j| a[j][i]).collect();
+        let mut u = inner_product(&column, &y_hat);
+        ntt_inverse(&mut u);
+        byte_encode(&compress(&poly_add(&u, &e1[i]), params.du()), params.du(), &mut c);
+    }
+    let mut v = inner_product(&t_hat, &y_hat);
+    ntt_inverse(&mut v);
+    let mu = decompress(&byte_decode(m, 1), 1);
+    let v = poly_add(&poly_add(&v, &e2), &mu);
+    byte_encode(&compress(&v, params.dv()), params.dv(), &mut c);
+    return c;
+}
+
+//
+// K-PKE decryption (Algorithm 15).
+//
+fn pke_decrypt(params: MlKemParams, dk: &[u8], c: &[u8]) -> Vec<u8>
+{
+    let k = params.k();
+    let du_len = 32 * params.du();
+    let mut w = decompress(&byte_decode(&c[du_len * k..], params.dv()), params.dv());
+    let mut u_hat = Vec::new();
+    for i in 0..k {
+        let mut u = decompress(&byte_decode(&c[du_len * i..du_len * (i + 1)], params.du()),
+                               params.du());
+        ntt(&mut u);
+        u_hat.push(u);
+    }
+    let s_hat: Vec<Poly> = (0..k).map(|i| byte_decode(&dk[384 * i..384 * (i + 1)], 12)).collect();
+    let mut su = inner_product(&s_hat, &u_hat);
+    ntt_inverse(&mut su);
+    w = poly_sub(&w, &su);
+
+    let mut m = Vec::with_capacity(32);
+    byte_encode(&compress(&w, 1), 1, &mut m);
+    return m;
+}
+
+/// ML-KEM encapsulation key
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MlKemPublicKey {
+    /// parameter set
+    pub params: MlKemParams,
+    /// encoded encapsulation key
+    pub key: Vec<u8>,
+}
+
+/// ML-KEM decapsulation key
+#[derive(Clone, Debug)]
+pub struct MlKemPrivateKey {
+    /// parameter set
+    pub params: MlKemParams,
+    /// 64 octet seed d || z the key was derived from, if known
+    pub seed: Option<Vec<u8>>,
+    /// encoded decapsulation key
+    pub expanded: Vec<u8>,
+}
+
+impl MlKemPrivateKey {
+    pub fn generate(params: MlKemParams) -> MlKemPrivateKey
+    {
+        return MlKemPrivateKey::from_seed(params, &rng_bytes(64)).expect("valid seed length");
+    }
+
+    //
+    // Derive a key pair from a 64 octet seed d || z (Algorithms 13 and 16).
+    //
+    pub fn from_seed(params: MlKemParams, seed: &[u8]) -> Result<MlKemPrivateKey, String>
+    {
+        if seed.len() != 64 {
+            return Err("invalid ML-KEM seed length".to_string());
+        }
+        let k = params.k();
+        let mut input = seed[..32].to_vec();
+        input.push(k as u8);
+        let g = sha3_512(&input);
+        let (rho, sigma) = (&g[..32], &g[32..]);
+        let a = expand_a(params, rho);
+
+        let mut nonce = 0;
+        let mut sample = || {
+            let mut p = sample_cbd(sigma, nonce, params.eta1());
+            ntt(&mut p);
+            nonce += 1;
+            p
+        };
+        let s_hat: Vec<Poly> = (0..k).map(|_| sample()).collect();
+        let e_hat: Vec<Poly> = (0..k).map(|_| sample()).collect();
+
+        let mut ek = Vec::with_capacity(params.public_key_len());
+        for i in 0..k {
+            let t = poly_add(&inner_product(&a[i], &s_hat), &e_hat[i]);
+            byte_encode(&t, 12, &mut ek);
+        }
+        ek.extend_from_slice(rho);
+
+        let mut dk = Vec::with_capacity(params.private_key_len());
+        for s in &s_hat {
+            byte_encode(s, 12, &mut dk);
+        }
+        dk.extend_from_slice(&ek);
+        dk.extend_from_slice(&sha3_256(&ek));
+        dk.extend_from_slice(&seed[32..]);
+        Ok(MlKemPrivateKey { params, seed: Some(seed.to_vec()), expanded: dk })
+    }
+
+    //
+    // Load an expanded decapsulation key after checking the hash of the
+    // embedded encapsulation key (FIPS 203, 7.3).
+    //
+    pub fn from_expanded(params: MlKemParams, data: &[u8]) -> Result<MlKemPrivateKey, String>
+    {
+        let k = params.k();
+        if data.len() != params.private_key_len() {
+            return Err("invalid ML-KEM private key length".to_string());
+        }
+        let ek = &data[384 * k..768 * k + 32];
+        if sha3_256(ek) != data[768 * k + 32..768 * k + 64] {
+            return Err("inconsistent ML-KEM private key".to_string());
+        }
+        MlKemPublicKey::from_bytes(params, ek)?;
+        Ok(MlKemPrivateKey { params, seed: None, expanded: data.to_vec() })
+    }
+
+    pub fn public_key(&self) -> MlKemPublicKey
+    {
+        let k = self.params.k();
+        MlKemPublicKey { params: self.params, key: self.expanded[384 * k..768 * k + 32].to_vec() }
+    }
+
+    //
+    // Recover the shared secret from a ciphertext, returning a pseudorandom
+    // secret on failure (Algorithms 18 and 21).
+    //
+    pub fn decapsulate(&self, c: &[u8]) -> Result<Vec<u8>, String>
+    {
+        let params = self.params;
+        let k = params.k();
+        if c.len() != params.ciphertext_len() {
+            return Err("invalid ML-KEM ciphertext length".to_string());
+        }
+        let dk = &self.expanded;
+        let ek = &dk[384 * k..768 * k + 32];
+        let h = &dk[768 * k + 32..768 * k + 64];
+        let z = &dk[768 * k + 64..];
+
+        let mut m = pke_decrypt(params, dk, c);
+        m.extend_from_slice(h);
+        let g = sha3_512(&m);
+        let mut rejected = z.to_vec();
+        rejected.extend_from_slice(c);
+        let rejected = shake256(&rejected, 32);
+
+        let c2 = pke_encrypt(params, ek, &m[..32], &g[32..]);
+        let equal = c.iter().zip(&c2).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0;
+        let mask = if equal { 0xff } else { 0x00 };
+        return Ok(g[..32].iter().zip(&rejected).map(|(k, r)| (k & mask) | (r & !mask)).collect());
+    }
+}
+
+impl MlKemPublicKey {
+    //
+    // Load an encapsulation key after the modulus check (FIPS 203, 7.2).
+    //
+    pub fn from_bytes(params: MlKemParams, data: &[u8]) -> Result<MlKemPublicKey, String>
+    {
+        if data.len() != params.public_key_len() {
+            return Err("invalid ML-KEM public key length".to_string());
+        }
+        for chunk in data[..384 * params.k()].chunks(384) {
+            if byte_decode(chunk, 12).iter().any(|c| *c >= Q) {
+                return Err("invalid ML-KEM public key".to_string());
+            }
+        }
+        Ok(MlKemPublicKey { params, key: data.to_vec() })
+    }
+
+    //
+    // Generate a shared secret and its ciphertext (Algorithms 17 and 20).
+    //
+    pub fn encapsulate(&self) -> (Vec<u8>, Vec<u8>)
+    {
+        let mut m = rng_bytes(32);
+        m.extend_from_slice(&sha3_256(&self.key));
+        let g = sha3_512(&m);
+        let c = pke_encrypt(self.params, &self.key, &m[..32], &g[32..]);
+        return (g[..32].to_vec(), c);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::hash::sha256;
+    use crate::hex;
+
+    // SHA-256 of the encapsulation keys OpenSSL 3.5 derives from the seed 00..3f
+    const PUBLIC_KEYS: &[(MlKemParams, &str)] = &[
+        (MlKemParams::MlKem512,  "3ae268dccc5456ac0d0f9b39257dc48fe081383b97c400512d712b739762daee"),
+        (MlKemParams::MlKem768,  "0b7934c83125c788995e2ba6bd761e33046b3e40571be53e023309a29f398cc9"),
+        (MlKemParams::MlKem1024, "c7b8fa0aa471d5ae18922d6ccad5b31e1d84f92ae723abfd13747018740a8530"),
+    ];
+
+    // OpenSSL 3.5 ML-KEM-512 encapsulation to the seed 00..3f key
+    const CIPHERTEXT_512: &str = "
+     471fa009929b30e87d6f2f60ef4a9ba39a120e6eba658fd8b362e16fe75aec7f290a6ea0080801656f1baac98920b02e
+     0a5942d8a71a6f2130d8079b4ab5d3ef768766c584b68637d8f6471994518afde5b0269e474850daf0ca698b661c069c
+     f6e413b2e6bd79c38d794d55ccb09d052448d7b6c15b4f6b94a2737f77bee36d474fd9537451745078f187543a05baea
+     d08a7f932473c124fc42d8e4b7a3cfe09e072fe8c5b169d83da4f106fdca7fb4527ca579f4e3ee9e996105d044ee520c
+     7a5d9cf5de5dfb71772f8ab985a4095019dfbc992feedc7ef79e5868eb8bdd4f2ae7d405519e99131d26b286f9a83c56
+     4eee39e88cfc69a3a4dd823d3b3a88c9ea90c5b157a08a1be63b844678a48e6df2b1c88b2a69643cf514dbef995cc332
+     60bf802a8edd8cdf57d498afe3e4bf511e0a3c6d540240cba8633e043d647320a15c2bfe6511c03a54a7fd116f105303
+     24951c7e979225be2ce3658ac33227be69f8db5fa5be6e29f513833e839f989580f383ac63cd7282328eecaa00cdfff0
+     1fcdf7cac3d771439924b2407380f7c9f44c3e7043b5343932ccc90fa56ab328db02066fcf08784eb0566083b3eb89eb
+     5ef13106831582d42a1678e7785bf1c590ba19b076ea7eba30631472b96203488464a3ef4a423b07b62b619da037dd57
+     35ead074cdfdb33d852d1ad0c60462f03d061972a512564203a57c5c12ccac8d112639f67f2ac498caec6ec11c4e5175
+     d961168192c3ba8676f54994117d4d3ae0d35fc1a86c9c8653f2c401bd715cb0ef7e5daf0c73a086e7c32980bb877d67
+     2fbbcfbc72336047045b4e78d4d2cbaf3c7b4c400294ae40455d814761b0ad1b0bde007ca8e21f6e580b1656f42c0dda
+     c339cc83eb02ab054d90c28bd42f4bfa33259e8253cfedac5aa3b6ab22695f1212bd4ed7da58eb58800697210ba72fde
+     6fa7b1d3a6756907c6635b50e7f1cf3ee5271f39500bde70c69fb5a4de5bd42e3aee11accdba62ddf51ba879474e877c
+     b12ee9adf9de3cc5933af6509f7182441227001ada070ba1ea01067ec2c51fc87590a725cbfb6371d22ab9960a99cbb1";
+    const SECRET_512: &str = "e470a6b18fc3a0d8e83fafaf19c23c210be82da60e8c1f4620f7b1b817b60c5c";
+
+    // SHAKE256(z || c) for the ciphertext with its first bit flipped
+    const REJECTED_512: &str = "c4a91c705faa81c916d9b7388c40eb4c06990d3e5b21619a8fa68a527c022697";
+
+    fn seed() -> Vec<u8>
+    {
+        return (0..64).collect();
+    }
+
+    #[test]
+    fn key_derivation()
+    {
+        for (params, digest) in PUBLIC_KEYS {
+            let key = MlKemPrivateKey::from_seed(*params, &seed()).unwrap();
+            let public = key.public_key();
+            assert_eq!(public.key.len(), params.public_key_len());
+            assert_eq!(sha256(&public.key).to_vec(), hex(digest));
+            assert_eq!(key.expanded.len(), params.private_key_len());
+
+            let expanded = MlKemPrivateKey::from_expanded(*params, &key.expanded).unwrap();
+            assert_eq!(expanded.public_key(), public);
+            let mut tampered = key.expanded.clone();
+            tampered[384 * 4] ^= 1;
+            assert!(MlKemPrivateKey::from_expanded(*params, &tampered).is_err());
+        }
+        let key = MlKemPrivateKey::from_seed(MlKemParams::MlKem512, &seed()).unwrap();
+        assert_eq!(sha256(&key.expanded).to_vec(),
+                   hex("17fb29b8c4baf74fb81eea15ffd583b3e37f5a5b8dcf6db96c72c3b3751d6f17"));
+        assert!(MlKemPrivateKey::from_seed(MlKemParams::MlKem512, &[0; 32]).is_err());
+        assert!(MlKemPublicKey::from_bytes(MlKemParams::MlKem512, &[0xff; 800]).is_err());
+    }
+
+    #[test]
+    fn decapsulation_vector()
+    {
+        let key = MlKemPrivateKey::from_seed(MlKemParams::MlKem512, &seed()).unwrap();
+        let mut c = hex(CIPHERTEXT_512);
+        assert_eq!(key.decapsulate(&c).unwrap(), hex(SECRET_512));
+        c[0] ^= 1;
+        assert_eq!(key.decapsulate(&c).unwrap(), hex(REJECTED_512));
+        assert!(key.decapsulate(&c[1..]).is_err());
+    }
+
+    #[test]
+    fn encapsulate_and_decapsulate()
+    {
+        for params in [MlKemParams::MlKem512, MlKemParams::MlKem768, MlKemParams::MlKem1024] {
+            let key = MlKemPrivateKey::generate(params);
+            let (secret, c) = key.public_key().encapsulate();
+            assert_eq!(c.len(), params.ciphertext_len());
+            assert_eq!(key.decapsulate(&c).unwrap(), secret);
+        }
+    }
+}
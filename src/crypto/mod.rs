@@ -0,0 +1,30 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! Native implementations of the cryptographic primitives used by pki.
+
+// index loops mirror the algorithm descriptions in the standards
+#![allow(clippy::needless_range_loop)]
+
+pub mod aes;
+pub mod bigint;
+pub mod composite;
+pub mod ec;
+pub mod eddsa;
+pub mod hash;
+pub mod kdf;
+pub mod mldsa;
+pub mod mlkem;
+pub mod rng;
+pub mod rsa;
+pub mod rsa_threshold;
+pub mod slhdsa;
@@ -0,0 +1,37 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! Cryptographically secure random numbers from the kernel CSPRNG.
+
+use std::fs::File;
+use std::io::Read;
+
+const RANDOM_DEVICE: &str = "/dev/urandom";
+
+//
+// Fill a buffer with random bytes.
+//
+pub fn rng_fill(buf: &mut [u8])
+{
+    let mut dev = File::open(RANDOM_DEVICE).expect("opening random device");
+    dev.read_exact(buf).expect("reading random device");
+}
+
+//
+// Allocate a buffer of random bytes.
+//
+pub fn rng_bytes(len: usize) -> Vec<u8>
+{
+    let mut buf = vec![0u8; len];
+    rng_fill(&mut buf);
+    return buf;
+}
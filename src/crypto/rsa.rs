@@ -0,0 +1,659 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! RSA key generation, PKCS#1 key encodings, RSASSA-PKCS1-v1_5 and
+//! RSASSA-PSS signatures and RSAES-PKCS1-v1_5 encryption (RFC 8017).
+
+use crate::asn1::*;
+use crate::crypto::bigint::{BigUint, Monty, is_probable_prime, small_primes};
+use crate::crypto::hash::HashAlgorithm;
+use crate::crypto::rng::rng_bytes;
+
+/// public exponent used for all generated keys
+pub const RSA_PUBLIC_EXPONENT: u64 = 0x10001;
+
+/// smallest accepted modulus size in bits
+pub const RSA_MIN_BITS: usize = 1024;
+
+/// largest accepted modulus size in bits
+pub const RSA_MAX_BITS: usize = 16384;
+
+/// RSA public key (n, e)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RsaPublicKey {
+    /// modulus
+    pub n: BigUint,
+    /// public exponent
+    pub e: BigUint,
+}
+
+/// RSA private key with CRT parameters
+#[derive(Clone, Debug)]
+pub struct RsaPrivateKey {
+    /// modulus
+    pub n: BigUint,
+    /// public exponent
+    pub e: BigUint,
+    /// private exponent
+    pub d: BigUint,
+    /// first prime factor
+    pub p: BigUint,
+    /// second prime factor
+    pub q: BigUint,
+    /// d mod (p-1)
+    pub dp: BigUint,
+    /// d mod (q-1)
+    pub dq: BigUint,
+    /// q^-1 mod p
+    pub qinv: BigUint,
+}
+
+//
+// Number of Miller-Rabin rounds for a 2^-100 error bound (FIPS 186-4 C.3).
+//
+fn mr_rounds(bits: usize) -> usize
+{
+    if bits >= 1536 {
+        return 4;
+    }
+    if bits >= 1024 {
+        return 5;
+    }
+    if bits >= 512 {
+        return 8;
+    }
+    return 40;
+}
+
+//
+// Generate a random prime with exactly the given number of bits and the
+// two most significant bits set. With safe set, (p-1)/2 is prime as well.
+// Candidates p where e divides p-1 are skipped.
+//
+pub fn generate_prime(bits: usize, safe: bool, e: u64) -> BigUint
+{
+    let primes = small_primes();
+
+    loop {
+        // for safe primes, sieve for q = (p-1)/2 and derive p = 2q+1
+        let qbits = if safe { bits - 1 } else { bits };
+        let mut base = BigUint::random_bits(qbits);
+        base.set_bit(qbits - 1);
+        base.set_bit(qbits - 2);
+        base.set_bit(0);
+        let residues: Vec<u64> = primes.iter().map(|p| base.rem_u64(*p)).collect();
+
+        let mut delta: u64 = 0;
+        while delta < (1 << 20) {
+            let sieved = primes.iter().zip(&residues).all(|(p, r)| {
+                let c = (r + delta) % p;
+                c != 0 && (!safe || !(2 * c + 1).is_multiple_of(*p))
+            });
+            delta += 2;
+            if !sieved {
+                continue;
+            }
+            let cand = base.add_u64(delta - 2);
+            let p = if safe { cand.shl(1).add_u64(1) } else { cand.clone() };
+            if p.bits() != bits || p.sub_u64(1).rem_u64(e) == 0 {
+                continue;
+            }
+            if safe {
+                // cheap single-round tests first, safe primes are rare
+                if !is_probable_prime(&cand, 1) || !is_probable_prime(&p, 1) ||
+                   !is_probable_prime(&cand, mr_rounds(qbits)) {
+                    continue;
+                }
+            }
+            if is_probable_prime(&p, mr_rounds(bits)) {
+                return p;
+            }
+        }
+    }
+}
+
+//
+// Inverse of a small value a modulo m by the extended Euclidean algorithm.
+//
+fn inv_mod_u64(a: u64, m: u64) -> Option<u64>
+{
+    let (mut old_r, mut r) = (a as i128 % m as i128, m as i128);
+    let (mut old_s, mut s) = (1i128, 0i128);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_s, s) = (s, old_s - q * s);
+    }
+    if old_r != 1 {
+        return None;
+    }
+    return Some(old_s.rem_euclid(m as i128) as u64);
+}
+
+//
+// Inverse of the public exponent e modulo a secret m, e.g. p-1. With
+// u = m^-1 mod e, d = (1 + m (e - u)) / e satisfies e d = 1 mod m, so only
+// the residue m mod e takes part in an inversion, and that residue is
+// blinded by a random unit r modulo e. The variable-time arithmetic thus
+// operates on public or random values only, apart from the division by
+// the public e.
+//
+pub fn inv_exponent(e: u64, m: &BigUint) -> Result<BigUint, String>
+{
+    if e < 3 || e.is_multiple_of(2) {
+        return Err(format!("invalid public exponent {}", e));
+    }
+    let r = loop {
+        let r = BigUint::random_below(&BigUint::from_u64(e)).low_u64();
+        if inv_mod_u64(r, e).is_some() {
+            break r;
+        }
+    };
+    let mul_mod = |a: u64, b: u64| (a as u128 * b as u128 % e as u128) as u64;
+    let s = inv_mod_u64(mul_mod(m.rem_u64(e), r), e).ok_or("public exponent not invertible")?;
+    let u = mul_mod(s, r);
+    let (d, rem) = m.mul_u64(e - u).add_u64(1).divrem_u64(e);
+    debug_assert_eq!(rem, 0);
+    return Ok(d);
+}
+
+//
+// EMSA-PKCS1-v1_5 encoding of a message hashed with the given algorithm
+// into k octets (RFC 8017, 9.2).
+//
+pub fn emsa_pkcs1_encode(hash: HashAlgorithm, data: &[u8], k: usize) -> Result<Vec<u8>, String>
+{
+    let digest_info = asn1_sequence(&[
+        asn1_algorithm_identifier(hash.oid(), Some(&asn1_null())),
+        asn1_octet_string(&hash.hash(data)),
+    ]);
+    if k < digest_info.len() + 11 {
+        return Err("RSA modulus too short for PKCS#1 signature".to_string());
+    }
+    let mut em = vec![0x00, 0x01];
+    em.resize(k - digest_info.len() - 1, 0xff);
+    em.push(0x00);
+    em.extend_from_slice(&digest_info);
+    return Ok(em);
+}
+
+//
+// MGF1 mask generation function (RFC 8017, B.2.1).
+//
+pub fn mgf1(hash: HashAlgorithm, seed: &[u8], len: usize) -> Vec<u8>
+{
+    let mut mask = Vec::with_capacity(len + hash.digest_len());
+    let mut counter: u32 = 0;
+    while mask.len() < len {
+        let mut block = seed.to_vec();
+        block.extend_from_slice(&counter.to_be_bytes());
+        mask.extend_from_slice(&hash.hash(&block));
+        counter += 1;
+    }
+    mask.truncate(len);
+    return mask;
+}
+
+//
+// EMSA-PSS encoding with a salt of the hash length and MGF1 using the
+// same hash (RFC 8017, 9.1.1).
+//
+pub fn emsa_pss_encode(hash: HashAlgorithm, data: &[u8], em_bits: usize) -> Result<Vec<u8>, String>
+{
+    let h_len = hash.digest_len();
+    let em_len = em_bits.div_ceil(8);
+    if em_len < 2 * h_len + 2 {
+        return Err("RSA modulus too short for PSS signature".to_string());
+    }
+    let salt = rng_bytes(h_len);
+    let mut m = vec![0u8; 8];
+    m.extend_from_slice(&hash.hash(data));
+    m.extend_from_slice(&salt);
+    let h = hash.hash(&m);
+
+    let mut db = vec![0u8; em_len - 2 * h_len - 2];
+    db.push(0x01);
+    db.extend_from_slice(&salt);
+    for (d, m) in db.iter_mut().zip(mgf1(hash, &h, em_len - h_len - 1)) {
+        *d ^= m;
+    }
+    db[0] &= 0xff >> (8 * em_len - em_bits);
+
+    let mut em = db;
+    em.extend_from_slice(&h);
+    em.push(0xbc);
+    return Ok(em);
+}
+
+//
+// EMSA-PSS verification (RFC 8017, 9.1.2).
+//
+pub fn emsa_pss_verify(hash: HashAlgorithm, data: &[u8], em: &[u8], em_bits: usize) -> bool
+{
+    let h_len = hash.digest_len();
+    let em_len = em_bits.div_ceil(8);
+    if em.len() != em_len || em_len < 2 * h_len + 2 || em[em_len - 1] != 0xbc {
+        return false;
+    }
+    let (masked_db, h) = em[..em_len - 1].split_at(em_len - h_len - 1);
+    let top_mask = 0xffu8 >> (8 * em_len - em_bits);
+    if masked_db[0] & !top_mask != 0 {
+        return false;
+    }
+    let mut db: Vec<u8> = masked_db.iter().zip(mgf1(hash, h, masked_db.len()))
+                                   .map(|(d, m)| d ^ m).collect();
+    db[0] &= top_mask;
+
+    let ps_len = em_len - 2 * h_len - 2;
+    if db[..ps_len].iter().any(|b| *b != 0) || db[ps_len] != 0x01 {
+        return false;
+    }
+    let mut m = vec![0u8; 8];
+    m.extend_from_slice(&hash.hash(data));
+    m.extend_from_slice(&db[ps_len + 1..]);
+    return hash.hash(&m) == h;
+}
+
+impl RsaPrivateKey {
+    //
+    // Generate a new RSA key with a modulus of the given size.
+    //
+    pub fn generate(bits: usize, safe_primes: bool) -> Result<RsaPrivateKey, String>
+    {
+        if !(RSA_MIN_BITS..=RSA_MAX_BITS).contains(&bits) || !bits.is_multiple_of(16) {
+            return Err(format!("invalid RSA key size of {} bits", bits));
+        }
+        let e = BigUint::from_u64(RSA_PUBLIC_EXPONENT);
+
+        loop {
+            let p = generate_prime(bits.div_ceil(2), safe_primes, RSA_PUBLIC_EXPONENT);
+            let q = generate_prime(bits / 2, safe_primes, RSA_PUBLIC_EXPONENT);
+            if p == q {
+                continue;
+            }
+            let key = RsaPrivateKey::from_primes(&p, &q, &e)?;
+            if key.n.bits() == bits {
+                return Ok(key);
+            }
+        }
+    }
+
+    //
+    // Derive the private key parameters from two primes and the public
+    // exponent, ordering the primes so that p > q. The private exponent is
+    // reduced modulo phi(n) = (p-1)(q-1) rather than lambda(n), which would
+    // need the gcd of the secret p-1 and q-1; RFC 8017 accepts either, as
+    // lambda(n) divides phi(n). All inversions go through inv_exponent()
+    // or a Fermat exponentiation on the Montgomery form.
+    //
+    pub fn from_primes(p: &BigUint, q: &BigUint, e: &BigUint) -> Result<RsaPrivateKey, String>
+    {
+        if e.bits() > 64 {
+            return Err("public exponents larger than 64 bits are not supported".to_string());
+        }
+        let (p, q) = if p > q { (p.clone(), q.clone()) } else { (q.clone(), p.clone()) };
+        let p1 = p.sub_u64(1);
+        let q1 = q.sub_u64(1);
+        let e64 = e.low_u64();
+
+        let d = inv_exponent(e64, &p1.mul(&q1))?;
+        let dp = inv_exponent(e64, &p1)?;
+        let dq = inv_exponent(e64, &q1)?;
+        let qinv = Monty::new(&p).pow(&q, &p.sub_u64(2));
+
+        Ok(RsaPrivateKey {
+            n: p.mul(&q), e: e.clone(), d, p, q, dp, dq, qinv,
+        })
+    }
+
+    //
+    // Size of the modulus in bits.
+    //
+    pub fn bits(&self) -> usize
+    {
+        return self.n.bits();
+    }
+
+    pub fn public_key(&self) -> RsaPublicKey
+    {
+        RsaPublicKey { n: self.n.clone(), e: self.e.clone() }
+    }
+
+    //
+    // Compute c^ep mod p and c^eq mod q for c < n and recombine the results
+    // with Garner's formula, all reductions done in the Montgomery domain
+    // with a running time independent of c.
+    //
+    fn crt_pow(&self, mp: &Monty, mq: &Monty, c: &BigUint, ep: &BigUint, eq: &BigUint) -> BigUint
+    {
+        let m1 = mp.pow_mont(&mp.to_mont_ct(c), ep);
+        let m2 = mq.from_mont(&mq.pow_mont(&mq.to_mont_ct(c), eq));
+
+        // h = (m1 - m2) qinv mod p, m2 < q < p
+        let h = mp.mul(&mp.sub(&m1, &mp.to_mont_ct(&m2)), &mp.to_mont_ct(&self.qinv));
+        return m2.add(&mp.from_mont(&h).mul(&self.q));
+    }
+
+    //
+    // RSA signature primitive using the CRT on a message blinded with r^e
+    // for a random r, whose inverse is computed modulo p and q with Fermat's
+    // little theorem.  The result is checked with the public exponent to
+    // guard against faults.
+    //
+    fn rsasp1(&self, m: &BigUint) -> Result<BigUint, String>
+    {
+        let mn = Monty::new(&self.n);
+        let mp = Monty::new(&self.p);
+        let mq = Monty::new(&self.q);
+
+        let r = loop {
+            let r = BigUint::random_below(&self.n);
+            if !r.is_zero() {
+                break r;
+            }
+        };
+        let r_inv = self.crt_pow(&mp, &mq, &r, &self.p.sub_u64(2), &self.q.sub_u64(2));
+        let blinded = mn.from_mont(&mn.mul(&mn.to_mont_ct(m), &mn.pow_mont(&mn.to_mont_ct(&r), &self.e)));
+        let s = self.crt_pow(&mp, &mq, &blinded, &self.dp, &self.dq);
+        let s = mn.from_mont(&mn.mul(&mn.to_mont_ct(&s), &mn.to_mont_ct(&r_inv)));
+
+        if mn.pow(&s, &self.e) != *m {
+            return Err("RSA signature verification failed".to_string());
+        }
+        return Ok(s);
+    }
+
+    //
+    // Create an RSASSA-PKCS1-v1_5 signature.
+    //
+    pub fn sign_pkcs1(&self, hash: HashAlgorithm, data: &[u8]) -> Result<Vec<u8>, String>
+    {
+        let k = self.n.bits().div_ceil(8);
+        let em = emsa_pkcs1_encode(hash, data, k)?;
+        let s = self.rsasp1(&BigUint::from_bytes_be(&em))?;
+        return Ok(s.to_bytes_be_padded(k));
+    }
+
+    //
+    // Create an RSASSA-PSS signature.
+    //
+    pub fn sign_pss(&self, hash: HashAlgorithm, data: &[u8]) -> Result<Vec<u8>, String>
+    {
+        let em = emsa_pss_encode(hash, data, self.n.bits() - 1)?;
+        let s = self.rsasp1(&BigUint::from_bytes_be(&em))?;
+        return Ok(s.to_bytes_be_padded(self.n.bits().div_ceil(8)));
+    }
+
+    //
+    // Decrypt an RSAES-PKCS1-v1_5 ciphertext.
+    //
+    pub fn decrypt_pkcs1(&self, ct: &[u8]) -> Result<Vec<u8>, String>
+    {
+        let k = self.n.bits().div_ceil(8);
+        let c = BigUint::from_bytes_be(ct);
+        if ct.len() != k || c >= self.n {
+            return Err("invalid RSA ciphertext".to_string());
+        }
+        let em = self.rsasp1(&c)?.to_bytes_be_padded(k);
+
+        // EM = 0x00 || 0x02 || PS || 0x00 || M with at least 8 octets of PS
+        let sep = em.iter().skip(2).position(|b| *b == 0).map(|i| i + 2);
+        match sep {
+            Some(i) if em[0] == 0x00 && em[1] == 0x02 && i >= 10 => { Ok(em[i + 1..].to_vec()) }
+            _ => { Err("RSA decryption failed".to_string()) }
+        }
+    }
+
+    //
+    // Encode as PKCS#1 RSAPrivateKey.
+    //
+    pub fn to_pkcs1(&self) -> Vec<u8>
+    {
+        return asn1_sequence(&[
+            asn1_integer_from_u64(0),
+            asn1_integer(&self.n.to_bytes_be()),
+            asn1_integer(&self.e.to_bytes_be()),
+            asn1_integer(&self.d.to_bytes_be()),
+            asn1_integer(&self.p.to_bytes_be()),
+            asn1_integer(&self.q.to_bytes_be()),
+            asn1_integer(&self.dp.to_bytes_be()),
+            asn1_integer(&self.dq.to_bytes_be()),
+            asn1_integer(&self.qinv.to_bytes_be()),
+        ]);
+    }
+
+    //
+    // Decode a PKCS#1 RSAPrivateKey.
+    //
+    pub fn from_pkcs1(der: &[u8]) -> Result<RsaPrivateKey, String>
+    {
+        let obj = asn1_parse(der)?;
+        if obj.tag != ASN1_SEQUENCE {
+            return Err("not an RSAPrivateKey".to_string());
+        }
+        let mut parser = obj.parser();
+        let version = parser.expect(ASN1_INTEGER)?.to_u64()?;
+        if version != 0 {
+            return Err("multi-prime RSA keys are not supported".to_string());
+        }
+        let mut next = || -> Result<BigUint, String> {
+            Ok(BigUint::from_bytes_be(parser.expect(ASN1_INTEGER)?.to_unsigned()?))
+        };
+        let key = RsaPrivateKey {
+            n: next()?, e: next()?, d: next()?, p: next()?, q: next()?,
+            dp: next()?, dq: next()?, qinv: next()?,
+        };
+        parser.finish()?;
+
+        if key.p.mul(&key.q) != key.n || !key.p.is_odd() || !key.q.is_odd() {
+            return Err("inconsistent RSA private key".to_string());
+        }
+        return Ok(key);
+    }
+}
+
+impl RsaPublicKey {
+    //
+    // Size of the modulus in bits.
+    //
+    pub fn bits(&self) -> usize
+    {
+        return self.n.bits();
+    }
+
+    //
+    // RSA verification primitive, returning the encoded message of the
+    // given length.
+    //
+    fn rsavp1(&self, sig: &[u8], len: usize) -> Option<Vec<u8>>
+    {
+        if sig.len() != self.n.bits().div_ceil(8) {
+            return None;
+        }
+        let s = BigUint::from_bytes_be(sig);
+        if s >= self.n {
+            return None;
+        }
+        let m = Monty::new(&self.n).pow(&s, &self.e);
+        if m.bits() > 8 * len {
+            return None;
+        }
+        return Some(m.to_bytes_be_padded(len));
+    }
+
+    //
+    // Encrypt a short message with RSAES-PKCS1-v1_5.
+    //
+    pub fn encrypt_pkcs1(&self, msg: &[u8]) -> Result<Vec<u8>, String>
+    {
+        let k = self.n.bits().div_ceil(8);
+        if msg.len() + 11 > k {
+            return Err("message too long for RSA encryption".to_string());
+        }
+        let mut em = vec![0x00, 0x02];
+        while em.len() < k - msg.len() - 1 {
+            em.extend(rng_bytes(k - msg.len() - 1 - em.len()).into_iter().filter(|b| *b != 0));
+        }
+        em.push(0x00);
+        em.extend_from_slice(msg);
+        let c = Monty::new(&self.n).pow(&BigUint::from_bytes_be(&em), &self.e);
+        return Ok(c.to_bytes_be_padded(k));
+    }
+
+    //
+    // Verify an RSASSA-PKCS1-v1_5 signature.
+    //
+    pub fn verify_pkcs1(&self, hash: HashAlgorithm, data: &[u8], sig: &[u8]) -> bool
+    {
+        let k = self.n.bits().div_ceil(8);
+        match (self.rsavp1(sig, k), emsa_pkcs1_encode(hash, data, k)) {
+            (Some(em), Ok(expected)) => { em == expected }
+            _ => { false }
+        }
+    }
+
+    //
+    // Verify an RSASSA-PSS signature with a salt of the hash length.
+    //
+    pub fn verify_pss(&self, hash: HashAlgorithm, data: &[u8], sig: &[u8]) -> bool
+    {
+        let em_bits = self.n.bits() - 1;
+        match self.rsavp1(sig, em_bits.div_ceil(8)) {
+            Some(em) => { emsa_pss_verify(hash, data, &em, em_bits) }
+            None => { false }
+        }
+    }
+
+    //
+    // Encode as PKCS#1 RSAPublicKey.
+    //
+    pub fn to_pkcs1(&self) -> Vec<u8>
+    {
+        return asn1_sequence(&[
+            asn1_integer(&self.n.to_bytes_be()),
+            asn1_integer(&self.e.to_bytes_be()),
+        ]);
+    }
+
+    //
+    // Decode a PKCS#1 RSAPublicKey.
+    //
+    pub fn from_pkcs1(der: &[u8]) -> Result<RsaPublicKey, String>
+    {
+        let obj = asn1_parse(der)?;
+        if obj.tag != ASN1_SEQUENCE {
+            return Err("not an RSAPublicKey".to_string());
+        }
+        let mut parser = obj.parser();
+        let n = BigUint::from_bytes_be(parser.expect(ASN1_INTEGER)?.to_unsigned()?);
+        let e = BigUint::from_bytes_be(parser.expect(ASN1_INTEGER)?.to_unsigned()?);
+        parser.finish()?;
+
+        let key = RsaPublicKey { n, e };
+        key.validate()?;
+        return Ok(key);
+    }
+
+    //
+    // Check the modulus and public exponent for plausibility.
+    //
+    pub fn validate(&self) -> Result<(), String>
+    {
+        if !self.n.is_odd() || self.n.bits() < 512 || !self.e.is_odd() || self.e.is_one() ||
+           self.e >= self.n {
+            return Err("invalid RSA public key".to_string());
+        }
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex;
+
+    // 1024 bit key and RSASSA-PKCS1-v1_5 SHA-256 signature created with
+    // pyca/cryptography
+    const PKCS1_KEY: &str = "
+        3082025d02010002818100c1a2602da5f7333638be6c315d5521f338500bdbfe1247aed5b778d4596cd8b717e6e2478d
+        cdc9f5170ababf3d4c2553e813b2ef64b948b1c84cc1af88417194140a79ea8c0ec752c4ce0dd219be091ae9a1ff3210
+        2274f9535ac05f2b3e378cc1933b9e9ba67b85c0c369fa8386dab2595e7c164723be6cb0d6e68b1559333f0203010001
+        028180725619089b596e69f3f3ad1b310b55f2b22bc1f4f2ff84485e75e9e929acb602d7204439102895ba1d12fed671
+        b186d116d69aeff4bfdb86eec9340d1c1dd89c1909413859b883ec61ae4d38a5c8beb3fd8194d13f3549829c94f5f52e
+        25cde50143f6f50a48e5f15a80c2388eea1ed41ef00931bf55526f848a3cce0fd99aa1024100f805dc512541f0511450
+        3e7838cd854f9a5c4292b1ef534193a525a28db6f5428fe320daba4a632715c5d7ea340c169a8adc7b0bc781ac5ac507
+        bb7447a09319024100c7dcb270abdf2748c7c5dbb063257cb5fe620c5bb4ca758fa568e054404fd5df594eac29d73d1b
+        845befc425720f5c7808e82b9b00151cd8f186c9a812675c170241009e58978553051b19989f7692a8a945f0417b9fa1
+        2a6ac23bbeb8942388d8831320ec0040c4e8d30ed40ec0bfacc4fef561ac4bfe843c1cbec2c621fea5f0359902403831
+        f2ae4175263b8d883164200c7b4b44b256dd363132eae7cc5c3ef4323e65655bfc866b31905a1feb83902fc8e7ec0c3d
+        137daa8145322e7f0296524e6393024100d24f584b3bfccd757ea4b0a82165800502d29624d2c56882798630afd957ad
+        47b8d8eca3ed7b0f6e23a2003edce7de4681ec774bf6fdbde19a13a98a813aebab";
+    const PKCS1_SIG: &str = "
+        56e5e9dedc1e3fd1409ff62852281d5ebdaae7c984f5db404ae91ca229b5649caeb2baea799b70765ff81d575a00fae5
+        2db9187f0b23b6b7f66974d1d8466bae8611019971ec5ca20d3725a32207bb4ac3f954d35e2d180059c8b6943e959344
+        810c3438b71d7906d1c65601d84d9a0efc71dd1da7d4437df93aed4f7f8a50a2";
+
+    #[test]
+    fn pkcs1_known_answer()
+    {
+        let key = RsaPrivateKey::from_pkcs1(&hex(PKCS1_KEY)).unwrap();
+        let data = b"RSASSA-PKCS1-v1_5 known answer test";
+        let sig = key.sign_pkcs1(HashAlgorithm::Sha256, data).unwrap();
+        assert_eq!(sig, hex(PKCS1_SIG));
+        assert!(key.public_key().verify_pkcs1(HashAlgorithm::Sha256, data, &sig));
+        assert!(!key.public_key().verify_pkcs1(HashAlgorithm::Sha256, b"other data", &sig));
+    }
+
+    // blinding and the Montgomery reductions must not depend on balanced
+    // primes or on the message
+    #[test]
+    fn rsasp1_crt()
+    {
+        let key = RsaPrivateKey::from_pkcs1(&hex(PKCS1_KEY)).unwrap();
+        for m in [BigUint::zero(), BigUint::one(), key.n.sub_u64(1), key.p.clone(), key.q.add_u64(1)] {
+            assert_eq!(key.rsasp1(&m).unwrap(), m.modpow(&key.d, &key.n));
+        }
+
+        let p = BigUint::from_hex("f7e75fdc469067ffdc4e847c51f452df").unwrap();
+        let q = BigUint::from_hex("e85cc4e7").unwrap();
+        let key = RsaPrivateKey::from_primes(&p, &q, &BigUint::from_u64(RSA_PUBLIC_EXPONENT)).unwrap();
+        let m = key.n.sub_u64(2);
+        assert_eq!(key.rsasp1(&m).unwrap(), m.modpow(&key.d, &key.n));
+    }
+
+    #[test]
+    fn blinded_exponent_inverse()
+    {
+        let e = RSA_PUBLIC_EXPONENT;
+        let key = RsaPrivateKey::from_pkcs1(&hex(PKCS1_KEY)).unwrap();
+        let (p1, q1) = (key.p.sub_u64(1), key.q.sub_u64(1));
+        for m in [p1.clone(), q1.clone(), p1.mul(&q1), BigUint::from_u64(e + 1)] {
+            for _ in 0..8 {
+                let d = inv_exponent(e, &m).unwrap();
+                assert!(d < m);
+                assert!(d.mul_u64(e).rem(&m).is_one());
+            }
+        }
+        assert_eq!(inv_exponent(e, &p1).unwrap(), key.dp);
+        assert_eq!(inv_exponent(e, &q1).unwrap(), key.dq);
+        assert_eq!(inv_exponent(3, &BigUint::from_u64(4)).unwrap(), BigUint::from_u64(3));
+
+        assert!(inv_exponent(e, &BigUint::from_u64(2 * e)).is_err());
+        assert!(inv_exponent(4, &BigUint::from_u64(9)).is_err());
+        assert!(inv_exponent(1, &BigUint::from_u64(9)).is_err());
+
+        let key = RsaPrivateKey::from_primes(&key.q, &key.p, &key.e).unwrap();
+        assert!(key.d.mul(&key.e).rem(&p1.mul(&q1)).is_one());
+        assert!(RsaPrivateKey::from_primes(&key.p, &key.q, &BigUint::from_hex("10000000000000001")
+                                                                .unwrap()).is_err());
+    }
+}
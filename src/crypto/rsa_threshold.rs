@@ -0,0 +1,322 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! Threshold RSA signatures after V. Shoup, "Practical Threshold
+//! Signatures", EUROCRYPT 2000. The private exponent is shared with a
+//! polynomial of degree threshold-1 over Z_m, m = p'q', so that any
+//! threshold out of the shares can create partial signatures which are
+//! combined into a standard RSA signature without reconstructing d.
+//! Each shareholder signs on its own with a single share, the partial
+//! signatures being combined afterwards, so that the shares never have to
+//! be brought together on one host.
+//!
+//! Key shares are encoded as
+//!
+//! ```text
+//! RSAKeyShare ::= SEQUENCE {
+//!     version          INTEGER (0),
+//!     modulus          INTEGER,  -- n
+//!     publicExponent   INTEGER,  -- e
+//!     shares           INTEGER,  -- total number of shares l
+//!     threshold        INTEGER,  -- shares required to sign k
+//!     index            INTEGER,  -- index i of this share, 1..l
+//!     share            INTEGER   -- s_i = f(i) mod m
+//! }
+//! ```
+
+use crate::asn1::*;
+use crate::crypto::bigint::{BigUint, Monty};
+use crate::crypto::rsa::*;
+
+/// largest supported number of shares, bounding the size of l!
+pub const RSA_MAX_SHARES: u32 = 64;
+
+/// Share of a threshold RSA private key
+#[derive(Clone, Debug)]
+pub struct RsaKeyShare {
+    /// modulus
+    pub n: BigUint,
+    /// public exponent
+    pub e: BigUint,
+    /// total number of shares
+    pub shares: u32,
+    /// number of shares required to sign
+    pub threshold: u32,
+    /// index of this share, starting at 1
+    pub index: u32,
+    /// share of the private exponent
+    pub s: BigUint,
+}
+
+/// Partial signature created with a single key share
+#[derive(Clone, Debug)]
+pub struct RsaPartialSignature {
+    /// index of the key share
+    pub index: u32,
+    /// x^(2 l! s_i) mod n
+    pub value: BigUint,
+}
+
+//
+// Compute l! for the given number of shares.
+//
+fn delta(shares: u32) -> BigUint
+{
+    let mut d = BigUint::one();
+    for i in 2..=shares as u64 {
+        d = d.mul_u64(i);
+    }
+    return d;
+}
+
+//
+// Generate an RSA key built from safe primes and split its private exponent
+// into the given number of shares, any threshold of them able to sign.
+//
+pub fn generate_shares(bits: usize, shares: u32, threshold: u32)
+                       -> Result<(RsaPublicKey, Vec<RsaKeyShare>), String>
+{
+    if !(RSA_MIN_BITS..=RSA_MAX_BITS).contains(&bits) || !bits.is_multiple_of(16) {
+        return Err(format!("invalid RSA key size of {} bits", bits));
+    }
+    if shares == 0 || shares > RSA_MAX_SHARES {
+        return Err(format!("number of shares must be between 1 and {}", RSA_MAX_SHARES));
+    }
+    if threshold == 0 || threshold > shares {
+        return Err(format!("threshold must be between 1 and the number of shares {}", shares));
+    }
+    let e = BigUint::from_u64(RSA_PUBLIC_EXPONENT);
+
+    let (p, q) = loop {
+        let p = generate_prime(bits.div_ceil(2), true, RSA_PUBLIC_EXPONENT);
+        let q = generate_prime(bits / 2, true, RSA_PUBLIC_EXPONENT);
+        if p != q && p.mul(&q).bits() == bits {
+            break (p, q);
+        }
+    };
+    let n = p.mul(&q);
+    let m = p.shr(1).mul(&q.shr(1));
+    let d = inv_exponent(RSA_PUBLIC_EXPONENT, &m)?;
+
+    // f(X) = d + a_1 X + ... + a_(k-1) X^(k-1) mod m
+    let mut coeffs = vec![d];
+    for _ in 1..threshold {
+        coeffs.push(BigUint::random_below(&m));
+    }
+    let key_shares = (1..=shares).map(|i| {
+        let s = coeffs.iter().rev().fold(BigUint::zero(), |acc, a| {
+            acc.mul_u64(i as u64).add(a).rem(&m)
+        });
+        RsaKeyShare { n: n.clone(), e: e.clone(), shares, threshold, index: i, s }
+    }).collect();
+
+    return Ok((RsaPublicKey { n, e }, key_shares));
+}
+
+impl RsaKeyShare {
+    pub fn public_key(&self) -> RsaPublicKey
+    {
+        RsaPublicKey { n: self.n.clone(), e: self.e.clone() }
+    }
+
+    //
+    // Size of the modulus in bits.
+    //
+    pub fn bits(&self) -> usize
+    {
+        return self.n.bits();
+    }
+
+    //
+    // Create a partial signature x^(2 l! s_i) over an encoded message.
+    //
+    pub fn sign_partial(&self, em: &[u8]) -> RsaPartialSignature
+    {
+        let x = BigUint::from_bytes_be(em);
+        let exp = delta(self.shares).shl(1).mul(&self.s);
+        RsaPartialSignature { index: self.index, value: Monty::new(&self.n).pow(&x, &exp) }
+    }
+
+    //
+    // Encode as RSAKeyShare.
+    //
+    pub fn to_der(&self) -> Vec<u8>
+    {
+        return asn1_sequence(&[
+            asn1_integer_from_u64(0),
+            asn1_integer(&self.n.to_bytes_be()),
+            asn1_integer(&self.e.to_bytes_be()),
+            asn1_integer_from_u64(self.shares as u64),
+            asn1_integer_from_u64(self.threshold as u64),
+            asn1_integer_from_u64(self.index as u64),
+            asn1_integer(&self.s.to_bytes_be()),
+        ]);
+    }
+
+    //
+    // Decode an RSAKeyShare.
+    //
+    pub fn from_der(der: &[u8]) -> Result<RsaKeyShare, String>
+    {
+        let obj = asn1_parse(der)?;
+        if obj.tag != ASN1_SEQUENCE {
+            return Err("not an RSAKeyShare".to_string());
+        }
+        let mut parser = obj.parser();
+        if parser.expect(ASN1_INTEGER)?.to_u64()? != 0 {
+            return Err("unsupported RSAKeyShare version".to_string());
+        }
+        let n = BigUint::from_bytes_be(parser.expect(ASN1_INTEGER)?.to_unsigned()?);
+        let e = BigUint::from_bytes_be(parser.expect(ASN1_INTEGER)?.to_unsigned()?);
+        let shares = parser.expect(ASN1_INTEGER)?.to_u64()?;
+        let threshold = parser.expect(ASN1_INTEGER)?.to_u64()?;
+        let index = parser.expect(ASN1_INTEGER)?.to_u64()?;
+        let s = BigUint::from_bytes_be(parser.expect(ASN1_INTEGER)?.to_unsigned()?);
+        parser.finish()?;
+
+        if shares == 0 || shares > RSA_MAX_SHARES as u64 || threshold == 0 ||
+           threshold > shares || index == 0 || index > shares {
+            return Err("invalid RSA key share parameters".to_string());
+        }
+        let share = RsaKeyShare {
+            n, e, s, shares: shares as u32, threshold: threshold as u32, index: index as u32,
+        };
+        share.public_key().validate()?;
+        return Ok(share);
+    }
+}
+
+//
+// Combine threshold partial signatures over an encoded message into an
+// RSA signature, verifying the result with the public key.
+//
+pub fn combine_partial_signatures(key: &RsaPublicKey, shares: u32, threshold: u32, em: &[u8],
+                                  partials: &[RsaPartialSignature]) -> Result<Vec<u8>, String>
+{
+    let mut set: Vec<&RsaPartialSignature> = Vec::new();
+    for partial in partials {
+        if partial.index == 0 || partial.index > shares {
+            return Err(format!("invalid key share index {}", partial.index));
+        }
+        if !set.iter().any(|p| p.index == partial.index) {
+            set.push(partial);
+        }
+    }
+    if set.len() < threshold as usize {
+        return Err(format!("{} partial signatures but a threshold of {} required",
+                           set.len(), threshold));
+    }
+    set.truncate(threshold as usize);
+
+    let monty = Monty::new(&key.n);
+    let x = BigUint::from_bytes_be(em);
+    let delta = delta(shares);
+
+    // w = prod x_i^(2 lambda_i), lambda_i = l! prod_(j != i) j / (j - i)
+    let mut w = monty.one();
+    for pi in &set {
+        let i = pi.index as i64;
+        let mut num = delta.clone();
+        let mut den = BigUint::one();
+        let mut negative = false;
+        for pj in &set {
+            let j = pj.index as i64;
+            if j != i {
+                num = num.mul_u64(j as u64);
+                den = den.mul_u64((j - i).unsigned_abs());
+                negative ^= j < i;
+            }
+        }
+        let lambda = num.divrem(&den).0.shl(1);
+        let base = if negative {
+            pi.value.modinv(&key.n).ok_or("partial signature not invertible")?
+        } else {
+            pi.value.clone()
+        };
+        w = monty.mul(&w, &monty.pow_mont(&monty.to_mont(&base), &lambda));
+    }
+
+    // w^e = x^e' with e' = 4 l!^2, y = w^a x^b where a e' + b e = 1
+    let e_prime = delta.mul(&delta).shl(2);
+    let a = e_prime.modinv(&key.e).ok_or("public exponent not coprime to 4 l!^2")?;
+    let b_neg = a.mul(&e_prime).sub_u64(1).divrem(&key.e).0;
+    let x_inv = x.modinv(&key.n).ok_or("message representative not invertible")?;
+    let y = monty.mul(&monty.pow_mont(&w, &a),
+                      &monty.pow_mont(&monty.to_mont(&x_inv), &b_neg));
+    let y = monty.from_mont(&y);
+
+    if monty.pow(&y, &key.e) != x {
+        return Err("combined signature is invalid, inconsistent key shares".to_string());
+    }
+    return Ok(y.to_bytes_be_padded(key.n.bits().div_ceil(8)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::hash::HashAlgorithm;
+
+    #[test]
+    fn combine_threshold_shares()
+    {
+        let data = b"threshold RSA signature";
+        let (public, shares) = generate_shares(RSA_MIN_BITS, 5, 3).unwrap();
+        let k = public.n.bits().div_ceil(8);
+
+        // partial signatures created independently by the shareholders
+        for indices in [[1, 2, 3], [1, 3, 5], [5, 4, 2]] {
+            let em = emsa_pkcs1_encode(HashAlgorithm::Sha256, data, k).unwrap();
+            let partials: Vec<RsaPartialSignature> = indices.iter()
+                .map(|i| shares[i - 1].sign_partial(&em)).collect();
+            let sig = combine_partial_signatures(&public, 5, 3, &em, &partials).unwrap();
+            assert!(public.verify_pkcs1(HashAlgorithm::Sha256, data, &sig));
+
+            let em = emsa_pss_encode(HashAlgorithm::Sha256, data, public.n.bits() - 1).unwrap();
+            let partials: Vec<RsaPartialSignature> = indices.iter()
+                .map(|i| shares[i - 1].sign_partial(&em)).collect();
+            let sig = combine_partial_signatures(&public, 5, 3, &em, &partials).unwrap();
+            assert!(public.verify_pss(HashAlgorithm::Sha256, data, &sig));
+        }
+
+        let em = emsa_pkcs1_encode(HashAlgorithm::Sha384, data, k).unwrap();
+        let partials: Vec<RsaPartialSignature> = [&shares[4], &shares[1], &shares[2]]
+            .iter().map(|s| s.sign_partial(&em)).collect();
+        let sig = combine_partial_signatures(&public, 5, 3, &em, &partials).unwrap();
+        assert!(public.verify_pkcs1(HashAlgorithm::Sha384, data, &sig));
+
+        assert!(combine_partial_signatures(&public, 5, 3, &em, &partials[..2]).is_err());
+        let duplicates = vec![partials[0].clone(), partials[1].clone(), partials[0].clone()];
+        assert!(combine_partial_signatures(&public, 5, 3, &em, &duplicates).is_err());
+        let mut forged = partials.clone();
+        forged[2].value = forged[2].value.add(&BigUint::one());
+        assert!(combine_partial_signatures(&public, 5, 3, &em, &forged).is_err());
+
+        // a share of another key spoils the combination
+        let (_, other) = generate_shares(RSA_MIN_BITS, 5, 3).unwrap();
+        let mixed = vec![partials[0].clone(), partials[1].clone(), other[0].sign_partial(&em)];
+        assert!(combine_partial_signatures(&public, 5, 3, &em, &mixed).is_err());
+    }
+
+    #[test]
+    fn key_share_encoding()
+    {
+        let (_, shares) = generate_shares(RSA_MIN_BITS, 3, 2).unwrap();
+        let share = RsaKeyShare::from_der(&shares[1].to_der()).unwrap();
+        assert_eq!((share.index, share.shares, share.threshold), (2, 3, 2));
+        assert_eq!(share.s, shares[1].s);
+        assert_eq!(share.public_key(), shares[1].public_key());
+
+        let mut invalid = shares[0].clone();
+        invalid.index = 4;
+        assert!(RsaKeyShare::from_der(&invalid.to_der()).is_err());
+    }
+}
@@ -0,0 +1,889 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! Stateless Hash-Based Digital Signature Algorithm SLH-DSA (FIPS 205),
+//! pure variant with an empty context string, for all SHA2 and SHAKE
+//! parameter sets.
+
+use crate::oid::*;
+use crate::crypto::hash::{HashAlgorithm, Keccak, sha256, sha512};
+use crate::crypto::kdf::hmac;
+use crate::crypto::rsa::mgf1;
+use crate::crypto::rng::rng_bytes;
+
+// Winternitz parameter lg_w and w = 2^lg_w
+const LG_W: usize = 4;
+const W: u32 = 16;
+
+// WOTS+ checksum chains
+const LEN2: usize = 3;
+
+// address types
+const WOTS_HASH: u32 = 0;
+const WOTS_PK: u32 = 1;
+const TREE: u32 = 2;
+const FORS_TREE: u32 = 3;
+const FORS_ROOTS: u32 = 4;
+const WOTS_PRF: u32 = 5;
+const FORS_PRF: u32 = 6;
+
+/// SLH-DSA parameter sets
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SlhDsaParams {
+    Sha2_128s,
+    Sha2_128f,
+    Sha2_192s,
+    Sha2_192f,
+    Sha2_256s,
+    Sha2_256f,
+    Shake128s,
+    Shake128f,
+    Shake192s,
+    Shake192f,
+    Shake256s,
+    Shake256f,
+}
+
+/// Properties of a parameter set (FIPS 205, Table 2)
+struct SlhDsaInfo {
+    params: SlhDsaParams,
+    /// key type name used with --type
+    name: &'static str,
+    oid: &'static str,
+    shake: bool,
+    /// hash output length in octets
+    n: usize,
+    /// total hypertree height
+    h: usize,
+    /// number of hypertree layers
+    d: usize,
+    /// FORS tree height
+    a: usize,
+    /// number of FORS trees
+    k: usize,
+    /// message digest length in octets
+    m: usize,
+}
+
+const SLH_DSA: &[SlhDsaInfo] = &[
+    SlhDsaInfo { params: SlhDsaParams::Sha2_128s, name: "slhdsa-sha2-128s", oid: OID_SLH_DSA_SHA2_128S,
+                 shake: false, n: 16, h: 63, d: 7,  a: 12, k: 14, m: 30 },
+    SlhDsaInfo { params: SlhDsaParams::Sha2_128f, name: "slhdsa-sha2-128f", oid: OID_SLH_DSA_SHA2_128F,
+                 shake: false, n: 16, h: 66, d: 22, a: 6,  k: 33, m: 34 },
+    SlhDsaInfo { params: SlhDsaParams::Sha2_192s, name: "slhdsa-sha2-192s", oid: OID_SLH_DSA_SHA2_192S,
+                 shake: false, n: 24, h: 63, d: 7,  a: 14, k: 17, m: 39 },
+    SlhDsaInfo { params: SlhDsaParams::Sha2_192f, name: "slhdsa-sha2-192f", oid: OID_SLH_DSA_SHA2_192F,
+                 shake: false, n: 24, h: 66, d: 22, a: 8,  k: 33, m: 42 },
+    SlhDsaInfo { params: SlhDsaParams::Sha2_256s, name: "slhdsa-sha2-256s", oid: OID_SLH_DSA_SHA2_256S,
+                 shake: false, n: 32, h: 64, d: 8,  a: 14, k: 22, m: 47 },
+    SlhDsaInfo { params: SlhDsaParams::Sha2_256f, name: "slhdsa-sha2-256f", oid: OID_SLH_DSA_SHA2_256F,
+                 shake: false, n: 32, h: 68, d: 17, a: 9,  k: 35, m: 49 },
+    SlhDsaInfo { params: SlhDsaParams::Shake128s, name: "slhdsa-shake-128s", oid: OID_SLH_DSA_SHAKE_128S,
+                 shake: true,  n: 16, h: 63, d: 7,  a: 12, k: 14, m: 30 },
+    SlhDsaInfo { params: SlhDsaParams::Shake128f, name: "slhdsa-shake-128f", oid: OID_SLH_DSA_SHAKE_128F,
+                 shake: true,  n: 16, h: 66, d: 22, a: 6,  k: 33, m: 34 },
+    SlhDsaInfo { params: SlhDsaParams::Shake192s, name: "slhdsa-shake-192s", oid: OID_SLH_DSA_SHAKE_192S,
+                 shake: true,  n: 24, h: 63, d: 7,  a: 14, k: 17, m: 39 },
+    SlhDsaInfo { params: SlhDsaParams::Shake192f, name: "slhdsa-shake-192f", oid: OID_SLH_DSA_SHAKE_192F,
+                 shake: true,  n: 24, h: 66, d: 22, a: 8,  k: 33, m: 42 },
+    SlhDsaInfo { params: SlhDsaParams::Shake256s, name: "slhdsa-shake-256s", oid: OID_SLH_DSA_SHAKE_256S,
+                 shake: true,  n: 32, h: 64, d: 8,  a: 14, k: 22, m: 47 },
+    SlhDsaInfo { params: SlhDsaParams::Shake256f, name: "slhdsa-shake-256f", oid: OID_SLH_DSA_SHAKE_256F,
+                 shake: true,  n: 32, h: 68, d: 17, a: 9,  k: 35, m: 49 },
+];
+
+impl SlhDsaParams {
+    fn info(&self) -> &'static SlhDsaInfo
+    {
+        return SLH_DSA.iter().find(|i| i.params == *self).expect("SLH-DSA parameters");
+    }
+
+    //
+    // Parse a parameter set given with --type.
+    //
+    pub fn parse(name: &str) -> Option<SlhDsaParams>
+    {
+        return SLH_DSA.iter().find(|i| i.name == name).map(|i| i.params);
+    }
+
+    pub fn from_oid(oid: &str) -> Option<SlhDsaParams>
+    {
+        return SLH_DSA.iter().find(|i| i.oid == oid).map(|i| i.params);
+    }
+
+    pub fn oid(&self) -> &'static str
+    {
+        return self.info().oid;
+    }
+
+    //
+    // Security strength in bits.
+    //
+    pub fn strength(&self) -> usize
+    {
+        return 8 * self.info().n;
+    }
+
+    pub fn public_key_len(&self) -> usize
+    {
+        return 2 * self.info().n;
+    }
+
+    pub fn private_key_len(&self) -> usize
+    {
+        return 4 * self.info().n;
+    }
+
+    pub fn signature_len(&self) -> usize
+    {
+        let p = self.info();
+        return p.n * (1 + p.k * (1 + p.a) + p.h + p.d * wots_len(p.n));
+    }
+}
+
+//
+// Number of WOTS+ chains for hash length n.
+//
+fn wots_len(n: usize) -> usize
+{
+    return 2 * n + LEN2;
+}
+
+/// 32 octet hash function address
+#[derive(Clone, Copy)]
+struct Adrs([u8; 32]);
+
+impl Adrs {
+    fn new() -> Adrs
+    {
+        Adrs([0; 32])
+    }
+
+    fn set_word(&mut self, offset: usize, value: u32)
+    {
+        self.0[offset..offset + 4].copy_from_slice(&value.to_be_bytes());
+    }
+
+    fn word(&self, offset: usize) -> u32
+    {
+        return u32::from_be_bytes(self.0[offset..offset + 4].try_into().unwrap());
+    }
+
+    fn set_layer(&mut self, layer: u32)
+    {
+        self.set_word(0, layer);
+    }
+
+    fn set_tree(&mut self, tree: u64)
+    {
+        self.0[4..8].fill(0);
+        self.0[8..16].copy_from_slice(&tree.to_be_bytes());
+    }
+
+    fn set_type_and_clear(&mut self, adrs_type: u32)
+    {
+        self.set_word(16, adrs_type);
+        self.0[20..].fill(0);
+    }
+
+    fn set_key_pair(&mut self, index: u32)
+    {
+        self.set_word(20, index);
+    }
+
+    fn key_pair(&self) -> u32
+    {
+        return self.word(20);
+    }
+
+    fn set_chain(&mut self, index: u32)
+    {
+        self.set_word(24, index);
+    }
+
+    fn set_tree_height(&mut self, height: u32)
+    {
+        self.set_word(24, height);
+    }
+
+    fn set_hash(&mut self, index: u32)
+    {
+        self.set_word(28, index);
+    }
+
+    fn set_tree_index(&mut self, index: u32)
+    {
+        self.set_word(28, index);
+    }
+
+    fn tree_index(&self) -> u32
+    {
+        return self.word(28);
+    }
+
+    //
+    // Compressed 22 octet address used with SHA2 (FIPS 205, 11.2).
+    //
+    fn compressed(&self) -> [u8; 22]
+    {
+        let mut c = [0u8; 22];
+        c[0] = self.0[3];
+        c[1..9].copy_from_slice(&self.0[8..16]);
+        c[9] = self.0[19];
+        c[10..].copy_from_slice(&self.0[20..]);
+        return c;
+    }
+}
+
+//
+// Split input into b-bit integers (Algorithm 4).
+//
+fn base_2b(x: &[u8], b: usize, out_len: usize) -> Vec<u32>
+{
+    let mut out = Vec::with_capacity(out_len);
+    let mut pos = 0;
+    let mut bits = 0;
+    let mut total: u64 = 0;
+    for _ in 0..out_len {
+        while bits < b {
+            total = (total << 8) | x[pos] as u64;
+            pos += 1;
+            bits += 8;
+        }
+        bits -= b;
+        out.push(((total >> bits) & ((1 << b) - 1)) as u32);
+    }
+    return out;
+}
+
+//
+// Big-endian integer of at most 8 octets.
+//
+fn to_int(x: &[u8]) -> u64
+{
+    return x.iter().fold(0, |acc, b| (acc << 8) | *b as u64);
+}
+
+/// Hash function context bound to a key pair
+struct Context<'a> {
+    p: &'static SlhDsaInfo,
+    pk_seed: &'a [u8],
+    sk_seed: &'a [u8],
+}
+
+impl Context<'_> {
+    //
+    // Tweakable hash T_l, also serving as F and H (FIPS 205, 11.1/11.2).
+    //
+    fn t(&self, adrs: &Adrs, m: &[u8]) -> Vec<u8>
+    {
+        let n = self.p.n;
+        if self.p.shake {
+            let mut xof = Keccak::shake256();
+            xof.absorb(self.pk_seed);
+            xof.absorb(&adrs.0);
+            xof.absorb(m);
+            return xof.squeeze(n);
+        }
+        // F uses SHA-256 for all security categories
+        let (block, big) = if n == 16 || m.len() == n { (64, false) } else { (128, true) };
+        let mut data = self.pk_seed.to_vec();
+        data.resize(block, 0);
+        data.extend_from_slice(&adrs.compressed());
+        data.extend_from_slice(m);
+        if big {
+            return sha512(&data)[..n].to_vec();
+        }
+        return sha256(&data)[..n].to_vec();
+    }
+
+    //
+    // Pseudorandom function PRF for secret key generation.
+    //
+    fn prf(&self, adrs: &Adrs) -> Vec<u8>
+    {
+        if self.p.shake {
+            return self.t(adrs, self.sk_seed);
+        }
+        let mut data = self.pk_seed.to_vec();
+        data.resize(64, 0);
+        data.extend_from_slice(&adrs.compressed());
+        data.extend_from_slice(self.sk_seed);
+        return sha256(&data)[..self.p.n].to_vec();
+    }
+
+    //
+    // Chaining function (Algorithm 5).
+    //
+    fn chain(&self, x: &[u8], start: u32, steps: u32, adrs: &mut Adrs) -> Vec<u8>
+    {
+        let mut tmp = x.to_vec();
+        for j in start..start + steps {
+            adrs.set_hash(j);
+            tmp = self.t(adrs, &tmp);
+        }
+        return tmp;
+    }
+
+    //
+    // Message to sign as base-w digits including the checksum.
+    //
+    fn wots_digits(&self, m: &[u8]) -> Vec<u32>
+    {
+        let len1 = 2 * self.p.n;
+        let mut msg = base_2b(m, LG_W, len1);
+        let mut csum: u32 = msg.iter().map(|v| W - 1 - v).sum();
+        csum <<= (8 - (LEN2 * LG_W) % 8) % 8;
+        msg.extend(base_2b(&csum.to_be_bytes()[2..], LG_W, LEN2));
+        return msg;
+    }
+
+    fn wots_sk(&self, adrs: &Adrs, chain: u32) -> Vec<u8>
+    {
+        let mut sk_adrs = *adrs;
+        sk_adrs.set_type_and_clear(WOTS_PRF);
+        sk_adrs.set_key_pair(adrs.key_pair());
+        sk_adrs.set_chain(chain);
+        return self.prf(&sk_adrs);
+    }
+
+    fn wots_compress(&self, adrs: &Adrs, tmp: &[u8]) -> Vec<u8>
+    {
+        let mut pk_adrs = *adrs;
+        pk_adrs.set_type_and_clear(WOTS_PK);
+        pk_adrs.set_key_pair(adrs.key_pair());
+        return self.t(&pk_adrs, tmp);
+    }
+
+    //
+    // Generate a WOTS+ public key (Algorithm 6).
+    //
+    fn wots_pk_gen(&self, adrs: &mut Adrs) -> Vec<u8>
+    {
+        let mut tmp = Vec::new();
+        for i in 0..wots_len(self.p.n) as u32 {
+            let sk = self.wots_sk(adrs, i);
+            adrs.set_chain(i);
+            tmp.extend(self.chain(&sk, 0, W - 1, adrs));
+        }
+        return self.wots_compress(adrs, &tmp);
+    }
+
+    //
+    // Generate a WOTS+ signature (Algorithm 7).
+    //
+    fn wots_sign(&self, m: &[u8], adrs: &mut Adrs) -> Vec<u8>
+    {
+        let mut sig = Vec::new();
+        for (i, digit) in self.wots_digits(m).into_iter().enumerate() {
+            let sk = self.wots_sk(adrs, i as u32);
+            adrs.set_chain(i as u32);
+            sig.extend(self.chain(&sk, 0, digit, adrs));
+        }
+        return sig;
+    }
+
+    //
+    // Compute a WOTS+ public key from a signature (Algorithm 8).
+    //
+    fn wots_pk_from_sig(&self, sig: &[u8], m: &[u8], adrs: &mut Adrs) -> Vec<u8>
+    {
+        let n = self.p.n;
+        let mut tmp = Vec::new();
+        for (i, digit) in self.wots_digits(m).into_iter().enumerate() {
+            adrs.set_chain(i as u32);
+            tmp.extend(self.chain(&sig[i * n..(i + 1) * n], digit, W - 1 - digit, adrs));
+        }
+        return self.wots_compress(adrs, &tmp);
+    }
+
+    //
+    // Compute the root of a Merkle subtree of WOTS+ public keys
+    // (Algorithm 9).
+    //
+    fn xmss_node(&self, i: u32, z: u32, adrs: &mut Adrs) -> Vec<u8>
+    {
+        if z == 0 {
+            adrs.set_type_and_clear(WOTS_HASH);
+            adrs.set_key_pair(i);
+            return self.wots_pk_gen(adrs);
+        }
+        let mut node = self.xmss_node(2 * i, z - 1, adrs);
+        node.extend(self.xmss_node(2 * i + 1, z - 1, adrs));
+        adrs.set_type_and_clear(TREE);
+        adrs.set_tree_height(z);
+        adrs.set_tree_index(i);
+        return self.t(adrs, &node);
+    }
+
+    //
+    // Generate an XMSS signature (Algorithm 10).
+    //
+    fn xmss_sign(&self, m: &[u8], idx: u32, adrs: &mut Adrs) -> Vec<u8>
+    {
+        let hp = (self.p.h / self.p.d) as u32;
+        let mut auth = Vec::new();
+        for j in 0..hp {
+            let k = (idx >> j) ^ 1;
+            auth.extend(self.xmss_node(k, j, adrs));
+        }
+        adrs.set_type_and_clear(WOTS_HASH);
+        adrs.set_key_pair(idx);
+        let mut sig = self.wots_sign(m, adrs);
+        sig.extend(auth);
+        return sig;
+    }
+
+    //
+    // Compute an XMSS public key from a signature (Algorithm 11).
+    //
+    fn xmss_pk_from_sig(&self, idx: u32, sig: &[u8], m: &[u8], adrs: &mut Adrs) -> Vec<u8>
+    {
+        let n = self.p.n;
+        let hp = (self.p.h / self.p.d) as u32;
+        let wots_sig_len = wots_len(n) * n;
+        adrs.set_type_and_clear(WOTS_HASH);
+        adrs.set_key_pair(idx);
+        let mut node = self.wots_pk_from_sig(&sig[..wots_sig_len], m, adrs);
+
+        adrs.set_type_and_clear(TREE);
+        adrs.set_tree_index(idx);
+        for k in 0..hp {
+            let auth = &sig[wots_sig_len + k as usize * n..wots_sig_len + (k as usize + 1) * n];
+            adrs.set_tree_height(k + 1);
+            let mut input = Vec::with_capacity(2 * n);
+            if (idx >> k) & 1 == 0 {
+                adrs.set_tree_index(adrs.tree_index() / 2);
+                input.extend_from_slice(&node);
+                input.extend_from_slice(auth);
+            } else {
+                adrs.set_tree_index((adrs.tree_index() - 1) / 2);
+                input.extend_from_slice(auth);
+                input.extend_from_slice(&node);
+            }
+            node = self.t(adrs, &input);
+        }
+        return node;
+    }
+
+    //
+    // Generate a hypertree signature (Algorithm 12).
+    //
+    fn ht_sign(&self, m: &[u8], mut idx_tree: u64, mut idx_leaf: u32) -> Vec<u8>
+    {
+        let hp = self.p.h / self.p.d;
+        let mut adrs = Adrs::new();
+        adrs.set_tree(idx_tree);
+        let mut sig = self.xmss_sign(m, idx_leaf, &mut adrs);
+        let mut root = self.xmss_pk_from_sig(idx_leaf, &sig, m, &mut adrs);
+        for j in 1..self.p.d {
+            idx_leaf = (idx_tree & ((1 << hp) - 1)) as u32;
+            idx_tree >>= hp;
+            adrs.set_layer(j as u32);
+            adrs.set_tree(idx_tree);
+            let tmp = self.xmss_sign(&root, idx_leaf, &mut adrs);
+            if j < self.p.d - 1 {
+                root = self.xmss_pk_from_sig(idx_leaf, &tmp, &root, &mut adrs);
+            }
+            sig.extend(tmp);
+        }
+        return sig;
+    }
+
+    //
+    // Verify a hypertree signature (Algorithm 13).
+    //
+    fn ht_verify(&self, m: &[u8], sig: &[u8], mut idx_tree: u64, mut idx_leaf: u32,
+                 pk_root: &[u8]) -> bool
+    {
+        let hp = self.p.h / self.p.d;
+        let xmss_len = (wots_len(self.p.n) + hp) * self.p.n;
+        let mut adrs = Adrs::new();
+        adrs.set_tree(idx_tree);
+        let mut node = self.xmss_pk_from_sig(idx_leaf, &sig[..xmss_len], m, &mut adrs);
+        for j in 1..self.p.d {
+            idx_leaf = (idx_tree & ((1 << hp) - 1)) as u32;
+            idx_tree >>= hp;
+            adrs.set_layer(j as u32);
+            adrs.set_tree(idx_tree);
+            node = self.xmss_pk_from_sig(idx_leaf, &sig[j * xmss_len..(j + 1) * xmss_len],
+                                         &node, &mut adrs);
+        }
+        return node == pk_root;
+    }
+
+    //
+    // Generate a FORS private key value (Algorithm 14).
+    //
+    fn fors_sk(&self, adrs: &Adrs, idx: u32) -> Vec<u8>
+    {
+        let mut sk_adrs = *adrs;
+        sk_adrs.set_type_and_clear(FORS_PRF);
+        sk_adrs.set_key_pair(adrs.key_pair());
+        sk_adrs.set_tree_index(idx);
+        return self.prf(&sk_adrs);
+    }
+
+    //
+    // Compute the root of a Merkle subtree of FORS public values
+    // (Algorithm 15).
+    //
+    fn fors_node(&self, i: u32, z: u32, adrs: &mut Adrs) -> Vec<u8>
+    {
+        if z == 0 {
+            let sk = self.fors_sk(adrs, i);
+            adrs.set_tree_height(0);
+            adrs.set_tree_index(i);
+            return self.t(adrs, &sk);
+        }
+        let mut node = self.fors_node(2 * i, z - 1, adrs);
+        node.extend(self.fors_node(2 * i + 1, z - 1, adrs));
+        adrs.set_tree_height(z);
+        adrs.set_tree_index(i);
+        return self.t(adrs, &node);
+    }
+
+    //
+    // Generate a FORS signature (Algorithm 16).
+    //
+    fn fors_sign(&self, md: &[u8], adrs: &mut Adrs) -> Vec<u8>
+    {
+        let (a, k) = (self.p.a as u32, self.p.k);
+        let mut sig = Vec::new();
+        for (i, index) in base_2b(md, a as usize, k).into_iter().enumerate() {
+            let i = i as u32;
+            sig.extend(self.fors_sk(adrs, (i << a) + index));
+            for j in 0..a {
+                let s = (index >> j) ^ 1;
+                sig.extend(self.fors_node((i << (a - j)) + s, j, adrs));
+            }
+        }
+        return sig;
+    }
+
+    //
+    // Compute a FORS public key from a signature (Algorithm 17).
+    //
+    fn fors_pk_from_sig(&self, sig: &[u8], md: &[u8], adrs: &mut Adrs) -> Vec<u8>
+    {
+        let (n, a, k) = (self.p.n, self.p.a as u32, self.p.k);
+        let tree_len = (1 + a as usize) * n;
+        let mut roots = Vec::new();
+        for (i, index) in base_2b(md, a as usize, k).into_iter().enumerate() {
+            let tree = &sig[i * tree_len..(i + 1) * tree_len];
+            adrs.set_tree_height(0);
+            adrs.set_tree_index(((i as u32) << a) + index);
+            let mut node = self.t(adrs, &tree[..n]);
+            for j in 0..a {
+                let auth = &tree[(1 + j as usize) * n..(2 + j as usize) * n];
+                adrs.set_tree_height(j + 1);
+                let mut input = Vec::with_capacity(2 * n);
+                if (index >> j) & 1 == 0 {
+                    adrs.set_tree_index(adrs.tree_index() / 2);
+                    input.extend_from_slice(&node);
+                    input.extend_from_slice(auth);
+                } else {
+                    adrs.set_tree_index((adrs.tree_index() - 1) / 2);
+                    input.extend_from_slice(auth);
+                    input.extend_from_slice(&node);
+                }
+                node = self.t(adrs, &input);
+            }
+            roots.extend(node);
+        }
+        let mut pk_adrs = *adrs;
+        pk_adrs.set_type_and_clear(FORS_ROOTS);
+        pk_adrs.set_key_pair(adrs.key_pair());
+        return self.t(&pk_adrs, &roots);
+    }
+
+    //
+    // Randomizer R from the PRF_msg function.
+    //
+    fn prf_msg(&self, sk_prf: &[u8], opt_rand: &[u8], m: &[u8]) -> Vec<u8>
+    {
+        let n = self.p.n;
+        if self.p.shake {
+            let mut xof = Keccak::shake256();
+            xof.absorb(sk_prf);
+            xof.absorb(opt_rand);
+            xof.absorb(m);
+            return xof.squeeze(n);
+        }
+        let hash = if n == 16 { HashAlgorithm::Sha256 } else { HashAlgorithm::Sha512 };
+        let mut data = opt_rand.to_vec();
+        data.extend_from_slice(m);
+        return hmac(hash, sk_prf, &data)[..n].to_vec();
+    }
+
+    //
+    // Message digest from the H_msg function.
+    //
+    fn h_msg(&self, r: &[u8], pk_root: &[u8], m: &[u8]) -> Vec<u8>
+    {
+        if self.p.shake {
+            let mut xof = Keccak::shake256();
+            xof.absorb(r);
+            xof.absorb(self.pk_seed);
+            xof.absorb(pk_root);
+            xof.absorb(m);
+            return xof.squeeze(self.p.m);
+        }
+        let hash = if self.p.n == 16 { HashAlgorithm::Sha256 } else { HashAlgorithm::Sha512 };
+        let mut data = r.to_vec();
+        data.extend_from_slice(self.pk_seed);
+        data.extend_from_slice(pk_root);
+        data.extend_from_slice(m);
+        let mut seed = r.to_vec();
+        seed.extend_from_slice(self.pk_seed);
+        seed.extend_from_slice(&hash.hash(&data));
+        return mgf1(hash, &seed, self.p.m);
+    }
+
+    //
+    // Split the message digest into the FORS message and the hypertree
+    // and leaf indices.
+    //
+    fn split_digest<'d>(&self, digest: &'d [u8]) -> (&'d [u8], u64, u32)
+    {
+        let p = self.p;
+        let hp = p.h / p.d;
+        let md_len = (p.k * p.a).div_ceil(8);
+        let tree_len = (p.h - hp).div_ceil(8);
+        let leaf_len = hp.div_ceil(8);
+        let mut idx_tree = to_int(&digest[md_len..md_len + tree_len]);
+        if p.h - hp < 64 {
+            idx_tree &= (1 << (p.h - hp)) - 1;
+        }
+        let idx_leaf = to_int(&digest[md_len + tree_len..md_len + tree_len + leaf_len]) &
+                       ((1 << hp) - 1);
+        return (&digest[..md_len], idx_tree, idx_leaf as u32);
+    }
+}
+
+/// SLH-DSA public key
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SlhDsaPublicKey {
+    /// parameter set
+    pub params: SlhDsaParams,
+    /// PK.seed || PK.root
+    pub key: Vec<u8>,
+}
+
+/// SLH-DSA private key
+#[derive(Clone, Debug)]
+pub struct SlhDsaPrivateKey {
+    /// parameter set
+    pub params: SlhDsaParams,
+    /// SK.seed || SK.prf || PK.seed || PK.root
+    pub key: Vec<u8>,
+}
+
+impl SlhDsaPrivateKey {
+    pub fn generate(params: SlhDsaParams) -> SlhDsaPrivateKey
+    {
+        let n = params.info().n;
+        let seeds = rng_bytes(3 * n);
+        return SlhDsaPrivateKey::from_seeds(params, &seeds[..n], &seeds[n..2 * n], &seeds[2 * n..]);
+    }
+
+    //
+    // Derive the key pair from SK.seed, SK.prf and PK.seed (Algorithm 18).
+    //
+    pub fn from_seeds(params: SlhDsaParams, sk_seed: &[u8], sk_prf: &[u8], pk_seed: &[u8])
+                      -> SlhDsaPrivateKey
+    {
+        let p = params.info();
+        let ctx = Context { p, pk_seed, sk_seed };
+        let mut adrs = Adrs::new();
+        adrs.set_layer((p.d - 1) as u32);
+        let pk_root = ctx.xmss_node(0, (p.h / p.d) as u32, &mut adrs);
+        let key = [sk_seed, sk_prf, pk_seed, &pk_root].concat();
+        return SlhDsaPrivateKey { params, key };
+    }
+
+    //
+    // Load a private key, recomputing and checking PK.root.
+    //
+    pub fn from_bytes(params: SlhDsaParams, data: &[u8]) -> Result<SlhDsaPrivateKey, String>
+    {
+        let n = params.info().n;
+        if data.len() != params.private_key_len() {
+            return Err("invalid SLH-DSA private key length".to_string());
+        }
+        let key = SlhDsaPrivateKey::from_seeds(params, &data[..n], &data[n..2 * n],
+                                               &data[2 * n..3 * n]);
+        if key.key != data {
+            return Err("inconsistent SLH-DSA private key".to_string());
+        }
+        return Ok(key);
+    }
+
+    pub fn public_key(&self) -> SlhDsaPublicKey
+    {
+        let n = self.params.info().n;
+        SlhDsaPublicKey { params: self.params, key: self.key[2 * n..].to_vec() }
+    }
+
+    //
+    // Create a hedged pure SLH-DSA signature with an empty context
+    // (Algorithm 22).
+    //
+    pub fn sign(&self, msg: &[u8]) -> Vec<u8>
+    {
+        let mut m = vec![0, 0];
+        m.extend_from_slice(msg);
+        return self.sign_internal(&m, &rng_bytes(self.params.info().n));
+    }
+
+    //
+    // Sign an encoded message M' using the given randomizer, which is
+    // PK.seed for the deterministic variant (Algorithm 19).
+    //
+    fn sign_internal(&self, m: &[u8], opt_rand: &[u8]) -> Vec<u8>
+    {
+        let p = self.params.info();
+        let n = p.n;
+        let (sk_seed, sk_prf) = (&self.key[..n], &self.key[n..2 * n]);
+        let (pk_seed, pk_root) = (&self.key[2 * n..3 * n], &self.key[3 * n..]);
+        let ctx = Context { p, pk_seed, sk_seed };
+
+        let r = ctx.prf_msg(sk_prf, opt_rand, m);
+        let digest = ctx.h_msg(&r, pk_root, m);
+        let (md, idx_tree, idx_leaf) = ctx.split_digest(&digest);
+
+        let mut adrs = Adrs::new();
+        adrs.set_tree(idx_tree);
+        adrs.set_type_and_clear(FORS_TREE);
+        adrs.set_key_pair(idx_leaf);
+        let sig_fors = ctx.fors_sign(md, &mut adrs);
+        let pk_fors = ctx.fors_pk_from_sig(&sig_fors, md, &mut adrs);
+
+        let mut sig = r;
+        sig.extend(sig_fors);
+        sig.extend(ctx.ht_sign(&pk_fors, idx_tree, idx_leaf));
+        return sig;
+    }
+}
+
+impl SlhDsaPublicKey {
+    pub fn from_bytes(params: SlhDsaParams, data: &[u8]) -> Result<SlhDsaPublicKey, String>
+    {
+        if data.len() != params.public_key_len() {
+            return Err("invalid SLH-DSA public key length".to_string());
+        }
+        Ok(SlhDsaPublicKey { params, key: data.to_vec() })
+    }
+
+    //
+    // Verify a pure SLH-DSA signature with an empty context (Algorithms 20
+    // and 24).
+    //
+    pub fn verify(&self, msg: &[u8], sig: &[u8]) -> bool
+    {
+        let p = self.params.info();
+        let n = p.n;
+        if sig.len() != self.params.signature_len() {
+            return false;
+        }
+        let (pk_seed, pk_root) = (&self.key[..n], &self.key[n..]);
+        let ctx = Context { p, pk_seed, sk_seed: &[] };
+        let mut m = vec![0, 0];
+        m.extend_from_slice(msg);
+
+        let fors_len = p.k * (1 + p.a) * n;
+        let (r, sig_fors, sig_ht) = (&sig[..n], &sig[n..n + fors_len], &sig[n + fors_len..]);
+        let digest = ctx.h_msg(r, pk_root, &m);
+        let (md, idx_tree, idx_leaf) = ctx.split_digest(&digest);
+
+        let mut adrs = Adrs::new();
+        adrs.set_tree(idx_tree);
+        adrs.set_type_and_clear(FORS_TREE);
+        adrs.set_key_pair(idx_leaf);
+        let pk_fors = ctx.fors_pk_from_sig(sig_fors, md, &mut adrs);
+        return ctx.ht_verify(&pk_fors, sig_ht, idx_tree, idx_leaf, pk_root);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::hash::sha256;
+    use crate::hex;
+
+    // PK.root OpenSSL 3.5 derives from the seeds 00..(3n - 1), limited to the
+    // fast parameter sets as the small ones take minutes in debug builds
+    const ROOTS: &[(SlhDsaParams, &str)] = &[
+        (SlhDsaParams::Sha2_128f, "3b56e816847f000386aeec2e2bb9e1b5"),
+        (SlhDsaParams::Sha2_192f, "9236ccebbb3a90ac2452dd89de49dab1340ec02419a2870e"),
+        (SlhDsaParams::Sha2_256f, "42cffe64ddbd6731063752684df77c8b58c225dc6b491208916b654ea1393176"),
+        (SlhDsaParams::Shake128f, "a90e4715b9a925c332801767fd786371"),
+        (SlhDsaParams::Shake192f, "3f01b06bebed020a459696868d115fe8507ded8dc08e825d"),
+        (SlhDsaParams::Shake256f, "818d7e76beef979b5bbf9161fdefa21bd0fe0bfe19157a5711a8de8a8f6878e6"),
+    ];
+
+    // SHA-256 of deterministic OpenSSL 3.5 signatures by the keys above
+    const SIGNATURES: &[(SlhDsaParams, &str)] = &[
+        (SlhDsaParams::Sha2_128f, "a05e919cf29c062cca21d93beee821b12bb9ecc8da70095f90b2615e7644c933"),
+        (SlhDsaParams::Shake128f, "3e5d6a2134148bb9679201793eb3913d07f944a97fde14f1a3e1135b00ef25ee"),
+    ];
+
+    fn key(params: SlhDsaParams) -> SlhDsaPrivateKey
+    {
+        let n = params.info().n as u8;
+        let seeds: Vec<u8> = (0..3 * n).collect();
+        let n = n as usize;
+        return SlhDsaPrivateKey::from_seeds(params, &seeds[..n], &seeds[n..2 * n], &seeds[2 * n..]);
+    }
+
+    #[test]
+    fn key_derivation()
+    {
+        for (params, root) in ROOTS {
+            let key = key(*params);
+            let n = params.info().n;
+            assert_eq!(key.key.len(), params.private_key_len());
+            assert_eq!(key.key[3 * n..], hex(root));
+            assert_eq!(key.public_key().key.len(), params.public_key_len());
+
+            assert!(SlhDsaPrivateKey::from_bytes(*params, &key.key).is_ok());
+            let mut tampered = key.key.clone();
+            tampered[4 * n - 1] ^= 1;
+            assert!(SlhDsaPrivateKey::from_bytes(*params, &tampered).is_err());
+        }
+    }
+
+    #[test]
+    fn signature_vectors()
+    {
+        let msg = b"SLH-DSA known answer test";
+        for (params, digest) in SIGNATURES {
+            let key = key(*params);
+            let n = params.info().n;
+            let m = [&[0, 0], &msg[..]].concat();
+            let sig = key.sign_internal(&m, &key.key[2 * n..3 * n]);
+            assert_eq!(sig.len(), params.signature_len());
+            assert_eq!(sha256(&sig).to_vec(), hex(digest));
+
+            let public = key.public_key();
+            assert!(public.verify(msg, &sig));
+            assert!(!public.verify(b"other", &sig));
+            let mut tampered = sig.clone();
+            tampered[n] ^= 1;
+            assert!(!public.verify(msg, &tampered));
+
+            let sig = key.sign(msg);
+            assert!(public.verify(msg, &sig));
+            assert!(!public.verify(msg, &sig[1..]));
+        }
+    }
+}
@@ -0,0 +1,400 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! Certificate Transparency (RFC 6962).  Signed certificate timestamps are
+//! handled in their TLS presentation encoding, as returned by a log for a
+//! submitted precertificate and embedded in the final certificate as a
+//! SignedCertificateTimestampList.  For testing without a log, SCTs may be
+//! signed with a local log key.
+
+use std::fs;
+use crate::crypto::hash::HashAlgorithm;
+use crate::keys::{KeyType, PrivateKey, PublicKey};
+use crate::pem::base64_decode;
+use crate::signature::SignatureScheme;
+
+/// Version of the only defined SCT format
+pub const SCT_V1: u8 = 0;
+
+/// Length of a log ID, the SHA-256 hash of the log's public key
+pub const LOG_ID_LEN: usize = 32;
+
+/// TLS HashAlgorithm sha256
+const TLS_HASH_SHA256: u8 = 4;
+
+/// TLS SignatureAlgorithm rsa
+const TLS_SIGNATURE_RSA: u8 = 1;
+
+/// TLS SignatureAlgorithm ecdsa
+const TLS_SIGNATURE_ECDSA: u8 = 3;
+
+/// A SignedCertificateTimestamp (RFC 6962, 3.2)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Sct {
+    /// SCT version, SCT_V1
+    pub version: u8,
+    /// SHA-256 hash of the log's public key
+    pub log_id: Vec<u8>,
+    /// milliseconds since the epoch
+    pub timestamp: u64,
+    /// CtExtensions, empty for v1
+    pub extensions: Vec<u8>,
+    /// TLS HashAlgorithm of the signature
+    pub hash: u8,
+    /// TLS SignatureAlgorithm of the signature
+    pub signature_alg: u8,
+    /// signature of the log
+    pub signature: Vec<u8>,
+}
+
+/// Sequential reader for TLS encoded structures
+struct TlsReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> TlsReader<'a> {
+    //
+    // Read a fixed number of octets.
+    //
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], String>
+    {
+        if self.data.len() - self.pos < len {
+            return Err("truncated SCT".to_string());
+        }
+        let bytes = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+        return Ok(bytes);
+    }
+
+    //
+    // Read a big-endian unsigned integer of the given number of octets.
+    //
+    fn uint(&mut self, len: usize) -> Result<u64, String>
+    {
+        return Ok(self.bytes(len)?.iter().fold(0, |acc, b| acc << 8 | *b as u64));
+    }
+
+    //
+    // Read an opaque vector with a two octet length prefix.
+    //
+    fn vector(&mut self) -> Result<&'a [u8], String>
+    {
+        let len = self.uint(2)? as usize;
+        return self.bytes(len);
+    }
+
+    //
+    // Ensure all data has been consumed.
+    //
+    fn finish(&self) -> Result<(), String>
+    {
+        if self.pos != self.data.len() {
+            return Err("trailing data after SCT".to_string());
+        }
+        return Ok(());
+    }
+}
+
+//
+// Append an opaque vector with a two octet length prefix.
+//
+fn push_vector(out: &mut Vec<u8>, data: &[u8]) -> Result<(), String>
+{
+    let len = u16::try_from(data.len()).map_err(|_| "SCT field exceeds 65535 octets".to_string())?;
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(data);
+    return Ok(());
+}
+
+//
+// Get the name of a TLS HashAlgorithm.
+//
+fn hash_name(hash: u8) -> String
+{
+    match hash {
+        0 => { "none".to_string() }
+        1 => { "md5".to_string() }
+        2 => { "sha1".to_string() }
+        3 => { "sha224".to_string() }
+        4 => { "sha256".to_string() }
+        5 => { "sha384".to_string() }
+        6 => { "sha512".to_string() }
+        _ => { format!("hash{}", hash) }
+    }
+}
+
+//
+// Get the name of a TLS SignatureAlgorithm.
+//
+fn signature_name(alg: u8) -> String
+{
+    match alg {
+        0 => { "anonymous".to_string() }
+        1 => { "rsa".to_string() }
+        2 => { "dsa".to_string() }
+        3 => { "ecdsa".to_string() }
+        _ => { format!("signature{}", alg) }
+    }
+}
+
+//
+// Get the signature scheme of a log key, RFC 6962 allowing ECDSA P-256 and
+// RSA keys of at least 2048 bits with SHA-256 only.
+//
+fn log_scheme(key_type: KeyType, bits: usize) -> Result<(SignatureScheme, u8), String>
+{
+    match key_type {
+        KeyType::Ecdsa if bits == 256 => {
+            Ok((SignatureScheme::Ecdsa(HashAlgorithm::Sha256), TLS_SIGNATURE_ECDSA))
+        }
+        KeyType::Rsa if bits >= 2048 => {
+            Ok((SignatureScheme::RsaPkcs1(HashAlgorithm::Sha256), TLS_SIGNATURE_RSA))
+        }
+        _ => { Err(format!("{} bit {} keys can't be used as CT log keys", bits, key_type)) }
+    }
+}
+
+//
+// Build the digitally-signed data of a v1 SCT for a precertificate entry,
+// given the hash of the issuer's SubjectPublicKeyInfo and the
+// TBSCertificate of the precertificate without poison extension.
+//
+fn precert_signed_data(timestamp: u64, extensions: &[u8], issuer_key_hash: &[u8], tbs: &[u8])
+                      -> Result<Vec<u8>, String>
+{
+    if issuer_key_hash.len() != LOG_ID_LEN || tbs.len() >= 1 << 24 {
+        return Err("invalid precertificate entry".to_string());
+    }
+    // sct_version, signature_type certificate_timestamp
+    let mut out = vec![SCT_V1, 0];
+    out.extend_from_slice(&timestamp.to_be_bytes());
+    // entry_type precert_entry
+    out.extend_from_slice(&1u16.to_be_bytes());
+    out.extend_from_slice(issuer_key_hash);
+    out.extend_from_slice(&(tbs.len() as u32).to_be_bytes()[1..]);
+    out.extend_from_slice(tbs);
+    push_vector(&mut out, extensions)?;
+    return Ok(out);
+}
+
+impl Sct {
+    //
+    // Decode a TLS encoded SCT.
+    //
+    pub fn from_tls(data: &[u8]) -> Result<Sct, String>
+    {
+        let mut reader = TlsReader { data, pos: 0 };
+        let version = reader.uint(1)? as u8;
+        if version != SCT_V1 {
+            return Err(format!("unsupported SCT version v{}", version as u16 + 1));
+        }
+        let log_id = reader.bytes(LOG_ID_LEN)?.to_vec();
+        let timestamp = reader.uint(8)?;
+        let extensions = reader.vector()?.to_vec();
+        let hash = reader.uint(1)? as u8;
+        let signature_alg = reader.uint(1)? as u8;
+        let signature = reader.vector()?.to_vec();
+        reader.finish()?;
+        if signature.is_empty() {
+            return Err("SCT lacks a signature".to_string());
+        }
+        return Ok(Sct { version, log_id, timestamp, extensions, hash, signature_alg, signature });
+    }
+
+    //
+    // Encode as a TLS SignedCertificateTimestamp.
+    //
+    pub fn to_tls(&self) -> Result<Vec<u8>, String>
+    {
+        let mut out = vec![self.version];
+        out.extend_from_slice(&self.log_id);
+        out.extend_from_slice(&self.timestamp.to_be_bytes());
+        push_vector(&mut out, &self.extensions)?;
+        out.push(self.hash);
+        out.push(self.signature_alg);
+        push_vector(&mut out, &self.signature)?;
+        return Ok(out);
+    }
+
+    //
+    // Load an SCT from a file, either binary or base64 encoded.
+    //
+    pub fn load(path: &str) -> Result<Sct, String>
+    {
+        let data = fs::read(path).map_err(|e| format!("reading SCT '{}' failed: {}", path, e))?;
+        let sct = match Sct::from_tls(&data) {
+            Ok(sct) => { Ok(sct) }
+            Err(e) => {
+                match std::str::from_utf8(&data).ok().and_then(|text| base64_decode(text.trim()).ok()) {
+                    Some(decoded) => { Sct::from_tls(&decoded) }
+                    None => { Err(e) }
+                }
+            }
+        };
+        return sct.map_err(|e| format!("parsing SCT '{}' failed: {}", path, e));
+    }
+
+    //
+    // Sign an SCT for a precertificate entry with the key of a local test
+    // log, standing in for a log the precertificate was submitted to.
+    //
+    pub fn sign_precert(log_key: &PrivateKey, issuer_key_hash: &[u8], tbs: &[u8], timestamp: u64)
+                        -> Result<Sct, String>
+    {
+        let (scheme, signature_alg) = log_scheme(log_key.key_type(), log_key.bits())?;
+        let data = precert_signed_data(timestamp, &[], issuer_key_hash, tbs)?;
+        let signature = log_key.sign(scheme, &data)?;
+        let log_id = HashAlgorithm::Sha256.hash(&log_key.public_key().to_spki());
+        return Ok(Sct { version: SCT_V1, log_id, timestamp, extensions: Vec::new(),
+                        hash: TLS_HASH_SHA256, signature_alg, signature });
+    }
+
+    //
+    // Verify the SCT of a precertificate entry with the public key of a log.
+    //
+    pub fn verify_precert(&self, log_key: &PublicKey, issuer_key_hash: &[u8], tbs: &[u8]) -> bool
+    {
+        let Ok((scheme, signature_alg)) = log_scheme(log_key.key_type(), log_key.bits()) else {
+            return false;
+        };
+        if self.hash != TLS_HASH_SHA256 || self.signature_alg != signature_alg ||
+           self.log_id != HashAlgorithm::Sha256.hash(&log_key.to_spki()) {
+            return false;
+        }
+        match precert_signed_data(self.timestamp, &self.extensions, issuer_key_hash, tbs) {
+            Ok(data) => { log_key.verify(scheme, &data, &self.signature) }
+            Err(_) => { false }
+        }
+    }
+
+    //
+    // Describe the signature algorithm, e.g. ecdsa-with-sha256.
+    //
+    pub fn algorithm(&self) -> String
+    {
+        return format!("{}-with-{}", signature_name(self.signature_alg), hash_name(self.hash));
+    }
+}
+
+//
+// Encode SCTs as a TLS SignedCertificateTimestampList (RFC 6962, 3.3).
+//
+pub fn encode_sct_list(scts: &[Sct]) -> Result<Vec<u8>, String>
+{
+    if scts.is_empty() {
+        return Err("empty SCT list".to_string());
+    }
+    let mut list = Vec::new();
+    for sct in scts {
+        push_vector(&mut list, &sct.to_tls()?)?;
+    }
+    let mut out = Vec::new();
+    push_vector(&mut out, &list).map_err(|_| "SCT list exceeds 65535 octets".to_string())?;
+    return Ok(out);
+}
+
+//
+// Decode a TLS SignedCertificateTimestampList.
+//
+pub fn decode_sct_list(data: &[u8]) -> Result<Vec<Sct>, String>
+{
+    let mut reader = TlsReader { data, pos: 0 };
+    let list = reader.vector()?;
+    reader.finish()?;
+
+    let mut scts = Vec::new();
+    let mut reader = TlsReader { data: list, pos: 0 };
+    while reader.pos < list.len() {
+        scts.push(Sct::from_tls(reader.vector()?)?);
+    }
+    if scts.is_empty() {
+        return Err("empty SCT list".to_string());
+    }
+    return Ok(scts);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::ec::{Curve, EcPrivateKey};
+
+    fn sct(log: u8, timestamp: u64) -> Sct
+    {
+        return Sct { version: SCT_V1, log_id: vec![log; LOG_ID_LEN], timestamp,
+                     extensions: Vec::new(), hash: TLS_HASH_SHA256,
+                     signature_alg: TLS_SIGNATURE_ECDSA, signature: vec![0x30, 0x00] };
+    }
+
+    #[test]
+    fn tls_encoding()
+    {
+        let tls = sct(0xaa, 0x0102030405060708).to_tls().unwrap();
+        assert_eq!(tls.len(), 1 + 32 + 8 + 2 + 2 + 2 + 2);
+        assert_eq!(tls[0], SCT_V1);
+        assert_eq!(&tls[33..41], &[1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(&tls[41..], &[0, 0, 4, 3, 0, 2, 0x30, 0x00]);
+        assert_eq!(Sct::from_tls(&tls).unwrap(), sct(0xaa, 0x0102030405060708));
+        assert_eq!(Sct::from_tls(&tls).unwrap().algorithm(), "ecdsa-with-sha256");
+
+        assert!(Sct::from_tls(&tls[..tls.len() - 1]).is_err());
+        let mut v2 = tls.clone();
+        v2[0] = 1;
+        assert!(Sct::from_tls(&v2).is_err());
+        let mut trailing = tls.clone();
+        trailing.push(0);
+        assert!(Sct::from_tls(&trailing).is_err());
+    }
+
+    #[test]
+    fn sct_list()
+    {
+        let scts = vec![sct(0x01, 1), sct(0x02, 2)];
+        let list = encode_sct_list(&scts).unwrap();
+        assert_eq!(&list[..4], &[0x00, 0x66, 0x00, 0x31]);
+        assert_eq!(decode_sct_list(&list).unwrap(), scts);
+
+        assert!(encode_sct_list(&[]).is_err());
+        assert!(decode_sct_list(&[0x00, 0x00]).is_err());
+        assert!(decode_sct_list(&list[..list.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn precert_signed_data_layout()
+    {
+        let data = precert_signed_data(0x10, &[], &[0x55; 32], &[0x30, 0x00]).unwrap();
+        let mut expected = vec![0x00, 0x00, 0, 0, 0, 0, 0, 0, 0, 0x10, 0x00, 0x01];
+        expected.extend_from_slice(&[0x55; 32]);
+        expected.extend_from_slice(&[0x00, 0x00, 0x02, 0x30, 0x00, 0x00, 0x00]);
+        assert_eq!(data, expected);
+        assert!(precert_signed_data(0, &[], &[0x55; 20], &[]).is_err());
+    }
+
+    #[test]
+    fn local_test_log()
+    {
+        let log_key = PrivateKey::Ecdsa(EcPrivateKey::generate(Curve::P256));
+        let issuer_key_hash = [0x42; 32];
+        let tbs = [0x30, 0x03, 0x02, 0x01, 0x05];
+        let sct = Sct::sign_precert(&log_key, &issuer_key_hash, &tbs, 1760000000000).unwrap();
+
+        assert_eq!(sct.log_id, HashAlgorithm::Sha256.hash(&log_key.public_key().to_spki()));
+        assert!(sct.verify_precert(&log_key.public_key(), &issuer_key_hash, &tbs));
+        assert!(!sct.verify_precert(&log_key.public_key(), &[0x43; 32], &tbs));
+        assert!(!sct.verify_precert(&log_key.public_key(), &issuer_key_hash, &tbs[..4]));
+
+        let other = PrivateKey::Ecdsa(EcPrivateKey::generate(Curve::P256));
+        assert!(!sct.verify_precert(&other.public_key(), &issuer_key_hash, &tbs));
+        let p384 = PrivateKey::Ecdsa(EcPrivateKey::generate(Curve::P384));
+        assert!(Sct::sign_precert(&p384, &issuer_key_hash, &tbs, 0).is_err());
+    }
+}
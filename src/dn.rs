@@ -0,0 +1,598 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! Distinguished names (X.501) and their string representations.
+//!
+//! The strongSwan syntax `C=CH, O=strongSwan, CN=moon` accepted by --dn
+//! lists the RDNs in encoding order, whereas RFC 4514
+//! `CN=moon,O=strongSwan,C=CH`, available as output format, starts with the
+//! last RDN.  Both share the lexical rules: RDNs are separated by `,`,
+//! the attributes of a multi-valued RDN by `+`, the attribute type is a
+//! keyword or a dotted OID, and values may contain `\` escapes of special
+//! characters or hex pairs, be quoted with `"` or be given as `#` followed
+//! by the hex encoded BER value.
+//!
+//! The attributes of a multi-valued RDN are encoded as DER SET OF, so they
+//! are sorted by their encoding when parsed and both string representations
+//! list them in this canonical order, e.g. `O=b+CN=a` becomes `CN=a+O=b`.
+
+use std::fmt;
+use crate::asn1::*;
+use crate::oid::*;
+
+/// String type selection for an attribute value
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum StringType {
+    /// PrintableString only
+    Printable,
+    /// IA5String only
+    Ia5,
+    /// PrintableString if possible, UTF8String otherwise
+    Directory,
+}
+
+/// Attribute type keywords with the canonical one listed first
+const ATTRIBUTE_TYPES: &[(&[&str], &str, StringType)] = &[
+    (&["C", "countryName"],                    OID_COUNTRY,                 StringType::Printable),
+    (&["ST", "S", "stateOrProvinceName"],      OID_STATE_OR_PROVINCE,       StringType::Directory),
+    (&["L", "localityName"],                   OID_LOCALITY,                StringType::Directory),
+    (&["street", "streetAddress"],             OID_STREET_ADDRESS,          StringType::Directory),
+    (&["O", "organizationName"],               OID_ORGANIZATION,            StringType::Directory),
+    (&["OU", "organizationalUnitName"],        OID_ORGANIZATION_UNIT,       StringType::Directory),
+    (&["CN", "commonName"],                    OID_COMMON_NAME,             StringType::Directory),
+    (&["SN", "surname"],                       OID_SURNAME,                 StringType::Directory),
+    (&["serialNumber"],                        OID_SERIAL_NUMBER,           StringType::Printable),
+    (&["title", "T"],                          OID_TITLE,                   StringType::Directory),
+    (&["description", "D"],                    OID_DESCRIPTION,             StringType::Directory),
+    (&["postalCode"],                          OID_POSTAL_CODE,             StringType::Directory),
+    (&["N", "name"],                           OID_NAME,                    StringType::Directory),
+    (&["G", "givenName", "GN"],                OID_GIVEN_NAME,              StringType::Directory),
+    (&["I", "initials"],                       OID_INITIALS,                StringType::Directory),
+    (&["generationQualifier"],                 OID_GENERATION_QUALIFIER,    StringType::Directory),
+    (&["dnQualifier"],                         OID_DN_QUALIFIER,            StringType::Printable),
+    (&["pseudonym"],                           OID_PSEUDONYM,               StringType::Directory),
+    (&["role"],                                OID_ROLE,                    StringType::Directory),
+    (&["organizationIdentifier"],              OID_ORGANIZATION_IDENTIFIER, StringType::Directory),
+    (&["E", "emailAddress", "Email"],          OID_EMAIL_ADDRESS,           StringType::Ia5),
+    (&["unstructuredName"],                    OID_UNSTRUCTURED_NAME,       StringType::Ia5),
+    (&["UID", "userId"],                       OID_USER_ID,                 StringType::Directory),
+    (&["DC", "domainComponent"],               OID_DOMAIN_COMPONENT,        StringType::Ia5),
+];
+
+/// Attribute type keywords defined by RFC 4514, 3
+const RFC4514_TYPES: &[(&str, &str)] = &[
+    (OID_COMMON_NAME,          "CN"),
+    (OID_LOCALITY,             "L"),
+    (OID_STATE_OR_PROVINCE,    "ST"),
+    (OID_ORGANIZATION,         "O"),
+    (OID_ORGANIZATION_UNIT,    "OU"),
+    (OID_COUNTRY,              "C"),
+    (OID_STREET_ADDRESS,       "STREET"),
+    (OID_DOMAIN_COMPONENT,     "DC"),
+    (OID_USER_ID,              "UID"),
+];
+
+/// An attribute type and value of a relative distinguished name
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Attribute {
+    /// attribute type OID in dotted notation
+    pub oid: String,
+    /// DER encoded attribute value
+    pub value: Vec<u8>,
+}
+
+/// A distinguished name as list of (possibly multi-valued) RDNs in
+/// encoding order
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Dn {
+    pub rdns: Vec<Vec<Attribute>>,
+}
+
+impl Attribute {
+    //
+    // Encode as AttributeTypeAndValue.
+    //
+    fn encode(&self) -> Vec<u8>
+    {
+        let oid = asn1_oid_from_string(&self.oid).expect("valid attribute type");
+        return asn1_wrap(ASN1_SEQUENCE, &[&asn1_simple_object(ASN1_OID, &oid), &self.value]);
+    }
+}
+
+//
+// Sort the attributes of an RDN into the DER SET OF order of their encoding.
+//
+fn sort_rdn(mut rdn: Vec<Attribute>) -> Vec<Attribute>
+{
+    rdn.sort_by_cached_key(|a| a.encode());
+    return rdn;
+}
+
+//
+// Build an error message pointing at the offending character.
+//
+fn error(chars: &[char], pos: usize, msg: &str) -> String
+{
+    let text: String = chars.iter().collect();
+    return format!("{} at position {}\n  {}\n  {}^", msg, pos + 1, text, " ".repeat(pos));
+}
+
+//
+// Characters that must be escaped in RFC 4514 attribute values.
+//
+fn is_special(c: char) -> bool
+{
+    return matches!(c, ',' | '+' | '"' | '\\' | '<' | '>' | ';');
+}
+
+//
+// Encode an attribute value string in the string type of the attribute.
+//
+fn encode_value(string_type: StringType, oid: &str, value: &str) -> Result<Vec<u8>, String>
+{
+    match string_type {
+        StringType::Printable => {
+            if !asn1_is_printablestring(value) {
+                return Err("invalid character in PrintableString value".to_string());
+            }
+            if oid == OID_COUNTRY && (value.len() != 2 || !value.bytes().all(|c| c.is_ascii_alphabetic())) {
+                return Err("country must be a two letter code".to_string());
+            }
+            Ok(asn1_string(ASN1_PRINTABLESTRING, value))
+        }
+        StringType::Ia5 => {
+            if !value.is_ascii() {
+                return Err("invalid character in IA5String value".to_string());
+            }
+            Ok(asn1_string(ASN1_IA5STRING, value))
+        }
+        StringType::Directory => {
+            if asn1_is_printablestring(value) {
+                Ok(asn1_string(ASN1_PRINTABLESTRING, value))
+            } else {
+                Ok(asn1_string(ASN1_UTF8STRING, value))
+            }
+        }
+    }
+}
+
+//
+// Parse two hex digits.
+//
+fn hex_pair(chars: &[char], pos: usize) -> Option<u8>
+{
+    let hi = chars.get(pos)?.to_digit(16)?;
+    let lo = chars.get(pos + 1)?.to_digit(16)?;
+    return Some((hi << 4 | lo) as u8);
+}
+
+//
+// Parse an attribute value starting at pos, returning its DER encoding and
+// the position after it.
+//
+fn parse_value(chars: &[char], mut pos: usize, oid: &str, string_type: StringType)
+              -> Result<(Vec<u8>, usize), String>
+{
+    let start = pos;
+
+    if chars.get(pos) == Some(&'#') {
+        pos += 1;
+        let mut der = Vec::new();
+        while pos < chars.len() && chars[pos].is_ascii_hexdigit() {
+            der.push(hex_pair(chars, pos).ok_or_else(|| error(chars, pos, "odd number of hex digits"))?);
+            pos += 2;
+        }
+        if der.is_empty() {
+            return Err(error(chars, pos, "hex digits expected after '#'"));
+        }
+        if let Err(e) = asn1_parse(&der) {
+            return Err(error(chars, start, &format!("invalid BER value ({})", e)));
+        }
+        return Ok((der, pos));
+    }
+
+    let quoted = chars.get(pos) == Some(&'"');
+    if quoted {
+        pos += 1;
+    }
+    let mut bytes: Vec<u8> = Vec::new();
+    // length of the value without trailing unescaped spaces
+    let mut len = 0;
+    loop {
+        let c = match chars.get(pos) {
+            Some(c) => { *c }
+            None if quoted => { return Err(error(chars, start, "unterminated quoted value")); }
+            None => { break; }
+        };
+        if quoted && c == '"' {
+            pos += 1;
+            len = bytes.len();
+            break;
+        }
+        if !quoted && (c == ',' || c == '+') {
+            break;
+        }
+        if c == '\\' {
+            let next = chars.get(pos + 1).copied();
+            match next {
+                Some(n) if is_special(n) || n == ' ' || n == '#' || n == '=' => {
+                    bytes.push(n as u8);
+                    pos += 2;
+                }
+                Some(_) if hex_pair(chars, pos + 1).is_some() => {
+                    bytes.push(hex_pair(chars, pos + 1).unwrap());
+                    pos += 3;
+                }
+                _ => { return Err(error(chars, pos, "invalid escape sequence")); }
+            }
+            len = bytes.len();
+            continue;
+        }
+        if !quoted && is_special(c) {
+            return Err(error(chars, pos, &format!("unescaped '{}' in value", c)));
+        }
+        let mut buf = [0u8; 4];
+        bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+        if quoted || c != ' ' {
+            len = bytes.len();
+        }
+        pos += 1;
+    }
+    bytes.truncate(len);
+
+    let value = String::from_utf8(bytes)
+                       .map_err(|_| error(chars, start, "escaped octets are not valid UTF-8"))?;
+    if value.is_empty() {
+        return Err(error(chars, start, "empty attribute value"));
+    }
+    let der = encode_value(string_type, oid, &value).map_err(|e| error(chars, start, &e))?;
+    return Ok((der, pos));
+}
+
+//
+// Skip spaces starting at pos.
+//
+fn skip_spaces(chars: &[char], mut pos: usize) -> usize
+{
+    while pos < chars.len() && chars[pos] == ' ' {
+        pos += 1;
+    }
+    return pos;
+}
+
+//
+// Look up an attribute type keyword or dotted OID.
+//
+fn attribute_type(name: &str) -> Option<(String, StringType)>
+{
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        return asn1_oid_from_string(name).ok().map(|_| {
+            let string_type = ATTRIBUTE_TYPES.iter().find(|a| a.1 == name)
+                                             .map_or(StringType::Directory, |a| a.2);
+            (name.to_string(), string_type)
+        });
+    }
+    return ATTRIBUTE_TYPES.iter()
+                          .find(|a| a.0.iter().any(|n| n.eq_ignore_ascii_case(name)))
+                          .map(|a| (a.1.to_string(), a.2));
+}
+
+//
+// Format an attribute value with RFC 4514 escaping, or as '#' followed
+// by its hex encoding if it is not a character string.
+//
+fn format_value(value: &[u8]) -> String
+{
+    let string = match asn1_parse(value).and_then(|obj| obj.to_string()) {
+        Ok(string) => { string }
+        Err(_) => {
+            let hex: String = value.iter().map(|b| format!("{:02x}", b)).collect();
+            return format!("#{}", hex);
+        }
+    };
+    let count = string.chars().count();
+    let mut out = String::new();
+    for (i, c) in string.chars().enumerate() {
+        if c == '\0' {
+            out.push_str("\\00");
+            continue;
+        }
+        if is_special(c) || (i == 0 && (c == '#' || c == ' ')) || (i == count - 1 && c == ' ') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    return out;
+}
+
+impl Dn {
+    //
+    // Parse a distinguished name listing the RDNs in encoding order.
+    //
+    pub fn parse(dn: &str) -> Result<Dn, String>
+    {
+        let chars: Vec<char> = dn.chars().collect();
+        let mut rdns: Vec<Vec<Attribute>> = Vec::new();
+        let mut rdn: Vec<Attribute> = Vec::new();
+        let mut pos = skip_spaces(&chars, 0);
+
+        if pos == chars.len() {
+            return Err(error(&chars, pos, "empty distinguished name"));
+        }
+        loop {
+            let start = pos;
+            while pos < chars.len() && (chars[pos].is_ascii_alphanumeric() ||
+                                        chars[pos] == '-' || chars[pos] == '.') {
+                pos += 1;
+            }
+            if pos == start {
+                let msg = match chars.get(pos) {
+                    Some(c) => { format!("attribute type expected, found '{}'", c) }
+                    None    => { "attribute type expected".to_string() }
+                };
+                return Err(error(&chars, pos, &msg));
+            }
+            let name: String = chars[start..pos].iter().collect();
+            let (oid, string_type) = attribute_type(&name).ok_or_else(||
+                            error(&chars, start, &format!("unknown attribute type '{}'", name)))?;
+
+            pos = skip_spaces(&chars, pos);
+            if chars.get(pos) != Some(&'=') {
+                return Err(error(&chars, pos, &format!("'=' expected after '{}'", name)));
+            }
+            pos = skip_spaces(&chars, pos + 1);
+
+            let (value, next) = parse_value(&chars, pos, &oid, string_type)?;
+            rdn.push(Attribute { oid, value });
+            pos = skip_spaces(&chars, next);
+
+            match chars.get(pos) {
+                None => {
+                    rdns.push(sort_rdn(rdn));
+                    break;
+                }
+                Some(',') => {
+                    rdns.push(sort_rdn(std::mem::take(&mut rdn)));
+                }
+                Some('+') => {}
+                Some(c) => {
+                    return Err(error(&chars, pos, &format!("',' or '+' expected, found '{}'", c)));
+                }
+            }
+            pos = skip_spaces(&chars, pos + 1);
+        }
+        return Ok(Dn { rdns });
+    }
+
+    //
+    // Decode a DER encoded X.501 Name.
+    //
+    pub fn from_der(der: &[u8]) -> Result<Dn, String>
+    {
+        let name = asn1_parse(der)?;
+        if name.tag != ASN1_SEQUENCE {
+            return Err("invalid Name".to_string());
+        }
+        let mut rdns: Vec<Vec<Attribute>> = Vec::new();
+        let mut parser = name.parser();
+        while let Some(set) = parser.next_object()? {
+            if set.tag != ASN1_SET {
+                return Err("invalid RelativeDistinguishedName".to_string());
+            }
+            let mut rdn: Vec<Attribute> = Vec::new();
+            let mut p = set.parser();
+            while let Some(atv) = p.next_object()? {
+                if atv.tag != ASN1_SEQUENCE {
+                    return Err("invalid AttributeTypeAndValue".to_string());
+                }
+                let mut a = atv.parser();
+                let oid = a.expect(ASN1_OID)?.to_oid()?;
+                let value = a.next_object()?.ok_or("missing attribute value")?.raw.to_vec();
+                a.finish()?;
+                rdn.push(Attribute { oid, value });
+            }
+            if rdn.is_empty() {
+                return Err("empty RelativeDistinguishedName".to_string());
+            }
+            rdns.push(rdn);
+        }
+        return Ok(Dn { rdns });
+    }
+
+    //
+    // Encode as DER X.501 Name.
+    //
+    pub fn to_der(&self) -> Vec<u8>
+    {
+        let rdns: Vec<Vec<u8>> = self.rdns.iter().map(|rdn| {
+            let atvs: Vec<Vec<u8>> = rdn.iter().map(|a| a.encode()).collect();
+            asn1_set_of(&atvs)
+        }).collect();
+        return asn1_sequence(&rdns);
+    }
+
+    //
+    // Format as RFC 4514 string, starting with the last RDN.
+    //
+    pub fn to_rfc4514(&self) -> String
+    {
+        let rdns: Vec<String> = self.rdns.iter().rev().map(|rdn| {
+            let atvs: Vec<String> = rdn.iter().map(|a| {
+                let name = match RFC4514_TYPES.iter().find(|t| t.0 == a.oid) {
+                    Some((_, name)) => { name.to_string() }
+                    None => { a.oid.clone() }
+                };
+                format!("{}={}", name, format_value(&a.value))
+            }).collect();
+            atvs.join("+")
+        }).collect();
+        return rdns.join(",");
+    }
+}
+
+impl fmt::Display for Dn {
+    //
+    // Format in strongSwan syntax, listing the RDNs in encoding order.
+    //
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        let rdns: Vec<String> = self.rdns.iter().map(|rdn| {
+            let atvs: Vec<String> = rdn.iter().map(|a| {
+                let name = match ATTRIBUTE_TYPES.iter().find(|t| t.1 == a.oid) {
+                    Some((names, _, _)) => { names[0].to_string() }
+                    None => { a.oid.clone() }
+                };
+                format!("{}={}", name, format_value(&a.value))
+            }).collect();
+            atvs.join("+")
+        }).collect();
+        write!(f, "{}", rdns.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex;
+
+    //
+    // Get the decoded string of a single-valued RDN.
+    //
+    fn value(dn: &Dn, rdn: usize) -> String
+    {
+        return asn1_parse(&dn.rdns[rdn][0].value).unwrap().to_string().unwrap();
+    }
+
+    #[test]
+    fn escaped_and_quoted_values()
+    {
+        let dn = Dn::parse(r#"CN=a\,b\+c\"d\\e\<f\>g\;h\=i"#).unwrap();
+        assert_eq!(value(&dn, 0), r#"a,b+c"d\e<f>g;h=i"#);
+        assert_eq!(dn.to_string(), r#"CN=a\,b\+c\"d\\e\<f\>g\;h=i"#);
+
+        let dn = Dn::parse(r"CN=caf\c3\a9, O=\#1, OU=\ x\ , L=  y  ").unwrap();
+        assert_eq!(dn.rdns[0][0].value, asn1_string(ASN1_UTF8STRING, "café"));
+        assert_eq!(value(&dn, 1), "#1");
+        assert_eq!(value(&dn, 2), " x ");
+        assert_eq!(value(&dn, 3), "y");
+        assert_eq!(dn.to_string(), r"CN=café, O=\#1, OU=\ x\ , L=y");
+
+        let dn = Dn::parse(r#"CN=" a, b+c ", O="q\"t""#).unwrap();
+        assert_eq!(value(&dn, 0), " a, b+c ");
+        assert_eq!(value(&dn, 1), "q\"t");
+
+        // '#' followed by the BER value is taken as is
+        let dn = Dn::parse("CN=#0c03616263, UID=#020105").unwrap();
+        assert_eq!(dn.rdns[0][0].value, hex("0c03616263"));
+        assert_eq!(dn.rdns[1][0].value, hex("020105"));
+        assert_eq!(dn.to_string(), "CN=abc, UID=#020105");
+    }
+
+    #[test]
+    fn multi_valued_rdns()
+    {
+        let dn = Dn::parse("CN=a+O=b").unwrap();
+        assert_eq!(dn.rdns.len(), 1);
+        assert_eq!(dn.to_der(), hex("30163114300806035504031301613008060355040a130162"));
+
+        // attributes are kept in their canonical SET OF order
+        let reversed = Dn::parse("O=b + CN=a").unwrap();
+        assert_eq!(reversed, dn);
+        assert_eq!(reversed.to_string(), "CN=a+O=b");
+        assert_eq!(reversed.to_rfc4514(), "CN=a+O=b");
+        assert_eq!(Dn::from_der(&reversed.to_der()).unwrap(), dn);
+
+        let dn = Dn::parse("C=CH, O=strongSwan+CN=moon, OU=x").unwrap();
+        assert_eq!(dn.rdns.iter().map(|rdn| rdn.len()).collect::<Vec<_>>(), [1, 2, 1]);
+        assert_eq!(dn.to_string(), "C=CH, CN=moon+O=strongSwan, OU=x");
+        assert_eq!(dn.to_rfc4514(), "OU=x,CN=moon+O=strongSwan,C=CH");
+    }
+
+    #[test]
+    fn attribute_types()
+    {
+        let dn = Dn::parse("2.5.4.97=VATCH-123, 2.5.4.6=CH, 1.3.6.1.4.1.36906.1=x, cn=y").unwrap();
+        let oids: Vec<&str> = dn.rdns.iter().map(|rdn| rdn[0].oid.as_str()).collect();
+        assert_eq!(oids, [OID_ORGANIZATION_IDENTIFIER, OID_COUNTRY, "1.3.6.1.4.1.36906.1",
+                          OID_COMMON_NAME]);
+        assert_eq!(dn.to_string(),
+                   "organizationIdentifier=VATCH-123, C=CH, 1.3.6.1.4.1.36906.1=x, CN=y");
+        assert_eq!(dn.to_rfc4514(), "CN=y,1.3.6.1.4.1.36906.1=x,C=CH,2.5.4.97=VATCH-123");
+
+        // numeric OIDs are subject to the rules of the attribute type
+        assert!(Dn::parse("2.5.4.6=CHE").is_err());
+        assert!(Dn::parse("2.5..4=x").is_err());
+    }
+
+    #[test]
+    fn string_types()
+    {
+        let dn = Dn::parse("C=CH, CN=moon, O=Müller, OU=a@b, E=a@b, DC=org, serialNumber=1")
+                    .unwrap();
+        let tags: Vec<u8> = dn.rdns.iter().map(|rdn| rdn[0].value[0]).collect();
+        assert_eq!(tags, [ASN1_PRINTABLESTRING, ASN1_PRINTABLESTRING, ASN1_UTF8STRING,
+                          ASN1_UTF8STRING, ASN1_IA5STRING, ASN1_IA5STRING, ASN1_PRINTABLESTRING]);
+
+        for invalid in ["C=C1", "C=C", "C=ü", "E=ü@b", "serialNumber=a@b", "dnQualifier=ü"] {
+            assert!(Dn::parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn round_trip()
+    {
+        let dn = Dn::parse("C=CH, O=strongSwan, CN=moon").unwrap();
+        assert_eq!(dn.to_der(), hex("3031310b300906035504061302434831133011060355040a130a\
+                                     7374726f6e675377616e310d300b060355040313046d6f6f6e"));
+
+        for text in ["C=CH, O=strongSwan, CN=moon", "CN=a\\,b, O=x\\+y", "CN=\\#x\\ ",
+                     "DC=org, DC=strongswan, UID=jdoe", "CN=Müller+E=m@example.org",
+                     "CN=abc, 1.2.3.4=#020105", "serialNumber=12345, title=Dr."] {
+            let dn = Dn::parse(text).unwrap();
+            let decoded = Dn::from_der(&dn.to_der()).unwrap();
+            assert_eq!(decoded, dn);
+            assert_eq!(decoded.to_string(), text);
+            assert_eq!(Dn::parse(&decoded.to_string()).unwrap().to_der(), dn.to_der());
+        }
+    }
+
+    #[test]
+    fn error_positions()
+    {
+        let errors = [
+            ("",              "empty distinguished name at position 1"),
+            ("CN",            "'=' expected after 'CN' at position 3"),
+            ("CN=moon, X=y",  "unknown attribute type 'X' at position 10"),
+            ("CN=a,",         "attribute type expected at position 6"),
+            ("CN=a + ",       "attribute type expected at position 8"),
+            ("CN=a\\x",       "invalid escape sequence at position 5"),
+            ("CN=\"abc",      "unterminated quoted value at position 4"),
+            ("CN=\"a\"b",     "',' or '+' expected, found 'b' at position 7"),
+            ("CN=#0c0",       "odd number of hex digits at position 7"),
+            ("CN=#zz",        "hex digits expected after '#' at position 5"),
+            ("CN=#0c05",      "invalid BER value"),
+            ("CN=a;b",        "unescaped ';' in value at position 5"),
+            ("O=x, CN=",      "empty attribute value at position 9"),
+            ("O=x, C=CHE",    "country must be a two letter code at position 8"),
+            ("CN=\\ff",       "escaped octets are not valid UTF-8 at position 4"),
+        ];
+        for (text, msg) in errors {
+            let err = Dn::parse(text).unwrap_err();
+            assert!(err.starts_with(msg), "{}: {}", text, err);
+        }
+
+        // the error message points at the offending character
+        let err = Dn::parse("CN=moon, X=y").unwrap_err();
+        assert_eq!(err.lines().collect::<Vec<_>>(),
+                   ["unknown attribute type 'X' at position 10", "  CN=moon, X=y", "           ^"]);
+    }
+}
@@ -0,0 +1,695 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! Encoding of X.509v3 certificate and CRL extensions (RFC 5280, 4.2 and 5.2).
+
+use std::fs;
+use std::net::IpAddr;
+use crate::addrblock::AddrBlocks;
+use crate::asn1::*;
+use crate::ct::{Sct, decode_sct_list, encode_sct_list};
+use crate::general_name::{host_name, ip_octets, parse_general_name};
+use crate::keys::{KeyType, PublicKey};
+use crate::name_constraints::netmask_prefix;
+use crate::oid::*;
+use crate::x509::Extension;
+use crate::X509_NO_CONSTRAINT;
+
+pub const KU_DIGITAL_SIGNATURE: u32 = 1 << 0;
+pub const KU_NON_REPUDIATION: u32   = 1 << 1;
+pub const KU_KEY_ENCIPHERMENT: u32  = 1 << 2;
+pub const KU_DATA_ENCIPHERMENT: u32 = 1 << 3;
+pub const KU_KEY_AGREEMENT: u32     = 1 << 4;
+pub const KU_KEY_CERT_SIGN: u32     = 1 << 5;
+pub const KU_CRL_SIGN: u32          = 1 << 6;
+
+/// A certificatePolicies entry together with its policy qualifiers
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CertPolicy {
+    /// policy OID in dotted notation
+    pub oid: String,
+    /// CPS pointer qualifiers
+    pub cps_uris: Vec<String>,
+    /// user notice explicit texts
+    pub user_notices: Vec<String>,
+}
+
+/// A CRL distribution point with the issuers of an indirect CRL
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CrlDistPoint {
+    /// URI of the distribution point
+    pub uri: String,
+    /// cRLIssuer names, empty if the CRL is issued by the CA
+    pub issuers: Vec<String>,
+}
+
+//
+// Build a DER OBJECT IDENTIFIER from a user-supplied OID.
+//
+fn user_oid(oid: &str) -> Result<Vec<u8>, String>
+{
+    return Ok(asn1_simple_object(ASN1_OID, &asn1_oid_from_string(oid)?));
+}
+
+//
+// Build the base GeneralName of a name constraint subtree, encoding
+// address/prefix and address/netmask subnets as iPAddress with netmask and
+// uri: hosts or domains as uniformResourceIdentifier (RFC 5280, 4.2.1.10).
+// As such a constraint only applies to the host of a URI, URIs with a scheme
+// are reduced to their host and rejected if they contain anything else.
+//
+fn subtree_base(name: &str) -> Result<Vec<u8>, String>
+{
+    let (prefix, value) = name.split_once(':').unwrap_or(("", name));
+    let uri = if prefix.eq_ignore_ascii_case("uri") {
+        Some(value)
+    } else if value.starts_with("//") {
+        Some(name)
+    } else {
+        None
+    };
+    if let Some(uri) = uri {
+        let host = match uri.split_once("://") {
+            Some((_, rest)) => { rest.strip_suffix('/').unwrap_or(rest) }
+            None => { uri }
+        };
+        if host.contains(['/', '?', '#', '@', ':']) {
+            return Err(format!("URI name constraint '{}' may only contain a host or domain", name));
+        }
+        return Ok(asn1_simple_object(ASN1_CONTEXT_S_6, host_name(host)?.as_bytes()));
+    }
+    let subnet = if prefix.eq_ignore_ascii_case("ip") { value } else { name };
+    if let Some((addr, mask)) = subnet.split_once('/') &&
+       let Ok(addr) = addr.parse::<IpAddr>() {
+        let mut octets = ip_octets(&addr);
+        let bits = octets.len() * 8;
+        let prefix: usize = match (mask.parse::<usize>(), mask.parse::<IpAddr>()) {
+            (Ok(prefix), _) if prefix <= bits => { prefix }
+            (_, Ok(mask)) if ip_octets(&mask).len() == octets.len() &&
+                             let Some(prefix) = netmask_prefix(&mask) => { prefix }
+            _ => { return Err(format!("invalid subnet '{}'", name)); }
+        };
+        let mut mask = vec![0u8; octets.len()];
+        for i in 0..prefix {
+            mask[i / 8] |= 0x80 >> (i % 8);
+        }
+        for (o, m) in octets.iter_mut().zip(&mask) {
+            *o &= m;
+        }
+        octets.extend_from_slice(&mask);
+        return Ok(asn1_simple_object(ASN1_CONTEXT_S_7, &octets));
+    }
+    return parse_general_name(name);
+}
+
+//
+// Build a basicConstraints extension, omitting pathLenConstraint if set to
+// X509_NO_CONSTRAINT.
+//
+pub fn basic_constraints(ca: bool, pathlen: u32) -> Extension
+{
+    let mut items: Vec<Vec<u8>> = Vec::new();
+    if ca {
+        items.push(asn1_boolean(true));
+        if pathlen != X509_NO_CONSTRAINT {
+            items.push(asn1_integer_from_u64(pathlen as u64));
+        }
+    }
+    return Extension { oid: OID_BASIC_CONSTRAINTS.to_string(), critical: ca,
+                       value: asn1_sequence(&items) };
+}
+
+//
+// Build a critical keyUsage extension from KU_* flags.
+//
+pub fn key_usage(flags: u32) -> Extension
+{
+    return Extension { oid: OID_KEY_USAGE.to_string(), critical: true,
+                       value: asn1_named_bits(flags) };
+}
+
+//
+// Build a subjectKeyIdentifier extension for a public key.
+//
+pub fn subject_key_identifier(key: &PublicKey) -> Extension
+{
+    return Extension { oid: OID_SUBJECT_KEY_ID.to_string(), critical: false,
+                       value: asn1_octet_string(&key.subject_key_id()) };
+}
+
+//
+// Build an authorityKeyIdentifier extension containing a keyIdentifier.
+//
+pub fn authority_key_identifier(keyid: &[u8]) -> Extension
+{
+    let value = asn1_wrap(ASN1_SEQUENCE, &[&asn1_simple_object(ASN1_CONTEXT_S_0, keyid)]);
+    return Extension { oid: OID_AUTHORITY_KEY_ID.to_string(), critical: false, value };
+}
+
+//
+// Build a subjectAltName extension from a list of names.
+//
+pub fn subject_alt_name(names: &[String]) -> Result<Extension, String>
+{
+    let names = names.iter().map(|n| parse_general_name(n)).collect::<Result<Vec<_>, _>>()?;
+    return Ok(Extension { oid: OID_SUBJECT_ALT_NAME.to_string(), critical: false,
+                          value: asn1_sequence(&names) });
+}
+
+//
+// Map a --flag value to an extendedKeyUsage OID and additional keyUsage
+// bits, accepting key purpose OIDs in dotted notation.
+//
+pub fn parse_flag(flag: &str) -> Result<(Option<String>, u32), String>
+{
+    let oid = match flag {
+        "serverAuth"       => { OID_SERVER_AUTH }
+        "clientAuth"       => { OID_CLIENT_AUTH }
+        "codeSigning"      => { OID_CODE_SIGNING }
+        "emailProtection"  => { OID_EMAIL_PROTECTION }
+        "timeStamping"     => { OID_TIME_STAMPING }
+        "ocspSigning"      => { OID_OCSP_SIGNING }
+        "ikeIntermediate"  => { OID_IKE_INTERMEDIATE }
+        "msSmartcardLogon" => { OID_MS_SMARTCARD_LOGON }
+        "crlSign"          => { return Ok((None, KU_CRL_SIGN)); }
+        _ => {
+            if flag.starts_with(|c: char| c.is_ascii_digit()) && asn1_oid_from_string(flag).is_ok() {
+                return Ok((Some(flag.to_string()), 0));
+            }
+            return Err(format!("invalid --flag value '{}'", flag));
+        }
+    };
+    return Ok((Some(oid.to_string()), 0));
+}
+
+//
+// Collect the extendedKeyUsage purposes and keyUsage bits of --flag values.
+//
+pub fn parse_flags(flags: &[String]) -> Result<(Vec<String>, u32), String>
+{
+    let mut purposes: Vec<String> = Vec::new();
+    let mut usage = 0;
+    for flag in flags {
+        let (purpose, bits) = parse_flag(flag)?;
+        if let Some(oid) = purpose && !purposes.contains(&oid) {
+            purposes.push(oid);
+        }
+        usage |= bits;
+    }
+    return Ok((purposes, usage));
+}
+
+//
+// Derive the keyUsage bits from the key type, the CA status and the
+// extendedKeyUsage purposes.  CAs get keyCertSign and cRLSign, end entities
+// and CAs with purposes digitalSignature, and RSA end entity keys usable
+// for key transport keyEncipherment.  ML-KEM keys are restricted to
+// keyEncipherment.
+//
+pub fn derive_key_usage(key_type: KeyType, ca: bool, purposes: &[String], bits: u32)
+                       -> Result<u32, String>
+{
+    if matches!(key_type, KeyType::MlKem512 | KeyType::MlKem768 | KeyType::MlKem1024) {
+        if ca || bits != 0 || !purposes.is_empty() {
+            return Err(format!("{} keys can't be used for signatures", key_type));
+        }
+        return Ok(KU_KEY_ENCIPHERMENT);
+    }
+    let mut usage = bits;
+    if ca {
+        usage |= KU_KEY_CERT_SIGN | KU_CRL_SIGN;
+    }
+    if !ca || !purposes.is_empty() {
+        usage |= KU_DIGITAL_SIGNATURE;
+    }
+    let key_transport = [OID_SERVER_AUTH, OID_CLIENT_AUTH, OID_EMAIL_PROTECTION, OID_IKE_INTERMEDIATE];
+    if key_type == KeyType::Rsa && !ca &&
+       (purposes.is_empty() || purposes.iter().any(|p| key_transport.contains(&p.as_str()))) {
+        usage |= KU_KEY_ENCIPHERMENT;
+    }
+    return Ok(usage);
+}
+
+//
+// Build an extendedKeyUsage extension from a list of key purpose OIDs.  A
+// timeStamping purpose must be the only one and makes the extension
+// critical (RFC 3161, 2.3).
+//
+pub fn extended_key_usage(purposes: &[String]) -> Result<Extension, String>
+{
+    let time_stamping = purposes.iter().any(|p| p == OID_TIME_STAMPING);
+    if time_stamping && purposes.len() > 1 {
+        return Err("timeStamping can't be combined with other purposes".to_string());
+    }
+    let oids = purposes.iter().map(|p| user_oid(p)).collect::<Result<Vec<_>, _>>()?;
+    return Ok(Extension { oid: OID_EXTENDED_KEY_USAGE.to_string(), critical: time_stamping,
+                          value: asn1_sequence(&oids) });
+}
+
+//
+// Decode the purposes of an extendedKeyUsage extension.
+//
+pub fn parse_extended_key_usage(ext: &Extension) -> Result<Vec<String>, String>
+{
+    let mut purposes = Vec::new();
+    let mut parser = Asn1Parser::new(&ext.value).expect(ASN1_SEQUENCE)?.parser();
+    while let Some(oid) = parser.next_object()? {
+        purposes.push(oid.to_oid()?);
+    }
+    return Ok(purposes);
+}
+
+//
+// Build a uniformResourceIdentifier GeneralName from a URI option value.
+//
+fn uri_name(uri: &str, what: &str) -> Result<Vec<u8>, String>
+{
+    if !uri.contains("://") {
+        return Err(format!("invalid {} URI '{}'", what, uri));
+    }
+    return parse_general_name(&format!("uri:{}", uri))
+                .map_err(|e| format!("invalid {} URI '{}': {}", what, uri, e));
+}
+
+//
+// Build an authorityInfoAccess extension with OCSP responder and CA issuers
+// URIs.
+//
+pub fn authority_info_access(ocsp_uris: &[String], ca_issuers: &[String])
+                            -> Result<Extension, String>
+{
+    let mut descriptions: Vec<Vec<u8>> = Vec::new();
+    for uri in ocsp_uris {
+        descriptions.push(asn1_sequence(&[asn1_oid(OID_OCSP), uri_name(uri, "OCSP")?]));
+    }
+    for uri in ca_issuers {
+        descriptions.push(asn1_sequence(&[asn1_oid(OID_CA_ISSUERS), uri_name(uri, "CA issuers")?]));
+    }
+    return Ok(Extension { oid: OID_AUTHORITY_INFO_ACCESS.to_string(), critical: false,
+                          value: asn1_sequence(&descriptions) });
+}
+
+//
+// Build a cRLDistributionPoints extension with a distribution point
+// fullName per URI and the cRLIssuer names of indirect CRLs.
+//
+pub fn crl_distribution_points(points: &[CrlDistPoint]) -> Result<Extension, String>
+{
+    let mut items: Vec<Vec<u8>> = Vec::new();
+    for point in points {
+        let full_name = asn1_wrap(ASN1_CONTEXT_C_0, &[&uri_name(&point.uri, "CRL")?]);
+        let mut dp = vec![asn1_wrap(ASN1_CONTEXT_C_0, &[&full_name])];
+        if !point.issuers.is_empty() {
+            let names = point.issuers.iter().map(|n| parse_general_name(n))
+                                            .collect::<Result<Vec<_>, _>>()
+                                            .map_err(|e| format!("invalid CRL issuer: {}", e))?;
+            let parts: Vec<&[u8]> = names.iter().map(|n| n.as_slice()).collect();
+            dp.push(asn1_wrap(ASN1_CONTEXT_C_2, &parts));
+        }
+        items.push(asn1_sequence(&dp));
+    }
+    return Ok(Extension { oid: OID_CRL_DISTRIBUTION_POINTS.to_string(), critical: false,
+                          value: asn1_sequence(&items) });
+}
+
+//
+// Build a freshestCRL extension with a distribution point per delta CRL
+// URI (RFC 5280, 5.2.6).
+//
+pub fn freshest_crl(uris: &[String]) -> Result<Extension, String>
+{
+    let points: Vec<CrlDistPoint> = uris.iter().map(|uri| CrlDistPoint { uri: uri.clone(),
+                                                                         issuers: Vec::new() })
+                                        .collect();
+    let mut ext = crl_distribution_points(&points)?;
+    ext.oid = OID_FRESHEST_CRL.to_string();
+    return Ok(ext);
+}
+
+//
+// Build a critical sbgp-ipAddrBlock extension from IP address prefixes and
+// ranges (RFC 3779, 2.2).
+//
+pub fn ip_addr_blocks(blocks: &[String]) -> Result<Extension, String>
+{
+    let blocks = AddrBlocks::parse(blocks)?;
+    return Ok(Extension { oid: OID_IP_ADDR_BLOCKS.to_string(), critical: true,
+                          value: blocks.encode() });
+}
+
+//
+// Build a critical nameConstraints extension from permitted and excluded
+// subtrees.
+//
+pub fn name_constraints(permitted: &[String], excluded: &[String]) -> Result<Extension, String>
+{
+    fn subtrees(tag: u8, names: &[String]) -> Result<Vec<u8>, String>
+    {
+        let mut trees: Vec<Vec<u8>> = Vec::new();
+        for name in names {
+            trees.push(asn1_wrap(ASN1_SEQUENCE, &[&subtree_base(name)?]));
+        }
+        let parts: Vec<&[u8]> = trees.iter().map(|t| t.as_slice()).collect();
+        return Ok(asn1_wrap(tag, &parts));
+    }
+
+    let mut items: Vec<Vec<u8>> = Vec::new();
+    if !permitted.is_empty() {
+        items.push(subtrees(ASN1_CONTEXT_C_0, permitted)?);
+    }
+    if !excluded.is_empty() {
+        items.push(subtrees(ASN1_CONTEXT_C_1, excluded)?);
+    }
+    return Ok(Extension { oid: OID_NAME_CONSTRAINTS.to_string(), critical: true,
+                          value: asn1_sequence(&items) });
+}
+
+//
+// Build a certificatePolicies extension with CPS pointer and user notice
+// qualifiers.
+//
+pub fn certificate_policies(policies: &[CertPolicy]) -> Result<Extension, String>
+{
+    let mut infos: Vec<Vec<u8>> = Vec::new();
+    for (i, policy) in policies.iter().enumerate() {
+        if policies[..i].iter().any(|p| p.oid == policy.oid) {
+            return Err(format!("duplicate certificate policy {}", policy.oid));
+        }
+        let mut qualifiers: Vec<Vec<u8>> = Vec::new();
+        for uri in &policy.cps_uris {
+            if !uri.is_ascii() {
+                return Err(format!("invalid CPS URI '{}'", uri));
+            }
+            qualifiers.push(asn1_sequence(&[asn1_oid(OID_CPS),
+                                            asn1_simple_object(ASN1_IA5STRING, uri.as_bytes())]));
+        }
+        for text in &policy.user_notices {
+            if text.chars().count() > 200 {
+                return Err("user notice text exceeds 200 characters".to_string());
+            }
+            let notice = asn1_sequence(&[asn1_string(ASN1_UTF8STRING, text)]);
+            qualifiers.push(asn1_sequence(&[asn1_oid(OID_USER_NOTICE), notice]));
+        }
+        let mut info = vec![user_oid(&policy.oid)?];
+        if !qualifiers.is_empty() {
+            info.push(asn1_sequence(&qualifiers));
+        }
+        infos.push(asn1_sequence(&info));
+    }
+    return Ok(Extension { oid: OID_CERTIFICATE_POLICIES.to_string(), critical: false,
+                          value: asn1_sequence(&infos) });
+}
+
+//
+// Build a critical policyMappings extension from issuer-oid:subject-oid pairs.
+//
+pub fn policy_mappings(mappings: &[String]) -> Result<Extension, String>
+{
+    let mut items: Vec<Vec<u8>> = Vec::new();
+    for mapping in mappings {
+        let (issuer, subject) = mapping.split_once(':')
+                                       .ok_or(format!("invalid policy mapping '{}'", mapping))?;
+        if issuer == OID_ANY_POLICY || subject == OID_ANY_POLICY {
+            return Err(format!("anyPolicy can't be mapped in '{}'", mapping));
+        }
+        items.push(asn1_sequence(&[user_oid(issuer)?,
+                                   user_oid(subject)?]));
+    }
+    return Ok(Extension { oid: OID_POLICY_MAPPINGS.to_string(), critical: true,
+                          value: asn1_sequence(&items) });
+}
+
+//
+// Build a critical policyConstraints extension, omitting unset constraints.
+//
+pub fn policy_constraints(require_explicit: u32, inhibit_mapping: u32) -> Extension
+{
+    let mut items: Vec<Vec<u8>> = Vec::new();
+    for (tag, value) in [(ASN1_CONTEXT_S_0, require_explicit),
+                         (ASN1_CONTEXT_S_1, inhibit_mapping)] {
+        if value != X509_NO_CONSTRAINT {
+            let mut int = asn1_integer_from_u64(value as u64);
+            int[0] = tag;
+            items.push(int);
+        }
+    }
+    return Extension { oid: OID_POLICY_CONSTRAINTS.to_string(), critical: true,
+                       value: asn1_sequence(&items) };
+}
+
+//
+// Build a critical inhibitAnyPolicy extension.
+//
+pub fn inhibit_any_policy(skip_certs: u32) -> Extension
+{
+    return Extension { oid: OID_INHIBIT_ANY_POLICY.to_string(), critical: true,
+                       value: asn1_integer_from_u64(skip_certs as u64) };
+}
+
+//
+// Build a critical precertificate poison extension (RFC 6962, 3.1).
+//
+pub fn precert_poison() -> Extension
+{
+    return Extension { oid: OID_CT_PRECERT_POISON.to_string(), critical: true, value: asn1_null() };
+}
+
+//
+// Build a SignedCertificateTimestampList extension embedding the SCTs of
+// a precertificate (RFC 6962, 3.3).
+//
+pub fn sct_list(scts: &[Sct]) -> Result<Extension, String>
+{
+    return Ok(Extension { oid: OID_CT_SCT_LIST.to_string(), critical: false,
+                          value: asn1_octet_string(&encode_sct_list(scts)?) });
+}
+
+//
+// Decode the SCTs of a SignedCertificateTimestampList extension.
+//
+pub fn parse_sct_list(ext: &Extension) -> Result<Vec<Sct>, String>
+{
+    let mut parser = Asn1Parser::new(&ext.value);
+    let scts = decode_sct_list(parser.expect(ASN1_OCTET_STRING)?.content)?;
+    parser.finish()?;
+    return Ok(scts);
+}
+
+//
+// Build an extension from an oid[:critical]=value specification, where the
+// DER encoded value is given in hex, as "utf8:" text to be encoded as a
+// UTF8String, or as the name of a file containing it.
+//
+pub fn custom_extension(spec: &str) -> Result<Extension, String>
+{
+    let (name, value) = spec.split_once('=')
+                            .ok_or(format!("invalid extension '{}', expected oid[:critical]=value", spec))?;
+    let (oid, critical) = match name.split_once(':') {
+        Some((oid, "critical")) => { (oid, true) }
+        Some(_) => { return Err(format!("invalid extension flag in '{}'", name)); }
+        None => { (name, false) }
+    };
+    user_oid(oid)?;
+
+    let der = if let Some(text) = value.strip_prefix("utf8:") {
+        asn1_string(ASN1_UTF8STRING, text)
+    } else if !value.is_empty() && value.len() % 2 == 0 && value.bytes().all(|c| c.is_ascii_hexdigit()) {
+        (0..value.len()).step_by(2).map(|i| u8::from_str_radix(&value[i..i + 2], 16).unwrap()).collect()
+    } else {
+        fs::read(value).map_err(|e| format!("reading extension value '{}' failed: {}", value, e))?
+    };
+    asn1_parse(&der).map_err(|e| format!("value of extension {} is not a DER object: {}", oid, e))?;
+    return Ok(Extension { oid: oid.to_string(), critical, value: der });
+}
+
+//
+// Build a critical extension with a NULL value, unknown to relying parties
+// unless they recognize the given OID.
+//
+pub fn critical_extension(oid: &str) -> Result<Extension, String>
+{
+    user_oid(oid)?;
+    return Ok(Extension { oid: oid.to_string(), critical: true, value: asn1_null() });
+}
+
+//
+// Build the extensions given with --ext and the --critical test extension.
+//
+pub fn custom_extensions(specs: &[String], critical: Option<&str>) -> Result<Vec<Extension>, String>
+{
+    let mut custom = specs.iter().map(|spec| custom_extension(spec))
+                                 .collect::<Result<Vec<_>, _>>()?;
+    if let Some(oid) = critical {
+        custom.push(critical_extension(oid)?);
+    }
+    return Ok(custom);
+}
+
+//
+// Add custom extensions, replacing generated extensions of the same type.
+//
+pub fn merge_extensions(extensions: &mut Vec<Extension>, custom: Vec<Extension>) -> Result<(), String>
+{
+    for (i, ext) in custom.iter().enumerate() {
+        if custom[..i].iter().any(|e| e.oid == ext.oid) {
+            return Err(format!("extension {} given multiple times", oid_display(&ext.oid)));
+        }
+    }
+    for ext in custom {
+        match extensions.iter_mut().find(|e| e.oid == ext.oid) {
+            Some(existing) => { *existing = ext; }
+            None => { extensions.push(ext); }
+        }
+    }
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_extension_values()
+    {
+        let ext = custom_extension("1.2.3.4=0500").unwrap();
+        assert_eq!((ext.oid.as_str(), ext.critical, ext.value), ("1.2.3.4", false, vec![0x05, 0x00]));
+
+        let ext = custom_extension("1.2.3.4:critical=utf8:test").unwrap();
+        assert!(ext.critical);
+        assert_eq!(ext.value, b"\x0c\x04test");
+
+        assert!(custom_extension("1.2.3.4").is_err());
+        assert!(custom_extension("1.2.3.4:noncritical=0500").is_err());
+        assert!(custom_extension("1.2.x=0500").is_err());
+        // trailing data after the DER object
+        assert!(custom_extension("1.2.3.4=050000").is_err());
+    }
+
+    #[test]
+    fn key_purpose_flags()
+    {
+        let flags = [
+            ("serverAuth",       "1.3.6.1.5.5.7.3.1"),
+            ("clientAuth",       "1.3.6.1.5.5.7.3.2"),
+            ("codeSigning",      "1.3.6.1.5.5.7.3.3"),
+            ("emailProtection",  "1.3.6.1.5.5.7.3.4"),
+            ("timeStamping",     "1.3.6.1.5.5.7.3.8"),
+            ("ocspSigning",      "1.3.6.1.5.5.7.3.9"),
+            ("ikeIntermediate",  "1.3.6.1.5.5.8.2.2"),
+            ("msSmartcardLogon", "1.3.6.1.4.1.311.20.2.2"),
+            ("1.3.6.1.4.1.36906.3", "1.3.6.1.4.1.36906.3"),
+        ];
+        for (flag, oid) in flags {
+            assert_eq!(parse_flag(flag).unwrap(), (Some(oid.to_string()), 0), "{}", flag);
+        }
+        assert_eq!(parse_flag("crlSign").unwrap(), (None, KU_CRL_SIGN));
+        for invalid in ["serverauth", "server", "1.2.x", "", "1.2.3."] {
+            assert!(parse_flag(invalid).is_err(), "{}", invalid);
+        }
+
+        let flags: Vec<String> = ["clientAuth", "crlSign", "serverAuth", "clientAuth"]
+                                     .iter().map(|f| f.to_string()).collect();
+        assert_eq!(parse_flags(&flags).unwrap(),
+                   (vec![OID_CLIENT_AUTH.to_string(), OID_SERVER_AUTH.to_string()], KU_CRL_SIGN));
+    }
+
+    #[test]
+    fn derived_key_usage()
+    {
+        let none: Vec<String> = Vec::new();
+        let server = vec![OID_SERVER_AUTH.to_string()];
+        let code = vec![OID_CODE_SIGNING.to_string()];
+        let ca = KU_KEY_CERT_SIGN | KU_CRL_SIGN;
+
+        let cases = [
+            (KeyType::Rsa,     false, &none,   0,           KU_DIGITAL_SIGNATURE | KU_KEY_ENCIPHERMENT),
+            (KeyType::Rsa,     false, &server, 0,           KU_DIGITAL_SIGNATURE | KU_KEY_ENCIPHERMENT),
+            (KeyType::Rsa,     false, &code,   0,           KU_DIGITAL_SIGNATURE),
+            (KeyType::Rsa,     true,  &none,   0,           ca),
+            (KeyType::Rsa,     true,  &server, 0,           ca | KU_DIGITAL_SIGNATURE),
+            (KeyType::Ecdsa,   false, &none,   0,           KU_DIGITAL_SIGNATURE),
+            (KeyType::Ecdsa,   false, &server, 0,           KU_DIGITAL_SIGNATURE),
+            (KeyType::Ed25519, false, &none,   KU_CRL_SIGN, KU_DIGITAL_SIGNATURE | KU_CRL_SIGN),
+            (KeyType::MlDsa65, true,  &none,   0,           ca),
+            (KeyType::MlKem768, false, &none,  0,           KU_KEY_ENCIPHERMENT),
+        ];
+        for (key_type, is_ca, purposes, bits, usage) in cases {
+            assert_eq!(derive_key_usage(key_type, is_ca, purposes, bits).unwrap(), usage,
+                       "{} ca={} {:?}", key_type, is_ca, purposes);
+        }
+
+        // ML-KEM keys can't sign
+        assert!(derive_key_usage(KeyType::MlKem512, true, &none, 0).is_err());
+        assert!(derive_key_usage(KeyType::MlKem512, false, &server, 0).is_err());
+        assert!(derive_key_usage(KeyType::MlKem1024, false, &none, KU_CRL_SIGN).is_err());
+    }
+
+    #[test]
+    fn time_stamping_purpose()
+    {
+        let ext = extended_key_usage(&[OID_SERVER_AUTH.to_string(), OID_CLIENT_AUTH.to_string()])
+                      .unwrap();
+        assert_eq!(ext.oid, OID_EXTENDED_KEY_USAGE);
+        assert!(!ext.critical);
+        assert_eq!(ext.value, asn1_sequence(&[asn1_oid(OID_SERVER_AUTH), asn1_oid(OID_CLIENT_AUTH)]));
+        assert_eq!(parse_extended_key_usage(&ext).unwrap(), [OID_SERVER_AUTH, OID_CLIENT_AUTH]);
+
+        // timeStamping is exclusive and makes the extension critical (RFC 3161, 2.3)
+        let ext = extended_key_usage(&[OID_TIME_STAMPING.to_string()]).unwrap();
+        assert!(ext.critical);
+        assert_eq!(ext.value, asn1_sequence(&[asn1_oid(OID_TIME_STAMPING)]));
+        assert!(extended_key_usage(&[OID_TIME_STAMPING.to_string(),
+                                     OID_SERVER_AUTH.to_string()]).is_err());
+        assert!(extended_key_usage(&[OID_CODE_SIGNING.to_string(),
+                                     OID_TIME_STAMPING.to_string()]).is_err());
+    }
+
+    #[test]
+    fn merge_custom_extensions()
+    {
+        let mut extensions = vec![basic_constraints(false, X509_NO_CONSTRAINT), key_usage(KU_CRL_SIGN)];
+        let custom = custom_extensions(&["2.5.29.15=030201fe".to_string()],
+                                       Some("1.3.6.1.4.1.36906.99")).unwrap();
+        merge_extensions(&mut extensions, custom).unwrap();
+
+        assert_eq!(extensions.len(), 3);
+        assert_eq!(extensions[1].oid, OID_KEY_USAGE);
+        assert!(!extensions[1].critical);
+        assert_eq!(extensions[1].value, vec![0x03, 0x02, 0x01, 0xfe]);
+        assert_eq!(extensions[2].oid, "1.3.6.1.4.1.36906.99");
+        assert!(extensions[2].critical);
+        assert_eq!(extensions[2].value, asn1_null());
+
+        let twice = vec![critical_extension("1.2.3.4").unwrap(), custom_extension("1.2.3.4=0500").unwrap()];
+        assert!(merge_extensions(&mut extensions, twice).is_err());
+    }
+
+    #[test]
+    fn name_constraint_subtrees()
+    {
+        let uri = |host: &str| asn1_simple_object(ASN1_CONTEXT_S_6, host.as_bytes());
+        assert_eq!(subtree_base("uri:example.com").unwrap(), uri("example.com"));
+        assert_eq!(subtree_base("uri:.example.com").unwrap(), uri(".example.com"));
+        assert_eq!(subtree_base("https://example.com").unwrap(), uri("example.com"));
+        assert_eq!(subtree_base("https://example.com/").unwrap(), uri("example.com"));
+        assert_eq!(subtree_base("uri:http://.example.com").unwrap(), uri(".example.com"));
+        assert!(subtree_base("https://example.com/path").is_err());
+        assert!(subtree_base("https://example.com:8443").is_err());
+        assert!(subtree_base("uri:https://user@example.com").is_err());
+
+        assert_eq!(subtree_base("10.1.0.0/16").unwrap(),
+                   asn1_simple_object(ASN1_CONTEXT_S_7, &[10, 1, 0, 0, 255, 255, 0, 0]));
+        assert_eq!(subtree_base("ip:192.168.1.7/255.255.255.0").unwrap(),
+                   asn1_simple_object(ASN1_CONTEXT_S_7, &[192, 168, 1, 0, 255, 255, 255, 0]));
+        assert!(subtree_base("10.1.0.0/33").is_err());
+    }
+}
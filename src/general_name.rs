@@ -0,0 +1,236 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! GeneralName parsing (RFC 5280, 4.2.1.6).  Without a prefix, names are
+//! typed as in strongSwan: a URI if they contain "://", an iPAddress for
+//! IPv4 and IPv6 literals, a directoryName if they contain '=', an
+//! rfc822Name if they contain '@' (a leading '@' forces a dNSName), and a
+//! dNSName otherwise.  The prefixes `dns:`, `ip:`, `email:`, `uri:` and
+//! `dn:` select the type explicitly, `upn:` and `xmpp:` produce the
+//! msUPN and id-on-xmppAddr otherName forms.  Internationalized host names
+//! are converted to A-labels.
+
+use std::net::IpAddr;
+use crate::asn1::*;
+use crate::dn::Dn;
+use crate::idna::domain_to_ascii;
+use crate::oid::*;
+
+//
+// Get the network order octets of an IP address.
+//
+pub fn ip_octets(addr: &IpAddr) -> Vec<u8>
+{
+    match addr {
+        IpAddr::V4(a) => { a.octets().to_vec() }
+        IpAddr::V6(a) => { a.octets().to_vec() }
+    }
+}
+
+//
+// Convert and validate a host name, allowing a leading "*." wildcard or a
+// leading '.' as used in name constraints.
+//
+pub fn host_name(name: &str) -> Result<String, String>
+{
+    let host = domain_to_ascii(name)?;
+    let labels = host.strip_prefix("*.").or_else(|| host.strip_prefix('.')).unwrap_or(&host);
+    let valid = labels.split('.').all(|label| {
+        !label.is_empty() && label.len() <= 63 &&
+        label.bytes().all(|c| c.is_ascii_alphanumeric() || c == b'-' || c == b'_')
+    });
+    if !valid || host.len() > 253 {
+        return Err(format!("invalid host name '{}'", name));
+    }
+    return Ok(host);
+}
+
+//
+// Build an rfc822Name, converting the domain part to A-labels.
+//
+fn rfc822_name(name: &str) -> Result<Vec<u8>, String>
+{
+    let (local, domain) = match name.rsplit_once('@') {
+        Some((local, domain)) => { (Some(local), domain) }
+        None => { (None, name) }
+    };
+    let mut mailbox = String::new();
+    if let Some(local) = local {
+        if local.is_empty() || !local.is_ascii() || local.contains(['@', ' ']) {
+            return Err(format!("invalid email address '{}'", name));
+        }
+        mailbox = format!("{}@", local);
+    }
+    mailbox.push_str(&host_name(domain)?);
+    return Ok(asn1_simple_object(ASN1_CONTEXT_S_1, mailbox.as_bytes()));
+}
+
+//
+// Build a uniformResourceIdentifier, converting an internationalized host
+// to A-labels and percent-encoding any other non-ASCII characters.
+//
+fn uri(name: &str) -> Result<Vec<u8>, String>
+{
+    let (scheme, rest) = name.split_once("://")
+                             .ok_or(format!("URI '{}' lacks a scheme", name))?;
+    if scheme.is_empty() || !scheme.bytes().all(|c| c.is_ascii_alphanumeric() || b"+-.".contains(&c)) {
+        return Err(format!("invalid URI scheme in '{}'", name));
+    }
+    let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let (authority, path) = rest.split_at(end);
+    let (userinfo, hostport) = match authority.rsplit_once('@') {
+        Some((userinfo, hostport)) => { (format!("{}@", userinfo), hostport) }
+        None => { (String::new(), authority) }
+    };
+    let (host, port) = match hostport.rsplit_once(':') {
+        Some((host, port)) if !host.ends_with(']') && port.bytes().all(|c| c.is_ascii_digit()) => {
+            (host, format!(":{}", port))
+        }
+        _ => { (hostport, String::new()) }
+    };
+    let host = if host.starts_with('[') { host.to_string() } else { domain_to_ascii(host)? };
+
+    let mut out = format!("{}://{}{}{}", scheme, userinfo, host, port);
+    for c in path.chars() {
+        if c.is_ascii() {
+            out.push(c);
+        } else {
+            let mut buf = [0u8; 4];
+            for b in c.encode_utf8(&mut buf).bytes() {
+                out.push_str(&format!("%{:02X}", b));
+            }
+        }
+    }
+    if out.contains(|c: char| c.is_ascii_whitespace() || c.is_ascii_control()) {
+        return Err(format!("invalid URI '{}'", name));
+    }
+    return Ok(asn1_simple_object(ASN1_CONTEXT_S_6, out.as_bytes()));
+}
+
+//
+// Build an otherName with a UTF8String value.
+//
+fn other_name(oid: &str, value: &str) -> Result<Vec<u8>, String>
+{
+    if value.is_empty() {
+        return Err(format!("empty {} value", oid_display(oid)));
+    }
+    let value = asn1_wrap(ASN1_CONTEXT_C_0, &[&asn1_string(ASN1_UTF8STRING, value)]);
+    return Ok(asn1_wrap(ASN1_CONTEXT_C_0, &[&asn1_oid(oid), &value]));
+}
+
+//
+// Build a GeneralName from its string representation.
+//
+pub fn parse_general_name(name: &str) -> Result<Vec<u8>, String>
+{
+    if name.is_empty() {
+        return Err("empty general name".to_string());
+    }
+    if let Some((prefix, value)) = name.split_once(':') {
+        match prefix.to_ascii_lowercase().as_str() {
+            "dns" => {
+                return Ok(asn1_simple_object(ASN1_CONTEXT_S_2, host_name(value)?.as_bytes()));
+            }
+            "ip" => {
+                let addr = value.parse::<IpAddr>()
+                                .map_err(|_| format!("invalid IP address '{}'", value))?;
+                return Ok(asn1_simple_object(ASN1_CONTEXT_S_7, &ip_octets(&addr)));
+            }
+            "email" | "rfc822" => { return rfc822_name(value); }
+            "uri"   => { return uri(value); }
+            "dn"    => { return Ok(asn1_wrap(ASN1_CONTEXT_C_4, &[&Dn::parse(value)?.to_der()])); }
+            "upn"   => { return other_name(OID_MS_UPN, value); }
+            "xmpp"  => { return other_name(OID_XMPP_ADDR, value); }
+            _ => {}
+        }
+    }
+
+    if name.contains("://") {
+        return uri(name);
+    }
+    if let Ok(addr) = name.parse::<IpAddr>() {
+        return Ok(asn1_simple_object(ASN1_CONTEXT_S_7, &ip_octets(&addr)));
+    }
+    if name.contains('=') {
+        return Ok(asn1_wrap(ASN1_CONTEXT_C_4, &[&Dn::parse(name)?.to_der()]));
+    }
+    if let Some(fqdn) = name.strip_prefix('@') {
+        return Ok(asn1_simple_object(ASN1_CONTEXT_S_2, host_name(fqdn)?.as_bytes()));
+    }
+    if name.contains('@') {
+        return rfc822_name(name);
+    }
+    return Ok(asn1_simple_object(ASN1_CONTEXT_S_2, host_name(name)?.as_bytes()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex;
+
+    #[test]
+    fn implicit_types()
+    {
+        let names = [
+            ("moon.strongswan.org",     "82136d6f6f6e2e7374726f6e677377616e2e6f7267"),
+            ("@moon",                   "82046d6f6f6e"),
+            ("carol@strongswan.org",    "81146361726f6c407374726f6e677377616e2e6f7267"),
+            ("192.168.0.1",             "8704c0a80001"),
+            ("fec0::1",                 "8710fec00000000000000000000000000001"),
+            ("http://crl.strongswan.org/strongswan.crl",
+             "8628687474703a2f2f63726c2e7374726f6e677377616e2e6f72672f7374726f6e677377616e2e63726c"),
+            ("C=CH, CN=x",              "a41b3019310b3009060355040613024348310a30080603550403130178"),
+            ("E=a@b",                   "a41630143112301006092a864886f70d0109011603614062"),
+        ];
+        for (name, der) in names {
+            assert_eq!(parse_general_name(name).unwrap(), hex(der), "{}", name);
+        }
+    }
+
+    #[test]
+    fn explicit_types()
+    {
+        let names = [
+            ("dns:moon.strongswan.org", "82136d6f6f6e2e7374726f6e677377616e2e6f7267"),
+            ("DNS:bücher.example",      "8215786e2d2d62636865722d6b76612e6578616d706c65"),
+            ("ip:10.0.0.1",             "87040a000001"),
+            ("email:carol@strongswan.org",
+             "81146361726f6c407374726f6e677377616e2e6f7267"),
+            ("rfc822:strongswan.org",   "810e7374726f6e677377616e2e6f7267"),
+            ("uri:ldap://ldap.example", "86136c6461703a2f2f6c6461702e6578616d706c65"),
+            ("dn:CN=x",                 "a40e300c310a30080603550403130178"),
+            ("upn:user@corp.example",
+             "a021060a2b060104018237140203a0130c117573657240636f72702e6578616d706c65"),
+            ("xmpp:jürg@im.example",
+             "a01e06082b06010505070805a0120c106ac3bc726740696d2e6578616d706c65"),
+        ];
+        for (name, der) in names {
+            assert_eq!(parse_general_name(name).unwrap(), hex(der), "{}", name);
+        }
+
+        // internationalized hosts in URIs, percent-encoded paths
+        assert_eq!(parse_general_name("http://bücher.example:8080/päth").unwrap(),
+                   asn1_simple_object(ASN1_CONTEXT_S_6,
+                                      b"http://xn--bcher-kva.example:8080/p%C3%A4th"));
+    }
+
+    #[test]
+    fn malformed_names()
+    {
+        for invalid in ["", "ip:1.2.3", "ip:moon", "dns:a..b", "dns:a_b c", "a b", "-.", "dns:",
+                        "email:@strongswan.org", "email:jürg@strongswan.org", "x@y@", "://x",
+                        "ht tp://x", "http://x y", "C=CH, X=y", "dn:CN", "upn:", "xmpp:"] {
+            assert!(parse_general_name(invalid).is_err(), "{}", invalid);
+        }
+    }
+}
@@ -0,0 +1,164 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! Conversion of internationalized domain names to A-labels using the
+//! Punycode encoding of RFC 3492 (IDNA2008, RFC 5890/5891).  Labels are
+//! lowercased but not NFC normalized, so names should be given in NFC.
+
+const BASE: u32 = 36;
+const TMIN: u32 = 1;
+const TMAX: u32 = 26;
+const SKEW: u32 = 38;
+const DAMP: u32 = 700;
+const INITIAL_BIAS: u32 = 72;
+const INITIAL_N: u32 = 128;
+
+//
+// Bias adaptation function (RFC 3492, 6.1).
+//
+fn adapt(mut delta: u32, numpoints: u32, first: bool) -> u32
+{
+    delta = if first { delta / DAMP } else { delta / 2 };
+    delta += delta / numpoints;
+    let mut k = 0;
+    while delta > ((BASE - TMIN) * TMAX) / 2 {
+        delta /= BASE - TMIN;
+        k += BASE;
+    }
+    return k + (BASE - TMIN + 1) * delta / (delta + SKEW);
+}
+
+//
+// Map a digit value to its basic code point.
+//
+fn encode_digit(d: u32) -> char
+{
+    return if d < 26 { (b'a' + d as u8) as char } else { (b'0' + (d - 26) as u8) as char };
+}
+
+//
+// Encode a Unicode string with Punycode (RFC 3492, 6.3).
+//
+pub fn punycode_encode(input: &str) -> Result<String, String>
+{
+    let overflow = || "Punycode overflow".to_string();
+    let code_points: Vec<u32> = input.chars().map(|c| c as u32).collect();
+    let mut out: String = input.chars().filter(|c| c.is_ascii()).collect();
+    let basic = out.len() as u32;
+    if basic > 0 {
+        out.push('-');
+    }
+
+    let (mut n, mut delta, mut bias, mut h) = (INITIAL_N, 0u32, INITIAL_BIAS, basic);
+    while (h as usize) < code_points.len() {
+        let m = *code_points.iter().filter(|c| **c >= n).min().unwrap();
+        delta = (m - n).checked_mul(h + 1).and_then(|d| d.checked_add(delta))
+                       .ok_or_else(overflow)?;
+        n = m;
+        for c in &code_points {
+            if *c < n {
+                delta = delta.checked_add(1).ok_or_else(overflow)?;
+            }
+            if *c == n {
+                let mut q = delta;
+                let mut k = BASE;
+                loop {
+                    let t = if k <= bias { TMIN } else if k >= bias + TMAX { TMAX } else { k - bias };
+                    if q < t {
+                        break;
+                    }
+                    out.push(encode_digit(t + (q - t) % (BASE - t)));
+                    q = (q - t) / (BASE - t);
+                    k += BASE;
+                }
+                out.push(encode_digit(q));
+                bias = adapt(delta, h + 1, h == basic);
+                delta = 0;
+                h += 1;
+            }
+        }
+        delta += 1;
+        n += 1;
+    }
+    return Ok(out);
+}
+
+//
+// Convert a domain name to its ASCII form, replacing every label containing
+// non-ASCII characters by an "xn--" A-label.
+//
+pub fn domain_to_ascii(domain: &str) -> Result<String, String>
+{
+    // map the IDNA label separators to full stops
+    let domain: String = domain.chars()
+                               .map(|c| if matches!(c, '\u{3002}' | '\u{FF0E}' | '\u{FF61}') { '.' } else { c })
+                               .collect();
+    if domain.is_ascii() {
+        return Ok(domain);
+    }
+    let mut labels: Vec<String> = Vec::new();
+    for label in domain.split('.') {
+        if label.is_ascii() {
+            labels.push(label.to_string());
+            continue;
+        }
+        let label = label.to_lowercase();
+        if label.chars().any(|c| c.is_whitespace() || c.is_control()) {
+            return Err(format!("invalid internationalized label '{}'", label));
+        }
+        let alabel = format!("xn--{}", punycode_encode(&label)?);
+        if alabel.len() > 63 {
+            return Err(format!("label '{}' exceeds 63 octets as A-label", label));
+        }
+        labels.push(alabel);
+    }
+    return Ok(labels.join("."));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn punycode_samples()
+    {
+        // RFC 3492, 7.1
+        let samples = [
+            ("\u{644}\u{64A}\u{647}\u{645}\u{627}\u{628}\u{62A}\u{643}\u{644}\u{645}\u{648}\
+              \u{634}\u{639}\u{631}\u{628}\u{64A}\u{61F}",  "egbpdaj6bu4bxfgehfvwxn"),
+            ("他们为什么不说中文",                          "ihqwcrb4cv8a8dqg056pqjye"),
+            ("他們爲什麽不說中文",                          "ihqwctvzc91f659drss3x8bo0yb"),
+            ("3年B組金八先生",                              "3B-ww4c5e180e575a65lsy2b"),
+            ("安室奈美恵-with-SUPER-MONKEYS",               "-with-SUPER-MONKEYS-pc58ag80a8qai00g7n9n"),
+            ("パフィーdeルンバ",                            "de-jg4avhby1noc0d"),
+            ("そのスピードで",                              "d9juau41awczczp"),
+            ("-> $1.00 <-",                                 "-> $1.00 <--"),
+        ];
+        for (input, output) in samples {
+            assert_eq!(punycode_encode(input).unwrap(), output, "{}", input);
+        }
+    }
+
+    #[test]
+    fn domain_names()
+    {
+        assert_eq!(domain_to_ascii("moon.strongswan.org").unwrap(), "moon.strongswan.org");
+        assert_eq!(domain_to_ascii("bücher.example").unwrap(), "xn--bcher-kva.example");
+        assert_eq!(domain_to_ascii("MÜNCHEN.de").unwrap(), "xn--mnchen-3ya.de");
+        assert_eq!(domain_to_ascii("例え。jp").unwrap(), "xn--r8jz45g.jp");
+        assert_eq!(domain_to_ascii("他们为什么不说中文.example").unwrap(),
+                   "xn--ihqwcrb4cv8a8dqg056pqjye.example");
+
+        assert!(domain_to_ascii("bü cher.example").is_err());
+        assert!(domain_to_ascii(&format!("{}.example", "ü".repeat(60))).is_err());
+    }
+}
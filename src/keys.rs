@@ -0,0 +1,882 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! Private and public keys with their PKCS#1, SEC1, PKCS#8 and X.509
+//! SubjectPublicKeyInfo encodings, signature creation and verification.
+//!
+//! ML-DSA and ML-KEM private keys are stored in PKCS#8 as
+//!
+//! ```text
+//! ML-DSA-PrivateKey ::= CHOICE {      -- ML-KEM-PrivateKey alike
+//!     seed         [0] IMPLICIT OCTET STRING (SIZE (32)),  -- 64 for ML-KEM
+//!     expandedKey  OCTET STRING,
+//!     both         SEQUENCE {
+//!         seed         OCTET STRING (SIZE (32)),
+//!         expandedKey  OCTET STRING
+//!     }
+//! }
+//! ```
+//!
+//! of which the seed is written if known, all three being accepted.
+//!
+//! SLH-DSA keys are stored as raw octet strings (RFC 9909), composite
+//! ML-DSA keys as the concatenation of their components described in the
+//! composite module, both in PKCS#8 and SubjectPublicKeyInfo.
+//!
+//! A threshold RSA key share is loaded on its own and creates partial
+//! signatures only, see the threshold module.  Loading several shares at
+//! once is refused so that the full signing capability is never assembled
+//! on a single host.
+
+use std::fmt;
+use crate::asn1::*;
+use crate::oid::*;
+use crate::pem::{CredType, PemBlock, base64_encode};
+use crate::crypto::rsa::{RsaPrivateKey, RsaPublicKey};
+use crate::crypto::rsa_threshold::RsaKeyShare;
+use crate::crypto::ec::{Curve, EcPrivateKey, EcPublicKey};
+use crate::crypto::eddsa::{EdCurve, EdPrivateKey, EdPublicKey};
+use crate::crypto::mldsa::{MlDsaParams, MlDsaPrivateKey, MlDsaPublicKey};
+use crate::crypto::mlkem::{MlKemParams, MlKemPrivateKey, MlKemPublicKey};
+use crate::crypto::slhdsa::{SlhDsaParams, SlhDsaPrivateKey, SlhDsaPublicKey};
+use crate::crypto::composite::{CompositeParams, CompositePrivateKey, CompositePublicKey};
+use crate::crypto::hash::sha1;
+use crate::signature::SignatureScheme;
+
+/// Supported key types
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyType {
+    Rsa,
+    Ecdsa,
+    Ed25519,
+    Ed448,
+    MlDsa44,
+    MlDsa65,
+    MlDsa87,
+    MlKem512,
+    MlKem768,
+    MlKem1024,
+    SlhDsa(SlhDsaParams),
+    Composite(CompositeParams),
+}
+
+impl KeyType {
+    //
+    // Parse a key type given with --type.
+    //
+    pub fn parse(name: &str) -> Option<KeyType>
+    {
+        match name {
+            "rsa"       => { Some(KeyType::Rsa) }
+            "ecdsa"     => { Some(KeyType::Ecdsa) }
+            "ed25519"   => { Some(KeyType::Ed25519) }
+            "ed448"     => { Some(KeyType::Ed448) }
+            "mldsa44"   => { Some(KeyType::MlDsa44) }
+            "mldsa65"   => { Some(KeyType::MlDsa65) }
+            "mldsa87"   => { Some(KeyType::MlDsa87) }
+            "mlkem512"  => { Some(KeyType::MlKem512) }
+            "mlkem768"  => { Some(KeyType::MlKem768) }
+            "mlkem1024" => { Some(KeyType::MlKem1024) }
+            _ => {
+                if let Some(params) = SlhDsaParams::parse(name) {
+                    return Some(KeyType::SlhDsa(params));
+                }
+                CompositeParams::parse(name).map(KeyType::Composite)
+            }
+        }
+    }
+}
+
+impl From<EdCurve> for KeyType {
+    fn from(curve: EdCurve) -> KeyType
+    {
+        match curve {
+            EdCurve::Ed25519 => { KeyType::Ed25519 }
+            EdCurve::Ed448   => { KeyType::Ed448 }
+        }
+    }
+}
+
+impl From<MlDsaParams> for KeyType {
+    fn from(params: MlDsaParams) -> KeyType
+    {
+        match params {
+            MlDsaParams::MlDsa44 => { KeyType::MlDsa44 }
+            MlDsaParams::MlDsa65 => { KeyType::MlDsa65 }
+            MlDsaParams::MlDsa87 => { KeyType::MlDsa87 }
+        }
+    }
+}
+
+impl From<MlKemParams> for KeyType {
+    fn from(params: MlKemParams) -> KeyType
+    {
+        match params {
+            MlKemParams::MlKem512  => { KeyType::MlKem512 }
+            MlKemParams::MlKem768  => { KeyType::MlKem768 }
+            MlKemParams::MlKem1024 => { KeyType::MlKem1024 }
+        }
+    }
+}
+
+impl fmt::Display for KeyType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self {
+            KeyType::Rsa       => { write!(f, "RSA") }
+            KeyType::Ecdsa     => { write!(f, "ECDSA") }
+            KeyType::Ed25519   => { write!(f, "ED25519") }
+            KeyType::Ed448     => { write!(f, "ED448") }
+            KeyType::MlDsa44   => { write!(f, "ML-DSA-44") }
+            KeyType::MlDsa65   => { write!(f, "ML-DSA-65") }
+            KeyType::MlDsa87   => { write!(f, "ML-DSA-87") }
+            KeyType::MlKem512  => { write!(f, "ML-KEM-512") }
+            KeyType::MlKem768  => { write!(f, "ML-KEM-768") }
+            KeyType::MlKem1024 => { write!(f, "ML-KEM-1024") }
+            KeyType::SlhDsa(params)    => { write!(f, "{}", oid_display(params.oid())) }
+            KeyType::Composite(params) => { write!(f, "{}", oid_display(params.oid())) }
+        }
+    }
+}
+
+/// A private key of any supported type
+#[derive(Clone, Debug)]
+pub enum PrivateKey {
+    Rsa(RsaPrivateKey),
+    Ecdsa(EcPrivateKey),
+    Eddsa(EdPrivateKey),
+    MlDsa(MlDsaPrivateKey),
+    MlKem(MlKemPrivateKey),
+    SlhDsa(SlhDsaPrivateKey),
+    Composite(CompositePrivateKey),
+    RsaShare(RsaKeyShare),
+}
+
+/// A public key of any supported type
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PublicKey {
+    Rsa(RsaPublicKey),
+    Ecdsa(EcPublicKey),
+    Eddsa(EdPublicKey),
+    MlDsa(MlDsaPublicKey),
+    MlKem(MlKemPublicKey),
+    SlhDsa(SlhDsaPublicKey),
+    Composite(CompositePublicKey),
+}
+
+impl PrivateKey {
+    pub fn key_type(&self) -> KeyType
+    {
+        match self {
+            PrivateKey::Rsa(_)   => { KeyType::Rsa }
+            PrivateKey::Ecdsa(_) => { KeyType::Ecdsa }
+            PrivateKey::Eddsa(key) => { key.curve.into() }
+            PrivateKey::MlDsa(key) => { key.params.into() }
+            PrivateKey::MlKem(key) => { key.params.into() }
+            PrivateKey::SlhDsa(key) => { KeyType::SlhDsa(key.params) }
+            PrivateKey::Composite(key) => { KeyType::Composite(key.params) }
+            PrivateKey::RsaShare(_) => { KeyType::Rsa }
+        }
+    }
+
+    //
+    // Key strength in bits, the security strength for ML-DSA, ML-KEM,
+    // SLH-DSA and composite keys.
+    //
+    pub fn bits(&self) -> usize
+    {
+        match self {
+            PrivateKey::Rsa(key)   => { key.bits() }
+            PrivateKey::Ecdsa(key) => { key.curve.bits() }
+            PrivateKey::Eddsa(key) => { 8 * key.curve.key_len() }
+            PrivateKey::MlDsa(key) => { key.params.lambda() }
+            PrivateKey::MlKem(key) => { key.params.strength() }
+            PrivateKey::SlhDsa(key) => { key.params.strength() }
+            PrivateKey::Composite(key) => { key.params.mldsa().lambda() }
+            PrivateKey::RsaShare(key) => { key.bits() }
+        }
+    }
+
+    pub fn public_key(&self) -> PublicKey
+    {
+        match self {
+            PrivateKey::Rsa(key)   => { PublicKey::Rsa(key.public_key()) }
+            PrivateKey::Ecdsa(key) => { PublicKey::Ecdsa(key.public_key()) }
+            PrivateKey::Eddsa(key) => { PublicKey::Eddsa(key.public_key()) }
+            PrivateKey::MlDsa(key) => { PublicKey::MlDsa(key.public_key()) }
+            PrivateKey::MlKem(key) => { PublicKey::MlKem(key.public_key()) }
+            PrivateKey::SlhDsa(key) => { PublicKey::SlhDsa(key.public_key()) }
+            PrivateKey::Composite(key) => { PublicKey::Composite(key.public_key()) }
+            PrivateKey::RsaShare(key) => { PublicKey::Rsa(key.public_key()) }
+        }
+    }
+
+    //
+    // Create a signature with the given scheme.
+    //
+    pub fn sign(&self, scheme: SignatureScheme, data: &[u8]) -> Result<Vec<u8>, String>
+    {
+        match (self, scheme) {
+            (PrivateKey::Rsa(key), SignatureScheme::RsaPkcs1(hash)) => {
+                key.sign_pkcs1(hash, data)
+            }
+            (PrivateKey::Rsa(key), SignatureScheme::RsaPss(hash)) => {
+                key.sign_pss(hash, data)
+            }
+            (PrivateKey::Ecdsa(key), SignatureScheme::Ecdsa(hash)) => {
+                Ok(key.sign(hash, data))
+            }
+            (PrivateKey::Eddsa(key), SignatureScheme::Ed25519) |
+            (PrivateKey::Eddsa(key), SignatureScheme::Ed448)
+                if scheme.key_type() == self.key_type() => {
+                Ok(key.sign(data))
+            }
+            (PrivateKey::MlDsa(key), SignatureScheme::MlDsa(params)) if params == key.params => {
+                Ok(key.sign(data))
+            }
+            (PrivateKey::SlhDsa(key), SignatureScheme::SlhDsa(params)) if params == key.params => {
+                Ok(key.sign(data))
+            }
+            (PrivateKey::Composite(key), SignatureScheme::Composite(params))
+                if params == key.params => {
+                key.sign(data)
+            }
+            (PrivateKey::RsaShare(_), _) => {
+                Err("an RSA key share creates partial signatures only".to_string())
+            }
+            _ => {
+                Err(format!("signature scheme {} not supported by {} key", scheme,
+                            self.key_type()))
+            }
+        }
+    }
+
+    //
+    // Encode in the traditional key-specific format, EdDSA, ML-DSA, ML-KEM,
+    // SLH-DSA and composite keys having PKCS#8 as their only format.
+    //
+    pub fn to_der(&self) -> Result<(Vec<u8>, CredType), String>
+    {
+        match self {
+            PrivateKey::Rsa(key)   => { Ok((key.to_pkcs1(), CredType::RsaPrivateKey)) }
+            PrivateKey::Ecdsa(key) => { Ok((key.to_sec1(true), CredType::EcPrivateKey)) }
+            PrivateKey::Eddsa(_) |
+            PrivateKey::MlDsa(_) |
+            PrivateKey::MlKem(_) |
+            PrivateKey::SlhDsa(_) |
+            PrivateKey::Composite(_) => { Ok((self.to_pkcs8()?, CredType::PrivateKey)) }
+            PrivateKey::RsaShare(key) => { Ok((key.to_der(), CredType::RsaKeyShare)) }
+        }
+    }
+
+    //
+    // Encode as PKCS#8 PrivateKeyInfo.
+    //
+    pub fn to_pkcs8(&self) -> Result<Vec<u8>, String>
+    {
+        let (algorithm, key) = match self {
+            PrivateKey::Rsa(key) => {
+                (asn1_algorithm_identifier(OID_RSA_ENCRYPTION, Some(&asn1_null())),
+                 key.to_pkcs1())
+            }
+            PrivateKey::Ecdsa(key) => {
+                // the curve is given by the algorithm parameters (RFC 5915)
+                (asn1_algorithm_identifier(OID_EC_PUBLICKEY, Some(&asn1_oid(key.curve.oid()))),
+                 key.to_sec1(false))
+            }
+            PrivateKey::Eddsa(key) => {
+                // CurvePrivateKey wrapped in the privateKey OCTET STRING (RFC 8410)
+                (asn1_algorithm_identifier(key.curve.oid(), None),
+                 asn1_octet_string(&key.seed))
+            }
+            PrivateKey::MlDsa(key) => {
+                (asn1_algorithm_identifier(key.params.oid(), None),
+                 encode_seed_or_expanded(&key.seed, &key.expanded))
+            }
+            PrivateKey::MlKem(key) => {
+                (asn1_algorithm_identifier(key.params.oid(), None),
+                 encode_seed_or_expanded(&key.seed, &key.expanded))
+            }
+            PrivateKey::SlhDsa(key) => {
+                (asn1_algorithm_identifier(key.params.oid(), None), key.key.clone())
+            }
+            PrivateKey::Composite(key) => {
+                (asn1_algorithm_identifier(key.params.oid(), None), key.to_bytes())
+            }
+            PrivateKey::RsaShare(_) => {
+                return Err("RSA key shares have no PKCS#8 encoding".to_string());
+            }
+        };
+        return Ok(asn1_sequence(&[
+            asn1_integer_from_u64(0),
+            algorithm,
+            asn1_octet_string(&key),
+        ]));
+    }
+
+    //
+    // Decode a PKCS#8 PrivateKeyInfo or a key in traditional format.
+    //
+    pub fn from_der(der: &[u8]) -> Result<PrivateKey, String>
+    {
+        if let Ok(key) = PrivateKey::from_pkcs8(der) {
+            return Ok(key);
+        }
+        if let Ok(key) = RsaPrivateKey::from_pkcs1(der) {
+            return Ok(PrivateKey::Rsa(key));
+        }
+        if let Ok(key) = EcPrivateKey::from_sec1(der, None) {
+            return Ok(PrivateKey::Ecdsa(key));
+        }
+        return Err("unsupported or malformed private key".to_string());
+    }
+
+    //
+    // Decode a private key from a single object, or a single RSA key share,
+    // optionally accompanied by the public key as written by --gen --shares.
+    // More than one share is refused, each share signing on its own.
+    //
+    pub fn from_blocks(blocks: &[PemBlock]) -> Result<PrivateKey, String>
+    {
+        if let [block] = blocks && RsaKeyShare::from_der(&block.der).is_err() {
+            return PrivateKey::from_der(&block.der);
+        }
+        let mut shares = Vec::new();
+        for block in blocks {
+            match RsaKeyShare::from_der(&block.der) {
+                Ok(share) => { shares.push(share); }
+                Err(_) if PublicKey::from_spki(&block.der).is_ok() => {}
+                Err(_) => {
+                    return Err(format!("expected a private key or an RSA key share, found {} objects",
+                                       blocks.len()));
+                }
+            }
+        }
+        match shares.len() {
+            0 => { Err("expected a private key or an RSA key share".to_string()) }
+            1 => { Ok(PrivateKey::RsaShare(shares.remove(0))) }
+            n => {
+                Err(format!("found {} RSA key shares, partial signatures are created with a \
+                             single share", n))
+            }
+        }
+    }
+
+    //
+    // Decode a PKCS#8 PrivateKeyInfo (RFC 5208) or OneAsymmetricKey
+    // (RFC 5958).
+    //
+    pub fn from_pkcs8(der: &[u8]) -> Result<PrivateKey, String>
+    {
+        let obj = asn1_parse(der)?;
+        if obj.tag != ASN1_SEQUENCE {
+            return Err("not a PrivateKeyInfo".to_string());
+        }
+        let mut parser = obj.parser();
+        let version = parser.expect(ASN1_INTEGER)?.to_u64()?;
+        if version > 1 {
+            return Err(format!("unsupported PrivateKeyInfo version {}", version));
+        }
+        let (oid, params) = parse_algorithm_identifier(&parser.expect(ASN1_SEQUENCE)?)?;
+        let key = parser.expect(ASN1_OCTET_STRING)?;
+
+        match oid.as_str() {
+            OID_RSA_ENCRYPTION => {
+                Ok(PrivateKey::Rsa(RsaPrivateKey::from_pkcs1(key.content)?))
+            }
+            OID_EC_PUBLICKEY => {
+                let curve = parse_named_curve(params)?;
+                Ok(PrivateKey::Ecdsa(EcPrivateKey::from_sec1(key.content, Some(curve))?))
+            }
+            OID_ED25519 | OID_ED448 => {
+                if params.is_some() {
+                    return Err(format!("invalid parameters for {}", oid_display(&oid)));
+                }
+                let curve = EdCurve::from_oid(&oid).unwrap();
+                let seed = asn1_parse(key.content)?;
+                if seed.tag != ASN1_OCTET_STRING {
+                    return Err("not a CurvePrivateKey".to_string());
+                }
+                Ok(PrivateKey::Eddsa(EdPrivateKey::from_seed(curve, seed.content)?))
+            }
+            OID_ML_DSA_44 | OID_ML_DSA_65 | OID_ML_DSA_87 => {
+                if params.is_some() {
+                    return Err(format!("invalid parameters for {}", oid_display(&oid)));
+                }
+                let params = MlDsaParams::from_oid(&oid).unwrap();
+                let (seed, expanded) = parse_seed_or_expanded(key.content)?;
+                let key = match seed {
+                    Some(seed) => { MlDsaPrivateKey::from_seed(params, seed)? }
+                    None => { MlDsaPrivateKey::from_expanded(params, expanded.unwrap())? }
+                };
+                check_expanded(&key.expanded, expanded)?;
+                Ok(PrivateKey::MlDsa(key))
+            }
+            OID_ML_KEM_512 | OID_ML_KEM_768 | OID_ML_KEM_1024 => {
+                if params.is_some() {
+                    return Err(format!("invalid parameters for {}", oid_display(&oid)));
+                }
+                let params = MlKemParams::from_oid(&oid).unwrap();
+                let (seed, expanded) = parse_seed_or_expanded(key.content)?;
+                let key = match seed {
+                    Some(seed) => { MlKemPrivateKey::from_seed(params, seed)? }
+                    None => { MlKemPrivateKey::from_expanded(params, expanded.unwrap())? }
+                };
+                check_expanded(&key.expanded, expanded)?;
+                Ok(PrivateKey::MlKem(key))
+            }
+            _ if let Some(slhdsa) = SlhDsaParams::from_oid(&oid) => {
+                if params.is_some() {
+                    return Err(format!("invalid parameters for {}", oid_display(&oid)));
+                }
+                Ok(PrivateKey::SlhDsa(SlhDsaPrivateKey::from_bytes(slhdsa, key.content)?))
+            }
+            _ if let Some(composite) = CompositeParams::from_oid(&oid) => {
+                if params.is_some() {
+                    return Err(format!("invalid parameters for {}", oid_display(&oid)));
+                }
+                Ok(PrivateKey::Composite(CompositePrivateKey::from_bytes(composite, key.content)?))
+            }
+            _ => { Err(format!("unsupported private key algorithm {}", oid_display(&oid))) }
+        }
+    }
+}
+
+impl PublicKey {
+    pub fn key_type(&self) -> KeyType
+    {
+        match self {
+            PublicKey::Rsa(_)   => { KeyType::Rsa }
+            PublicKey::Ecdsa(_) => { KeyType::Ecdsa }
+            PublicKey::Eddsa(key) => { key.curve.into() }
+            PublicKey::MlDsa(key) => { key.params.into() }
+            PublicKey::MlKem(key) => { key.params.into() }
+            PublicKey::SlhDsa(key) => { KeyType::SlhDsa(key.params) }
+            PublicKey::Composite(key) => { KeyType::Composite(key.params) }
+        }
+    }
+
+    //
+    // Key strength in bits, the security strength for ML-DSA, ML-KEM,
+    // SLH-DSA and composite keys.
+    //
+    pub fn bits(&self) -> usize
+    {
+        match self {
+            PublicKey::Rsa(key)   => { key.bits() }
+            PublicKey::Ecdsa(key) => { key.curve.bits() }
+            PublicKey::Eddsa(key) => { 8 * key.curve.key_len() }
+            PublicKey::MlDsa(key) => { key.params.lambda() }
+            PublicKey::MlKem(key) => { key.params.strength() }
+            PublicKey::SlhDsa(key) => { key.params.strength() }
+            PublicKey::Composite(key) => { key.params.mldsa().lambda() }
+        }
+    }
+
+    //
+    // Verify a signature with the given scheme.
+    //
+    pub fn verify(&self, scheme: SignatureScheme, data: &[u8], sig: &[u8]) -> bool
+    {
+        match (self, scheme) {
+            (PublicKey::Rsa(key), SignatureScheme::RsaPkcs1(hash)) => {
+                key.verify_pkcs1(hash, data, sig)
+            }
+            (PublicKey::Rsa(key), SignatureScheme::RsaPss(hash)) => {
+                key.verify_pss(hash, data, sig)
+            }
+            (PublicKey::Ecdsa(key), SignatureScheme::Ecdsa(hash)) => {
+                key.verify(hash, data, sig)
+            }
+            (PublicKey::Eddsa(key), SignatureScheme::Ed25519) |
+            (PublicKey::Eddsa(key), SignatureScheme::Ed448) => {
+                scheme.key_type() == self.key_type() && key.verify(data, sig)
+            }
+            (PublicKey::MlDsa(key), SignatureScheme::MlDsa(params)) => {
+                params == key.params && key.verify(data, sig)
+            }
+            (PublicKey::SlhDsa(key), SignatureScheme::SlhDsa(params)) => {
+                params == key.params && key.verify(data, sig)
+            }
+            (PublicKey::Composite(key), SignatureScheme::Composite(params)) => {
+                params == key.params && key.verify(data, sig)
+            }
+            _ => { false }
+        }
+    }
+
+    //
+    // SHA-1 hash over the SubjectPublicKeyInfo.
+    //
+    pub fn keyid(&self) -> Vec<u8>
+    {
+        return sha1(&self.to_spki()).to_vec();
+    }
+
+    //
+    // SHA-1 hash over the subjectPublicKey bits (RFC 5280, 4.2.1.2).
+    //
+    pub fn subject_key_id(&self) -> Vec<u8>
+    {
+        return sha1(&self.algorithm_and_key().1).to_vec();
+    }
+
+    //
+    // Encode as X.509 SubjectPublicKeyInfo.
+    //
+    pub fn to_spki(&self) -> Vec<u8>
+    {
+        let (algorithm, key) = self.algorithm_and_key();
+        return asn1_sequence(&[algorithm, asn1_bitstring(&key)]);
+    }
+
+    //
+    // Encode in the OpenSSH public key format (RFC 4253, RFC 5656,
+    // RFC 8709).
+    //
+    pub fn to_ssh_key(&self) -> Result<String, String>
+    {
+        fn ssh_string(out: &mut Vec<u8>, data: &[u8])
+        {
+            out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            out.extend_from_slice(data);
+        }
+        fn ssh_mpint(out: &mut Vec<u8>, magnitude: &[u8])
+        {
+            let mut value = magnitude.to_vec();
+            if value.first().is_some_and(|b| b & 0x80 != 0) {
+                value.insert(0, 0);
+            }
+            ssh_string(out, &value);
+        }
+
+        let mut blob = Vec::new();
+        let name = match self {
+            PublicKey::Rsa(key) => {
+                ssh_string(&mut blob, b"ssh-rsa");
+                ssh_mpint(&mut blob, &key.e.to_bytes_be());
+                ssh_mpint(&mut blob, &key.n.to_bytes_be());
+                "ssh-rsa".to_string()
+            }
+            PublicKey::Ecdsa(key) => {
+                let curve = format!("nistp{}", key.curve.bits());
+                let name = format!("ecdsa-sha2-{}", curve);
+                ssh_string(&mut blob, name.as_bytes());
+                ssh_string(&mut blob, curve.as_bytes());
+                ssh_string(&mut blob, &key.point);
+                name
+            }
+            PublicKey::Eddsa(key) => {
+                let name = match key.curve {
+                    EdCurve::Ed25519 => { "ssh-ed25519" }
+                    EdCurve::Ed448   => { "ssh-ed448" }
+                };
+                ssh_string(&mut blob, name.as_bytes());
+                ssh_string(&mut blob, &key.key);
+                name.to_string()
+            }
+            PublicKey::MlDsa(_) | PublicKey::MlKem(_) | PublicKey::SlhDsa(_) |
+            PublicKey::Composite(_) => {
+                return Err(format!("no SSH key format for {} keys", self.key_type()));
+            }
+        };
+        return Ok(format!("{} {}", name, base64_encode(&blob, 0)));
+    }
+
+    //
+    // Encode as DNSKEY public key field (RFC 3110, RFC 6605, RFC 8080).
+    //
+    pub fn to_dnskey(&self) -> Result<Vec<u8>, String>
+    {
+        match self {
+            PublicKey::Rsa(key) => {
+                let e = key.e.to_bytes_be();
+                let mut out = Vec::new();
+                if e.len() < 256 {
+                    out.push(e.len() as u8);
+                } else {
+                    out.push(0);
+                    out.extend_from_slice(&(e.len() as u16).to_be_bytes());
+                }
+                out.extend_from_slice(&e);
+                out.extend_from_slice(&key.n.to_bytes_be());
+                Ok(out)
+            }
+            PublicKey::Ecdsa(key) if key.curve != Curve::P521 => { Ok(key.point[1..].to_vec()) }
+            PublicKey::Eddsa(key) => { Ok(key.key.clone()) }
+            _ => { Err(format!("no DNSKEY format for {} keys", self.key_type())) }
+        }
+    }
+
+    //
+    // AlgorithmIdentifier and subjectPublicKey contents of the key.
+    //
+    fn algorithm_and_key(&self) -> (Vec<u8>, Vec<u8>)
+    {
+        match self {
+            PublicKey::Rsa(key) => {
+                (asn1_algorithm_identifier(OID_RSA_ENCRYPTION, Some(&asn1_null())),
+                 key.to_pkcs1())
+            }
+            PublicKey::Ecdsa(key) => {
+                (asn1_algorithm_identifier(OID_EC_PUBLICKEY, Some(&asn1_oid(key.curve.oid()))),
+                 key.point.clone())
+            }
+            PublicKey::Eddsa(key) => {
+                (asn1_algorithm_identifier(key.curve.oid(), None), key.key.clone())
+            }
+            PublicKey::MlDsa(key) => {
+                (asn1_algorithm_identifier(key.params.oid(), None), key.key.clone())
+            }
+            PublicKey::MlKem(key) => {
+                (asn1_algorithm_identifier(key.params.oid(), None), key.key.clone())
+            }
+            PublicKey::SlhDsa(key) => {
+                (asn1_algorithm_identifier(key.params.oid(), None), key.key.clone())
+            }
+            PublicKey::Composite(key) => {
+                (asn1_algorithm_identifier(key.params.oid(), None), key.to_bytes())
+            }
+        }
+    }
+
+    //
+    // Decode an X.509 SubjectPublicKeyInfo.
+    //
+    pub fn from_spki(der: &[u8]) -> Result<PublicKey, String>
+    {
+        let obj = asn1_parse(der)?;
+        if obj.tag != ASN1_SEQUENCE {
+            return Err("not a SubjectPublicKeyInfo".to_string());
+        }
+        let mut parser = obj.parser();
+        let (oid, params) = parse_algorithm_identifier(&parser.expect(ASN1_SEQUENCE)?)?;
+        let key = parser.expect(ASN1_BIT_STRING)?.to_bitstring_octets()?;
+        parser.finish()?;
+
+        match oid.as_str() {
+            OID_RSA_ENCRYPTION => { Ok(PublicKey::Rsa(RsaPublicKey::from_pkcs1(key)?)) }
+            OID_EC_PUBLICKEY => {
+                let curve = parse_named_curve(params)?;
+                Ok(PublicKey::Ecdsa(EcPublicKey::from_point(curve, key)?))
+            }
+            OID_ED25519 | OID_ED448 => {
+                if params.is_some() {
+                    return Err(format!("invalid parameters for {}", oid_display(&oid)));
+                }
+                let curve = EdCurve::from_oid(&oid).unwrap();
+                Ok(PublicKey::Eddsa(EdPublicKey::from_bytes(curve, key)?))
+            }
+            OID_ML_DSA_44 | OID_ML_DSA_65 | OID_ML_DSA_87 => {
+                if params.is_some() {
+                    return Err(format!("invalid parameters for {}", oid_display(&oid)));
+                }
+                let params = MlDsaParams::from_oid(&oid).unwrap();
+                Ok(PublicKey::MlDsa(MlDsaPublicKey::from_bytes(params, key)?))
+            }
+            OID_ML_KEM_512 | OID_ML_KEM_768 | OID_ML_KEM_1024 => {
+                if params.is_some() {
+                    return Err(format!("invalid parameters for {}", oid_display(&oid)));
+                }
+                let params = MlKemParams::from_oid(&oid).unwrap();
+                Ok(PublicKey::MlKem(MlKemPublicKey::from_bytes(params, key)?))
+            }
+            _ if let Some(slhdsa) = SlhDsaParams::from_oid(&oid) => {
+                if params.is_some() {
+                    return Err(format!("invalid parameters for {}", oid_display(&oid)));
+                }
+                Ok(PublicKey::SlhDsa(SlhDsaPublicKey::from_bytes(slhdsa, key)?))
+            }
+            _ if let Some(composite) = CompositeParams::from_oid(&oid) => {
+                if params.is_some() {
+                    return Err(format!("invalid parameters for {}", oid_display(&oid)));
+                }
+                Ok(PublicKey::Composite(CompositePublicKey::from_bytes(composite, key)?))
+            }
+            _ => { Err(format!("unsupported public key algorithm {}", oid_display(&oid))) }
+        }
+    }
+}
+
+//
+// Split an AlgorithmIdentifier into its OID and optional parameters.
+//
+pub fn parse_algorithm_identifier<'a>(obj: &Asn1Object<'a>)
+                                      -> Result<(String, Option<Asn1Object<'a>>), String>
+{
+    let mut parser = obj.parser();
+    let oid = parser.expect(ASN1_OID)?.to_oid()?;
+    let params = parser.next_object()?;
+    parser.finish()?;
+    return Ok((oid, params));
+}
+
+//
+// Get the curve from namedCurve ECParameters (RFC 5480), explicit curve
+// parameters are not supported.
+//
+fn parse_named_curve(params: Option<Asn1Object>) -> Result<Curve, String>
+{
+    let oid = match params {
+        Some(obj) if obj.tag == ASN1_OID => { obj.to_oid()? }
+        _ => { return Err("EC key without namedCurve parameters".to_string()); }
+    };
+    return Curve::from_oid(&oid).ok_or(format!("unsupported curve {}", oid_display(&oid)));
+}
+
+//
+// Encode an ML-DSA or ML-KEM private key as seed if known, as expanded key
+// otherwise.
+//
+fn encode_seed_or_expanded(seed: &Option<Vec<u8>>, expanded: &[u8]) -> Vec<u8>
+{
+    match seed {
+        Some(seed) => { asn1_simple_object(ASN1_CONTEXT_S_0, seed) }
+        None       => { asn1_octet_string(expanded) }
+    }
+}
+
+/// Seed and expanded key found in a private key encoding
+type SeedAndExpanded<'a> = (Option<&'a [u8]>, Option<&'a [u8]>);
+
+//
+// Decode an ML-DSA or ML-KEM private key in seed, expandedKey or both
+// format, returning the seed and expanded key found.
+//
+fn parse_seed_or_expanded(der: &[u8]) -> Result<SeedAndExpanded<'_>, String>
+{
+    let obj = asn1_parse(der)?;
+    match obj.tag {
+        ASN1_CONTEXT_S_0 => { Ok((Some(obj.content), None)) }
+        ASN1_OCTET_STRING => { Ok((None, Some(obj.content))) }
+        ASN1_SEQUENCE => {
+            let mut parser = obj.parser();
+            let seed = parser.expect(ASN1_OCTET_STRING)?;
+            let expanded = parser.expect(ASN1_OCTET_STRING)?;
+            parser.finish()?;
+            Ok((Some(seed.content), Some(expanded.content)))
+        }
+        _ => { Err("unsupported private key format".to_string()) }
+    }
+}
+
+//
+// Check that an expanded key given along with the seed matches the key
+// derived from it.
+//
+fn check_expanded(derived: &[u8], expanded: Option<&[u8]>) -> Result<(), String>
+{
+    match expanded {
+        Some(expanded) if expanded != derived => {
+            Err("expanded private key does not match seed".to_string())
+        }
+        _ => { Ok(()) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex;
+
+    fn keys() -> Vec<PrivateKey>
+    {
+        return vec![
+            PrivateKey::Rsa(RsaPrivateKey::generate(1024, false).unwrap()),
+            PrivateKey::Ecdsa(EcPrivateKey::generate(Curve::P256)),
+            PrivateKey::Ecdsa(EcPrivateKey::generate(Curve::P384)),
+            PrivateKey::Eddsa(EdPrivateKey::generate(EdCurve::Ed25519)),
+            PrivateKey::Eddsa(EdPrivateKey::generate(EdCurve::Ed448)),
+            PrivateKey::MlDsa(MlDsaPrivateKey::generate(MlDsaParams::MlDsa44)),
+            PrivateKey::MlKem(MlKemPrivateKey::generate(MlKemParams::MlKem512)),
+            PrivateKey::SlhDsa(SlhDsaPrivateKey::generate(SlhDsaParams::Sha2_128f)),
+            PrivateKey::Composite(CompositePrivateKey::generate(CompositeParams::MlDsa44Ed25519)
+                                      .unwrap()),
+        ];
+    }
+
+    #[test]
+    fn private_key_encoding()
+    {
+        for key in keys() {
+            let public = key.public_key();
+            let (der, _) = key.to_der().unwrap();
+            let decoded = PrivateKey::from_der(&der).unwrap();
+            assert_eq!(decoded.key_type(), key.key_type());
+            assert_eq!(decoded.public_key(), public);
+            assert_eq!(decoded.to_der().unwrap().0, der);
+
+            let pkcs8 = key.to_pkcs8().unwrap();
+            let decoded = PrivateKey::from_pkcs8(&pkcs8).unwrap();
+            assert_eq!(decoded.public_key(), public);
+            assert_eq!(decoded.to_pkcs8().unwrap(), pkcs8);
+            assert!(PrivateKey::from_der(&pkcs8[..pkcs8.len() - 1]).is_err());
+
+            let spki = public.to_spki();
+            assert_eq!(PublicKey::from_spki(&spki).unwrap(), public);
+            assert_eq!(public.bits(), key.bits());
+        }
+    }
+
+    #[test]
+    fn signatures()
+    {
+        for key in keys() {
+            if let PrivateKey::MlKem(_) = key {
+                continue;
+            }
+            let scheme = SignatureScheme::select(key.key_type(), key.bits(), None, false).unwrap();
+            let sig = key.sign(scheme, b"data").unwrap();
+            assert!(key.public_key().verify(scheme, b"data", &sig));
+            assert!(!key.public_key().verify(scheme, b"Data", &sig));
+        }
+    }
+
+    // RFC 8410, section 10.3
+    #[test]
+    fn ed25519_pkcs8()
+    {
+        let der = hex("302e020100300506032b657004220420
+                       d4ee72dbf913584ad5b6d8f1f769f8ad3afe7c28cbf1d4fbe097a88f44755842");
+        let key = PrivateKey::from_der(&der).unwrap();
+        assert_eq!(key.key_type(), KeyType::Ed25519);
+        assert_eq!(key.to_pkcs8().unwrap(), der);
+        assert_eq!(key.public_key().to_spki(),
+                   hex("302a300506032b6570032100
+                        19bf44096984cdfe8541bac167dc3b96c85086aa30b6b6cb0c5c38ad703166e1"));
+    }
+
+    #[test]
+    fn seed_and_expanded_encoding()
+    {
+        let key = MlDsaPrivateKey::from_seed(MlDsaParams::MlDsa44, &[7; 32]).unwrap();
+        let encode = |expanded: &[u8]| {
+            let both = asn1_sequence(&[asn1_octet_string(&[7; 32]), asn1_octet_string(expanded)]);
+            asn1_sequence(&[asn1_integer_from_u64(0),
+                            asn1_algorithm_identifier(OID_ML_DSA_44, None),
+                            asn1_octet_string(&both)])
+        };
+        let decoded = PrivateKey::from_pkcs8(&encode(&key.expanded)).unwrap();
+        assert_eq!(decoded.public_key(), PublicKey::MlDsa(key.public_key()));
+
+        let mut expanded = key.expanded.clone();
+        expanded[40] ^= 1;
+        assert_eq!(PrivateKey::from_pkcs8(&encode(&expanded)).unwrap_err(),
+                   "expanded private key does not match seed");
+
+        // an expanded-only key is kept in that format
+        let expanded = PrivateKey::MlDsa(MlDsaPrivateKey::from_expanded(MlDsaParams::MlDsa44,
+                                                                        &key.expanded).unwrap());
+        let decoded = PrivateKey::from_pkcs8(&expanded.to_pkcs8().unwrap()).unwrap();
+        assert_eq!(decoded.to_pkcs8().unwrap(), expanded.to_pkcs8().unwrap());
+    }
+}
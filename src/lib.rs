@@ -10,11 +10,49 @@
 // or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
 // for more details.
 
+#![allow(clippy::needless_return)]
+
 use std::process::ExitCode;
 use getopts::Matches;
 
+pub mod addrblock;
+pub mod asn1;
+pub mod cms;
+pub mod crl;
+pub mod crypto;
+pub mod ct;
+pub mod dn;
+pub mod extensions;
+pub mod general_name;
+pub mod idna;
+pub mod keys;
+pub mod name_constraints;
+pub mod oid;
+pub mod pem;
+pub mod pkcs10;
+pub mod policy;
+pub mod profile;
+pub mod signature;
+pub mod template;
+pub mod threshold;
+pub mod validity;
+pub mod verify;
+pub mod x509;
+
 pub const X509_NO_CONSTRAINT: u32 = 255;
 
+//
+// Decode a hex encoded test vector, ignoring any whitespace.
+//
+#[cfg(test)]
+pub(crate) fn hex(data: &str) -> Vec<u8>
+{
+    let digits: Vec<u8> = data.bytes().filter(|c| !c.is_ascii_whitespace()).collect();
+    return digits.chunks(2)
+                 .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).unwrap(), 16).unwrap())
+                 .collect();
+}
+
 type Op = fn(&Matches) -> ExitCode;
 
 pub struct Opt {
@@ -10,10 +10,13 @@
 // or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
 // for more details.
 
+#![allow(clippy::needless_return)]
+
 use std::env;
 use std::process::ExitCode;
 use getopts::Options;
 use pki::Command;
+use pki::profile::profile_args;
 
 pub mod pki_acert;
 pub mod pki_dn;
@@ -23,6 +26,7 @@ pub mod pki_gen;
 pub mod pki_issue;
 pub mod pki_keyid;
 pub mod pki_ocsp;
+pub mod pki_partial;
 pub mod pki_pkcs12;
 pub mod pki_pkcs7;
 pub mod pki_print;
@@ -42,7 +46,7 @@ fn usage()
     println!("usage:");
     println!("  pki command [options]");
     println!("commands:");
-    println!("  --{:7} (-{})  {}", "help", "h", "show usage information");
+    println!("  --{:7} (-h)  show usage information", "help");
     for cmd in inventory::iter::<Command> {
         println!("  --{:7} (-{})  {}", cmd.long, cmd.short, cmd.descr);
     }
@@ -95,7 +99,7 @@ fn main() -> ExitCode
     let matches = match opts.parse(&command_arg) {
         Ok(m)  => { m }
         Err(f) => {
-            println!("{}", f.to_string());
+            println!("{}", f);
             usage();
             return ExitCode::FAILURE;
         }
@@ -122,15 +126,37 @@ fn main() -> ExitCode
             }
 
             // parse command line arguments for specific pki command
-            let cmd_matches = match cmd_opts.parse(&args[2..]) {
+            let mut cmd_matches = match cmd_opts.parse(&args[2..]) {
                 Ok(m)  => { m }
                 Err(f) => {
-                    println!("{}", f.to_string());
+                    println!("{}", f);
                     cmd_usage(cmd);
                     return  ExitCode::FAILURE;
                 }
             };
 
+            // prepend the options of a certificate profile not given on the command line
+            if cmd.options.iter().any(|o| o.long == "profile-file") {
+                let mut cmd_args = match profile_args(cmd.options, &cmd_matches) {
+                    Ok(a)  => { a }
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        return ExitCode::FAILURE;
+                    }
+                };
+                if !cmd_args.is_empty() {
+                    cmd_args.extend_from_slice(&args[2..]);
+                    cmd_matches = match cmd_opts.parse(&cmd_args) {
+                        Ok(m)  => { m }
+                        Err(f) => {
+                            println!("{}", f);
+                            cmd_usage(cmd);
+                            return  ExitCode::FAILURE;
+                        }
+                    };
+                }
+            }
+
             if cmd_matches.opt_present("h") {
                 cmd_usage(cmd);
                 return ExitCode::SUCCESS;
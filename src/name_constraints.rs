@@ -0,0 +1,397 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! Name constraint processing (RFC 5280, 4.2.1.10).  Names are kept as
+//! GeneralName tag and content octets, the content of a directoryName being
+//! the DER encoded Name.  The dNSName, rfc822Name, uniformResourceIdentifier,
+//! iPAddress and directoryName forms are supported, names of other forms
+//! are rejected if the constraints restrict their form.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use crate::asn1::*;
+use crate::dn::{Attribute, Dn};
+use crate::oid::*;
+use crate::x509::Extension;
+
+/// A GeneralName as tag and content octets
+pub type GeneralName = (u8, Vec<u8>);
+
+/// Decoded permitted and excluded subtrees of a nameConstraints extension
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NameConstraints {
+    /// bases of the permitted subtrees
+    pub permitted: Vec<GeneralName>,
+    /// bases of the excluded subtrees
+    pub excluded: Vec<GeneralName>,
+}
+
+//
+// Format a GeneralName for diagnostics.
+//
+pub fn general_name_string(name: &GeneralName) -> String
+{
+    let (tag, value) = name;
+    let text = || String::from_utf8_lossy(value).to_string();
+    match *tag {
+        ASN1_CONTEXT_S_1 => { format!("email:{}", text()) }
+        ASN1_CONTEXT_S_2 => { format!("dns:{}", text()) }
+        ASN1_CONTEXT_S_6 => { format!("uri:{}", text()) }
+        ASN1_CONTEXT_C_4 => {
+            match Dn::from_der(value) {
+                Ok(dn) => { format!("dn:{}", dn) }
+                Err(_) => { "dn:(invalid)".to_string() }
+            }
+        }
+        ASN1_CONTEXT_S_7 => {
+            let addr = |octets: &[u8]| -> String {
+                match octets.len() {
+                    4 => { Ipv4Addr::from(<[u8; 4]>::try_from(octets).unwrap()).to_string() }
+                    16 => { Ipv6Addr::from(<[u8; 16]>::try_from(octets).unwrap()).to_string() }
+                    _ => { "(invalid)".to_string() }
+                }
+            };
+            match value.len() {
+                8 | 32 => {
+                    let (net, mask) = value.split_at(value.len() / 2);
+                    let prefix: u32 = mask.iter().map(|b| b.count_ones()).sum();
+                    format!("ip:{}/{}", addr(net), prefix)
+                }
+                _ => { format!("ip:{}", addr(value)) }
+            }
+        }
+        tag => { format!("[{}] name", tag & 0x1F) }
+    }
+}
+
+//
+// Check if a dNSName is the base domain or one of its subdomains, a
+// leading '.' in the base matching subdomains only.
+//
+fn dns_matches(name: &[u8], base: &[u8]) -> bool
+{
+    let name = String::from_utf8_lossy(name).to_ascii_lowercase();
+    let base = String::from_utf8_lossy(base).to_ascii_lowercase();
+    if base.is_empty() {
+        return true;
+    }
+    if base.starts_with('.') {
+        return name.ends_with(&base);
+    }
+    return name == base || name.ends_with(&format!(".{}", base));
+}
+
+//
+// Check if an rfc822Name matches a mailbox, a host or a domain base.
+//
+fn email_matches(name: &[u8], base: &[u8]) -> bool
+{
+    let name = String::from_utf8_lossy(name);
+    let base = String::from_utf8_lossy(base);
+    let (local, domain) = match name.rsplit_once('@') {
+        Some(parts) => { parts }
+        None => { return false; }
+    };
+    if let Some((base_local, base_domain)) = base.rsplit_once('@') {
+        return local == base_local && domain.eq_ignore_ascii_case(base_domain);
+    }
+    if base.starts_with('.') {
+        return domain.to_ascii_lowercase().ends_with(&base.to_ascii_lowercase());
+    }
+    return domain.eq_ignore_ascii_case(&base);
+}
+
+//
+// Extract the host part of a URI, if it has an authority component.
+//
+fn uri_host(uri: &str) -> Option<&str>
+{
+    let (_, rest) = uri.split_once("://")?;
+    let authority = &rest[..rest.find(['/', '?', '#']).unwrap_or(rest.len())];
+    let hostport = authority.rsplit_once('@').map(|(_, h)| h).unwrap_or(authority);
+    if let Some(literal) = hostport.strip_prefix('[') {
+        return literal.split_once(']').map(|(host, _)| host);
+    }
+    let host = hostport.split_once(':').map(|(host, _)| host).unwrap_or(hostport);
+    return if host.is_empty() { None } else { Some(host) };
+}
+
+//
+// Check if the host of a URI matches a host or domain base.
+//
+fn uri_matches(name: &[u8], base: &[u8]) -> bool
+{
+    let name = String::from_utf8_lossy(name);
+    let base = String::from_utf8_lossy(base);
+    let host = match uri_host(&name) {
+        Some(host) => { host.to_ascii_lowercase() }
+        None => { return false; }
+    };
+    if base.starts_with('.') {
+        return host.ends_with(&base.to_ascii_lowercase());
+    }
+    return host.eq_ignore_ascii_case(&base);
+}
+
+//
+// Check if an iPAddress lies within an address and netmask base.
+//
+fn ip_matches(name: &[u8], base: &[u8]) -> bool
+{
+    if base.len() != 2 * name.len() {
+        return false;
+    }
+    let (net, mask) = base.split_at(name.len());
+    return name.iter().zip(net).zip(mask).all(|((n, a), m)| n & m == a & m);
+}
+
+//
+// Compare attribute values, comparing strings case-insensitively with
+// insignificant whitespace removed.
+//
+fn attribute_matches(a: &Attribute, b: &Attribute) -> bool
+{
+    if a.oid != b.oid {
+        return false;
+    }
+    if a.value == b.value {
+        return true;
+    }
+    let normalize = |value: &[u8]| -> Option<String> {
+        let string = asn1_parse(value).ok()?.to_string().ok()?;
+        return Some(string.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase());
+    };
+    return matches!((normalize(&a.value), normalize(&b.value)), (Some(x), Some(y)) if x == y);
+}
+
+//
+// Check if a directoryName starts with the RDNs of the base.
+//
+fn dn_matches(name: &[u8], base: &[u8]) -> bool
+{
+    let (name, base) = match (Dn::from_der(name), Dn::from_der(base)) {
+        (Ok(name), Ok(base)) => { (name, base) }
+        _ => { return false; }
+    };
+    if base.rdns.len() > name.rdns.len() {
+        return false;
+    }
+    return base.rdns.iter().zip(&name.rdns).all(|(b, n)| {
+        b.len() == n.len() && b.iter().all(|attr| n.iter().any(|a| attribute_matches(attr, a)))
+    });
+}
+
+//
+// Check if a name lies within the subtree of a base of the same form.
+//
+fn name_matches(tag: u8, name: &[u8], base: &[u8]) -> bool
+{
+    match tag {
+        ASN1_CONTEXT_S_1 => { email_matches(name, base) }
+        ASN1_CONTEXT_S_2 => { dns_matches(name, base) }
+        ASN1_CONTEXT_C_4 => { dn_matches(name, base) }
+        ASN1_CONTEXT_S_6 => { uri_matches(name, base) }
+        ASN1_CONTEXT_S_7 => { ip_matches(name, base) }
+        _ => { false }
+    }
+}
+
+//
+// Decode a sequence of GeneralSubtrees into their bases.
+//
+fn parse_subtrees(obj: &Asn1Object) -> Result<Vec<GeneralName>, String>
+{
+    let mut bases = Vec::new();
+    let mut parser = obj.parser();
+    while let Some(subtree) = parser.next_object()? {
+        if subtree.tag != ASN1_SEQUENCE {
+            return Err("invalid GeneralSubtree".to_string());
+        }
+        let mut p = subtree.parser();
+        let base = p.next_object()?.ok_or("missing GeneralSubtree base")?;
+        if let Some(minimum) = p.optional(ASN1_CONTEXT_S_0)? && minimum.content != [0] {
+            return Err("GeneralSubtree minimum must be zero".to_string());
+        }
+        if p.optional(ASN1_CONTEXT_S_1)?.is_some() {
+            return Err("GeneralSubtree maximum must be absent".to_string());
+        }
+        p.finish()?;
+        bases.push((base.tag, base.content.to_vec()));
+    }
+    if bases.is_empty() {
+        return Err("empty GeneralSubtrees".to_string());
+    }
+    return Ok(bases);
+}
+
+//
+// Collect the names of a certificate subject subject to name constraints:
+// a non-empty subject, the subjectAltNames and, if there are none, the
+// legacy emailAddress attributes of the subject.
+//
+pub fn subject_names(subject: &[u8], extensions: &[Extension]) -> Result<Vec<GeneralName>, String>
+{
+    let mut names: Vec<GeneralName> = Vec::new();
+    let dn = Dn::from_der(subject)?;
+    if !dn.rdns.is_empty() {
+        names.push((ASN1_CONTEXT_C_4, subject.to_vec()));
+    }
+    match extensions.iter().find(|e| e.oid == OID_SUBJECT_ALT_NAME) {
+        Some(ext) => {
+            let mut parser = Asn1Parser::new(&ext.value).expect(ASN1_SEQUENCE)?.parser();
+            while let Some(name) = parser.next_object()? {
+                names.push((name.tag, name.content.to_vec()));
+            }
+        }
+        None => {
+            for attr in dn.rdns.iter().flatten().filter(|a| a.oid == OID_EMAIL_ADDRESS) {
+                let email = asn1_parse(&attr.value)?.to_string()?;
+                names.push((ASN1_CONTEXT_S_1, email.into_bytes()));
+            }
+        }
+    }
+    return Ok(names);
+}
+
+impl NameConstraints {
+    //
+    // Decode the value of a nameConstraints extension.
+    //
+    pub fn from_der(der: &[u8]) -> Result<NameConstraints, String>
+    {
+        let mut parser = Asn1Parser::new(der).expect(ASN1_SEQUENCE)?.parser();
+        let permitted = match parser.optional(ASN1_CONTEXT_C_0)? {
+            Some(obj) => { parse_subtrees(&obj)? }
+            None => { Vec::new() }
+        };
+        let excluded = match parser.optional(ASN1_CONTEXT_C_1)? {
+            Some(obj) => { parse_subtrees(&obj)? }
+            None => { Vec::new() }
+        };
+        parser.finish()?;
+        if permitted.is_empty() && excluded.is_empty() {
+            return Err("empty nameConstraints".to_string());
+        }
+        return Ok(NameConstraints { permitted, excluded });
+    }
+
+    //
+    // Check names against the constraints: every name must lie within one
+    // of the permitted subtrees of its form, if there are any, and within
+    // none of the excluded subtrees.
+    //
+    pub fn check(&self, names: &[GeneralName]) -> Result<(), String>
+    {
+        for name in names {
+            let (tag, value) = name;
+            let permitted: Vec<&GeneralName> = self.permitted.iter().filter(|(t, _)| t == tag).collect();
+            let excluded: Vec<&GeneralName> = self.excluded.iter().filter(|(t, _)| t == tag).collect();
+            if !matches!(*tag, ASN1_CONTEXT_S_1 | ASN1_CONTEXT_S_2 | ASN1_CONTEXT_C_4 |
+                               ASN1_CONTEXT_S_6 | ASN1_CONTEXT_S_7) {
+                if !permitted.is_empty() || !excluded.is_empty() {
+                    return Err(format!("name constraints on the form of {} are not supported",
+                                       general_name_string(name)));
+                }
+                continue;
+            }
+            if !permitted.is_empty() && !permitted.iter().any(|(_, base)| name_matches(*tag, value, base)) {
+                return Err(format!("{} is not within the permitted subtrees",
+                                   general_name_string(name)));
+            }
+            if let Some(base) = excluded.iter().find(|(_, base)| name_matches(*tag, value, base)) {
+                return Err(format!("{} is within the excluded subtree {}",
+                                   general_name_string(name), general_name_string(base)));
+            }
+        }
+        return Ok(());
+    }
+}
+
+//
+// Convert a contiguous netmask into its prefix length.
+//
+pub fn netmask_prefix(mask: &IpAddr) -> Option<usize>
+{
+    let octets = match mask {
+        IpAddr::V4(a) => { a.octets().to_vec() }
+        IpAddr::V6(a) => { a.octets().to_vec() }
+    };
+    let prefix: usize = octets.iter().map(|b| b.count_ones() as usize).sum();
+    let contiguous = octets.iter().enumerate().all(|(i, b)| {
+        let ones = prefix.saturating_sub(8 * i).min(8);
+        *b == (0xFF00u16 >> ones) as u8
+    });
+    return if contiguous { Some(prefix) } else { None };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extensions::{name_constraints, subject_alt_name};
+
+    fn constraints(permitted: &[&str], excluded: &[&str]) -> NameConstraints
+    {
+        let strings = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        let ext = name_constraints(&strings(permitted), &strings(excluded)).unwrap();
+        return NameConstraints::from_der(&ext.value).unwrap();
+    }
+
+    fn names(subject: &str, alt_names: &[&str]) -> Vec<GeneralName>
+    {
+        let mut extensions = Vec::new();
+        if !alt_names.is_empty() {
+            let alt_names: Vec<String> = alt_names.iter().map(|n| n.to_string()).collect();
+            extensions.push(subject_alt_name(&alt_names).unwrap());
+        }
+        return subject_names(&Dn::parse(subject).unwrap().to_der(), &extensions).unwrap();
+    }
+
+    #[test]
+    fn dns_subtrees()
+    {
+        let nc = constraints(&["example.com"], &["secret.example.com"]);
+        assert!(nc.check(&names("CN=host", &["example.com", "www.example.com"])).is_ok());
+        assert!(nc.check(&names("CN=host", &["wwwexample.com"])).is_err());
+        assert!(nc.check(&names("CN=host", &["example.org"])).is_err());
+        assert!(nc.check(&names("CN=host", &["a.secret.example.com"])).is_err());
+    }
+
+    #[test]
+    fn uri_subtrees()
+    {
+        let nc = constraints(&["https://example.com", "uri:.example.net"], &[]);
+        assert!(nc.check(&names("CN=host", &["https://example.com/path"])).is_ok());
+        assert!(nc.check(&names("CN=host", &["http://user@www.example.net:8080/"])).is_ok());
+        assert!(nc.check(&names("CN=host", &["https://www.example.com/"])).is_err());
+        assert!(nc.check(&names("CN=host", &["https://example.net/"])).is_err());
+    }
+
+    #[test]
+    fn ip_subtrees()
+    {
+        let nc = constraints(&["10.0.0.0/8", "fec0::/16"], &["10.1.0.0/255.255.0.0"]);
+        assert!(nc.check(&names("CN=host", &["10.2.3.4", "fec0::1"])).is_ok());
+        assert!(nc.check(&names("CN=host", &["10.1.3.4"])).is_err());
+        assert!(nc.check(&names("CN=host", &["192.168.0.1"])).is_err());
+        assert!(nc.check(&names("CN=host", &["fec1::1"])).is_err());
+    }
+
+    #[test]
+    fn directory_name_and_email_subtrees()
+    {
+        let nc = constraints(&["dn:C=CH, O=strongSwan"], &["email:.example.org"]);
+        assert!(nc.check(&names("C=CH, O=strongSwan, CN=host", &[])).is_ok());
+        assert!(nc.check(&names("C=CH, O=Other, CN=host", &[])).is_err());
+        assert!(nc.check(&names("C=CH, O=strongSwan, CN=host", &["user@mail.example.org"])).is_err());
+        assert!(nc.check(&names("C=CH, O=strongSwan, CN=host, E=user@mail.example.org", &[])).is_err());
+        assert!(nc.check(&names("C=CH, O=strongSwan, CN=host", &["user@example.org"])).is_ok());
+    }
+}
@@ -0,0 +1,362 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! Well-known object identifiers in dotted notation.
+
+// X.500 attribute types
+pub const OID_COMMON_NAME: &str              = "2.5.4.3";
+pub const OID_SURNAME: &str                  = "2.5.4.4";
+pub const OID_SERIAL_NUMBER: &str            = "2.5.4.5";
+pub const OID_COUNTRY: &str                  = "2.5.4.6";
+pub const OID_LOCALITY: &str                 = "2.5.4.7";
+pub const OID_STATE_OR_PROVINCE: &str        = "2.5.4.8";
+pub const OID_STREET_ADDRESS: &str           = "2.5.4.9";
+pub const OID_ORGANIZATION: &str             = "2.5.4.10";
+pub const OID_ORGANIZATION_UNIT: &str        = "2.5.4.11";
+pub const OID_TITLE: &str                    = "2.5.4.12";
+pub const OID_DESCRIPTION: &str              = "2.5.4.13";
+pub const OID_POSTAL_CODE: &str              = "2.5.4.17";
+pub const OID_NAME: &str                     = "2.5.4.41";
+pub const OID_GIVEN_NAME: &str               = "2.5.4.42";
+pub const OID_INITIALS: &str                 = "2.5.4.43";
+pub const OID_GENERATION_QUALIFIER: &str     = "2.5.4.44";
+pub const OID_UNIQUE_IDENTIFIER: &str        = "2.5.4.45";
+pub const OID_DN_QUALIFIER: &str             = "2.5.4.46";
+pub const OID_PSEUDONYM: &str                = "2.5.4.65";
+pub const OID_ROLE: &str                     = "2.5.4.72";
+pub const OID_ORGANIZATION_IDENTIFIER: &str  = "2.5.4.97";
+pub const OID_EMAIL_ADDRESS: &str            = "1.2.840.113549.1.9.1";
+pub const OID_UNSTRUCTURED_NAME: &str        = "1.2.840.113549.1.9.2";
+pub const OID_USER_ID: &str                  = "0.9.2342.19200300.100.1.1";
+pub const OID_DOMAIN_COMPONENT: &str         = "0.9.2342.19200300.100.1.25";
+
+// X.509v3 certificate and CRL extensions
+pub const OID_SUBJECT_KEY_ID: &str           = "2.5.29.14";
+pub const OID_KEY_USAGE: &str                = "2.5.29.15";
+pub const OID_SUBJECT_ALT_NAME: &str         = "2.5.29.17";
+pub const OID_ISSUER_ALT_NAME: &str          = "2.5.29.18";
+pub const OID_BASIC_CONSTRAINTS: &str        = "2.5.29.19";
+pub const OID_CRL_NUMBER: &str               = "2.5.29.20";
+pub const OID_CRL_REASON_CODE: &str          = "2.5.29.21";
+pub const OID_INVALIDITY_DATE: &str          = "2.5.29.24";
+pub const OID_DELTA_CRL_INDICATOR: &str      = "2.5.29.27";
+pub const OID_ISSUING_DIST_POINT: &str       = "2.5.29.28";
+pub const OID_NAME_CONSTRAINTS: &str         = "2.5.29.30";
+pub const OID_CRL_DISTRIBUTION_POINTS: &str  = "2.5.29.31";
+pub const OID_CERTIFICATE_POLICIES: &str     = "2.5.29.32";
+pub const OID_ANY_POLICY: &str               = "2.5.29.32.0";
+pub const OID_POLICY_MAPPINGS: &str          = "2.5.29.33";
+pub const OID_AUTHORITY_KEY_ID: &str         = "2.5.29.35";
+pub const OID_POLICY_CONSTRAINTS: &str       = "2.5.29.36";
+pub const OID_EXTENDED_KEY_USAGE: &str       = "2.5.29.37";
+pub const OID_FRESHEST_CRL: &str             = "2.5.29.46";
+pub const OID_INHIBIT_ANY_POLICY: &str       = "2.5.29.54";
+pub const OID_AUTHORITY_INFO_ACCESS: &str    = "1.3.6.1.5.5.7.1.1";
+pub const OID_IP_ADDR_BLOCKS: &str           = "1.3.6.1.5.5.7.1.7";
+pub const OID_CT_PRECERT_POISON: &str        = "1.3.6.1.4.1.11129.2.4.3";
+pub const OID_CT_SCT_LIST: &str              = "1.3.6.1.4.1.11129.2.4.2";
+
+// PKIX access methods, qualifiers and extended key usages
+pub const OID_OCSP: &str                     = "1.3.6.1.5.5.7.48.1";
+pub const OID_CA_ISSUERS: &str               = "1.3.6.1.5.5.7.48.2";
+pub const OID_CPS: &str                      = "1.3.6.1.5.5.7.2.1";
+pub const OID_USER_NOTICE: &str              = "1.3.6.1.5.5.7.2.2";
+pub const OID_SERVER_AUTH: &str              = "1.3.6.1.5.5.7.3.1";
+pub const OID_CLIENT_AUTH: &str              = "1.3.6.1.5.5.7.3.2";
+pub const OID_CODE_SIGNING: &str             = "1.3.6.1.5.5.7.3.3";
+pub const OID_EMAIL_PROTECTION: &str         = "1.3.6.1.5.5.7.3.4";
+pub const OID_TIME_STAMPING: &str            = "1.3.6.1.5.5.7.3.8";
+pub const OID_OCSP_SIGNING: &str             = "1.3.6.1.5.5.7.3.9";
+pub const OID_IKE_INTERMEDIATE: &str         = "1.3.6.1.5.5.8.2.2";
+pub const OID_MS_SMARTCARD_LOGON: &str       = "1.3.6.1.4.1.311.20.2.2";
+pub const OID_XMPP_ADDR: &str                = "1.3.6.1.5.5.7.8.5";
+pub const OID_MS_UPN: &str                   = "1.3.6.1.4.1.311.20.2.3";
+
+// hash algorithms
+pub const OID_MD5: &str                      = "1.2.840.113549.2.5";
+pub const OID_SHA1: &str                     = "1.3.14.3.2.26";
+pub const OID_SHA224: &str                   = "2.16.840.1.101.3.4.2.4";
+pub const OID_SHA256: &str                   = "2.16.840.1.101.3.4.2.1";
+pub const OID_SHA384: &str                   = "2.16.840.1.101.3.4.2.2";
+pub const OID_SHA512: &str                   = "2.16.840.1.101.3.4.2.3";
+pub const OID_SHA3_224: &str                 = "2.16.840.1.101.3.4.2.7";
+pub const OID_SHA3_256: &str                 = "2.16.840.1.101.3.4.2.8";
+pub const OID_SHA3_384: &str                 = "2.16.840.1.101.3.4.2.9";
+pub const OID_SHA3_512: &str                 = "2.16.840.1.101.3.4.2.10";
+pub const OID_SHAKE128: &str                 = "2.16.840.1.101.3.4.2.11";
+pub const OID_SHAKE256: &str                 = "2.16.840.1.101.3.4.2.12";
+
+// public key algorithms
+pub const OID_RSA_ENCRYPTION: &str           = "1.2.840.113549.1.1.1";
+pub const OID_EC_PUBLICKEY: &str             = "1.2.840.10045.2.1";
+pub const OID_PRIME256V1: &str               = "1.2.840.10045.3.1.7";
+pub const OID_SECP384R1: &str                = "1.3.132.0.34";
+pub const OID_SECP521R1: &str                = "1.3.132.0.35";
+pub const OID_ED25519: &str                  = "1.3.101.112";
+pub const OID_ED448: &str                    = "1.3.101.113";
+pub const OID_ML_DSA_44: &str                = "2.16.840.1.101.3.4.3.17";
+pub const OID_ML_DSA_65: &str                = "2.16.840.1.101.3.4.3.18";
+pub const OID_ML_DSA_87: &str                = "2.16.840.1.101.3.4.3.19";
+pub const OID_MLDSA44_RSA2048_PSS: &str      = "1.3.6.1.5.5.7.6.37";
+pub const OID_MLDSA44_RSA2048_PKCS15: &str   = "1.3.6.1.5.5.7.6.38";
+pub const OID_MLDSA44_ED25519: &str          = "1.3.6.1.5.5.7.6.39";
+pub const OID_MLDSA44_ECDSA_P256: &str       = "1.3.6.1.5.5.7.6.40";
+pub const OID_MLDSA65_RSA3072_PSS: &str      = "1.3.6.1.5.5.7.6.41";
+pub const OID_MLDSA65_RSA3072_PKCS15: &str   = "1.3.6.1.5.5.7.6.42";
+pub const OID_MLDSA65_RSA4096_PSS: &str      = "1.3.6.1.5.5.7.6.43";
+pub const OID_MLDSA65_RSA4096_PKCS15: &str   = "1.3.6.1.5.5.7.6.44";
+pub const OID_MLDSA65_ECDSA_P256: &str       = "1.3.6.1.5.5.7.6.45";
+pub const OID_MLDSA65_ECDSA_P384: &str       = "1.3.6.1.5.5.7.6.46";
+pub const OID_MLDSA65_ED25519: &str          = "1.3.6.1.5.5.7.6.48";
+pub const OID_MLDSA87_ECDSA_P384: &str       = "1.3.6.1.5.5.7.6.49";
+pub const OID_MLDSA87_ED448: &str            = "1.3.6.1.5.5.7.6.51";
+pub const OID_MLDSA87_RSA3072_PSS: &str      = "1.3.6.1.5.5.7.6.52";
+pub const OID_MLDSA87_RSA4096_PSS: &str      = "1.3.6.1.5.5.7.6.53";
+pub const OID_MLDSA87_ECDSA_P521: &str       = "1.3.6.1.5.5.7.6.54";
+pub const OID_SLH_DSA_SHA2_128S: &str        = "2.16.840.1.101.3.4.3.20";
+pub const OID_SLH_DSA_SHA2_128F: &str        = "2.16.840.1.101.3.4.3.21";
+pub const OID_SLH_DSA_SHA2_192S: &str        = "2.16.840.1.101.3.4.3.22";
+pub const OID_SLH_DSA_SHA2_192F: &str        = "2.16.840.1.101.3.4.3.23";
+pub const OID_SLH_DSA_SHA2_256S: &str        = "2.16.840.1.101.3.4.3.24";
+pub const OID_SLH_DSA_SHA2_256F: &str        = "2.16.840.1.101.3.4.3.25";
+pub const OID_SLH_DSA_SHAKE_128S: &str       = "2.16.840.1.101.3.4.3.26";
+pub const OID_SLH_DSA_SHAKE_128F: &str       = "2.16.840.1.101.3.4.3.27";
+pub const OID_SLH_DSA_SHAKE_192S: &str       = "2.16.840.1.101.3.4.3.28";
+pub const OID_SLH_DSA_SHAKE_192F: &str       = "2.16.840.1.101.3.4.3.29";
+pub const OID_SLH_DSA_SHAKE_256S: &str       = "2.16.840.1.101.3.4.3.30";
+pub const OID_SLH_DSA_SHAKE_256F: &str       = "2.16.840.1.101.3.4.3.31";
+pub const OID_ML_KEM_512: &str               = "2.16.840.1.101.3.4.4.1";
+pub const OID_ML_KEM_768: &str               = "2.16.840.1.101.3.4.4.2";
+pub const OID_ML_KEM_1024: &str              = "2.16.840.1.101.3.4.4.3";
+
+// signature algorithms
+pub const OID_MD5_WITH_RSA: &str             = "1.2.840.113549.1.1.4";
+pub const OID_SHA1_WITH_RSA: &str            = "1.2.840.113549.1.1.5";
+pub const OID_RSASSA_PSS: &str               = "1.2.840.113549.1.1.10";
+pub const OID_MGF1: &str                     = "1.2.840.113549.1.1.8";
+pub const OID_SHA256_WITH_RSA: &str          = "1.2.840.113549.1.1.11";
+pub const OID_SHA384_WITH_RSA: &str          = "1.2.840.113549.1.1.12";
+pub const OID_SHA512_WITH_RSA: &str          = "1.2.840.113549.1.1.13";
+pub const OID_SHA224_WITH_RSA: &str          = "1.2.840.113549.1.1.14";
+pub const OID_SHA3_224_WITH_RSA: &str        = "2.16.840.1.101.3.4.3.13";
+pub const OID_SHA3_256_WITH_RSA: &str        = "2.16.840.1.101.3.4.3.14";
+pub const OID_SHA3_384_WITH_RSA: &str        = "2.16.840.1.101.3.4.3.15";
+pub const OID_SHA3_512_WITH_RSA: &str        = "2.16.840.1.101.3.4.3.16";
+pub const OID_ECDSA_WITH_SHA1: &str          = "1.2.840.10045.4.1";
+pub const OID_ECDSA_WITH_SHA224: &str        = "1.2.840.10045.4.3.1";
+pub const OID_ECDSA_WITH_SHA256: &str        = "1.2.840.10045.4.3.2";
+pub const OID_ECDSA_WITH_SHA384: &str        = "1.2.840.10045.4.3.3";
+pub const OID_ECDSA_WITH_SHA512: &str        = "1.2.840.10045.4.3.4";
+pub const OID_ECDSA_WITH_SHA3_224: &str      = "2.16.840.1.101.3.4.3.9";
+pub const OID_ECDSA_WITH_SHA3_256: &str      = "2.16.840.1.101.3.4.3.10";
+pub const OID_ECDSA_WITH_SHA3_384: &str      = "2.16.840.1.101.3.4.3.11";
+pub const OID_ECDSA_WITH_SHA3_512: &str      = "2.16.840.1.101.3.4.3.12";
+
+// PKCS#7/CMS content types and PKCS#9 attributes
+pub const OID_PKCS7_DATA: &str               = "1.2.840.113549.1.7.1";
+pub const OID_PKCS7_SIGNED_DATA: &str        = "1.2.840.113549.1.7.2";
+pub const OID_PKCS7_ENVELOPED_DATA: &str     = "1.2.840.113549.1.7.3";
+pub const OID_PKCS9_CONTENT_TYPE: &str       = "1.2.840.113549.1.9.3";
+pub const OID_PKCS9_MESSAGE_DIGEST: &str     = "1.2.840.113549.1.9.4";
+pub const OID_PKCS9_SIGNING_TIME: &str       = "1.2.840.113549.1.9.5";
+pub const OID_CHALLENGE_PASSWORD: &str       = "1.2.840.113549.1.9.7";
+pub const OID_EXTENSION_REQUEST: &str        = "1.2.840.113549.1.9.14";
+pub const OID_ORI_KEM: &str                  = "1.2.840.113549.1.9.16.13.3";
+
+// content encryption, key wrap and key derivation algorithms
+pub const OID_AES128_CBC: &str               = "2.16.840.1.101.3.4.1.2";
+pub const OID_AES192_CBC: &str               = "2.16.840.1.101.3.4.1.22";
+pub const OID_AES256_CBC: &str               = "2.16.840.1.101.3.4.1.42";
+pub const OID_AES128_WRAP: &str              = "2.16.840.1.101.3.4.1.5";
+pub const OID_AES192_WRAP: &str              = "2.16.840.1.101.3.4.1.25";
+pub const OID_AES256_WRAP: &str              = "2.16.840.1.101.3.4.1.45";
+pub const OID_HKDF_SHA256: &str              = "1.2.840.113549.1.9.16.3.28";
+pub const OID_HKDF_SHA384: &str              = "1.2.840.113549.1.9.16.3.29";
+pub const OID_HKDF_SHA512: &str              = "1.2.840.113549.1.9.16.3.30";
+
+/// Mapping of known OIDs to short names
+const OID_NAMES: &[(&str, &str)] = &[
+    (OID_COMMON_NAME,             "CN"),
+    (OID_SURNAME,                 "SN"),
+    (OID_SERIAL_NUMBER,           "serialNumber"),
+    (OID_COUNTRY,                 "C"),
+    (OID_LOCALITY,                "L"),
+    (OID_STATE_OR_PROVINCE,       "ST"),
+    (OID_STREET_ADDRESS,          "street"),
+    (OID_ORGANIZATION,            "O"),
+    (OID_ORGANIZATION_UNIT,       "OU"),
+    (OID_TITLE,                   "title"),
+    (OID_DESCRIPTION,             "description"),
+    (OID_POSTAL_CODE,             "postalCode"),
+    (OID_NAME,                    "N"),
+    (OID_GIVEN_NAME,              "G"),
+    (OID_INITIALS,                "I"),
+    (OID_GENERATION_QUALIFIER,    "generationQualifier"),
+    (OID_UNIQUE_IDENTIFIER,       "ID"),
+    (OID_DN_QUALIFIER,            "dnQualifier"),
+    (OID_PSEUDONYM,               "pseudonym"),
+    (OID_ROLE,                    "role"),
+    (OID_ORGANIZATION_IDENTIFIER, "organizationIdentifier"),
+    (OID_EMAIL_ADDRESS,           "E"),
+    (OID_UNSTRUCTURED_NAME,       "unstructuredName"),
+    (OID_USER_ID,                 "UID"),
+    (OID_DOMAIN_COMPONENT,        "DC"),
+    (OID_SUBJECT_KEY_ID,          "subjectKeyIdentifier"),
+    (OID_KEY_USAGE,               "keyUsage"),
+    (OID_SUBJECT_ALT_NAME,        "subjectAltName"),
+    (OID_ISSUER_ALT_NAME,         "issuerAltName"),
+    (OID_BASIC_CONSTRAINTS,       "basicConstraints"),
+    (OID_CRL_NUMBER,              "crlNumber"),
+    (OID_CRL_REASON_CODE,         "reasonCode"),
+    (OID_INVALIDITY_DATE,         "invalidityDate"),
+    (OID_DELTA_CRL_INDICATOR,     "deltaCRLIndicator"),
+    (OID_ISSUING_DIST_POINT,      "issuingDistributionPoint"),
+    (OID_NAME_CONSTRAINTS,        "nameConstraints"),
+    (OID_CRL_DISTRIBUTION_POINTS, "crlDistributionPoints"),
+    (OID_CERTIFICATE_POLICIES,    "certificatePolicies"),
+    (OID_ANY_POLICY,              "anyPolicy"),
+    (OID_POLICY_MAPPINGS,         "policyMappings"),
+    (OID_AUTHORITY_KEY_ID,        "authorityKeyIdentifier"),
+    (OID_POLICY_CONSTRAINTS,      "policyConstraints"),
+    (OID_EXTENDED_KEY_USAGE,      "extendedKeyUsage"),
+    (OID_FRESHEST_CRL,            "freshestCRL"),
+    (OID_INHIBIT_ANY_POLICY,      "inhibitAnyPolicy"),
+    (OID_AUTHORITY_INFO_ACCESS,   "authorityInfoAccess"),
+    (OID_IP_ADDR_BLOCKS,          "ipAddrBlocks"),
+    (OID_CT_PRECERT_POISON,       "ctPrecertificatePoison"),
+    (OID_CT_SCT_LIST,             "ctSignedCertificateTimestampList"),
+    (OID_OCSP,                    "ocsp"),
+    (OID_CA_ISSUERS,              "caIssuers"),
+    (OID_CPS,                     "cps"),
+    (OID_USER_NOTICE,             "userNotice"),
+    (OID_SERVER_AUTH,             "serverAuth"),
+    (OID_CLIENT_AUTH,             "clientAuth"),
+    (OID_CODE_SIGNING,            "codeSigning"),
+    (OID_EMAIL_PROTECTION,        "emailProtection"),
+    (OID_TIME_STAMPING,           "timeStamping"),
+    (OID_OCSP_SIGNING,            "ocspSigning"),
+    (OID_IKE_INTERMEDIATE,        "ikeIntermediate"),
+    (OID_MS_SMARTCARD_LOGON,      "msSmartcardLogon"),
+    (OID_XMPP_ADDR,               "xmppAddr"),
+    (OID_MS_UPN,                  "msUPN"),
+    (OID_MD5,                     "md5"),
+    (OID_SHA1,                    "sha1"),
+    (OID_SHA224,                  "sha224"),
+    (OID_SHA256,                  "sha256"),
+    (OID_SHA384,                  "sha384"),
+    (OID_SHA512,                  "sha512"),
+    (OID_SHA3_224,                "sha3_224"),
+    (OID_SHA3_256,                "sha3_256"),
+    (OID_SHA3_384,                "sha3_384"),
+    (OID_SHA3_512,                "sha3_512"),
+    (OID_SHAKE128,                "shake128"),
+    (OID_SHAKE256,                "shake256"),
+    (OID_RSA_ENCRYPTION,          "rsaEncryption"),
+    (OID_EC_PUBLICKEY,            "id-ecPublicKey"),
+    (OID_PRIME256V1,              "prime256v1"),
+    (OID_SECP384R1,               "secp384r1"),
+    (OID_SECP521R1,               "secp521r1"),
+    (OID_ED25519,                 "Ed25519"),
+    (OID_ED448,                   "Ed448"),
+    (OID_ML_DSA_44,               "ML-DSA-44"),
+    (OID_ML_DSA_65,               "ML-DSA-65"),
+    (OID_ML_DSA_87,               "ML-DSA-87"),
+    (OID_MLDSA44_RSA2048_PSS,     "MLDSA44-RSA2048-PSS-SHA256"),
+    (OID_MLDSA44_RSA2048_PKCS15,  "MLDSA44-RSA2048-PKCS15-SHA256"),
+    (OID_MLDSA44_ED25519,         "MLDSA44-Ed25519-SHA512"),
+    (OID_MLDSA44_ECDSA_P256,      "MLDSA44-ECDSA-P256-SHA256"),
+    (OID_MLDSA65_RSA3072_PSS,     "MLDSA65-RSA3072-PSS-SHA512"),
+    (OID_MLDSA65_RSA3072_PKCS15,  "MLDSA65-RSA3072-PKCS15-SHA512"),
+    (OID_MLDSA65_RSA4096_PSS,     "MLDSA65-RSA4096-PSS-SHA512"),
+    (OID_MLDSA65_RSA4096_PKCS15,  "MLDSA65-RSA4096-PKCS15-SHA512"),
+    (OID_MLDSA65_ECDSA_P256,      "MLDSA65-ECDSA-P256-SHA512"),
+    (OID_MLDSA65_ECDSA_P384,      "MLDSA65-ECDSA-P384-SHA512"),
+    (OID_MLDSA65_ED25519,         "MLDSA65-Ed25519-SHA512"),
+    (OID_MLDSA87_ECDSA_P384,      "MLDSA87-ECDSA-P384-SHA512"),
+    (OID_MLDSA87_ED448,           "MLDSA87-Ed448-SHAKE256"),
+    (OID_MLDSA87_RSA3072_PSS,     "MLDSA87-RSA3072-PSS-SHA512"),
+    (OID_MLDSA87_RSA4096_PSS,     "MLDSA87-RSA4096-PSS-SHA512"),
+    (OID_MLDSA87_ECDSA_P521,      "MLDSA87-ECDSA-P521-SHA512"),
+    (OID_SLH_DSA_SHA2_128S,       "SLH-DSA-SHA2-128s"),
+    (OID_SLH_DSA_SHA2_128F,       "SLH-DSA-SHA2-128f"),
+    (OID_SLH_DSA_SHA2_192S,       "SLH-DSA-SHA2-192s"),
+    (OID_SLH_DSA_SHA2_192F,       "SLH-DSA-SHA2-192f"),
+    (OID_SLH_DSA_SHA2_256S,       "SLH-DSA-SHA2-256s"),
+    (OID_SLH_DSA_SHA2_256F,       "SLH-DSA-SHA2-256f"),
+    (OID_SLH_DSA_SHAKE_128S,      "SLH-DSA-SHAKE-128s"),
+    (OID_SLH_DSA_SHAKE_128F,      "SLH-DSA-SHAKE-128f"),
+    (OID_SLH_DSA_SHAKE_192S,      "SLH-DSA-SHAKE-192s"),
+    (OID_SLH_DSA_SHAKE_192F,      "SLH-DSA-SHAKE-192f"),
+    (OID_SLH_DSA_SHAKE_256S,      "SLH-DSA-SHAKE-256s"),
+    (OID_SLH_DSA_SHAKE_256F,      "SLH-DSA-SHAKE-256f"),
+    (OID_ML_KEM_512,              "ML-KEM-512"),
+    (OID_ML_KEM_768,              "ML-KEM-768"),
+    (OID_ML_KEM_1024,             "ML-KEM-1024"),
+    (OID_MD5_WITH_RSA,            "md5WithRSAEncryption"),
+    (OID_SHA1_WITH_RSA,           "sha1WithRSAEncryption"),
+    (OID_RSASSA_PSS,              "RSASSA-PSS"),
+    (OID_MGF1,                    "mgf1"),
+    (OID_SHA256_WITH_RSA,         "sha256WithRSAEncryption"),
+    (OID_SHA384_WITH_RSA,         "sha384WithRSAEncryption"),
+    (OID_SHA512_WITH_RSA,         "sha512WithRSAEncryption"),
+    (OID_SHA224_WITH_RSA,         "sha224WithRSAEncryption"),
+    (OID_SHA3_224_WITH_RSA,       "sha3_224WithRSAEncryption"),
+    (OID_SHA3_256_WITH_RSA,       "sha3_256WithRSAEncryption"),
+    (OID_SHA3_384_WITH_RSA,       "sha3_384WithRSAEncryption"),
+    (OID_SHA3_512_WITH_RSA,       "sha3_512WithRSAEncryption"),
+    (OID_ECDSA_WITH_SHA1,         "ecdsa-with-SHA1"),
+    (OID_ECDSA_WITH_SHA224,       "ecdsa-with-SHA224"),
+    (OID_ECDSA_WITH_SHA256,       "ecdsa-with-SHA256"),
+    (OID_ECDSA_WITH_SHA384,       "ecdsa-with-SHA384"),
+    (OID_ECDSA_WITH_SHA512,       "ecdsa-with-SHA512"),
+    (OID_ECDSA_WITH_SHA3_224,     "ecdsa-with-SHA3-224"),
+    (OID_ECDSA_WITH_SHA3_256,     "ecdsa-with-SHA3-256"),
+    (OID_ECDSA_WITH_SHA3_384,     "ecdsa-with-SHA3-384"),
+    (OID_ECDSA_WITH_SHA3_512,     "ecdsa-with-SHA3-512"),
+    (OID_PKCS7_DATA,              "data"),
+    (OID_PKCS7_SIGNED_DATA,       "signedData"),
+    (OID_PKCS7_ENVELOPED_DATA,    "envelopedData"),
+    (OID_PKCS9_CONTENT_TYPE,      "contentType"),
+    (OID_PKCS9_MESSAGE_DIGEST,    "messageDigest"),
+    (OID_PKCS9_SIGNING_TIME,      "signingTime"),
+    (OID_CHALLENGE_PASSWORD,      "challengePassword"),
+    (OID_EXTENSION_REQUEST,       "extensionRequest"),
+    (OID_ORI_KEM,                 "id-ori-kem"),
+    (OID_AES128_CBC,              "aes128-CBC"),
+    (OID_AES192_CBC,              "aes192-CBC"),
+    (OID_AES256_CBC,              "aes256-CBC"),
+    (OID_AES128_WRAP,             "aes128-wrap"),
+    (OID_AES192_WRAP,             "aes192-wrap"),
+    (OID_AES256_WRAP,             "aes256-wrap"),
+    (OID_HKDF_SHA256,             "hkdf-with-sha256"),
+    (OID_HKDF_SHA384,             "hkdf-with-sha384"),
+    (OID_HKDF_SHA512,             "hkdf-with-sha512"),
+];
+
+//
+// Get the short name of a known OID.
+//
+pub fn oid_name(oid: &str) -> Option<&'static str>
+{
+    return OID_NAMES.iter().find(|(o, _)| *o == oid).map(|(_, n)| *n);
+}
+
+//
+// Get the short name of an OID or fall back to its dotted notation.
+//
+pub fn oid_display(oid: &str) -> String
+{
+    match oid_name(oid) {
+        Some(name) => { name.to_string() }
+        None       => { oid.to_string() }
+    }
+}
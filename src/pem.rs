@@ -0,0 +1,436 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! RFC 7468 PEM armor and DER/PEM input and output handling.
+
+use std::fmt;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::process::ExitCode;
+use getopts::Matches;
+use crate::asn1::Asn1Parser;
+
+const BASE64_CHARS: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encoding of a credential written with --outform
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    Der,
+    Pem,
+}
+
+impl Encoding {
+    //
+    // Parse the argument of an --outform option.
+    //
+    pub fn parse(form: &str) -> Option<Encoding>
+    {
+        match form {
+            "der" => { Some(Encoding::Der) }
+            "pem" => { Some(Encoding::Pem) }
+            _     => { None }
+        }
+    }
+
+    //
+    // Get the encoding selected with --outform, reporting an invalid value
+    // and returning the exit code for a usage error.
+    //
+    pub fn from_matches(matches: &Matches, default: Encoding) -> Result<Encoding, ExitCode>
+    {
+        match matches.opt_str("f") {
+            Some(v) => {
+                match Encoding::parse(&v) {
+                    Some(form) => { return Ok(form); }
+                    None => {
+                        eprintln!("invalid output format '{}'", v);
+                        return Err(ExitCode::from(2));
+                    }
+                }
+            }
+            None => { return Ok(default); }
+        }
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self {
+            Encoding::Der => { write!(f, "der") }
+            Encoding::Pem => { write!(f, "pem") }
+        }
+    }
+}
+
+/// Credential types with their RFC 7468 PEM labels
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CredType {
+    Certificate,
+    Crl,
+    CertRequest,
+    AttributeCert,
+    PrivateKey,
+    RsaPrivateKey,
+    EcPrivateKey,
+    PublicKey,
+    RsaPublicKey,
+    RsaKeyShare,
+    RsaPartialSignature,
+    Pkcs7,
+    Cms,
+    Pkcs12,
+}
+
+impl CredType {
+    //
+    // PEM label used in the BEGIN/END encapsulation boundaries.
+    //
+    pub fn label(&self) -> &'static str
+    {
+        match self {
+            CredType::Certificate   => { "CERTIFICATE" }
+            CredType::Crl           => { "X509 CRL" }
+            CredType::CertRequest   => { "CERTIFICATE REQUEST" }
+            CredType::AttributeCert => { "ATTRIBUTE CERTIFICATE" }
+            CredType::PrivateKey    => { "PRIVATE KEY" }
+            CredType::RsaPrivateKey => { "RSA PRIVATE KEY" }
+            CredType::EcPrivateKey  => { "EC PRIVATE KEY" }
+            CredType::PublicKey     => { "PUBLIC KEY" }
+            CredType::RsaPublicKey  => { "RSA PUBLIC KEY" }
+            CredType::RsaKeyShare   => { "RSA KEY SHARE" }
+            CredType::RsaPartialSignature => { "RSA PARTIAL SIGNATURE" }
+            CredType::Pkcs7         => { "PKCS7" }
+            CredType::Cms           => { "CMS" }
+            CredType::Pkcs12        => { "PKCS12" }
+        }
+    }
+}
+
+/// A DER object together with the PEM label it was armored with
+#[derive(Clone, Debug)]
+pub struct PemBlock {
+    /// label of the encapsulation boundary, None for raw DER input
+    pub label: Option<String>,
+    /// DER encoded content
+    pub der: Vec<u8>,
+}
+
+//
+// Encode binary data as base64 with optional line wrapping.
+//
+pub fn base64_encode(data: &[u8], line_len: usize) -> String
+{
+    let mut out = String::with_capacity(data.len() * 4 / 3 + 4);
+    let mut col = 0;
+
+    for chunk in data.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = ((b[0] as u32) << 16) | ((b[1] as u32) << 8) | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64_CHARS[((n >> (18 - 6 * i)) & 0x3F) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+        col += 4;
+        if line_len > 0 && col >= line_len {
+            out.push('\n');
+            col = 0;
+        }
+    }
+    if line_len > 0 && col > 0 {
+        out.push('\n');
+    }
+    return out;
+}
+
+//
+// Decode base64 data, ignoring whitespace. The final quantum must be padded
+// to four characters with '=' and padding is accepted only at the end.
+//
+pub fn base64_decode(text: &str) -> Result<Vec<u8>, String>
+{
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+    let mut acc: u32 = 0;
+    let mut bits = 0;
+    let mut count = 0;
+    let mut padding = 0;
+
+    for c in text.bytes() {
+        if c.is_ascii_whitespace() {
+            continue;
+        }
+        if c == b'=' {
+            padding += 1;
+            continue;
+        }
+        if padding > 0 {
+            return Err("base64 data after padding".to_string());
+        }
+        let v = match BASE64_CHARS.iter().position(|b| *b == c) {
+            Some(v) => { v as u32 }
+            None    => { return Err(format!("invalid base64 character '{}'", c as char)); }
+        };
+        acc = (acc << 6) | v;
+        bits += 6;
+        count += 1;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+    if count % 4 == 1 || padding != (4 - count % 4) % 4 || acc != 0 {
+        return Err("invalid base64 padding".to_string());
+    }
+    return Ok(out);
+}
+
+//
+// Wrap DER data into a PEM block with the given label.
+//
+pub fn pem_encode(label: &str, der: &[u8]) -> String
+{
+    return format!("-----BEGIN {}-----\n{}-----END {}-----\n", label,
+                   base64_encode(der, 64), label);
+}
+
+//
+// Check whether the data looks like PEM armored text.
+//
+pub fn is_pem(data: &[u8]) -> bool
+{
+    let start = data.iter().take_while(|c| c.is_ascii_whitespace()).count();
+    if data[start..].starts_with(b"-----BEGIN ") {
+        return true;
+    }
+    // DER objects start with a SEQUENCE, PEM files may lead with plain text
+    return data.first() != Some(&0x30) && data.windows(12).any(|w| w == b"\n-----BEGIN ");
+}
+
+//
+// Decode all PEM blocks contained in a text, skipping any explanatory text
+// between the blocks.
+//
+pub fn pem_decode(data: &[u8]) -> Result<Vec<PemBlock>, String>
+{
+    let text = String::from_utf8_lossy(data);
+    let mut blocks = Vec::new();
+    let mut label: Option<String> = None;
+    let mut body = String::new();
+
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim();
+        match &label {
+            None => {
+                if let Some(rest) = line.strip_prefix("-----BEGIN ") {
+                    let l = rest.strip_suffix("-----")
+                                .ok_or(format!("line {}: malformed PEM boundary", n + 1))?;
+                    label = Some(l.to_string());
+                    body.clear();
+                }
+            }
+            Some(l) => {
+                if let Some(rest) = line.strip_prefix("-----END ") {
+                    if rest.strip_suffix("-----") != Some(l.as_str()) {
+                        return Err(format!("line {}: PEM END label does not match '{}'",
+                                           n + 1, l));
+                    }
+                    let der = base64_decode(&body)
+                                .map_err(|e| format!("PEM block '{}': {}", l, e))?;
+                    blocks.push(PemBlock { label: label.take(), der });
+                } else if line.contains(':') {
+                    if line.starts_with("Proc-Type") && line.contains("ENCRYPTED") {
+                        return Err(format!("encrypted PEM block '{}' is not supported", l));
+                    }
+                    // skip RFC 1421 header lines
+                } else {
+                    body.push_str(line);
+                }
+            }
+        }
+    }
+    if let Some(l) = label {
+        return Err(format!("PEM block '{}' is not terminated", l));
+    }
+    if blocks.is_empty() {
+        return Err("no PEM blocks found".to_string());
+    }
+    return Ok(blocks);
+}
+
+//
+// Split binary input into its top-level DER/BER objects.
+//
+fn der_split(data: &[u8]) -> Result<Vec<PemBlock>, String>
+{
+    let mut parser = Asn1Parser::new_ber(data);
+    let mut blocks = Vec::new();
+
+    while let Some(obj) = parser.next_object()? {
+        blocks.push(PemBlock { label: None, der: obj.raw.to_vec() });
+    }
+    if blocks.is_empty() {
+        return Err("no DER objects found".to_string());
+    }
+    return Ok(blocks);
+}
+
+//
+// Auto-detect PEM or DER input and return all contained objects.
+//
+pub fn decode_blocks(data: &[u8]) -> Result<Vec<PemBlock>, String>
+{
+    if is_pem(data) {
+        return pem_decode(data);
+    }
+    return der_split(data);
+}
+
+//
+// Read a file or stdin if no file name is given.
+//
+pub fn read_input(file: Option<&str>) -> Result<Vec<u8>, String>
+{
+    match file {
+        Some(path) => {
+            fs::read(path).map_err(|e| format!("reading '{}' failed: {}", path, e))
+        }
+        None => {
+            let mut data = Vec::new();
+            io::stdin().read_to_end(&mut data)
+                       .map_err(|e| format!("reading from stdin failed: {}", e))?;
+            Ok(data)
+        }
+    }
+}
+
+//
+// Load all DER objects from a PEM or DER encoded file or stdin.
+//
+pub fn load_blocks(file: Option<&str>) -> Result<Vec<PemBlock>, String>
+{
+    let data = read_input(file)?;
+    let name = file.unwrap_or("stdin");
+    return decode_blocks(&data).map_err(|e| format!("loading '{}' failed: {}", name, e));
+}
+
+//
+// Load exactly one DER object from a PEM or DER encoded file or stdin.
+//
+pub fn load_block(file: Option<&str>) -> Result<PemBlock, String>
+{
+    let mut blocks = load_blocks(file)?;
+    if blocks.len() > 1 {
+        return Err(format!("'{}' contains {} objects where one was expected",
+                           file.unwrap_or("stdin"), blocks.len()));
+    }
+    return Ok(blocks.remove(0));
+}
+
+//
+// Encode a credential in the requested form.
+//
+pub fn encode(der: &[u8], form: Encoding, cred: CredType) -> Vec<u8>
+{
+    match form {
+        Encoding::Der => { der.to_vec() }
+        Encoding::Pem => { pem_encode(cred.label(), der).into_bytes() }
+    }
+}
+
+//
+// Write a credential in the requested form to stdout.
+//
+pub fn write_output(der: &[u8], form: Encoding, cred: CredType) -> Result<(), String>
+{
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(&encode(der, form, cred))
+          .and_then(|_| stdout.flush())
+          .map_err(|e| format!("writing {} failed: {}", cred.label(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asn1::*;
+
+    #[test]
+    fn base64_vectors()
+    {
+        // RFC 4648, section 10
+        let vectors = [("", ""), ("f", "Zg=="), ("fo", "Zm8="), ("foo", "Zm9v"),
+                       ("foob", "Zm9vYg=="), ("fooba", "Zm9vYmE="), ("foobar", "Zm9vYmFy")];
+        for (plain, encoded) in vectors {
+            assert_eq!(base64_encode(plain.as_bytes(), 0), encoded);
+            assert_eq!(base64_decode(encoded).unwrap(), plain.as_bytes());
+        }
+        assert_eq!(base64_encode(&[0xFB; 6], 4), "+/v7\n+/v7\n");
+        assert_eq!(base64_decode(" Zm9v\nYmE=\r\n").unwrap(), b"fooba");
+
+        assert_eq!(base64_decode("Zm8\n=").unwrap(), b"fo");
+
+        // padding must complete the final quantum, trailing bits must be zero
+        for invalid in ["Zg", "Zm8", "Zg=", "Zm8==", "QUJD=", "QQ=", "QUJD====", "Zm9v=",
+                        "=", "==Zm9v", "Zg==Zg==", "Zm9v=Zm9v", "Zh==", "Zm9*", "Z", "Z==="] {
+            assert!(base64_decode(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn pem_round_trip()
+    {
+        let cert = asn1_sequence(&[asn1_integer_from_u64(1), asn1_octet_string(&[0xA5; 100])]);
+        let key = asn1_sequence(&[asn1_integer_from_u64(0)]);
+
+        let pem = encode(&cert, Encoding::Pem, CredType::Certificate);
+        let text = String::from_utf8(pem.clone()).unwrap();
+        assert!(text.starts_with("-----BEGIN CERTIFICATE-----\n"));
+        assert!(text.ends_with("-----END CERTIFICATE-----\n"));
+        assert!(text.lines().all(|line| line.len() <= 64));
+        assert_eq!(encode(&cert, Encoding::Der, CredType::Certificate), cert);
+
+        let mut data = b"explanatory text\n".to_vec();
+        data.extend_from_slice(&pem);
+        data.extend_from_slice(&encode(&key, Encoding::Pem, CredType::RsaKeyShare));
+        assert!(is_pem(&data));
+        let blocks = decode_blocks(&data).unwrap();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!((blocks[0].label.as_deref(), &blocks[0].der), (Some("CERTIFICATE"), &cert));
+        assert_eq!((blocks[1].label.as_deref(), &blocks[1].der), (Some("RSA KEY SHARE"), &key));
+
+        // concatenated DER objects
+        let der = [cert.clone(), key.clone()].concat();
+        assert!(!is_pem(&der));
+        let blocks = decode_blocks(&der).unwrap();
+        assert_eq!(blocks.iter().map(|b| b.der.clone()).collect::<Vec<_>>(), vec![cert, key]);
+        assert!(blocks.iter().all(|b| b.label.is_none()));
+    }
+
+    #[test]
+    fn malformed_pem()
+    {
+        let pem = pem_encode("X509 CRL", &asn1_null());
+        assert!(pem_decode(pem.replace("END X509 CRL", "END CERTIFICATE").as_bytes()).is_err());
+        assert!(pem_decode(pem.replace("-----END X509 CRL-----\n", "").as_bytes()).is_err());
+        assert!(pem_decode(pem.replace("BQA=", "BQA*").as_bytes()).is_err());
+        assert!(pem_decode(b"no PEM data").is_err());
+
+        let encrypted = pem.replace("BQA=", "Proc-Type: 4,ENCRYPTED\nBQA=");
+        assert!(pem_decode(encrypted.as_bytes()).unwrap_err().contains("encrypted"));
+        let headers = pem.replace("BQA=", "Comment: test\n\nBQA=");
+        assert_eq!(pem_decode(headers.as_bytes()).unwrap()[0].der, asn1_null());
+    }
+}
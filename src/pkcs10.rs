@@ -0,0 +1,163 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! PKCS#10 certification requests (RFC 2986) with the PKCS#9
+//! challengePassword and extensionRequest attributes (RFC 2985).
+
+use crate::asn1::*;
+use crate::keys::{PrivateKey, PublicKey};
+use crate::oid::*;
+use crate::signature::SignatureScheme;
+use crate::x509::{Extension, parse_extensions};
+
+/// A decoded PKCS#10 certification request
+#[derive(Clone, Debug)]
+pub struct CertificateRequest {
+    /// complete DER encoding
+    pub der: Vec<u8>,
+    /// DER encoded certificationRequestInfo
+    pub info: Vec<u8>,
+    /// DER encoded subject Name
+    pub subject: Vec<u8>,
+    /// subject public key
+    pub public_key: PublicKey,
+    /// optional challengePassword attribute
+    pub challenge_password: Option<String>,
+    /// extensions of the extensionRequest attribute
+    pub extensions: Vec<Extension>,
+    /// signature algorithm
+    pub scheme: SignatureScheme,
+    /// signature value
+    pub signature: Vec<u8>,
+}
+
+/// The contents of a certification request to be signed
+#[derive(Clone, Debug)]
+pub struct RequestInfo {
+    /// DER encoded subject Name
+    pub subject: Vec<u8>,
+    /// subject public key
+    pub public_key: PublicKey,
+    /// optional challengePassword attribute
+    pub challenge_password: Option<String>,
+    /// extensions of the extensionRequest attribute
+    pub extensions: Vec<Extension>,
+}
+
+impl RequestInfo {
+    //
+    // Encode as certificationRequestInfo.
+    //
+    pub fn encode(&self) -> Result<Vec<u8>, String>
+    {
+        let mut attributes: Vec<Vec<u8>> = Vec::new();
+        if let Some(password) = &self.challenge_password {
+            if password.is_empty() || password.chars().count() > 255 {
+                return Err("challengePassword must have 1 to 255 characters".to_string());
+            }
+            let tag = if asn1_is_printablestring(password) { ASN1_PRINTABLESTRING } else { ASN1_UTF8STRING };
+            attributes.push(asn1_sequence(&[asn1_oid(OID_CHALLENGE_PASSWORD),
+                                            asn1_set_of(&[asn1_string(tag, password)])]));
+        }
+        if !self.extensions.is_empty() {
+            let exts = self.extensions.iter().map(|e| e.encode()).collect::<Result<Vec<_>, _>>()?;
+            attributes.push(asn1_sequence(&[asn1_oid(OID_EXTENSION_REQUEST),
+                                            asn1_set_of(&[asn1_sequence(&exts)])]));
+        }
+        let parts: Vec<&[u8]> = attributes.iter().map(|a| a.as_slice()).collect();
+        return Ok(asn1_sequence(&[asn1_integer_from_u64(0), self.subject.clone(),
+                                  self.public_key.to_spki(), asn1_wrap(ASN1_CONTEXT_C_0, &parts)]));
+    }
+
+    //
+    // Sign with the subject's private key and return the decoded request.
+    //
+    pub fn sign(&self, key: &PrivateKey, scheme: SignatureScheme) -> Result<CertificateRequest, String>
+    {
+        let info = self.encode()?;
+        let signature = key.sign(scheme, &info)?;
+        let der = asn1_sequence(&[info, scheme.to_algorithm_identifier(), asn1_bitstring(&signature)]);
+        return CertificateRequest::from_der(&der);
+    }
+}
+
+impl CertificateRequest {
+    //
+    // Decode a DER encoded certification request.
+    //
+    pub fn from_der(der: &[u8]) -> Result<CertificateRequest, String>
+    {
+        let obj = asn1_parse(der)?;
+        if obj.tag != ASN1_SEQUENCE {
+            return Err("not a CertificationRequest".to_string());
+        }
+        let mut parser = obj.parser();
+        let info = parser.expect(ASN1_SEQUENCE)?;
+        let scheme = SignatureScheme::from_algorithm_identifier(&parser.expect(ASN1_SEQUENCE)?)?;
+        let signature = parser.expect(ASN1_BIT_STRING)?.to_bitstring_octets()?.to_vec();
+        parser.finish()?;
+
+        let mut p = info.parser();
+        let version = p.expect(ASN1_INTEGER)?.to_u64()?;
+        if version != 0 {
+            return Err(format!("unsupported PKCS#10 version {}", version + 1));
+        }
+        let subject = p.expect(ASN1_SEQUENCE)?.raw.to_vec();
+        let public_key = PublicKey::from_spki(p.expect(ASN1_SEQUENCE)?.raw)?;
+        let attributes = p.expect(ASN1_CONTEXT_C_0)?;
+        p.finish()?;
+
+        let mut challenge_password = None;
+        let mut extensions = Vec::new();
+        let mut attrs = attributes.parser();
+        while let Some(attr) = attrs.next_object()? {
+            let mut a = attr.parser();
+            let oid = a.expect(ASN1_OID)?.to_oid()?;
+            let values = a.expect(ASN1_SET)?;
+            a.finish()?;
+
+            let mut v = values.parser();
+            match oid.as_str() {
+                OID_CHALLENGE_PASSWORD => {
+                    let value = v.next_object()?.ok_or("empty challengePassword")?;
+                    challenge_password = Some(value.to_string()?);
+                }
+                OID_EXTENSION_REQUEST => {
+                    extensions = parse_extensions(&v.expect(ASN1_SEQUENCE)?)?;
+                }
+                _ => { continue; }
+            }
+            v.finish()?;
+        }
+
+        return Ok(CertificateRequest {
+            der: der.to_vec(), info: info.raw.to_vec(), subject, public_key,
+            challenge_password, extensions, scheme, signature,
+        });
+    }
+
+    //
+    // Look up a requested extension by its OID.
+    //
+    pub fn extension(&self, oid: &str) -> Option<&Extension>
+    {
+        return self.extensions.iter().find(|e| e.oid == oid);
+    }
+
+    //
+    // Check the self-signature proving possession of the private key.
+    //
+    pub fn verify_signature(&self) -> bool
+    {
+        return self.public_key.verify(self.scheme, &self.info, &self.signature);
+    }
+}
@@ -16,6 +16,9 @@
 use std::process::ExitCode;
 use getopts::Matches;
 use pki::{Command, Opt};
+use pki::pem::{Encoding, load_blocks};
+use pki::validity::{Validity, time_string};
+use pki::x509::{parse_serial, random_serial};
 
 //
 // Issue an attribute certificate,
@@ -55,7 +58,14 @@ pub fn pki_acert(matches: &Matches) -> ExitCode
 
     if matches.opt_present("i") {
         let file = matches.opt_str("i").unwrap();
-        println!("option: --in {}", file);
+        let blocks = match load_blocks(Some(&file)) {
+            Ok(blocks) => { blocks }
+            Err(e) => {
+                println!("{}", e);
+                return ExitCode::FAILURE;
+            }
+        };
+        println!("option: --in {} ({} objects)", file, blocks.len());
     } else {
         println!("option '--in' missing: get input from stdin");
     }
@@ -66,31 +76,29 @@ pub fn pki_acert(matches: &Matches) -> ExitCode
         println!("option: --group {}", g);
     }
 
-    if matches.opt_present("s") {
-        let serial = matches.opt_str("s").unwrap();
-        println!("option: --serial {}", serial);
-    }
-
-    let lifetime: i64 = 60 * 60 * match matches.opt_str("l") {
-        Some(string) => { string.parse().unwrap() }
-        None => { 24 } // hours
+    let serial = match matches.opt_str("s") {
+        Some(hex) => {
+            match parse_serial(&hex) {
+                Ok(serial) => { serial }
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return ExitCode::from(2);
+                }
+            }
+        }
+        None => { random_serial() }
     };
-    println!("option: --lifetime {} seconds", lifetime);
-
-    if matches.opt_present("F") {
-        let datenb = matches.opt_str("F").unwrap();
-        println!("option: --not-before {}", datenb);
-    }
-
-    if matches.opt_present("T") {
-        let datena = matches.opt_str("T").unwrap();
-        println!("option: --not-after {}", datena);
-    }
+    println!("option: --serial {}", serial.iter().map(|b| format!("{:02x}", b)).collect::<String>());
 
-    if matches.opt_present("D") {
-        let dateform = matches.opt_str("D").unwrap();
-        println!("option: --dateform {}", dateform);
-    }
+    let validity = match Validity::from_matches(matches, 60 * 60, 24) {
+        Ok(validity) => { validity }
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(2);
+        }
+    };
+    println!("option: --not-before {}", time_string(validity.not_before));
+    println!("option: --not-after {}", time_string(validity.not_after));
 
     if matches.opt_present("g") {
         let digest = matches.opt_str("g").unwrap();
@@ -102,10 +110,11 @@ pub fn pki_acert(matches: &Matches) -> ExitCode
         println!("option: --rsa-padding {}", padding);
     }
 
-    if matches.opt_present("f") {
-        let form = matches.opt_str("f").unwrap();
-        println!("option: --outform {}", form);
-    }
+    let form = match Encoding::from_matches(matches, Encoding::Der) {
+        Ok(form) => { form }
+        Err(code) => { return code; }
+    };
+    println!("option: --outform {}", form);
 
     println!("acert()");
     return ExitCode::SUCCESS;
@@ -134,7 +143,7 @@ inventory::submit!
         Opt { long: "lifetime"   , short: "l", arg: 1, descr: "hours the acert is valid, default: 24" },
         Opt { long: "not-before" , short: "F", arg: 1, descr: "date/time the validity of the AC starts" },
         Opt { long: "not-after"  , short: "T", arg: 1, descr: "date/time the validity of the AC ends" },
-        Opt { long: "dateform"   , short: "D", arg: 1, descr: "strptime(3) input format, default: %d.%m.%y %T" },
+        Opt { long: "dateform"   , short: "D", arg: 1, descr: "strptime(3) input format, default: ISO 8601" },
         Opt { long: "digest"     , short: "g", arg: 1, descr: "digest for signature creation, default: key-specific" },
         Opt { long: "rsa-padding", short: "R", arg: 1, descr: "padding for RSA signatures, default: pkcs1" },
         Opt { long: "outform"    , short: "f", arg: 1, descr: "encoding of generated cert, default: der" },
@@ -13,28 +13,59 @@
 // or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
 // for more details.
 
+use std::io::{self, Write};
 use std::process::ExitCode;
 use getopts::Matches;
 use pki::{Command, Opt};
+use pki::dn::Dn;
+use pki::pem::{base64_encode, load_block};
+use pki::x509::Certificate;
 
 //
 // Extract subject DN.
 //
 pub fn pki_dn(matches: &Matches) -> ExitCode
 {
-    if matches.opt_present("i") {
-        let file = matches.opt_str("i").unwrap();
-        println!("option: --in {}", file);
-    } else {
-        println!("option '--in' missing: get input from stdin");
+    let format = match matches.opt_str("f") {
+        Some(v) => { v }
+        None => { "config".to_string() }
+    };
+    if !["config", "rfc4514", "hex", "base64", "bin"].contains(&format.as_str()) {
+        eprintln!("invalid output format '{}'", format);
+        return ExitCode::from(2);
     }
 
-    if matches.opt_present("f") {
-        let format = matches.opt_str("f").unwrap();
-        println!("option: --format {}", format);
-    }
+    let file = matches.opt_str("i");
+    let cert = match load_block(file.as_deref()).and_then(|block| Certificate::from_der(&block.der)) {
+        Ok(cert) => { cert }
+        Err(e) => {
+            eprintln!("parsing certificate failed: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let dn = match Dn::from_der(&cert.subject) {
+        Ok(dn) => { dn }
+        Err(e) => {
+            eprintln!("parsing subject DN failed: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
 
-    println!("dn()");
+    let mut out = io::stdout();
+    let written = match format.as_str() {
+        "config"  => { writeln!(out, "\"{}\"", dn) }
+        "rfc4514" => { writeln!(out, "{}", dn.to_rfc4514()) }
+        "base64"  => { writeln!(out, "{}", base64_encode(&cert.subject, 0)) }
+        "bin"     => { out.write_all(&cert.subject) }
+        _ => {
+            let hex: String = cert.subject.iter().map(|b| format!("{:02x}", b)).collect();
+            writeln!(out, "{}", hex)
+        }
+    };
+    if let Err(e) = written {
+        eprintln!("writing subject DN failed: {}", e);
+        return ExitCode::FAILURE;
+    }
     return ExitCode::SUCCESS;
 }
 
@@ -44,13 +75,13 @@ pub fn pki_dn(matches: &Matches) -> ExitCode
 inventory::submit!
 {
     let brief: &'static[&'static str] = &[
-        "[--in file] [--format config|hex|base64|bin]"
+        "[--in file] [--format config|rfc4514|hex|base64|bin]"
     ];
     let options: &'static[Opt] = &[
         Opt { long: "help",   short: "h", arg: 0, descr: "show usage information" },
         Opt { long: "in",     short: "i", arg: 1, descr: "input file, default: stdin" },
         Opt { long: "format", short: "f", arg: 1, descr: "output format, default: config" },
-];
+    ];
     Command::new(pki_dn, "d", "dn",
                 "extract the subject DN of an X.509 certificate", brief, options)
 }
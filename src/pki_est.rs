@@ -15,6 +15,7 @@
 use std::process::ExitCode;
 use getopts::Matches;
 use pki::{Command, Opt};
+use pki::pem::{Encoding, load_blocks};
 
 const DEFAULT_POLL_INTERVAL: u32 = 60; // seconds
 
@@ -36,7 +37,7 @@ pub fn pki_est(matches: &Matches) -> ExitCode
     for c in &cacerts {
          println!("option: --cacert {}", c);
     }
-    if cacerts.len() == 0 {
+    if cacerts.is_empty() {
         println!("option '--cacert' is required");
         return ExitCode::from(2);
     }
@@ -96,7 +97,14 @@ pub fn pki_est(matches: &Matches) -> ExitCode
 
     if matches.opt_present("i") {
         let file = matches.opt_str("i").unwrap();
-        println!("option: --in {}", file);
+        let blocks = match load_blocks(Some(&file)) {
+            Ok(blocks) => { blocks }
+            Err(e) => {
+                println!("{}", e);
+                return ExitCode::FAILURE;
+            }
+        };
+        println!("option: --in {} ({} objects)", file, blocks.len());
     } else {
         println!("option '--in' missing: get input from stdin");
     }
@@ -118,10 +126,11 @@ pub fn pki_est(matches: &Matches) -> ExitCode
     };
     println!("option: --maxpolltime {} seconds", max_poll_time);
 
-    if matches.opt_present("f") {
-        let form = matches.opt_str("m").unwrap();
-        println!("option: --outform {}", form);
-    }
+    let form = match Encoding::from_matches(matches, Encoding::Der) {
+        Ok(form) => { form }
+        Err(code) => { return code; }
+    };
+    println!("option: --outform {}", form);
 
     println!("est()");
     return ExitCode::SUCCESS;
@@ -15,6 +15,7 @@
 use std::process::ExitCode;
 use getopts::Matches;
 use pki::{Command, Opt};
+use pki::pem::Encoding;
 
 //
 // Get CA certificate[s] from an EST server (RFC 7030).
@@ -33,7 +34,7 @@ pub fn pki_estca(matches: &Matches) -> ExitCode
     for c in &cacerts {
          println!("option: --cacert {}", c);
     }
-    if cacerts.len() == 0 {
+    if cacerts.is_empty() {
         println!("option '--cacert' is required");
         return ExitCode::from(2);
     }
@@ -48,10 +49,11 @@ pub fn pki_estca(matches: &Matches) -> ExitCode
         println!("option: --caout {}", caout);
     }
 
-    if matches.opt_present("f") {
-        let form = matches.opt_str("f").unwrap();
-        println!("option: --outform {}", form);
-    }
+    let form = match Encoding::from_matches(matches, Encoding::Der) {
+        Ok(form) => { form }
+        Err(code) => { return code; }
+    };
+    println!("option: --outform {}", form);
 
     let force: bool = matches.opt_present("F");
     println!("force:  {}", force);
@@ -16,29 +16,166 @@
 use std::process::ExitCode;
 use getopts::Matches;
 use pki::{Command, Opt};
+use pki::pem::{Encoding, CredType, write_output};
+use pki::keys::{KeyType, PrivateKey};
+use pki::crypto::rsa::RsaPrivateKey;
+use pki::crypto::rsa_threshold::generate_shares;
+use pki::keys::PublicKey;
+use pki::crypto::ec::{Curve, EcPrivateKey};
+use pki::crypto::eddsa::{EdCurve, EdPrivateKey};
+use pki::crypto::mldsa::{MlDsaParams, MlDsaPrivateKey};
+use pki::crypto::mlkem::{MlKemParams, MlKemPrivateKey};
+use pki::crypto::slhdsa::SlhDsaPrivateKey;
+use pki::crypto::composite::CompositePrivateKey;
+
+//
+// Generate a threshold RSA key, writing the public key followed by the
+// private key shares.
+//
+fn gen_shares(size: usize, shares: u32, threshold: u32, form: Encoding) -> ExitCode
+{
+    let (public, key_shares) = match generate_shares(size, shares, threshold) {
+        Ok(result) => { result }
+        Err(e) => {
+            eprintln!("private key generation failed: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut objects = vec![(PublicKey::Rsa(public).to_spki(), CredType::PublicKey)];
+    for share in &key_shares {
+        objects.push((share.to_der(), CredType::RsaKeyShare));
+    }
+    for (der, cred_type) in objects {
+        if let Err(e) = write_output(&der, form, cred_type) {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    }
+    return ExitCode::SUCCESS;
+}
 
 //
 // Generate a private key.
 //
 pub fn pki_gen(matches: &Matches) -> ExitCode
 {
-    if matches.opt_present("t") {
-        let key_type = matches.opt_str("t").unwrap();
-        println!("option: --type {}", key_type);
-    }
+    let key_type = match matches.opt_str("t") {
+        Some(v) => {
+            match KeyType::parse(&v) {
+                Some(key_type) => { key_type }
+                None => {
+                    eprintln!("invalid key type '{}'", v);
+                    return ExitCode::from(2);
+                }
+            }
+        }
+        None => { KeyType::Rsa }
+    };
 
-    let size: u32 = match matches.opt_str("s") {
-        Some(string) => { string.parse().unwrap() }
+    let size: usize = match matches.opt_str("s") {
+        Some(string) => {
+            match string.parse() {
+                Ok(size) => { size }
+                Err(_) => {
+                    eprintln!("invalid key size '{}'", string);
+                    return ExitCode::from(2);
+                }
+            }
+        }
         None => { 0 } // bits
     };
-    println!("option: --size {} bits", size);
 
-    if matches.opt_present("f") {
-        let form = matches.opt_str("f").unwrap();
-        println!("option: --outform {}", form);
+    let safe_primes: bool = matches.opt_present("p");
+    let pkcs8: bool = matches.opt_present("8");
+
+    let mut shares: u32 = 0;
+    let mut threshold: u32 = 0;
+    for (opt, value) in [("n", &mut shares), ("l", &mut threshold)] {
+        if let Some(string) = matches.opt_str(opt) {
+            match string.parse() {
+                Ok(v) => { *value = v }
+                Err(_) => {
+                    eprintln!("invalid number '{}'", string);
+                    return ExitCode::from(2);
+                }
+            }
+        }
+    }
+
+    let form = match Encoding::from_matches(matches, Encoding::Der) {
+        Ok(form) => { form }
+        Err(code) => { return code; }
+    };
+
+    if shares > 0 || threshold > 0 {
+        if key_type != KeyType::Rsa || pkcs8 {
+            eprintln!("key shares are supported for RSA keys in their native format only");
+            return ExitCode::from(2);
+        }
+        let size = if size == 0 { 2048 } else { size };
+        let shares = if shares == 0 { threshold } else { shares };
+        let threshold = if threshold == 0 { shares } else { threshold };
+        return gen_shares(size, shares, threshold, form);
     }
 
-    println!("gen()");
+    let key = match key_type {
+        KeyType::Rsa => {
+            let size = if size == 0 { 2048 } else { size };
+            RsaPrivateKey::generate(size, safe_primes).map(PrivateKey::Rsa)
+        }
+        KeyType::Ecdsa => {
+            let size = if size == 0 { 384 } else { size };
+            match Curve::from_bits(size) {
+                Some(curve) => { Ok(PrivateKey::Ecdsa(EcPrivateKey::generate(curve))) }
+                None => {
+                    eprintln!("invalid ECDSA key size {}, must be 256, 384 or 521", size);
+                    return ExitCode::from(2);
+                }
+            }
+        }
+        KeyType::Ed25519 => { Ok(PrivateKey::Eddsa(EdPrivateKey::generate(EdCurve::Ed25519))) }
+        KeyType::Ed448   => { Ok(PrivateKey::Eddsa(EdPrivateKey::generate(EdCurve::Ed448))) }
+        KeyType::MlDsa44 => { Ok(PrivateKey::MlDsa(MlDsaPrivateKey::generate(MlDsaParams::MlDsa44))) }
+        KeyType::MlDsa65 => { Ok(PrivateKey::MlDsa(MlDsaPrivateKey::generate(MlDsaParams::MlDsa65))) }
+        KeyType::MlDsa87 => { Ok(PrivateKey::MlDsa(MlDsaPrivateKey::generate(MlDsaParams::MlDsa87))) }
+        KeyType::MlKem512 => {
+            Ok(PrivateKey::MlKem(MlKemPrivateKey::generate(MlKemParams::MlKem512)))
+        }
+        KeyType::MlKem768 => {
+            Ok(PrivateKey::MlKem(MlKemPrivateKey::generate(MlKemParams::MlKem768)))
+        }
+        KeyType::MlKem1024 => {
+            Ok(PrivateKey::MlKem(MlKemPrivateKey::generate(MlKemParams::MlKem1024)))
+        }
+        KeyType::SlhDsa(params) => { Ok(PrivateKey::SlhDsa(SlhDsaPrivateKey::generate(params))) }
+        KeyType::Composite(params) => {
+            CompositePrivateKey::generate(params).map(PrivateKey::Composite)
+        }
+    };
+    let key = match key {
+        Ok(key) => { key }
+        Err(e) => {
+            eprintln!("private key generation failed: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let encoded = if pkcs8 {
+        key.to_pkcs8().map(|der| (der, CredType::PrivateKey))
+    } else {
+        key.to_der()
+    };
+    let (der, cred_type) = match encoded {
+        Ok(encoded) => { encoded }
+        Err(e) => {
+            eprintln!("encoding private key failed: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Err(e) = write_output(&der, form, cred_type) {
+        eprintln!("{}", e);
+        return ExitCode::FAILURE;
+    }
     return ExitCode::SUCCESS;
 }
 
@@ -48,13 +185,27 @@ pub fn pki_gen(matches: &Matches) -> ExitCode
 inventory::submit!
 {
     let brief: &'static[&'static str] = &[
-        "[--type rsa|ecdsa|ed25519|ed448] [--size bits] [--safe-primes]",
-        "[--shares n] [--threshold l] [--outform der|pem]"
+        "[--type rsa|ecdsa|ed25519|ed448|mldsa44|mldsa65|mldsa87|",
+        "mlkem512|mlkem768|mlkem1024|",
+        "slhdsa-sha2-128s|slhdsa-sha2-128f|slhdsa-sha2-192s|slhdsa-sha2-192f|",
+        "slhdsa-sha2-256s|slhdsa-sha2-256f|slhdsa-shake-128s|slhdsa-shake-128f|",
+        "slhdsa-shake-192s|slhdsa-shake-192f|slhdsa-shake-256s|slhdsa-shake-256f|",
+        "mldsa44-rsa2048-pss|mldsa44-rsa2048-pkcs15|mldsa44-ed25519|mldsa44-ecdsa-p256|",
+        "mldsa65-rsa3072-pss|mldsa65-rsa3072-pkcs15|mldsa65-rsa4096-pss|",
+        "mldsa65-rsa4096-pkcs15|mldsa65-ecdsa-p256|mldsa65-ecdsa-p384|mldsa65-ed25519|",
+        "mldsa87-ecdsa-p384|mldsa87-ed448|mldsa87-rsa3072-pss|mldsa87-rsa4096-pss|",
+        "mldsa87-ecdsa-p521]",
+        "[--size bits] [--safe-primes]",
+        "[--shares n] [--threshold l] [--pkcs8] [--outform der|pem]"
     ];
     let options: &'static[Opt] = &[
         Opt { long: "help",        short: "h", arg: 0, descr: "show usage information" },
         Opt { long: "type",        short: "t", arg: 1, descr: "type of key, default: rsa" },
         Opt { long: "size",        short: "s", arg: 1, descr: "keylength in bits, default: rsa 2048, ecdsa 384" },
+        Opt { long: "safe-primes", short: "p", arg: 0, descr: "generate safe primes (RSA only)" },
+        Opt { long: "shares",      short: "n", arg: 1, descr: "number of private RSA key shares" },
+        Opt { long: "threshold",   short: "l", arg: 1, descr: "minimum number of participating RSA key shares" },
+        Opt { long: "pkcs8",       short: "8", arg: 0, descr: "encode private key in PKCS#8 format" },
         Opt { long: "outform",     short: "f", arg: 1, descr: "encoding of generated private key, default: der" },
     ];
     Command::new(pki_gen, "g", "gen",
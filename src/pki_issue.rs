@@ -14,230 +14,391 @@
 // for more details.
 
 use std::process::ExitCode;
+use chrono::Utc;
 use getopts::Matches;
-use chrono::{DateTime, Utc};
 use pki::{Command, Opt};
-use pki::X509_NO_CONSTRAINT;
+use pki::ct::Sct;
+use pki::crypto::hash::HashAlgorithm;
+use pki::dn::Dn;
+use pki::extensions::{merge_extensions, precert_poison, sct_list};
+use pki::keys::{KeyType, PrivateKey, PublicKey};
+use pki::oid::OID_CT_PRECERT_POISON;
+use pki::pem::{Encoding, CredType, load_block, load_blocks, write_output};
+use pki::pkcs10::CertificateRequest;
+use pki::signature::{SignatureScheme, parse_signature_options};
+use pki::template::{CertTemplate, parse_crl_points};
+use pki::threshold::PartialSignature;
+use pki::verify::check_issuance;
+use pki::x509::{Certificate, Extension, TbsCertificate};
+
+/// Options of the command
+const OPTIONS: &[Opt] = &[
+    Opt { long: "help",            short: "h", arg: 0, descr: "show usage information" },
+    Opt { long: "in",              short: "i", arg: 1, descr: "key/request file to issue, default: stdin" },
+    Opt { long: "type",            short: "t", arg: 1, descr: "type of input, default: pub" },
+    Opt { long: "cacert",          short: "c", arg: 1, descr: "CA certificate file" },
+    Opt { long: "cakey",           short: "k", arg: 1, descr: "CA private key or RSA key share file" },
+    Opt { long: "cakeyid",         short: "x", arg: 1, descr: "smartcard or TPM CA private key object handle" },
+    Opt { long: "dn",              short: "d", arg: 1, descr: "distinguished name to include as subject" },
+    Opt { long: "san",             short: "a", arg: 2, descr: "subjectAltName to include in certificate" },
+    Opt { long: "lifetime",        short: "l", arg: 1, descr: "days the certificate is valid, default: 1095" },
+    Opt { long: "not-before",      short: "F", arg: 1, descr: "date/time the validity of the cert starts" },
+    Opt { long: "not-after",       short: "T", arg: 1, descr: "date/time the validity of the cert ends" },
+    Opt { long: "dateform",        short: "D", arg: 1, descr: "strptime(3) input format, default: ISO 8601" },
+    Opt { long: "serial",          short: "s", arg: 1, descr: "serial number in hex, default: random" },
+    Opt { long: "ca",              short: "b", arg: 0, descr: "include CA basicConstraint, default: no" },
+    Opt { long: "pathlen",         short: "p", arg: 1, descr: "set path length constraint" },
+    Opt { long: "addrblock",       short: "B", arg: 2, descr: "RFC 3779 addrBlock to include" },
+    Opt { long: "nc-permitted",    short: "n", arg: 2, descr: "add permitted NameConstraint" },
+    Opt { long: "nc-excluded",     short: "N", arg: 2, descr: "add excluded NameConstraint" },
+    Opt { long: "cert-policy",     short: "P", arg: 2, descr: "certificatePolicy OID to include" },
+    Opt { long: "cps-uri",         short: "C", arg: 2, descr: "Certification Practice statement URI for certificatePolicy" },
+    Opt { long: "user-notice",     short: "U", arg: 2, descr: "user notice for certificatePolicy" },
+    Opt { long: "policy-mapping",  short: "M", arg: 2, descr: "policyMapping from issuer to subject OID" },
+    Opt { long: "policy-explicit", short: "E", arg: 1, descr: "requireExplicitPolicy constraint" },
+    Opt { long: "policy-inhibit",  short: "H", arg: 1, descr: "inhibitPolicyMapping constraint" },
+    Opt { long: "policy-any",      short: "A", arg: 1, descr: "inhibitAnyPolicy constraint" },
+    Opt { long: "flag",            short: "e", arg: 2, descr: "include extendedKeyUsage flag or key purpose OID" },
+    Opt { long: "crl",             short: "u", arg: 2, descr: "CRL distribution point URI to include" },
+    Opt { long: "crlissuer",       short: "I", arg: 2, descr: "CRL Issuer for CRL at distribution point" },
+    Opt { long: "ocsp",            short: "o", arg: 2, descr: "OCSP AuthorityInfoAccess URI to include" },
+    Opt { long: "caissuers",       short: "J", arg: 2, descr: "CA issuers AuthorityInfoAccess URI to include" },
+    Opt { long: "digest",          short: "g", arg: 1, descr: "digest for signature creation, default: key-specific" },
+    Opt { long: "rsa-padding",     short: "R", arg: 1, descr: "padding for RSA signatures, default: pkcs1" },
+    Opt { long: "critical",        short: "X", arg: 1, descr: "unknown critical extension OID to include for test purposes" },
+    Opt { long: "ext",             short: "V", arg: 2, descr: "custom extension to include or replace a generated one" },
+    Opt { long: "profile-file",    short: "y", arg: 1, descr: "file with named certificate profiles" },
+    Opt { long: "profile",         short: "Y", arg: 1, descr: "certificate profile to apply, options override all but its flags" },
+    Opt { long: "force",           short: "W", arg: 0, descr: "issue even if the CA constraints are violated" },
+    Opt { long: "precert",         short: "Q", arg: 0, descr: "issue an RFC 6962 precertificate with poison extension" },
+    Opt { long: "sct",             short: "S", arg: 2, descr: "SCT to embed in the final cert of a precert input" },
+    Opt { long: "ct-log",          short: "L", arg: 2, descr: "local test log key signing an SCT to embed" },
+    Opt { long: "partial",         short: "m", arg: 0, descr: "create a partial signature with an RSA key share" },
+    Opt { long: "outform",         short: "f", arg: 1, descr: "encoding of generated cert, default: der" },
+];
+
+/// Options accepted when issuing the final certificate of a precertificate
+const FINAL_OPTIONS: &[&str] = &["help", "in", "type", "cacert", "cakey", "cakeyid", "sct",
+                                 "ct-log", "partial", "outform"];
+
+/// Subject information taken from the input file
+struct Subject {
+    /// subject public key
+    public_key: PublicKey,
+    /// DER encoded subject Name of a PKCS#10 request
+    name: Option<Vec<u8>>,
+    /// extensions requested by a PKCS#10 request
+    extensions: Vec<Extension>,
+}
+
+//
+// Load the subject public key, and the subject name and requested
+// extensions of a PKCS#10 request.
+//
+fn load_subject(file: Option<&str>, in_type: &str) -> Result<Subject, String>
+{
+    let block = load_block(file)?;
+    match in_type {
+        "pub" => {
+            let key = PublicKey::from_spki(&block.der)
+                                .map_err(|e| format!("parsing public key failed: {}", e))?;
+            Ok(Subject { public_key: key, name: None, extensions: Vec::new() })
+        }
+        "pkcs10" => {
+            let req = CertificateRequest::from_der(&block.der)
+                                .map_err(|e| format!("parsing PKCS#10 request failed: {}", e))?;
+            if !req.verify_signature() {
+                return Err("PKCS#10 request signature verification failed".to_string());
+            }
+            Ok(Subject { public_key: req.public_key, name: Some(req.subject),
+                         extensions: req.extensions })
+        }
+        _ => {
+            let key = PrivateKey::from_der(&block.der)
+                                .map_err(|e| format!("parsing private key failed: {}", e))?;
+            if let Some(expected) = KeyType::parse(in_type) && key.key_type() != expected {
+                return Err(format!("expected {} private key but got {} key",
+                                   expected, key.key_type()));
+            }
+            Ok(Subject { public_key: key.public_key(), name: None, extensions: Vec::new() })
+        }
+    }
+}
+
+//
+// Rebuild the tbsCertificate of a precertificate issued by the CA, with the
+// poison extension replaced by the SCTs of the logs, if any (RFC 6962, 3.1).
+// SCTs are loaded from files or signed by local test log keys.  The
+// signature scheme of the precertificate is retained, as it is covered by
+// the SCTs.
+//
+fn final_tbs(file: Option<&str>, ca: &Certificate, sct_files: &[String], log_files: &[String])
+            -> Result<(TbsCertificate, SignatureScheme), String>
+{
+    let block = load_block(file)?;
+    let precert = Certificate::from_der(&block.der)
+                              .map_err(|e| format!("parsing precertificate failed: {}", e))?;
+    if precert.issuer != ca.subject || !precert.verify_signature(&ca.public_key) {
+        return Err("precertificate has not been issued by the CA".to_string());
+    }
+    let poison = precert.extensions.iter().position(|e| e.oid == OID_CT_PRECERT_POISON)
+                                   .ok_or("certificate lacks the precertificate poison extension")?;
+    let mut tbs = TbsCertificate {
+        serial: precert.serial, issuer: precert.issuer, not_before: precert.not_before,
+        not_after: precert.not_after, subject: precert.subject, public_key: precert.public_key,
+        extensions: precert.extensions,
+    };
+    tbs.extensions.remove(poison);
+
+    let mut scts = Vec::new();
+    for file in sct_files {
+        scts.push(Sct::load(file)?);
+    }
+    if !log_files.is_empty() {
+        let issuer_key_hash = HashAlgorithm::Sha256.hash(&ca.public_key.to_spki());
+        let entry = tbs.encode(precert.scheme)?;
+        let timestamp = Utc::now().timestamp_millis() as u64;
+        for file in log_files {
+            let log_key = load_block(Some(file)).and_then(|block| PrivateKey::from_der(&block.der))
+                                  .map_err(|e| format!("loading CT log key '{}' failed: {}", file, e))?;
+            scts.push(Sct::sign_precert(&log_key, &issuer_key_hash, &entry, timestamp)?);
+        }
+    }
+    if !scts.is_empty() {
+        tbs.extensions.insert(poison, sct_list(&scts)?);
+    }
+    return Ok((tbs, precert.scheme));
+}
+
+//
+// Sign a certificate and write it in the requested encoding, or write the
+// partial signature of an RSA key share.
+//
+fn sign_certificate(tbs: &TbsCertificate, key: &PrivateKey, scheme: SignatureScheme,
+                    partial: bool, form: Encoding) -> ExitCode
+{
+    if partial {
+        let partial = match tbs.encode(scheme).and_then(|tbs| PartialSignature::new(key, scheme, tbs)) {
+            Ok(partial) => { partial }
+            Err(e) => {
+                eprintln!("generating partial signature failed: {}", e);
+                return ExitCode::FAILURE;
+            }
+        };
+        if let Err(e) = write_output(&partial.to_der(), form, CredType::RsaPartialSignature) {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+        return ExitCode::SUCCESS;
+    }
+    let cert = match tbs.sign(key, scheme) {
+        Ok(cert) => { cert }
+        Err(e) => {
+            eprintln!("generating certificate failed: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Err(e) = write_output(&cert.der, form, CredType::Certificate) {
+        eprintln!("{}", e);
+        return ExitCode::FAILURE;
+    }
+    return ExitCode::SUCCESS;
+}
 
 //
 // Issue a certificate using a CA certificate and key.
 //
 pub fn pki_issue(matches: &Matches) -> ExitCode
 {
-   let cacert = match matches.opt_str("c") {
+    let cacert = match matches.opt_str("c") {
         Some(v) => { v }
         None => {
-            println!("option '--cacert' is required");
+            eprintln!("option '--cacert' is required");
             return ExitCode::from(2);
         }
     };
-    println!("option: --cacert {}", cacert);
-
-    let cakey = match matches.opt_str("k") {
+    let cakey = matches.opt_str("k");
+    if matches.opt_present("x") {
+        if cakey.is_some() {
+            eprintln!("options '--cakey' and '--cakeyid' can't be set both");
+            return ExitCode::from(2);
+        }
+        eprintln!("smartcard or TPM private keys are not supported");
+        return ExitCode::FAILURE;
+    }
+    let cakey = match cakey {
         Some(v) => { v }
-        None => { "".to_string() }
+        None => {
+            eprintln!("option '--cakey' or '--cakeyid' is required");
+            return ExitCode::from(2);
+        }
     };
-    println!("option: --cakey {}", cakey);
 
-    let cakeyid = match matches.opt_str("x") {
+    let in_type = match matches.opt_str("t") {
         Some(v) => { v }
-        None => { "".to_string() }
+        None => { "pub".to_string() }
     };
-    println!("option: --cakeyid {}", cakeyid);
-
-    if cakey.is_empty() && cakeyid.is_empty() {
-       println!("option '--cakey' or '--cakeyid' is required");
+    if KeyType::parse(&in_type).is_none() &&
+       !["pub", "pkcs10", "precert", "priv"].contains(&in_type.as_str()) {
+        eprintln!("invalid input type '{}'", in_type);
         return ExitCode::from(2);
     }
-
-    if !cakey.is_empty() && !cakeyid.is_empty() {
-        println!("options '--cakey' and '--cakeyid' can't be set both");
+    let precert = matches.opt_present("Q");
+    let partial = matches.opt_present("m");
+    let sct_files = matches.opt_strs("S");
+    let log_files = matches.opt_strs("L");
+    if in_type == "precert" {
+        // the final certificate is fully determined by the precertificate
+        if let Some(opt) = OPTIONS.iter().find(|o| !FINAL_OPTIONS.contains(&o.long) &&
+                                                   matches.opt_present(o.long)) {
+            eprintln!("option '--{}' can't be used with a precertificate as input", opt.long);
+            return ExitCode::from(2);
+        }
+    } else if !sct_files.is_empty() || !log_files.is_empty() {
+        eprintln!("options '--sct' and '--ct-log' require a precertificate as input");
         return ExitCode::from(2);
     }
 
-   if matches.opt_present("i") {
-        let file = matches.opt_str("i").unwrap();
-        println!("option: --in {}", file);
-    } else {
-        println!("option '--in' missing: get input from stdin");
-    }
-
-    if matches.opt_present("t") {
-        let in_type = matches.opt_str("t").unwrap();
-        println!("option: --type {}", in_type);
-    }
-
-    if matches.opt_present("d") {
-        let dn = matches.opt_str("d").unwrap();
-        println!("option: --dn {}", dn);
-    }
-
-    let san: Vec<String> = matches.opt_strs("a");
-    for s in &san
-    {
-        println!("option: --san {}", s);
-    }
-
-    let lifetime: i64 = 24 * 60 * 60 * match matches.opt_str("l") {
-        Some(string) => { string.parse().unwrap() }
-        None => { 1095 } // days
+    let mut template = match CertTemplate::from_matches(matches) {
+        Ok(template) => { template }
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(2);
+        }
     };
-    println!("option: --lifetime {} seconds", lifetime);
-
-    let dateform = match matches.opt_str("D") {
-        Some(v) => { v }
-        None => { "%Y-%m-%dT%H:%M:%S%z".to_string() }
+    template.crl_points = match parse_crl_points(matches) {
+        Ok(points) => { points }
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(2);
+        }
     };
-    println!("option: --dateform {}", dateform);
-
-    let datenb: i64 = match matches.opt_str("F") {
-        Some(v) => {
-            match DateTime::parse_from_str(v.as_str(), dateform.as_str()) {
-                Ok(dt) => { dt.timestamp() }
+    template.ca_issuers = matches.opt_strs("J");
+    let name = match &template.dn {
+        Some(dn) => {
+            match Dn::parse(dn) {
+                Ok(dn) => { Some(dn.to_der()) }
                 Err(e) => {
-                    println!("{e}");
-                    return ExitCode::FAILURE;
+                    eprintln!("invalid distinguished name: {}", e);
+                    return ExitCode::from(2);
                 }
             }
         }
-        None => { Utc::now().timestamp() }
+        None if in_type != "pkcs10" && in_type != "precert" => {
+            eprintln!("option '--dn' is required");
+            return ExitCode::from(2);
+        }
+        None => { None }
     };
-    println!("option: --not-before {}", datenb);
 
-    let datena: i64 = match matches.opt_str("T") {
-        Some(v) => {
-            match DateTime::parse_from_str(v.as_str(), dateform.as_str()) {
-                Ok(dt) => { dt.timestamp() }
-                Err(e) => {
-                    println!("{e}");
-                    return ExitCode::FAILURE;
-                }
-            }
+    let (digest, pss) = match parse_signature_options(matches.opt_str("g").as_deref(),
+                                                      matches.opt_str("R").as_deref()) {
+        Ok(options) => { options }
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(2);
         }
-        None => { datenb + lifetime }
     };
-    println!("option: --not-after  {}", datena);
-
-    if matches.opt_present("s") {
-        let serial = matches.opt_str("s").unwrap();
-        println!("option: --serial {}", serial);
-    }
 
-    let ca: bool = matches.opt_present("b");
-    println!("ca flag:  {}", ca);
-
-    let pathlen: u32 = match matches.opt_str("p") {
-        Some(string) => { string.parse().unwrap() }
-        None => { X509_NO_CONSTRAINT }
+    let form = match Encoding::from_matches(matches, Encoding::Der) {
+        Ok(form) => { form }
+        Err(code) => { return code; }
     };
-    println!("option: --pathlen {}", pathlen);
-
-    let flags: Vec<String> = matches.opt_strs("e");
-    for f in &flags
-     {
-            println!("option: --flag {}", f);
-    }
-
-    let crl_uris: Vec<String> = matches.opt_strs("u");
-    for u in &crl_uris
-    {
-        println!("option: --crl {}", u);
-    }
-
-    let crl_issuers: Vec<String> = matches.opt_strs("I");
-    for i in &crl_issuers
-    {
-        println!("option: --crlissuer {}", i);
-    }
-
-    let ocsp_uris: Vec<String> = matches.opt_strs("o");
-    for u in &ocsp_uris
-    {
-        println!("option: --ocsp {}", u);
-    }
-
-    let addrblocks: Vec<String> = matches.opt_strs("B");
-    for b in &addrblocks
-    {
-         println!("option: --addrblock {}", b);
-    }
-
-    let permitted: Vec<String> = matches.opt_strs("n");
-    for nc in &permitted
-    {
-        println!("option: --nc-permitted {}", nc);
-    }
-
-    let excluded: Vec<String> = matches.opt_strs("N");
-    for nc in &excluded
-    {
-        println!("option: --nc-excluded {}", nc);
-    }
-
-    let mappings: Vec<String> = matches.opt_strs("M");
-    for m in &mappings
-    {
-        println!("option: --policy-mapping {}", m);
-    }
 
-    let require_explicit: u32 = match matches.opt_str("E") {
-        Some(string) => { string.parse().unwrap() }
-        None => { X509_NO_CONSTRAINT }
-     };
-    println!("option: --policy-explicit {}", require_explicit);
-
-    let inhibit_mapping: u32 = match matches.opt_str("H") {
-        Some(string) => { string.parse().unwrap() }
-        None => { X509_NO_CONSTRAINT }
-     };
-    println!("option: --policy-inhibit {}", inhibit_mapping);
-
-    let inhibit_any: u32 = match matches.opt_str("A") {
-        Some(string) => { string.parse().unwrap() }
-        None => { X509_NO_CONSTRAINT }
-     };
-    println!("option: --policy-any {}", inhibit_any);
-
-    let policies: Vec<String> = matches.opt_strs("P");
-    for p in &policies
-    {
-        println!("option: --cert-policy {}", p);
-    }
-
-    let cps_uri: Vec<String> = matches.opt_strs("C");
-    for u in &cps_uri
-    {
-        println!("option: --cps-uri {}", u);
+    let ca = match load_block(Some(&cacert)).and_then(|block| Certificate::from_der(&block.der)) {
+        Ok(cert) => { cert }
+        Err(e) => {
+            eprintln!("loading CA certificate '{}' failed: {}", cacert, e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let key = match load_blocks(Some(&cakey)).and_then(|blocks| PrivateKey::from_blocks(&blocks)) {
+        Ok(key) => { key }
+        Err(e) => {
+            eprintln!("loading CA private key '{}' failed: {}", cakey, e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if key.public_key().keyid() != ca.public_key.keyid() {
+        eprintln!("CA private key does not match CA certificate");
+        return ExitCode::FAILURE;
     }
+    let scheme = match SignatureScheme::select(key.key_type(), key.bits(), digest, pss) {
+        Ok(scheme) => { scheme }
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
 
-    let user_notice: Vec<String> = matches.opt_strs("U");
-    for n in &user_notice
-    {
-        println!("option: --user-notice {}", n);
+    // issue the final certificate of a precertificate issued before
+    if in_type == "precert" {
+        return match final_tbs(matches.opt_str("i").as_deref(), &ca, &sct_files, &log_files) {
+            Ok((tbs, scheme)) => { sign_certificate(&tbs, &key, scheme, partial, form) }
+            Err(e) => {
+                eprintln!("{}", e);
+                ExitCode::FAILURE
+            }
+        };
     }
 
-    if matches.opt_present("X") {
-        let critical_extension_oid = matches.opt_str("X").unwrap();
-        println!("option: --critical {}", critical_extension_oid);
-    }
+    let requested = match load_subject(matches.opt_str("i").as_deref(), &in_type) {
+        Ok(requested) => { requested }
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let subject = match name.or(requested.name) {
+        Some(subject) if subject.len() > 2 => { subject }
+        _ => {
+            eprintln!("PKCS#10 request has an empty subject, option '--dn' is required");
+            return ExitCode::FAILURE;
+        }
+    };
 
-    if matches.opt_present("g") {
-        let digest = matches.opt_str("g").unwrap();
-        println!("option: --digest {}", digest);
+    let keyid = match ca.key_identifier() {
+        Ok(keyid) => { keyid }
+        Err(e) => {
+            eprintln!("invalid subjectKeyIdentifier in CA certificate: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut extensions = match template.extensions(&requested.public_key, &keyid,
+                                                    &requested.extensions) {
+        Ok(extensions) => { extensions }
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if precert && let Err(e) = merge_extensions(&mut extensions, vec![precert_poison()]) {
+        eprintln!("{}", e);
+        return ExitCode::FAILURE;
     }
 
-    if matches.opt_present("R") {
-        let padding = matches.opt_str("R").unwrap();
-        println!("option: --rsa-padding {}", padding);
+    // shorten the default lifetime to that of the CA, an explicit --lifetime
+    // or --not-after exceeding it is a violation of the CA constraints
+    if !matches.opt_present("l") && !matches.opt_present("T") && template.not_after > ca.not_after {
+        template.not_after = ca.not_after.max(template.not_before);
+        eprintln!("lifetime clamped to the notAfter time of the CA certificate");
     }
-
-    if matches.opt_present("f") {
-        let form = matches.opt_str("f").unwrap();
-        println!("option: --outform {}", form);
+    let tbs = TbsCertificate {
+        serial: template.serial, issuer: ca.subject.clone(), not_before: template.not_before,
+        not_after: template.not_after, subject, public_key: requested.public_key, extensions,
+    };
+    let violations = check_issuance(&ca, &tbs, Utc::now().timestamp());
+    if !violations.is_empty() {
+        let force = matches.opt_present("W");
+        for violation in &violations {
+            eprintln!("{}: {}", if force { "warning" } else { "error" }, violation);
+        }
+        if !force {
+            eprintln!("certificate not issued, use --force to override the CA constraints");
+            return ExitCode::FAILURE;
+        }
     }
-
-    println!("issue()");
-    return ExitCode::SUCCESS;
+    return sign_certificate(&tbs, &key, scheme, partial, form);
 }
 
 //
@@ -246,55 +407,34 @@ pub fn pki_issue(matches: &Matches) -> ExitCode
 inventory::submit!
 {
     let brief: &'static[&'static str] = &[
-        "[--in file] [--type pub|pkcs10|priv|rsa|ecdsa|ed25519|ed448]",
+        "[--in file]",
+        "[--type pub|pkcs10|precert|priv|rsa|ecdsa|ed25519|ed448|mldsa44|mldsa65|mldsa87|",
+        "mlkem512|mlkem768|mlkem1024|",
+        "slhdsa-sha2-128s|slhdsa-sha2-128f|slhdsa-sha2-192s|slhdsa-sha2-192f|",
+        "slhdsa-sha2-256s|slhdsa-sha2-256f|slhdsa-shake-128s|slhdsa-shake-128f|",
+        "slhdsa-shake-192s|slhdsa-shake-192f|slhdsa-shake-256s|slhdsa-shake-256f|",
+        "mldsa44-rsa2048-pss|mldsa44-rsa2048-pkcs15|mldsa44-ed25519|mldsa44-ecdsa-p256|",
+        "mldsa65-rsa3072-pss|mldsa65-rsa3072-pkcs15|mldsa65-rsa4096-pss|",
+        "mldsa65-rsa4096-pkcs15|mldsa65-ecdsa-p256|mldsa65-ecdsa-p384|mldsa65-ed25519|",
+        "mldsa87-ecdsa-p384|mldsa87-ed448|mldsa87-rsa3072-pss|mldsa87-rsa4096-pss|",
+        "mldsa87-ecdsa-p521]",
         " --cakey file|--cakeyid hex --cacert file [--dn subject-dn]",
         "[--san subjectAltName]+ [--lifetime days] [--serial hex]",
         "[--not-before datetime] [--not-after datetime] [--dateform form]",
         "[--ca] [--pathlen len] [--addrblock block]+",
-        "[--flag serverAuth|clientAuth|crlSign|ocspSigning|msSmartcardLogon]+",
-        "[--crl uri [--crlissuer i]]+ [--ocsp uri]+ [--nc-permitted name]+",
-        "[--nc-excluded name]+ [--policy-mapping issuer-oid:subject-oid]+",
+        "[--flag serverAuth|clientAuth|crlSign|ocspSigning|msSmartcardLogon|codeSigning|",
+        "timeStamping|emailProtection|ikeIntermediate|oid]+",
+        "[--crl uri [--crlissuer i]+]+ [--ocsp uri]+ [--caissuers uri]+",
+        "[--nc-permitted name]+ [--nc-excluded name]+",
+        "[--policy-mapping issuer-oid:subject-oid]+",
         "[--policy-explicit len] [--policy-inhibit len] [--policy-any len]",
         "[--cert-policy oid [--cps-uri uri] [--user-notice text]]+",
         "[--digest md5|sha1|sha224|sha256|sha384|sha512|sha3_224|sha3_256|sha3_384|sha3_512]",
-        "[--rsa-padding pkcs1|pss] [--critical oid]",
-        "[--outform der|pem]"
-    ];
-    let options: &'static[Opt] = &[
-        Opt { long: "help",            short: "h", arg: 0, descr: "show usage information" },
-        Opt { long: "in",              short: "i", arg: 1, descr: "key/request file to issue, default: stdin" },
-        Opt { long: "type",            short: "t", arg: 1, descr: "type of input, default: pub" },
-        Opt { long: "cacert",          short: "c", arg: 1, descr: "CA certificate file" },
-        Opt { long: "cakey",           short: "k", arg: 1, descr: "CA private key file" },
-        Opt { long: "cakeyid",         short: "x", arg: 1, descr: "smartcard or TPM CA private key object handle" },
-        Opt { long: "dn",              short: "d", arg: 1, descr: "distinguished name to include as subject" },
-        Opt { long: "san",             short: "a", arg: 2, descr: "subjectAltName to include in certificate" },
-        Opt { long: "lifetime",        short: "l", arg: 1, descr: "days the certificate is valid, default: 1095" },
-        Opt { long: "not-before",      short: "F", arg: 1, descr: "date/time the validity of the cert starts" },
-        Opt { long: "not-after",       short: "T", arg: 1, descr: "date/time the validity of the cert ends" },
-        Opt { long: "dateform",        short: "D", arg: 1, descr: "strptime(3) input format, default: %d.%m.%y %T" },
-        Opt { long: "serial",          short: "s", arg: 1, descr: "serial number in hex, default: random" },
-        Opt { long: "ca",              short: "b", arg: 0, descr: "include CA basicConstraint, default: no" },
-        Opt { long: "pathlen",         short: "p", arg: 1, descr: "set path length constraint" },
-        Opt { long: "addrblock",       short: "B", arg: 2, descr: "RFC 3779 addrBlock to include" },
-        Opt { long: "nc-permitted",    short: "n", arg: 2, descr: "add permitted NameConstraint" },
-        Opt { long: "nc-excluded",     short: "N", arg: 2, descr: "add excluded NameConstraint" },
-        Opt { long: "cert-policy",     short: "P", arg: 2, descr: "certificatePolicy OID to include" },
-        Opt { long: "cps-uri",         short: "C", arg: 2, descr: "Certification Practice statement URI for certificatePolicy" },
-        Opt { long: "user-notice",     short: "U", arg: 2, descr: "user notice for certificatePolicy" },
-        Opt { long: "policy-mapping",  short: "M", arg: 2, descr: "policyMapping from issuer to subject OID" },
-        Opt { long: "policy-explicit", short: "E", arg: 1, descr: "requireExplicitPolicy constraint" },
-        Opt { long: "policy-inhibit",  short: "H", arg: 1, descr: "inhibitPolicyMapping constraint" },
-        Opt { long: "policy-any",      short: "A", arg: 1, descr: "inhibitAnyPolicy constraint" },
-        Opt { long: "flag",            short: "e", arg: 2, descr: "include extendedKeyUsage flag" },
-        Opt { long: "crl",             short: "u", arg: 2, descr: "CRL distribution point URI to include" },
-        Opt { long: "crlissuer",       short: "I", arg: 2, descr: "CRL Issuer for CRL at distribution point" },
-        Opt { long: "ocsp",            short: "o", arg: 2, descr: "OCSP AuthorityInfoAccess URI to include" },
-        Opt { long: "digest",          short: "g", arg: 1, descr: "digest for signature creation, default: key-specific" },
-        Opt { long: "rsa-padding",     short: "R", arg: 1, descr: "padding for RSA signatures, default: pkcs1" },
-        Opt { long: "critical",        short: "X", arg: 1, descr: "critical extension OID to include" },
-        Opt { long: "outform",         short: "f", arg: 1, descr: "encoding of generated cert, default: der" },
+        "[--rsa-padding pkcs1|pss] [--critical oid] [--ext oid[:critical]=hex|file|utf8:text]+",
+        "[--profile-file file --profile name] [--force]",
+        "[--precert|[--sct file]+ [--ct-log key]+]",
+        "[--partial] [--outform der|pem]"
     ];
     Command::new(pki_issue, "i", "issue",
-                "issue an attribute certificate", brief, options)
+                "issue a certificate using a CA certificate and key", brief, OPTIONS)
 }
@@ -16,6 +16,7 @@
 use std::process::ExitCode;
 use getopts::Matches;
 use pki::{Command, Opt};
+use pki::pem::load_blocks;
 
 //
 // Print a single keyid in the requested format.
@@ -28,6 +29,11 @@ pub fn pki_keyid(matches: &Matches) -> ExitCode
     };
     println!("option: --in {}", file);
 
+    if !file.is_empty() && let Err(e) = load_blocks(Some(&file)) {
+        println!("{}", e);
+        return ExitCode::FAILURE;
+    }
+
     let keyid = match matches.opt_str("x") {
         Some(v) => { v }
         None => { "".to_string() }
@@ -15,6 +15,7 @@
 use std::process::ExitCode;
 use getopts::Matches;
 use pki::{Command, Opt};
+use pki::pem::load_blocks;
 
 //
 // Verifies the optional OCSP request signature generated by an OCSP requestor.
@@ -23,7 +24,14 @@ pub fn pki_ocsp(matches: &Matches) -> ExitCode
 {
     if matches.opt_present("i") {
         let file = matches.opt_str("i").unwrap();
-        println!("option: --in {}", file);
+        let blocks = match load_blocks(Some(&file)) {
+            Ok(blocks) => { blocks }
+            Err(e) => {
+                println!("{}", e);
+                return ExitCode::FAILURE;
+            }
+        };
+        println!("option: --in {} ({} objects)", file, blocks.len());
     } else {
         println!("option '--in' missing: get input from stdin");
     }
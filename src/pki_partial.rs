@@ -0,0 +1,133 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+use std::process::ExitCode;
+use getopts::Matches;
+use pki::{Command, Opt};
+use pki::keys::PrivateKey;
+use pki::pem::{Encoding, CredType, load_blocks, write_output};
+use pki::threshold::{PartialSignature, combine_partials};
+
+//
+// Load all partial signatures contained in the given files or stdin.
+//
+fn load_partials(files: &[String]) -> Result<Vec<PartialSignature>, String>
+{
+    let mut blocks = Vec::new();
+    if files.is_empty() {
+        blocks = load_blocks(None)?;
+    }
+    for file in files {
+        blocks.extend(load_blocks(Some(file)).map_err(|e| format!("{}: {}", file, e))?);
+    }
+    return blocks.iter().map(|block| PartialSignature::from_der(&block.der))
+                 .collect::<Result<Vec<_>, _>>()
+                 .map_err(|e| format!("parsing partial signature failed: {}", e));
+}
+
+//
+// Add the partial signature of another RSA key share over the data of an
+// existing partial signature.
+//
+fn sign(partials: &[PartialSignature], key: Option<&str>, form: Encoding) -> ExitCode
+{
+    let Some(file) = key else {
+        eprintln!("option '--key' is required");
+        return ExitCode::from(2);
+    };
+    let [partial] = partials else {
+        eprintln!("expected a single partial signature but got {}", partials.len());
+        return ExitCode::FAILURE;
+    };
+    let share = match load_blocks(Some(file)).and_then(|blocks| PrivateKey::from_blocks(&blocks)) {
+        Ok(PrivateKey::RsaShare(share)) => { share }
+        Ok(_) => {
+            eprintln!("'{}' does not contain an RSA key share", file);
+            return ExitCode::FAILURE;
+        }
+        Err(e) => {
+            eprintln!("loading RSA key share '{}' failed: {}", file, e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let partial = match partial.cosign(&share) {
+        Ok(partial) => { partial }
+        Err(e) => {
+            eprintln!("generating partial signature failed: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Err(e) = write_output(&partial.to_der(), form, CredType::RsaPartialSignature) {
+        eprintln!("{}", e);
+        return ExitCode::FAILURE;
+    }
+    return ExitCode::SUCCESS;
+}
+
+//
+// Create partial signatures with threshold RSA key shares or combine them
+// into the signed certificate or CRL.
+//
+pub fn pki_partial(matches: &Matches) -> ExitCode
+{
+    if matches.opt_present("s") == matches.opt_present("c") {
+        eprintln!("exactly one of --sign or --combine is required");
+        return ExitCode::from(2);
+    }
+    let form = match Encoding::from_matches(matches, Encoding::Der) {
+        Ok(form) => { form }
+        Err(code) => { return code; }
+    };
+    let partials = match load_partials(&matches.opt_strs("i")) {
+        Ok(partials) => { partials }
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if matches.opt_present("s") {
+        return sign(&partials, matches.opt_str("k").as_deref(), form);
+    }
+
+    let (der, cred_type) = match combine_partials(&partials) {
+        Ok(signed) => { signed }
+        Err(e) => {
+            eprintln!("combining partial signatures failed: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Err(e) = write_output(&der, form, cred_type) {
+        eprintln!("{}", e);
+        return ExitCode::FAILURE;
+    }
+    return ExitCode::SUCCESS;
+}
+
+//
+// Register the command.
+//
+inventory::submit!
+{
+    let brief: &'static[&'static str] = &[
+        "--sign|--combine [--in file]+ [--key file] [--outform der|pem]"
+    ];
+    let options: &'static[Opt] = &[
+        Opt { long: "help",    short: "h", arg: 0, descr: "show usage information" },
+        Opt { long: "sign",    short: "s", arg: 0, descr: "add the partial signature of an RSA key share" },
+        Opt { long: "combine", short: "c", arg: 0, descr: "combine partial signatures into a cert or crl" },
+        Opt { long: "in",      short: "i", arg: 2, descr: "partial signature input file, default: stdin" },
+        Opt { long: "key",     short: "k", arg: 1, descr: "RSA key share signing with --sign" },
+        Opt { long: "outform", short: "f", arg: 1, descr: "encoding of generated output, default: der" },
+    ];
+    Command::new(pki_partial, "m", "partial",
+                "sign or combine threshold RSA partial signatures", brief, options)
+}
@@ -16,6 +16,7 @@
 use std::process::ExitCode;
 use getopts::Matches;
 use pki::{Command, Opt};
+use pki::pem::{Encoding, load_blocks};
 
 //
 // Show info about PKCS#12 container.
@@ -24,7 +25,14 @@ pub fn pki_pkcs12(matches: &Matches) -> ExitCode
 {
     if matches.opt_present("i") {
         let file = matches.opt_str("i").unwrap();
-        println!("option: --in {}", file);
+        let blocks = match load_blocks(Some(&file)) {
+            Ok(blocks) => { blocks }
+            Err(e) => {
+                println!("{}", e);
+                return ExitCode::FAILURE;
+            }
+        };
+        println!("option: --in {} ({} objects)", file, blocks.len());
     } else {
         println!("option '--in' missing: get input from stdin");
     }
@@ -37,10 +45,11 @@ pub fn pki_pkcs12(matches: &Matches) -> ExitCode
         println!("option: --export {}", export);
     }
 
-    if matches.opt_present("f") {
-        let form = matches.opt_str("f").unwrap();
-        println!("option: --outform {}", form);
-    }
+    let form = match Encoding::from_matches(matches, Encoding::Der) {
+        Ok(form) => { form }
+        Err(code) => { return code; }
+    };
+    println!("option: --outform {}", form);
 
     println!("pkcs12()");
     return ExitCode::SUCCESS;
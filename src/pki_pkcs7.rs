@@ -13,15 +13,129 @@
 // or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
 // for more details.
 
+use std::io::{self, Write};
 use std::process::ExitCode;
 use getopts::Matches;
 use pki::{Command, Opt};
+use pki::cms::{cms_decrypt, cms_encrypt};
+use pki::keys::PrivateKey;
+use pki::pem::{Encoding, CredType, is_pem, load_block, pem_decode, read_input, write_output};
+use pki::x509::Certificate;
+
+//
+// Create enveloped-data for the recipient certificates.
+//
+fn encrypt(file: Option<&str>, certs: &[String], form: Encoding) -> ExitCode
+{
+    if certs.is_empty() {
+        eprintln!("encryption requires at least one recipient certificate");
+        return ExitCode::from(2);
+    }
+    let mut recipients = Vec::new();
+    for path in certs {
+        let cert = load_block(Some(path)).and_then(|block| Certificate::from_der(&block.der));
+        match cert {
+            Ok(cert) => { recipients.push(cert); }
+            Err(e) => {
+                eprintln!("loading certificate '{}' failed: {}", path, e);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    let data = match read_input(file) {
+        Ok(data) => { data }
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let der = match cms_encrypt(&data, &recipients) {
+        Ok(der) => { der }
+        Err(e) => {
+            eprintln!("creating enveloped-data failed: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Err(e) = write_output(&der, form, CredType::Cms) {
+        eprintln!("{}", e);
+        return ExitCode::FAILURE;
+    }
+    return ExitCode::SUCCESS;
+}
+
+//
+// Decrypt enveloped-data with the private key of a recipient.
+//
+fn decrypt(file: Option<&str>, key: Option<&str>) -> ExitCode
+{
+    let Some(key) = key else {
+        eprintln!("decryption requires a private key");
+        return ExitCode::from(2);
+    };
+    let key = match load_block(Some(key)).and_then(|block| PrivateKey::from_der(&block.der)) {
+        Ok(key) => { key }
+        Err(e) => {
+            eprintln!("loading private key '{}' failed: {}", key, e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // enveloped-data may use BER indefinite lengths, so don't split DER
+    let data = match read_input(file) {
+        Ok(data) if is_pem(&data) => {
+            match pem_decode(&data) {
+                Ok(mut blocks) if blocks.len() == 1 => { blocks.remove(0).der }
+                Ok(_) => {
+                    eprintln!("expected a single enveloped-data object");
+                    return ExitCode::FAILURE;
+                }
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+        Ok(data) => { data }
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let content = match cms_decrypt(&data, &key) {
+        Ok(content) => { content }
+        Err(e) => {
+            eprintln!("decrypting enveloped-data failed: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Err(e) = io::stdout().write_all(&content) {
+        eprintln!("writing decrypted content failed: {}", e);
+        return ExitCode::FAILURE;
+    }
+    return ExitCode::SUCCESS;
+}
 
 //
 // Wrap/Unwrap PKCs#7 containers.
 //
 pub fn pki_pkcs7(matches: &Matches) -> ExitCode
 {
+    let ops = ["s", "u", "e", "d", "p"].iter().filter(|o| matches.opt_present(o)).count();
+    if ops != 1 {
+        eprintln!("exactly one of --sign, --verify, --encrypt, --decrypt or --show is required");
+        return ExitCode::from(2);
+    }
+    let form = match Encoding::from_matches(matches, Encoding::Der) {
+        Ok(form) => { form }
+        Err(code) => { return code; }
+    };
+    if matches.opt_present("e") {
+        return encrypt(matches.opt_str("i").as_deref(), &matches.opt_strs("c"), form);
+    }
+    if matches.opt_present("d") {
+        return decrypt(matches.opt_str("i").as_deref(), matches.opt_str("k").as_deref());
+    }
+
     if matches.opt_present("i") {
         let file = matches.opt_str("i").unwrap();
         println!("option: --in {}", file);
@@ -79,7 +193,7 @@ inventory::submit!
         "--sign|--verify|--encrypt|--decrypt|--show",
         "[--in file] [--cert file]+ [--key file]",
         "[--digest md5|sha1|sha224|sha256|sha384|sha512|sha3_224|sha3_256|sha3_384|sha3_512]",
-        "[--rsa-padding pkcs1|pss]"
+        "[--rsa-padding pkcs1|pss] [--outform der|pem]"
     ];
     let options: &'static[Opt] = &[
         Opt { long: "help",        short: "h", arg: 0, descr: "show usage information" },
@@ -90,9 +204,10 @@ inventory::submit!
         Opt { long: "show",        short: "p", arg: 0, descr: "show info about PKCS#7, print certificates" },
         Opt { long: "in",          short: "i", arg: 1, descr: "input file, default: stdin" },
         Opt { long: "key",         short: "k", arg: 1, descr: "path to private key for sign/decrypt" },
-        Opt { long: "cert",        short: "c", arg: 1, descr: "path to certificate for sign/verify/encrypt" },
+        Opt { long: "cert",        short: "c", arg: 2, descr: "path to certificate for sign/verify/encrypt" },
         Opt { long: "digest",      short: "g", arg: 1, descr: "digest for signature creation, default: key-specific" },
         Opt { long: "rsa-padding", short: "R", arg: 1, descr: "padding for RSA signatures, default: pkcs1" },
+        Opt { long: "outform",     short: "f", arg: 1, descr: "encoding of generated PKCS#7 data, default: der" },
     ];
     Command::new(pki_pkcs7, "7", "pkcs7",
                 "PKCS#7 wrap/unwrap functions", brief, options)
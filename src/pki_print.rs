@@ -14,20 +14,248 @@
 // for more details.
 
 use std::process::ExitCode;
+use chrono::{DateTime, Utc};
 use getopts::Matches;
 use pki::{Command, Opt};
+use pki::asn1::{ASN1_BOOLEAN, ASN1_SEQUENCE, Asn1Parser};
+use pki::dn::Dn;
+use pki::extensions::{parse_extended_key_usage, parse_sct_list};
+use pki::name_constraints::general_name_string;
+use pki::oid::*;
+use pki::pem::{PemBlock, load_block, load_blocks};
+use pki::keys::{KeyType, PrivateKey, PublicKey};
+use pki::verify::{is_self_signed, subject_name};
+use pki::x509::{Certificate, Extension};
+use pki::crypto::rsa_threshold::RsaKeyShare;
+
+//
+// Format binary data as colon-separated hex octets.
+//
+fn hex_colon(data: &[u8]) -> String
+{
+    return data.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(":");
+}
+
+//
+// Print the identifiers of a public key.
+//
+fn print_keyids(key: &PublicKey)
+{
+    println!("  keyid:     {}", hex_colon(&key.keyid()));
+    println!("  subjkey:   {}", hex_colon(&key.subject_key_id()));
+}
+
+//
+// Print threshold RSA key shares, optionally accompanied by the public key
+// as written by --gen, returning false if the blocks are not key shares.
+//
+fn print_key_shares(blocks: &[PemBlock]) -> bool
+{
+    let mut shares = Vec::new();
+    for block in blocks {
+        match RsaKeyShare::from_der(&block.der) {
+            Ok(share) => { shares.push(share); }
+            Err(_) if PublicKey::from_spki(&block.der).is_ok() => {}
+            Err(_) => { return false; }
+        }
+    }
+    if shares.is_empty() {
+        return false;
+    }
+    for share in &shares {
+        println!("  privkey:   RSA {} bits, share {} of {} with threshold {}",
+                 share.bits(), share.index, share.shares, share.threshold);
+        print_keyids(&PublicKey::Rsa(share.public_key()));
+    }
+    return true;
+}
+
+//
+// Print a private or public key, checking the key type if requested.
+//
+fn print_key(file: Option<&str>, in_type: &str) -> ExitCode
+{
+    let blocks = match load_blocks(file) {
+        Ok(blocks) => { blocks }
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if (in_type == "priv" || in_type == "rsa") && print_key_shares(&blocks) {
+        return ExitCode::SUCCESS;
+    }
+    if blocks.len() != 1 {
+        eprintln!("'{}' contains {} objects where one was expected",
+                  file.unwrap_or("stdin"), blocks.len());
+        return ExitCode::FAILURE;
+    }
+    let block = &blocks[0];
+
+    if in_type == "pub" {
+        let key = match PublicKey::from_spki(&block.der) {
+            Ok(key) => { key }
+            Err(e) => {
+                eprintln!("parsing public key failed: {}", e);
+                return ExitCode::FAILURE;
+            }
+        };
+        println!("  pubkey:    {} {} bits", key.key_type(), key.bits());
+        print_keyids(&key);
+        return ExitCode::SUCCESS;
+    }
+
+    let key = match PrivateKey::from_der(&block.der) {
+        Ok(key) => { key }
+        Err(e) => {
+            eprintln!("parsing private key failed: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Some(expected) = KeyType::parse(in_type) && key.key_type() != expected {
+        eprintln!("expected {} private key but got {} key", expected, key.key_type());
+        return ExitCode::FAILURE;
+    }
+    println!("  privkey:   {} {} bits", key.key_type(), key.bits());
+    print_keyids(&key.public_key());
+    return ExitCode::SUCCESS;
+}
+
+//
+// Format a time as printed by strongSwan.
+//
+fn time_string(time: i64) -> String
+{
+    match DateTime::from_timestamp(time, 0) {
+        Some(dt) => { dt.format("%b %d %H:%M:%S %Y").to_string() }
+        None => { time.to_string() }
+    }
+}
+
+//
+// Print the SCTs of a SignedCertificateTimestampList extension.
+//
+fn print_scts(ext: &Extension)
+{
+    let scts = match parse_sct_list(ext) {
+        Ok(scts) => { scts }
+        Err(e) => {
+            println!("  CT SCTs:   invalid ({})", e);
+            return;
+        }
+    };
+    let mut label = "CT SCTs:";
+    for sct in &scts {
+        let timestamp = match DateTime::from_timestamp_millis(sct.timestamp as i64) {
+            Some(dt) => { dt.format("%b %d %H:%M:%S%.3f %Y").to_string() }
+            None => { sct.timestamp.to_string() }
+        };
+        println!("  {:10} log {}", label, hex_colon(&sct.log_id));
+        println!("             timestamp {}, {}", timestamp, sct.algorithm());
+        label = "";
+    }
+}
+
+//
+// Print an X.509 certificate.
+//
+fn print_cert(file: Option<&str>) -> ExitCode
+{
+    let cert = match load_block(file).and_then(|block| Certificate::from_der(&block.der)) {
+        Ok(cert) => { cert }
+        Err(e) => {
+            eprintln!("parsing certificate failed: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let now = Utc::now().timestamp();
+
+    println!("  subject:  \"{}\"", subject_name(&cert));
+    match Dn::from_der(&cert.issuer) {
+        Ok(dn) => { println!("  issuer:   \"{}\"", dn); }
+        Err(_) => { println!("  issuer:   (invalid)"); }
+    }
+    println!("  validity:  not before {}, {}", time_string(cert.not_before),
+             if now < cert.not_before { "not valid yet" } else { "ok" });
+    println!("             not after  {}, {}", time_string(cert.not_after),
+             if now > cert.not_after { "expired" } else { "ok" });
+    println!("  serial:    {}", hex_colon(&cert.serial));
+
+    let mut names = Vec::new();
+    if let Some(ext) = cert.extension(OID_SUBJECT_ALT_NAME) {
+        let mut parser = Asn1Parser::new(&ext.value);
+        if let Ok(seq) = parser.expect(ASN1_SEQUENCE) {
+            let mut parser = seq.parser();
+            while let Ok(Some(name)) = parser.next_object() {
+                names.push(general_name_string(&(name.tag, name.content.to_vec())));
+            }
+        }
+    }
+    if !names.is_empty() {
+        println!("  altNames:  {}", names.join(", "));
+    }
+
+    let mut flags = Vec::new();
+    if let Some(ext) = cert.extension(OID_BASIC_CONSTRAINTS) {
+        let ca = Asn1Parser::new(&ext.value).expect(ASN1_SEQUENCE)
+                     .and_then(|seq| seq.parser().optional(ASN1_BOOLEAN))
+                     .ok().flatten().and_then(|obj| obj.to_bool().ok());
+        if ca == Some(true) {
+            flags.push("CA".to_string());
+        }
+    }
+    if let Some(ext) = cert.extension(OID_EXTENDED_KEY_USAGE) {
+        flags.extend(parse_extended_key_usage(ext).unwrap_or_default().iter().map(|oid| oid_display(oid)));
+    }
+    if is_self_signed(&cert) {
+        flags.push("self-signed".to_string());
+    }
+    if cert.extension(OID_CT_PRECERT_POISON).is_some() {
+        flags.push("precertificate".to_string());
+    }
+    if !flags.is_empty() {
+        println!("  flags:     {}", flags.join(" "));
+    }
+    if let Some(ext) = cert.extension(OID_CT_SCT_LIST) {
+        print_scts(ext);
+    }
+    println!("  pubkey:    {} {} bits", cert.public_key.key_type(), cert.public_key.bits());
+    print_keyids(&cert.public_key);
+    return ExitCode::SUCCESS;
+}
 
 //
 // Print a credential in a human readable form.
 //
 pub fn pki_print(matches: &Matches) -> ExitCode
 {
+    let in_type = match matches.opt_str("t") {
+        Some(v) => { v }
+        None => { "x509".to_string() }
+    };
+    if matches!(in_type.as_str(), "priv" | "pub") || KeyType::parse(&in_type).is_some() {
+        if matches.opt_present("x") {
+            eprintln!("printing smartcard or TPM objects is not supported");
+            return ExitCode::FAILURE;
+        }
+        return print_key(matches.opt_str("i").as_deref(), &in_type);
+    }
+
+    if in_type == "x509" && !matches.opt_present("x") {
+        return print_cert(matches.opt_str("i").as_deref());
+    }
+
     let file = match matches.opt_str("i") {
         Some(v) => { v }
         None => { "".to_string() }
     };
     println!("option: --in {}", file);
 
+    if !file.is_empty() && let Err(e) = load_blocks(Some(&file)) {
+        println!("{}", e);
+        return ExitCode::FAILURE;
+    }
+
     let keyid = match matches.opt_str("x") {
         Some(v) => { v }
         None => { "".to_string() }
@@ -42,11 +270,7 @@ pub fn pki_print(matches: &Matches) -> ExitCode
     if file.is_empty() && keyid.is_empty() {
         println!("option '--in' or '--keyid' missing: get input from stdin");
     }
-
-    if matches.opt_present("t") {
-        let in_type = matches.opt_str("t").unwrap();
-        println!("option: --type {}", in_type);
-    }
+    println!("option: --type {}", in_type);
 
     println!("print()");
     return ExitCode::SUCCESS;
@@ -58,8 +282,17 @@ pub fn pki_print(matches: &Matches) -> ExitCode
 inventory::submit!
 {
     let brief: &'static[&'static str] = &[
-        "[--in file|--keyid hex]",
-        "[--type x509|crl|ac|pub|priv|rsa|ecdsa|ed25519|ed448|ocsp-req|ocsp-rsp]"
+        "[--in file|--keyid hex] [--type x509|crl|ac|ocsp-req|ocsp-rsp|",
+        "pub|priv|rsa|ecdsa|ed25519|ed448|mldsa44|mldsa65|mldsa87|",
+        "mlkem512|mlkem768|mlkem1024|",
+        "slhdsa-sha2-128s|slhdsa-sha2-128f|slhdsa-sha2-192s|slhdsa-sha2-192f|",
+        "slhdsa-sha2-256s|slhdsa-sha2-256f|slhdsa-shake-128s|slhdsa-shake-128f|",
+        "slhdsa-shake-192s|slhdsa-shake-192f|slhdsa-shake-256s|slhdsa-shake-256f|",
+        "mldsa44-rsa2048-pss|mldsa44-rsa2048-pkcs15|mldsa44-ed25519|mldsa44-ecdsa-p256|",
+        "mldsa65-rsa3072-pss|mldsa65-rsa3072-pkcs15|mldsa65-rsa4096-pss|",
+        "mldsa65-rsa4096-pkcs15|mldsa65-ecdsa-p256|mldsa65-ecdsa-p384|mldsa65-ed25519|",
+        "mldsa87-ecdsa-p384|mldsa87-ed448|mldsa87-rsa3072-pss|mldsa87-rsa4096-pss|",
+        "mldsa87-ecdsa-p521]"
     ];
     let options: &'static[Opt] = &[
         Opt { long: "help",  short: "h", arg: 0, descr: "show usage information" },
@@ -13,47 +13,101 @@
 // or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
 // for more details.
 
+use std::io::{self, Write};
 use std::process::ExitCode;
 use getopts::Matches;
 use pki::{Command, Opt};
+use pki::pem::{Encoding, CredType, base64_encode, load_block, write_output};
+use pki::keys::{KeyType, PrivateKey, PublicKey};
 
 //
 // Extract a public key from a private key/certificate.
 //
 pub fn pki_pub(matches: &Matches) -> ExitCode
 {
-   let file = match matches.opt_str("i") {
-        Some(v) => { v }
-        None => { "".to_string() }
+    let file = matches.opt_str("i");
+    if matches.opt_present("x") {
+        if file.is_some() {
+            eprintln!("options '--in' and '--keyid' can't be set both");
+            return ExitCode::from(2);
+        }
+        eprintln!("smartcard or TPM private keys are not supported");
+        return ExitCode::FAILURE;
+    }
+
+    // DNSKEY and SSH keys are text formats not handled by Encoding
+    let text_form = matches.opt_str("f").filter(|v| v == "dnskey" || v == "sshkey");
+    let form = if text_form.is_some() {
+        Encoding::Der
+    } else {
+        match Encoding::from_matches(matches, Encoding::Der) {
+            Ok(form) => { form }
+            Err(code) => { return code; }
+        }
     };
-    println!("option: --in {}", file);
 
-    let keyid = match matches.opt_str("x") {
+    let in_type = match matches.opt_str("t") {
         Some(v) => { v }
-        None => { "".to_string() }
+        None => { "priv".to_string() }
     };
-    println!("option: --keyid {}", keyid);
-
-    if !file.is_empty() && !keyid.is_empty() {
-        println!("options '--in' and '--keyid' can't be set both");
-        return ExitCode::SUCCESS;
-    }
-
-    if file.is_empty() && keyid.is_empty() {
-        println!("option '--in' or '--keyid' missing: get input from stdin");
+    let expected = KeyType::parse(&in_type);
+    if expected.is_none() && in_type != "priv" && in_type != "pub" {
+        if in_type == "pkcs10" || in_type == "x509" {
+            eprintln!("extracting public keys from {} objects is not supported", in_type);
+            return ExitCode::FAILURE;
+        }
+        eprintln!("invalid input type '{}'", in_type);
+        return ExitCode::from(2);
     }
 
-   if matches.opt_present("f") {
-        let form = matches.opt_str("f").unwrap();
-        println!("option: --outform {}", form);
+    let block = match load_block(file.as_deref()) {
+        Ok(block) => { block }
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let key = if in_type == "pub" {
+        PublicKey::from_spki(&block.der)
+    } else {
+        PrivateKey::from_der(&block.der).map(|key| key.public_key())
+    };
+    let key = match key {
+        Ok(key) => { key }
+        Err(e) => {
+            let kind = if in_type == "pub" { "public" } else { "private" };
+            eprintln!("parsing {} key failed: {}", kind, e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Some(expected) = expected && key.key_type() != expected {
+        eprintln!("expected {} private key but got {} key", expected, key.key_type());
+        return ExitCode::FAILURE;
     }
 
-    if matches.opt_present("t") {
-        let in_type = matches.opt_str("t").unwrap();
-        println!("option: --type {}", in_type);
+    let text = match text_form.as_deref() {
+        Some("sshkey") => { key.to_ssh_key() }
+        Some(_) => { key.to_dnskey().map(|data| base64_encode(&data, 0)) }
+        None => {
+            if let Err(e) = write_output(&key.to_spki(), form, CredType::PublicKey) {
+                eprintln!("{}", e);
+                return ExitCode::FAILURE;
+            }
+            return ExitCode::SUCCESS;
+        }
+    };
+    match text {
+        Ok(text) => {
+            if let Err(e) = writeln!(io::stdout(), "{}", text) {
+                eprintln!("writing public key failed: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
     }
-
-    println!("pub()");
     return ExitCode::SUCCESS;
 }
 
@@ -63,7 +117,17 @@ pub fn pki_pub(matches: &Matches) -> ExitCode
 inventory::submit!
 {
     let brief: &'static[&'static str] = &[
-        "[--in file|--keyid hex] [--type rsa|ecdsa|priv|pub|pkcs10|x509]",
+        "[--in file|--keyid hex]",
+        "[--type priv|pub|rsa|ecdsa|ed25519|ed448|mldsa44|mldsa65|mldsa87|",
+        "mlkem512|mlkem768|mlkem1024|",
+        "slhdsa-sha2-128s|slhdsa-sha2-128f|slhdsa-sha2-192s|slhdsa-sha2-192f|",
+        "slhdsa-sha2-256s|slhdsa-sha2-256f|slhdsa-shake-128s|slhdsa-shake-128f|",
+        "slhdsa-shake-192s|slhdsa-shake-192f|slhdsa-shake-256s|slhdsa-shake-256f|",
+        "mldsa44-rsa2048-pss|mldsa44-rsa2048-pkcs15|mldsa44-ed25519|mldsa44-ecdsa-p256|",
+        "mldsa65-rsa3072-pss|mldsa65-rsa3072-pkcs15|mldsa65-rsa4096-pss|",
+        "mldsa65-rsa4096-pkcs15|mldsa65-ecdsa-p256|mldsa65-ecdsa-p384|mldsa65-ed25519|",
+        "mldsa87-ecdsa-p384|mldsa87-ed448|mldsa87-rsa3072-pss|mldsa87-rsa4096-pss|",
+        "mldsa87-ecdsa-p521|pkcs10|x509]",
         "[--outform der|pem|dnskey|sshkey]"
     ];
     let options: &'static[Opt] = &[
@@ -16,86 +16,187 @@
 use std::process::ExitCode;
 use getopts::Matches;
 use pki::{Command, Opt};
+use pki::dn::Dn;
+use pki::extensions::{extended_key_usage, parse_extended_key_usage, parse_flags, subject_alt_name};
+use pki::keys::{KeyType, PrivateKey};
+use pki::oid::*;
+use pki::pem::{Encoding, CredType, load_block, write_output};
+use pki::pkcs10::{CertificateRequest, RequestInfo};
+use pki::signature::{SignatureScheme, parse_signature_options};
+
+//
+// Map a --profile name to the corresponding --flag values.
+//
+fn profile_flags(profile: &str) -> Option<Vec<String>>
+{
+    let flags: &[&str] = match profile {
+        "server" => { &["serverAuth"] }
+        "client" => { &["clientAuth"] }
+        "dual"   => { &["serverAuth", "clientAuth"] }
+        "ocsp"   => { &["ocspSigning"] }
+        _ => { return None; }
+    };
+    return Some(flags.iter().map(|f| f.to_string()).collect());
+}
 
 //
 // Create a self-signed PKCS#10 certificate request..
 //
 pub fn pki_req(matches: &Matches) -> ExitCode
 {
-   let file = match matches.opt_str("i") {
-        Some(v) => { v }
-        None => { "".to_string() }
-    };
-    println!("option: --in {}", file);
+    let file = matches.opt_str("i");
+    if matches.opt_present("x") {
+        if file.is_some() {
+            eprintln!("options '--in' and '--keyid' can't be set both");
+            return ExitCode::from(2);
+        }
+        eprintln!("smartcard or TPM private keys are not supported");
+        return ExitCode::FAILURE;
+    }
 
-    let keyid = match matches.opt_str("x") {
+    let in_type = match matches.opt_str("t") {
         Some(v) => { v }
-        None => { "".to_string() }
+        None => { "priv".to_string() }
     };
-    println!("option: --keyid {}", keyid);
-
-    if !file.is_empty() && !keyid.is_empty() {
-        println!("options '--in' and '--keyid' can't be set both");
-        return ExitCode::SUCCESS;
+    let expected = KeyType::parse(&in_type);
+    if expected.is_none() && in_type != "priv" {
+        eprintln!("invalid input type '{}'", in_type);
+        return ExitCode::from(2);
     }
 
-    if file.is_empty() && keyid.is_empty() {
-        println!("option '--in' or '--keyid' missing: get input from stdin");
-    }
-
-    if matches.opt_present("t") {
-        let in_type = matches.opt_str("t").unwrap();
-        println!("option: --type {}", in_type);
-    }
+    let oldreq = match matches.opt_str("o") {
+        Some(oldreq) => {
+            match load_block(Some(&oldreq)).and_then(|block| CertificateRequest::from_der(&block.der)) {
+                Ok(req) => { Some(req) }
+                Err(e) => {
+                    eprintln!("parsing old certificate request failed: {}", e);
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+        None => { None }
+    };
 
-    if matches.opt_present("o") {
-        let oldreq = matches.opt_str("o").unwrap();
-        println!("option: --oldreq {}", oldreq);
-    }
+    let subject = match (matches.opt_str("d"), &oldreq) {
+        (Some(dn), _) => {
+            match Dn::parse(&dn) {
+                Ok(dn) => { dn.to_der() }
+                Err(e) => {
+                    eprintln!("invalid distinguished name: {}", e);
+                    return ExitCode::from(2);
+                }
+            }
+        }
+        (None, Some(req)) => { req.subject.clone() }
+        (None, None) => {
+            eprintln!("option '--dn' or '--oldreq' is required");
+            return ExitCode::from(2);
+        }
+    };
 
-    if matches.opt_present("d") {
-        let dn = matches.opt_str("d").unwrap();
-        println!("option: --dn {}", dn);
+    let mut flags: Vec<String> = matches.opt_strs("e");
+    if let Some(profile) = matches.opt_str("P") {
+        match profile_flags(&profile) {
+            Some(profile_flags) => { flags.extend(profile_flags); }
+            None => {
+                eprintln!("invalid certificate profile '{}'", profile);
+                return ExitCode::from(2);
+            }
+        }
     }
+    let mut purposes = match parse_flags(&flags) {
+        Ok((purposes, 0)) => { purposes }
+        Ok(_) => {
+            eprintln!("keyUsage flags can't be requested");
+            return ExitCode::from(2);
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(2);
+        }
+    };
 
+    let mut extensions = Vec::new();
     let san: Vec<String> = matches.opt_strs("a");
-    for s in &san
-    {
-        println!("option: --san {}", s);
+    if !san.is_empty() {
+        match subject_alt_name(&san) {
+            Ok(ext) => { extensions.push(ext); }
+            Err(e) => {
+                eprintln!("invalid subjectAltName: {}", e);
+                return ExitCode::from(2);
+            }
+        }
+    } else if let Some(ext) = oldreq.as_ref().and_then(|req| req.extension(OID_SUBJECT_ALT_NAME)) {
+        extensions.push(ext.clone());
     }
-
-    let flags: Vec<String> = matches.opt_strs("e");
-    for f in &flags
-     {
-            println!("option: --flag {}", f);
+    if purposes.is_empty() && let Some(ext) = oldreq.as_ref()
+                                                     .and_then(|req| req.extension(OID_EXTENDED_KEY_USAGE)) {
+        purposes = match parse_extended_key_usage(ext) {
+            Ok(purposes) => { purposes }
+            Err(e) => {
+                eprintln!("invalid extendedKeyUsage in old certificate request: {}", e);
+                return ExitCode::FAILURE;
+            }
+        };
     }
-
-    if matches.opt_present("P") {
-        let profile = matches.opt_str("P").unwrap();
-        println!("option: --profile {}", profile);
+    if !purposes.is_empty() {
+        match extended_key_usage(&purposes) {
+            Ok(ext) => { extensions.push(ext); }
+            Err(e) => {
+                eprintln!("{}", e);
+                return ExitCode::from(2);
+            }
+        }
     }
 
-    if matches.opt_present("p") {
-        let password = matches.opt_str("p").unwrap();
-        println!("option: --password {}", password);
-    }
+    let challenge_password = matches.opt_str("p")
+                                    .or_else(|| oldreq.as_ref().and_then(|req| req.challenge_password.clone()));
 
-    if matches.opt_present("g") {
-        let digest = matches.opt_str("g").unwrap();
-        println!("option: --digest {}", digest);
-    }
+    let (digest, pss) = match parse_signature_options(matches.opt_str("g").as_deref(),
+                                                      matches.opt_str("R").as_deref()) {
+        Ok(options) => { options }
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(2);
+        }
+    };
 
-    if matches.opt_present("R") {
-        let padding = matches.opt_str("R").unwrap();
-        println!("option: --rsa-padding {}", padding);
-    }
+    let form = match Encoding::from_matches(matches, Encoding::Der) {
+        Ok(form) => { form }
+        Err(code) => { return code; }
+    };
 
-    if matches.opt_present("f") {
-        let form = matches.opt_str("f").unwrap();
-        println!("option: --outform {}", form);
+    let key = match load_block(file.as_deref()).and_then(|block| PrivateKey::from_der(&block.der)) {
+        Ok(key) => { key }
+        Err(e) => {
+            eprintln!("parsing private key failed: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Some(expected) = expected && key.key_type() != expected {
+        eprintln!("expected {} private key but got {} key", expected, key.key_type());
+        return ExitCode::FAILURE;
     }
+    let scheme = match SignatureScheme::select(key.key_type(), key.bits(), digest, pss) {
+        Ok(scheme) => { scheme }
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
 
-    println!("req()");
+    let info = RequestInfo { subject, public_key: key.public_key(), challenge_password, extensions };
+    let req = match info.sign(&key, scheme) {
+        Ok(req) => { req }
+        Err(e) => {
+            eprintln!("generating certificate request failed: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Err(e) = write_output(&req.der, form, CredType::CertRequest) {
+        eprintln!("{}", e);
+        return ExitCode::FAILURE;
+    }
     return ExitCode::SUCCESS;
 }
 
@@ -105,9 +206,19 @@ pub fn pki_req(matches: &Matches) -> ExitCode
 inventory::submit!
 {
     let brief: &'static[&'static str] = &[
-        "[--in file|--keyid hex] [--type rsa|ecdsa|priv]",
+        "[--in file|--keyid hex]",
+        "[--type priv|rsa|ecdsa|ed25519|ed448|mldsa44|mldsa65|mldsa87|",
+        "slhdsa-sha2-128s|slhdsa-sha2-128f|slhdsa-sha2-192s|slhdsa-sha2-192f|",
+        "slhdsa-sha2-256s|slhdsa-sha2-256f|slhdsa-shake-128s|slhdsa-shake-128f|",
+        "slhdsa-shake-192s|slhdsa-shake-192f|slhdsa-shake-256s|slhdsa-shake-256f|",
+        "mldsa44-rsa2048-pss|mldsa44-rsa2048-pkcs15|mldsa44-ed25519|mldsa44-ecdsa-p256|",
+        "mldsa65-rsa3072-pss|mldsa65-rsa3072-pkcs15|mldsa65-rsa4096-pss|",
+        "mldsa65-rsa4096-pkcs15|mldsa65-ecdsa-p256|mldsa65-ecdsa-p384|mldsa65-ed25519|",
+        "mldsa87-ecdsa-p384|mldsa87-ed448|mldsa87-rsa3072-pss|mldsa87-rsa4096-pss|",
+        "mldsa87-ecdsa-p521]",
         " --oldreq file|--dn distinguished-name [--san subjectAltName]+",
-        "[--flag serverAuth|clientAuth|ocspSigning|msSmartcardLogon]+",
+        "[--flag serverAuth|clientAuth|ocspSigning|msSmartcardLogon|codeSigning|",
+        "timeStamping|emailProtection|ikeIntermediate|oid]+",
         "[--profile server|client|dual|ocsp] [--password challengePassword]",
         "[--digest sha1|sha224|sha256|sha384|sha512|sha3_224|sha3_256|sha3_384|sha3_512]",
         "[--rsa-padding pkcs1|pss] [--outform der|pem]"
@@ -120,7 +231,7 @@ inventory::submit!
         Opt { long: "oldreq",      short: "o", arg: 1, descr: "old certificate request to be used as a template" },
         Opt { long: "dn",          short: "d", arg: 1, descr: "subject distinguished name" },
         Opt { long: "san",         short: "a", arg: 2, descr: "subjectAltName to include in cert request" },
-        Opt { long: "flag",        short: "e", arg: 2, descr: "include extendedKeyUsage flag" },
+        Opt { long: "flag",        short: "e", arg: 2, descr: "include extendedKeyUsage flag or key purpose OID" },
         Opt { long: "profile",     short: "P", arg: 1, descr: "certificate profile name to include in cert request" },
         Opt { long: "password",    short: "p", arg: 1, descr: "challengePassword to include in cert request" },
         Opt { long: "digest",      short: "g", arg: 1, descr: "digest for signature creation, default: key-specific" },
@@ -17,6 +17,9 @@
 use std::process::ExitCode;
 use getopts::Matches;
 use pki::{Command, Opt};
+use pki::dn::Dn;
+use pki::general_name::parse_general_name;
+use pki::pem::{Encoding, load_blocks};
 
 const DEFAULT_POLL_INTERVAL: u32 = 60; // seconds
 
@@ -35,19 +38,36 @@ pub fn pki_scep(matches: &Matches) -> ExitCode
 
     if matches.opt_present("i") {
         let file = matches.opt_str("i").unwrap();
-        println!("option: --in {}", file);
+        let blocks = match load_blocks(Some(&file)) {
+            Ok(blocks) => { blocks }
+            Err(e) => {
+                println!("{}", e);
+                return ExitCode::FAILURE;
+            }
+        };
+        println!("option: --in {} ({} objects)", file, blocks.len());
     } else {
         println!("option '--in' missing: get input from stdin");
     }
 
     if matches.opt_present("d") {
-        let dn = matches.opt_str("d").unwrap();
+        let dn = match Dn::parse(&matches.opt_str("d").unwrap()) {
+            Ok(dn) => { dn }
+            Err(e) => {
+                eprintln!("invalid distinguished name: {}", e);
+                return ExitCode::from(2);
+            }
+        };
         println!("option: --dn {}", dn);
     }
 
     let san: Vec<String> = matches.opt_strs("a");
     for s in &san
     {
+        if let Err(e) = parse_general_name(s) {
+            eprintln!("invalid subjectAltName: {}", e);
+            return ExitCode::from(2);
+        }
         println!("option: --san {}", s);
     }
 
@@ -127,10 +147,11 @@ pub fn pki_scep(matches: &Matches) -> ExitCode
     };
     println!("option: --maxpolltime {} seconds", max_poll_time);
 
-    if matches.opt_present("f") {
-        let form = matches.opt_str("f").unwrap();
-        println!("option: --outform {}", form);
-    }
+    let form = match Encoding::from_matches(matches, Encoding::Der) {
+        Ok(form) => { form }
+        Err(code) => { return code; }
+    };
+    println!("option: --outform {}", form);
 
     println!("scep()");
     return ExitCode::SUCCESS;
@@ -17,6 +17,7 @@
 use std::process::ExitCode;
 use getopts::Matches;
 use pki::{Command, Opt};
+use pki::pem::Encoding;
 
 //
 // Get CA certificate[s] from a SCEP server (RFC 8894).
@@ -41,10 +42,11 @@ pub fn pki_scepca(matches: &Matches) -> ExitCode
         println!("option: --raout {}", raout);
     }
 
-    if matches.opt_present("f") {
-        let form = matches.opt_str("f").unwrap();
-        println!("option: --outform {}", form);
-    }
+    let form = match Encoding::from_matches(matches, Encoding::Der) {
+        Ok(form) => { form }
+        Err(code) => { return code; }
+    };
+    println!("option: --outform {}", form);
 
     let force: bool = matches.opt_present("F");
     println!("force:  {}", force);
@@ -15,199 +15,132 @@
 
 use std::process::ExitCode;
 use getopts::Matches;
-use chrono::{DateTime, Utc};
 use pki::{Command, Opt};
-use pki::X509_NO_CONSTRAINT;
+use pki::dn::Dn;
+use pki::keys::{KeyType, PrivateKey};
+use pki::pem::{Encoding, CredType, load_blocks, write_output};
+use pki::signature::{SignatureScheme, parse_signature_options};
+use pki::template::CertTemplate;
+use pki::threshold::PartialSignature;
+use pki::x509::TbsCertificate;
 
 //
 // Create a self signed certificate.
 //
 pub fn pki_self(matches: &Matches) -> ExitCode
 {
-   let file = match matches.opt_str("i") {
-        Some(v) => { v }
-        None => { "".to_string() }
-    };
-    println!("option: --in {}", file);
+    let file = matches.opt_str("i");
+    if matches.opt_present("x") {
+        if file.is_some() {
+            eprintln!("options '--in' and '--keyid' can't be set both");
+            return ExitCode::from(2);
+        }
+        eprintln!("smartcard or TPM private keys are not supported");
+        return ExitCode::FAILURE;
+    }
 
-    let keyid = match matches.opt_str("x") {
+    let in_type = match matches.opt_str("t") {
         Some(v) => { v }
-        None => { "".to_string() }
+        None => { "priv".to_string() }
     };
-    println!("option: --keyid {}", keyid);
-
-    if !file.is_empty() && !keyid.is_empty() {
-        println!("options '--in' and '--keyid' can't be set both");
-        return ExitCode::SUCCESS;
-    }
-
-    if file.is_empty() && keyid.is_empty() {
-        println!("option '--in' or '--keyid' missing: get input from stdin");
+    let expected = KeyType::parse(&in_type);
+    if expected.is_none() && in_type != "priv" {
+        eprintln!("invalid input type '{}'", in_type);
+        return ExitCode::from(2);
     }
 
-    if matches.opt_present("t") {
-        let in_type = matches.opt_str("t").unwrap();
-        println!("option: --type {}", in_type);
-    }
-
-    if matches.opt_present("d") {
-        let dn = matches.opt_str("d").unwrap();
-        println!("option: --dn {}", dn);
-    }
-
-    let san: Vec<String> = matches.opt_strs("a");
-    for s in &san
-    {
-        println!("option: --san {}", s);
-    }
-
-    let lifetime: i64 = 24 * 60 * 60 * match matches.opt_str("l") {
-        Some(string) => { string.parse().unwrap() }
-        None => { 1095 } // days
+    let template = match CertTemplate::from_matches(matches) {
+        Ok(template) => { template }
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(2);
+        }
+    };
+    let dn = match &template.dn {
+        Some(dn) => { dn }
+        None => {
+            eprintln!("option '--dn' is required");
+            return ExitCode::from(2);
+        }
+    };
+    let name = match Dn::parse(dn) {
+        Ok(dn) => { dn.to_der() }
+        Err(e) => {
+            eprintln!("invalid distinguished name: {}", e);
+            return ExitCode::from(2);
+        }
     };
-    println!("option: --lifetime {} seconds", lifetime);
-
-    if matches.opt_present("s") {
-        let serial = matches.opt_str("s").unwrap();
-        println!("option: --serial {}", serial);
-    }
-
-    let ca: bool = matches.opt_present("b");
-    println!("ca flag:  {}", ca);
 
-    let pathlen: u32 = match matches.opt_str("p") {
-        Some(string) => { string.parse().unwrap() }
-        None => { X509_NO_CONSTRAINT }
+    let (digest, pss) = match parse_signature_options(matches.opt_str("g").as_deref(),
+                                                      matches.opt_str("R").as_deref()) {
+        Ok(options) => { options }
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(2);
+        }
     };
-    println!("option: --pathlen {}", pathlen);
 
-    let dateform = match matches.opt_str("D") {
-        Some(v) => { v }
-        None => { "%Y-%m-%dT%H:%M:%S%z".to_string() }
+    let form = match Encoding::from_matches(matches, Encoding::Der) {
+        Ok(form) => { form }
+        Err(code) => { return code; }
     };
-    println!("option: --dateform {}", dateform);
 
-    let datenb: i64 = match matches.opt_str("F") {
-        Some(v) => {
-            match DateTime::parse_from_str(v.as_str(), dateform.as_str()) {
-                Ok(dt) => { dt.timestamp() }
-                Err(e) => {
-                    println!("{e}");
-                    return ExitCode::FAILURE;
-                }
-            }
+    let key = match load_blocks(file.as_deref()).and_then(|blocks| PrivateKey::from_blocks(&blocks)) {
+        Ok(key) => { key }
+        Err(e) => {
+            eprintln!("parsing private key failed: {}", e);
+            return ExitCode::FAILURE;
         }
-        None => { Utc::now().timestamp() }
     };
-    println!("option: --not-before {}", datenb);
-
-    let datena: i64 = match matches.opt_str("T") {
-        Some(v) => {
-            match DateTime::parse_from_str(v.as_str(), dateform.as_str()) {
-                Ok(dt) => { dt.timestamp() }
-                Err(e) => {
-                    println!("{e}");
-                    return ExitCode::FAILURE;
-                }
-            }
+    if let Some(expected) = expected && key.key_type() != expected {
+        eprintln!("expected {} private key but got {} key", expected, key.key_type());
+        return ExitCode::FAILURE;
+    }
+    let scheme = match SignatureScheme::select(key.key_type(), key.bits(), digest, pss) {
+        Ok(scheme) => { scheme }
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
         }
-        None => { datenb + lifetime }
     };
-    println!("option: --not-after  {}", datena);
-
-    let flags: Vec<String> = matches.opt_strs("e");
-    for f in &flags
-     {
-            println!("option: --flag {}", f);
-    }
-
-    let ocsp_uris: Vec<String> = matches.opt_strs("o");
-    for u in &ocsp_uris
-    {
-        println!("option: --ocsp {}", u);
-    }
 
-    let addrblocks: Vec<String> = matches.opt_strs("B");
-    for b in &addrblocks
-    {
-         println!("option: --addrblock {}", b);
-    }
-
-    let permitted: Vec<String> = matches.opt_strs("n");
-    for nc in &permitted
-    {
-        println!("option: --nc-permitted {}", nc);
-    }
-
-    let excluded: Vec<String> = matches.opt_strs("N");
-    for nc in &excluded
-    {
-        println!("option: --nc-excluded {}", nc);
-    }
-
-    let mappings: Vec<String> = matches.opt_strs("M");
-    for m in &mappings
-    {
-        println!("option: --policy-mapping {}", m);
-    }
-
-    let require_explicit: u32 = match matches.opt_str("E") {
-        Some(string) => { string.parse().unwrap() }
-        None => { X509_NO_CONSTRAINT }
-     };
-    println!("option: --policy-explicit {}", require_explicit);
-
-    let inhibit_mapping: u32 = match matches.opt_str("H") {
-        Some(string) => { string.parse().unwrap() }
-        None => { X509_NO_CONSTRAINT }
-     };
-    println!("option: --policy-inhibit {}", inhibit_mapping);
-
-    let inhibit_any: u32 = match matches.opt_str("A") {
-        Some(string) => { string.parse().unwrap() }
-        None => { X509_NO_CONSTRAINT }
-     };
-    println!("option: --policy-any {}", inhibit_any);
-
-    let policies: Vec<String> = matches.opt_strs("P");
-    for p in &policies
-    {
-        println!("option: --cert-policy {}", p);
-    }
-
-    let cps_uri: Vec<String> = matches.opt_strs("C");
-    for u in &cps_uri
-    {
-        println!("option: --cps-uri {}", u);
-    }
-
-    let user_notice: Vec<String> = matches.opt_strs("U");
-    for n in &user_notice
-    {
-        println!("option: --user-notice {}", n);
-    }
-
-    if matches.opt_present("X") {
-        let critical_extension_oid = matches.opt_str("X").unwrap();
-        println!("option: --critical {}", critical_extension_oid);
-    }
-
-    if matches.opt_present("g") {
-        let digest = matches.opt_str("g").unwrap();
-        println!("option: --digest {}", digest);
-    }
-
-    if matches.opt_present("R") {
-        let padding = matches.opt_str("R").unwrap();
-        println!("option: --rsa-padding {}", padding);
+    let public_key = key.public_key();
+    let extensions = match template.extensions(&public_key, &public_key.subject_key_id(), &[]) {
+        Ok(extensions) => { extensions }
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let tbs = TbsCertificate {
+        serial: template.serial, issuer: name.clone(), not_before: template.not_before,
+        not_after: template.not_after, subject: name, public_key, extensions,
+    };
+    if matches.opt_present("m") {
+        let partial = match tbs.encode(scheme).and_then(|tbs| PartialSignature::new(&key, scheme, tbs)) {
+            Ok(partial) => { partial }
+            Err(e) => {
+                eprintln!("generating partial signature failed: {}", e);
+                return ExitCode::FAILURE;
+            }
+        };
+        if let Err(e) = write_output(&partial.to_der(), form, CredType::RsaPartialSignature) {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+        return ExitCode::SUCCESS;
     }
-
-    if matches.opt_present("f") {
-        let form = matches.opt_str("f").unwrap();
-        println!("option: --outform {}", form);
+    let cert = match tbs.sign(&key, scheme) {
+        Ok(cert) => { cert }
+        Err(e) => {
+            eprintln!("generating certificate failed: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Err(e) = write_output(&cert.der, form, CredType::Certificate) {
+        eprintln!("{}", e);
+        return ExitCode::FAILURE;
     }
-
-    println!("self()");
     return ExitCode::SUCCESS;
 }
 
@@ -217,22 +150,32 @@ pub fn pki_self(matches: &Matches) -> ExitCode
 inventory::submit!
 {
     let brief: &'static[&'static str] = &[
-        "[--in file|--keyid hex] [--type rsa|ecdsa|ed25519|ed448|priv]",
+        "[--in file|--keyid hex]",
+        "[--type priv|rsa|ecdsa|ed25519|ed448|mldsa44|mldsa65|mldsa87|",
+        "slhdsa-sha2-128s|slhdsa-sha2-128f|slhdsa-sha2-192s|slhdsa-sha2-192f|",
+        "slhdsa-sha2-256s|slhdsa-sha2-256f|slhdsa-shake-128s|slhdsa-shake-128f|",
+        "slhdsa-shake-192s|slhdsa-shake-192f|slhdsa-shake-256s|slhdsa-shake-256f|",
+        "mldsa44-rsa2048-pss|mldsa44-rsa2048-pkcs15|mldsa44-ed25519|mldsa44-ecdsa-p256|",
+        "mldsa65-rsa3072-pss|mldsa65-rsa3072-pkcs15|mldsa65-rsa4096-pss|",
+        "mldsa65-rsa4096-pkcs15|mldsa65-ecdsa-p256|mldsa65-ecdsa-p384|mldsa65-ed25519|",
+        "mldsa87-ecdsa-p384|mldsa87-ed448|mldsa87-rsa3072-pss|mldsa87-rsa4096-pss|",
+        "mldsa87-ecdsa-p521]",
         " --dn distinguished-name [--san subjectAltName]+",
         "[--lifetime days] [--serial hex] [--ca] [--ocsp uri]+",
         "[--not-before datetime] [--not-after datetime] [--dateform form]",
-        "[--flag serverAuth|clientAuth|crlSign|ocspSigning|msSmartcardLogon]+",
+        "[--flag serverAuth|clientAuth|crlSign|ocspSigning|msSmartcardLogon|codeSigning|",
+        "timeStamping|emailProtection|ikeIntermediate|oid]+",
         "[--nc-permitted name] [--nc-excluded name]",
-        "[--policy-map issuer-oid:subject-oid]",
+        "[--policy-mapping issuer-oid:subject-oid]+",
         "[--policy-explicit len] [--policy-inhibit len] [--policy-any len]",
         "[--cert-policy oid [--cps-uri uri] [--user-notice text]]+",
         "[--digest md5|sha1|sha224|sha256|sha384|sha512|sha3_224|sha3_256|sha3_384|sha3_512]",
-        "[--rsa-padding pkcs1|pss] [--critical oid]",
-        "[--outform der|pem]"
+        "[--rsa-padding pkcs1|pss] [--critical oid] [--ext oid[:critical]=hex|file|utf8:text]+",
+        "[--profile-file file --profile name] [--partial] [--outform der|pem]"
     ];
     let options: &'static[Opt] = &[
         Opt { long: "help",            short: "h", arg: 0, descr: "show usage information" },
-        Opt { long: "in",              short: "i", arg: 1, descr: "private key input file, default: stdin" },
+        Opt { long: "in",              short: "i", arg: 1, descr: "private key or RSA key share input file, default: stdin" },
         Opt { long: "keyid",           short: "x", arg: 1, descr: "smartcard or TPM private key object handle" },
         Opt { long: "type",            short: "t", arg: 1, descr: "type of input key, default: priv" },
         Opt { long: "dn",              short: "d", arg: 1, descr: "subject and issuer distinguished name" },
@@ -240,7 +183,7 @@ inventory::submit!
         Opt { long: "lifetime",        short: "l", arg: 1, descr: "days the certificate is valid, default: 1095" },
         Opt { long: "not-before",      short: "F", arg: 1, descr: "date/time the validity of the cert starts" },
         Opt { long: "not-after",       short: "T", arg: 1, descr: "date/time the validity of the cert ends" },
-        Opt { long: "dateform",        short: "D", arg: 1, descr: "strptime(3) input format, default: %d.%m.%y %T" },
+        Opt { long: "dateform",        short: "D", arg: 1, descr: "strptime(3) input format, default: ISO 8601" },
         Opt { long: "serial",          short: "s", arg: 1, descr: "serial number in hex, default: random" },
         Opt { long: "ca",              short: "b", arg: 0, descr: "include CA basicConstraint, default: no" },
         Opt { long: "pathlen",         short: "p", arg: 1, descr: "set path length constraint" },
@@ -254,11 +197,15 @@ inventory::submit!
         Opt { long: "policy-explicit", short: "E", arg: 1, descr: "requireExplicitPolicy constraint" },
         Opt { long: "policy-inhibit",  short: "H", arg: 1, descr: "inhibitPolicyMapping constraint" },
         Opt { long: "policy-any",      short: "A", arg: 1, descr: "inhibitAnyPolicy constraint" },
-        Opt { long: "flag",            short: "e", arg: 2, descr: "include extendedKeyUsage flag" },
+        Opt { long: "flag",            short: "e", arg: 2, descr: "include extendedKeyUsage flag or key purpose OID" },
         Opt { long: "ocsp",            short: "o", arg: 2, descr: "OCSP AuthorityInfoAccess URI to include" },
         Opt { long: "digest",          short: "g", arg: 1, descr: "digest for signature creation, default: key-specific" },
         Opt { long: "rsa-padding",     short: "R", arg: 1, descr: "padding for RSA signatures, default: pkcs1" },
-        Opt { long: "critical",        short: "X", arg: 1, descr: "critical extension OID to include for test purposes" },
+        Opt { long: "critical",        short: "X", arg: 1, descr: "unknown critical extension OID to include for test purposes" },
+        Opt { long: "ext",             short: "V", arg: 2, descr: "custom extension to include or replace a generated one" },
+        Opt { long: "profile-file",    short: "y", arg: 1, descr: "file with named certificate profiles" },
+        Opt { long: "profile",         short: "Y", arg: 1, descr: "certificate profile to apply, options override all but its flags" },
+        Opt { long: "partial",         short: "m", arg: 0, descr: "create a partial signature with an RSA key share" },
         Opt { long: "outform",         short: "f", arg: 1, descr: "encoding of generated cert, default: der" },
     ];
     Command::new(pki_self, "s", "self",
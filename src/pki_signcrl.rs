@@ -14,147 +14,291 @@
 // for more details.
 
 use std::process::ExitCode;
+use chrono::Utc;
 use getopts::Matches;
-use chrono::{DateTime, Utc};
 use pki::{Command, Opt};
+use pki::crl::{Crl, RevokedCert, TbsCertList, crl_number, delta_crl_indicator, next_crl_number,
+               parse_reason};
+use pki::extensions::{authority_key_identifier, custom_extensions, freshest_crl,
+                      merge_extensions};
+use pki::keys::PrivateKey;
+use pki::pem::{Encoding, CredType, load_block, load_blocks, write_output};
+use pki::signature::{SignatureScheme, parse_signature_options};
+use pki::threshold::PartialSignature;
+use pki::validity::{Validity, parse_time};
+use pki::verify::check_crl_issuance;
+use pki::x509::{Certificate, Extension, parse_serial};
+
+/// Certificate to revoke, given by file or serial number
+enum Target {
+    Cert(String),
+    Serial(Vec<u8>),
+}
+
+/// A --cert or --serial together with the --reason and --date preceding it
+struct Revocation {
+    target: Target,
+    date: Option<i64>,
+    reason: Option<u8>,
+}
 
 //
-// Sign a CRL.
+// Collect the certificates to revoke in command line order, each with the
+// --reason and --date options given since the previous --cert or --serial.
 //
-pub fn pki_signcrl(matches: &Matches) -> ExitCode
+fn parse_revocations(matches: &Matches, now: i64) -> Result<Vec<Revocation>, String>
 {
-  let cacert = match matches.opt_str("c") {
-        Some(v) => { v }
-        None => {
-            println!("option '--cacert' is required");
-            return ExitCode::from(2);
-        }
-    };
-    println!("option: --cacert {}", cacert);
-
-    let cakey = match matches.opt_str("k") {
-        Some(v) => { v }
-        None => { "".to_string() }
-    };
-    println!("option: --cakey {}", cakey);
+    let dateform = matches.opt_str("D");
+    let mut targets: Vec<(usize, Target)> = Vec::new();
+    for (pos, file) in matches.opt_strs_pos("z") {
+        targets.push((pos, Target::Cert(file)));
+    }
+    for (pos, hex) in matches.opt_strs_pos("s") {
+        targets.push((pos, Target::Serial(parse_serial(&hex)?)));
+    }
+    targets.sort_by_key(|(pos, _)| *pos);
 
-    let cakeyid = match matches.opt_str("x") {
-        Some(v) => { v }
-        None => { "".to_string() }
-    };
-    println!("option: --cakeyid {}", cakeyid);
+    let mut reasons: Vec<(usize, u8)> = Vec::new();
+    for (pos, name) in matches.opt_strs_pos("r") {
+        reasons.push((pos, parse_reason(&name)?));
+    }
+    let mut dates: Vec<(usize, i64)> = Vec::new();
+    for (pos, value) in matches.opt_strs_pos("d") {
+        dates.push((pos, parse_time(&value, dateform.as_deref(), now)?));
+    }
+    let trailing = |pos: usize| targets.last().is_none_or(|(last, _)| pos > *last);
+    if reasons.iter().any(|(pos, _)| trailing(*pos)) || dates.iter().any(|(pos, _)| trailing(*pos)) {
+        return Err("options '--reason' and '--date' must precede a '--cert' or '--serial'".to_string());
+    }
 
-    if cakey.is_empty() && cakeyid.is_empty() {
-       println!("option '--cakey' or '--cakeyid' is required");
-        return ExitCode::from(2);
+    let mut revocations: Vec<Revocation> = Vec::new();
+    let mut prev = 0;
+    for (pos, target) in targets {
+        let in_range = |p: usize| p >= prev && p < pos;
+        let reason = reasons.iter().rev().find(|(p, _)| in_range(*p)).map(|(_, r)| *r);
+        let date = dates.iter().rev().find(|(p, _)| in_range(*p)).map(|(_, d)| *d);
+        revocations.push(Revocation { target, date, reason });
+        prev = pos;
     }
+    return Ok(revocations);
+}
 
-    if !cakey.is_empty() && !cakeyid.is_empty() {
-        println!("options '--cakey' and '--cakeyid' can't be set both");
-        return ExitCode::from(2);
+//
+// Load a CRL and check that it has been issued by the CA.
+//
+fn load_crl(file: &str, ca: &Certificate) -> Result<Crl, String>
+{
+    let crl = load_block(Some(file)).and_then(|block| Crl::from_der(&block.der))
+                                    .map_err(|e| format!("loading CRL '{}' failed: {}", file, e))?;
+    if crl.issuer != ca.subject || !crl.verify_signature(&ca.public_key) {
+        return Err(format!("CRL '{}' has not been issued by the CA", file));
     }
+    return Ok(crl);
+}
 
-    let lifetime: i64 = 24 * 60 * 60 * match matches.opt_str("l") {
-        Some(string) => { string.parse().unwrap() }
-        None => { 15 } // days
-    };
-    println!("option: --lifetime {} seconds", lifetime);
+//
+// Get the serial number of a certificate issued by the CA.
+//
+fn cert_serial(file: &str, ca: &Certificate) -> Result<Vec<u8>, String>
+{
+    let cert = load_block(Some(file)).and_then(|block| Certificate::from_der(&block.der))
+                                     .map_err(|e| format!("loading certificate '{}' failed: {}",
+                                                          file, e))?;
+    if cert.issuer != ca.subject || !cert.verify_signature(&ca.public_key) {
+        return Err(format!("certificate '{}' has not been issued by the CA", file));
+    }
+    let skip = cert.serial.iter().take_while(|b| **b == 0).count().min(cert.serial.len() - 1);
+    return Ok(cert.serial[skip..].to_vec());
+}
 
-    let dateform = match matches.opt_str("D") {
-        Some(v) => { v }
-        None => { "%Y-%m-%dT%H:%M:%S%z".to_string() }
-    };
-    println!("option: --dateform {}", dateform);
-
-    let datetu: i64 = match matches.opt_str("F") {
-        Some(v) => {
-            match DateTime::parse_from_str(v.as_str(), dateform.as_str()) {
-                Ok(dt) => { dt.timestamp() }
-                Err(e) => {
-                    println!("{e}");
-                    return ExitCode::FAILURE;
-                }
-            }
+//
+// Build the crlExtensions and the revocation list, taking the entries and
+// the cRLNumber of the last CRL into account.
+//
+fn crl_contents(matches: &Matches, ca: &Certificate, revocations: Vec<Revocation>,
+                custom: Vec<Extension>, now: i64)
+               -> Result<(Vec<RevokedCert>, Vec<Extension>), String>
+{
+    let mut revoked: Vec<RevokedCert> = Vec::new();
+    let mut number = vec![1];
+    if let Some(file) = matches.opt_str("a") {
+        let last = load_crl(&file, ca)?;
+        revoked = last.revoked.clone();
+        if let Some(last_number) = last.number()? {
+            number = next_crl_number(&last_number)?;
         }
-        None => { Utc::now().timestamp() }
-    };
-    println!("option: --this-update {}", datetu);
-
-    let datenu: i64 = match matches.opt_str("T") {
-        Some(v) => {
-            match DateTime::parse_from_str(v.as_str(), dateform.as_str()) {
-                Ok(dt) => { dt.timestamp() }
-                Err(e) => {
-                    println!("{e}");
-                    return ExitCode::FAILURE;
-                }
-            }
+    }
+    let mut base_number = None;
+    if let Some(file) = matches.opt_str("b") {
+        let base = load_crl(&file, ca)?;
+        if base.base_number()?.is_some() {
+            return Err(format!("base CRL '{}' is a delta CRL", file));
         }
-        None => { datetu + lifetime }
-    };
-    println!("option: --next-update {}", datenu);
-
-    if matches.opt_present("a") {
-        let last_crl = matches.opt_str("a").unwrap();
-        println!("option: --lastcrl {}", last_crl);
+        let base_crl_number = base.number()?.ok_or(format!("base CRL '{}' lacks a cRLNumber", file))?;
+        let delta_number = next_crl_number(&base_crl_number)?;
+        number = [number, delta_number].into_iter().max_by_key(|n| (n.len(), n.clone())).unwrap();
+        base_number = Some(base_crl_number);
     }
 
-    if matches.opt_present("b") {
-        let base_crl = matches.opt_str("b").unwrap();
-        println!("option: --basecrl {}", base_crl);
+    for revocation in revocations {
+        let serial = match revocation.target {
+            Target::Cert(file) => { cert_serial(&file, ca)? }
+            Target::Serial(serial) => { serial }
+        };
+        let entry = RevokedCert { serial, date: revocation.date.unwrap_or(now),
+                                  reason: revocation.reason };
+        match revoked.iter_mut().find(|r| r.serial == entry.serial) {
+            Some(existing) => { *existing = entry; }
+            None => { revoked.push(entry); }
+        }
     }
 
-    let crl_uris: Vec<String> = matches.opt_strs("u");
-    for u in &crl_uris
-    {
-        println!("option: --crl {}", u);
+    let mut extensions = vec![authority_key_identifier(&ca.key_identifier()?), crl_number(&number)];
+    if let Some(base_number) = base_number {
+        extensions.push(delta_crl_indicator(&base_number));
     }
-
-    let certs: Vec<String> = matches.opt_strs("z");
-    for c in &certs
-    {
-        println!("option: --cert {}", c);
+    let uris = matches.opt_strs("u");
+    if !uris.is_empty() {
+        extensions.push(freshest_crl(&uris)?);
     }
+    merge_extensions(&mut extensions, custom)?;
+    return Ok((revoked, extensions));
+}
 
-    let serial: Vec<String> = matches.opt_strs("s");
-    for s in &serial
-    {
-        println!("option: --serlal {}", s);
+//
+// Sign a CRL.
+//
+pub fn pki_signcrl(matches: &Matches) -> ExitCode
+{
+    let cacert = match matches.opt_str("c") {
+        Some(v) => { v }
+        None => {
+            eprintln!("option '--cacert' is required");
+            return ExitCode::from(2);
+        }
+    };
+    let cakey = matches.opt_str("k");
+    if matches.opt_present("x") {
+        if cakey.is_some() {
+            eprintln!("options '--cakey' and '--cakeyid' can't be set both");
+            return ExitCode::from(2);
+        }
+        eprintln!("smartcard or TPM private keys are not supported");
+        return ExitCode::FAILURE;
     }
+    let cakey = match cakey {
+        Some(v) => { v }
+        None => {
+            eprintln!("option '--cakey' or '--cakeyid' is required");
+            return ExitCode::from(2);
+        }
+    };
 
-    let reason: Vec<String> = matches.opt_strs("r");
-    for r in &reason
-    {
-        println!("option: --reason {}", r);
-    }
+    let validity = match Validity::from_matches(matches, 24 * 60 * 60, 15) {
+        Ok(validity) => { validity }
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(2);
+        }
+    };
+    let now = Utc::now().timestamp();
+    let revocations = match parse_revocations(matches, now) {
+        Ok(revocations) => { revocations }
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(2);
+        }
+    };
 
-    let date: Vec<String> = matches.opt_strs("d");
-    for d in &date
-    {
-        println!("option: --date {}", d);
-    }
+    let custom = match custom_extensions(&matches.opt_strs("V"), matches.opt_str("X").as_deref()) {
+        Ok(custom) => { custom }
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(2);
+        }
+    };
 
-    if matches.opt_present("X") {
-        let critical_extension_oid = matches.opt_str("X").unwrap();
-        println!("option: --critical {}", critical_extension_oid);
-    }
+    let (digest, pss) = match parse_signature_options(matches.opt_str("g").as_deref(),
+                                                      matches.opt_str("R").as_deref()) {
+        Ok(options) => { options }
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(2);
+        }
+    };
 
-    if matches.opt_present("g") {
-        let digest = matches.opt_str("g").unwrap();
-        println!("option: --digest {}", digest);
-    }
+    let form = match Encoding::from_matches(matches, Encoding::Der) {
+        Ok(form) => { form }
+        Err(code) => { return code; }
+    };
 
-    if matches.opt_present("R") {
-        let padding = matches.opt_str("R").unwrap();
-        println!("option: --rsa-padding {}", padding);
+    let ca = match load_block(Some(&cacert)).and_then(|block| Certificate::from_der(&block.der)) {
+        Ok(cert) => { cert }
+        Err(e) => {
+            eprintln!("loading CA certificate '{}' failed: {}", cacert, e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let key = match load_blocks(Some(&cakey)).and_then(|blocks| PrivateKey::from_blocks(&blocks)) {
+        Ok(key) => { key }
+        Err(e) => {
+            eprintln!("loading CA private key '{}' failed: {}", cakey, e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if key.public_key().keyid() != ca.public_key.keyid() {
+        eprintln!("CA private key does not match CA certificate");
+        return ExitCode::FAILURE;
     }
-
-    if matches.opt_present("f") {
-        let form = matches.opt_str("f").unwrap();
-        println!("option: --outform {}", form);
+    let violations = check_crl_issuance(&ca, now);
+    if !violations.is_empty() {
+        for violation in &violations {
+            eprintln!("error: {}", violation);
+        }
+        return ExitCode::FAILURE;
     }
+    let scheme = match SignatureScheme::select(key.key_type(), key.bits(), digest, pss) {
+        Ok(scheme) => { scheme }
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
 
-    println!("signcrl()");
+    let (revoked, extensions) = match crl_contents(matches, &ca, revocations, custom, now) {
+        Ok(contents) => { contents }
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let tbs = TbsCertList { issuer: ca.subject.clone(), this_update: validity.not_before,
+                            next_update: validity.not_after, revoked, extensions };
+    if matches.opt_present("m") {
+        let partial = match tbs.encode(scheme).and_then(|tbs| PartialSignature::new(&key, scheme, tbs)) {
+            Ok(partial) => { partial }
+            Err(e) => {
+                eprintln!("generating partial signature failed: {}", e);
+                return ExitCode::FAILURE;
+            }
+        };
+        if let Err(e) = write_output(&partial.to_der(), form, CredType::RsaPartialSignature) {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+        return ExitCode::SUCCESS;
+    }
+    let crl = match tbs.sign(&key, scheme) {
+        Ok(crl) => { crl }
+        Err(e) => {
+            eprintln!("generating CRL failed: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Err(e) = write_output(&crl.der, form, CredType::Crl) {
+        eprintln!("{}", e);
+        return ExitCode::FAILURE;
+    }
     return ExitCode::SUCCESS;
 }
 
@@ -169,31 +313,75 @@ inventory::submit!
         "[--lastcrl crl] [--basecrl crl] [--crluri uri]+",
         "[[--reason key-compromise|ca-compromise|affiliation-changed|",
         "           superseded|cessation-of-operation|certificate-hold]",
-        " [--date timestamp] --cert file|--serial hex]*",
+        " [--date datetime] --cert file|--serial hex]*",
         "[--digest md5|sha1|sha224|sha256|sha384|sha512|sha3_224|sha3_256|sha3_384|sha3_512]",
-        "[--rsa-padding pkcs1|pss] [--critical oid] [--outform der|pem]"
+        "[--rsa-padding pkcs1|pss] [--critical oid] [--ext oid[:critical]=hex|file|utf8:text]+",
+        "[--partial] [--outform der|pem]"
     ];
     let options: &'static[Opt] = &[
         Opt { long: "help",        short: "h", arg: 0, descr: "show usage information" },
         Opt { long: "cacert",      short: "c", arg: 1, descr: "CA certificate file" },
-        Opt { long: "cakey",       short: "k", arg: 1, descr: "CA private key file" },
+        Opt { long: "cakey",       short: "k", arg: 1, descr: "CA private key or RSA key share file" },
         Opt { long: "cakeyid",     short: "x", arg: 1, descr: "smartcard or TPM CA private key object handle" },
         Opt { long: "lifetime",    short: "l", arg: 1, descr: "days the CRL gets a nextUpdate, default: 15" },
         Opt { long: "this-update", short: "F", arg: 1, descr: "date/time the validity of the CRL starts" },
         Opt { long: "next-update", short: "T", arg: 1, descr: "date/time the validity of the CRL ends" },
-        Opt { long: "dateform",    short: "D", arg: 1, descr: "strptime(3) input format, default: %d.%m.%y %T" },
+        Opt { long: "dateform",    short: "D", arg: 1, descr: "strptime(3) input format, default: ISO 8601" },
         Opt { long: "lastcrl",     short: "a", arg: 1, descr: "CRL of lastUpdate to copy revocations from" },
         Opt { long: "basecrl",     short: "b", arg: 1, descr: "base CRL to create a delta CRL for" },
         Opt { long: "crluri",      short: "u", arg: 2, descr: "freshest delta CRL URI to include" },
         Opt { long: "cert",        short: "z", arg: 2, descr: "certificate file to revoke" },
         Opt { long: "serial",      short: "s", arg: 2, descr: "hex encoded certificate serial number to revoke" },
         Opt { long: "reason",      short: "r", arg: 2, descr: "reason for certificate revocation" },
-        Opt { long: "date",        short: "d", arg: 2, descr: "revocation date as unix timestamp, default: now" },
+        Opt { long: "date",        short: "d", arg: 2, descr: "revocation date/time, default: now" },
         Opt { long: "digest",      short: "g", arg: 1, descr: "digest for signature creation, default: key-specific" },
         Opt { long: "rsa-padding", short: "R", arg: 1, descr: "padding for RSA signatures, default: pkcs1" },
-        Opt { long: "critical",    short: "X", arg: 1, descr: "critical extension OID to include for test purposes" },
+        Opt { long: "critical",    short: "X", arg: 1, descr: "unknown critical extension OID to include for test purposes" },
+        Opt { long: "ext",         short: "V", arg: 2, descr: "custom CRL extension to include" },
+        Opt { long: "partial",     short: "m", arg: 0, descr: "create a partial signature with an RSA key share" },
         Opt { long: "outform",     short: "f", arg: 1, descr: "encoding of generated crl, default: der" },
     ];
     Command::new(pki_signcrl, "c", "signcrl",
                 "issue a CRL using a CA certificate and key", brief, options)
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use getopts::Options;
+
+    fn matches(args: &[&str]) -> Matches
+    {
+        let mut opts = Options::new();
+        opts.optmulti("z", "cert", "", "");
+        opts.optmulti("s", "serial", "", "");
+        opts.optmulti("r", "reason", "", "");
+        opts.optmulti("d", "date", "", "");
+        opts.optopt("D", "dateform", "", "");
+        return opts.parse(args).unwrap();
+    }
+
+    #[test]
+    fn reason_and_date_apply_to_next_target()
+    {
+        let m = matches(&["--serial", "01", "--reason", "superseded", "--date", "@1700000000",
+                          "--cert", "a.crt", "--reason", "certificate-hold", "--serial", "0203"]);
+        let revocations = parse_revocations(&m, 1800000000).unwrap();
+        assert_eq!(revocations.len(), 3);
+
+        assert!(matches!(&revocations[0].target, Target::Serial(s) if *s == [0x01]));
+        assert_eq!((revocations[0].reason, revocations[0].date), (None, None));
+        assert!(matches!(&revocations[1].target, Target::Cert(f) if f == "a.crt"));
+        assert_eq!((revocations[1].reason, revocations[1].date), (Some(4), Some(1700000000)));
+        assert!(matches!(&revocations[2].target, Target::Serial(s) if *s == [0x02, 0x03]));
+        assert_eq!((revocations[2].reason, revocations[2].date), (Some(6), None));
+    }
+
+    #[test]
+    fn trailing_reason_rejected()
+    {
+        let m = matches(&["--serial", "01", "--reason", "superseded"]);
+        assert!(parse_revocations(&m, 0).is_err());
+        let m = matches(&["--date", "+1d"]);
+        assert!(parse_revocations(&m, 0).is_err());
+    }
+}
@@ -15,39 +15,93 @@
 // for more details.
 
 use std::process::ExitCode;
+use chrono::Utc;
 use getopts::Matches;
 use pki::{Command, Opt};
+use pki::asn1::asn1_oid_from_string;
+use pki::oid::oid_display;
+use pki::pem::{load_block, load_blocks};
+use pki::verify::{build_chain, subject_name, verify_chain};
+use pki::x509::Certificate;
 
 //
 // Verify a certificate signature.
 //
 pub fn pki_verify(matches: &Matches) -> ExitCode
 {
-    let cacerts: Vec<String> = matches.opt_strs("c");
-    for c in &cacerts {
-         println!("option: --cacert {}", c);
-    }
-    if cacerts.len() == 0 {
-        println!("option '--cacert' is required");
+    let files: Vec<String> = matches.opt_strs("c");
+    if files.is_empty() {
+        eprintln!("option '--cacert' is required");
         return ExitCode::from(2);
     }
+    let mut cacerts: Vec<Certificate> = Vec::new();
+    for file in &files {
+        let blocks = match load_blocks(Some(file)) {
+            Ok(blocks) => { blocks }
+            Err(e) => {
+                eprintln!("{}", e);
+                return ExitCode::FAILURE;
+            }
+        };
+        for block in blocks {
+            match Certificate::from_der(&block.der) {
+                Ok(cert) => { cacerts.push(cert); }
+                Err(e) => {
+                    eprintln!("parsing CA certificate '{}' failed: {}", file, e);
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+    }
 
-    let crls: Vec<String> = matches.opt_strs("l");
-    for c in &crls {
-         println!("option: --crl {}", c);
+    if matches.opt_present("l") || matches.opt_present("o") {
+        eprintln!("revocation checking is not supported, ignoring --crl and --online");
     }
 
-    if matches.opt_present("i") {
-        let file = matches.opt_str("i").unwrap();
-        println!("option: --in {}", file);
-    } else {
-        println!("option '--in' missing: get input from stdin");
+    let policies: Vec<String> = matches.opt_strs("p");
+    for policy in &policies {
+        if asn1_oid_from_string(policy).is_err() {
+            eprintln!("invalid policy OID '{}'", policy);
+            return ExitCode::from(2);
+        }
     }
 
-    let online: bool = matches.opt_present("o");
-    println!("online: {}", online);
+    let file = matches.opt_str("i");
+    let cert = match load_block(file.as_deref()).and_then(|block| Certificate::from_der(&block.der)) {
+        Ok(cert) => { cert }
+        Err(e) => {
+            eprintln!("parsing certificate failed: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
 
-    println!("verify()");
+    let chain = match build_chain(&cert, &cacerts) {
+        Ok(chain) => { chain }
+        Err(e) => {
+            eprintln!("{}", e);
+            println!("certificate untrusted");
+            return ExitCode::FAILURE;
+        }
+    };
+    for (i, c) in chain.iter().enumerate() {
+        let label = if i == 0 { "certificate" } else { "trusted ca certificate" };
+        println!("  using {} \"{}\"", label, subject_name(c));
+    }
+    println!("  reached self-signed root ca with a path length of {}", chain.len().saturating_sub(2));
+
+    match verify_chain(&chain, Utc::now().timestamp(), &policies) {
+        Ok(valid) => {
+            for policy in valid {
+                println!("  valid certificate policy {}", oid_display(&policy));
+            }
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            println!("certificate untrusted");
+            return ExitCode::FAILURE;
+        }
+    }
+    println!("certificate trusted, lifetimes valid");
     return ExitCode::SUCCESS;
 }
 
@@ -57,7 +111,7 @@ pub fn pki_verify(matches: &Matches) -> ExitCode
 inventory::submit!
 {
     let brief: &'static[&'static str] = &[
-        "[--in file] [--cacert file]+ [--crl file]"
+        "[--in file] [--cacert file]+ [--crl file] [--policy oid]+"
     ];
     let options: &'static[Opt] = &[
         Opt { long: "help",   short: "h", arg: 0, descr: "show usage information" },
@@ -65,6 +119,7 @@ inventory::submit!
         Opt { long: "cacert", short: "c", arg: 2, descr: "CA certificate(s) for trustchain verification" },
         Opt { long: "crl",    short: "l", arg: 2, descr: "CRL(s) for trustchain verification" },
         Opt { long: "online", short: "o", arg: 0, descr: "enable online CRL/OCSP revocation checking" },
+        Opt { long: "policy", short: "p", arg: 2, descr: "acceptable certificate policy, requires an explicit policy" },
     ];
     Command::new(pki_verify, "v", "verify",
                 "verify a certificate using one or more CA certificates", brief, options)
@@ -0,0 +1,489 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! Certificate policy processing (RFC 5280, 6.1.2 - 6.1.5).  The valid
+//! policy tree is kept as an arena of nodes referring to their parents,
+//! nodes being marked as deleted when pruned.  Policy qualifiers are not
+//! processed.
+
+use crate::asn1::*;
+use crate::oid::*;
+use crate::x509::Certificate;
+use crate::X509_NO_CONSTRAINT;
+
+/// A node of the valid policy tree
+#[derive(Clone, Debug)]
+struct Node {
+    /// depth of the node, the root being at depth 0
+    depth: usize,
+    /// valid_policy
+    policy: String,
+    /// expected_policy_set
+    expected: Vec<String>,
+    /// index of the parent node
+    parent: Option<usize>,
+    /// set if the node has been removed from the tree
+    deleted: bool,
+}
+
+/// The valid_policy_tree, NULL once its root node is deleted
+struct PolicyTree {
+    nodes: Vec<Node>,
+}
+
+/// The policy related extensions of a certificate
+#[derive(Clone, Debug, Default)]
+struct CertPolicies {
+    /// certificatePolicies OIDs, None if the extension is absent
+    policies: Option<Vec<String>>,
+    /// issuerDomainPolicy, subjectDomainPolicy pairs
+    mappings: Vec<(String, String)>,
+    /// requireExplicitPolicy or X509_NO_CONSTRAINT
+    require_explicit: u32,
+    /// inhibitPolicyMapping or X509_NO_CONSTRAINT
+    inhibit_mapping: u32,
+    /// inhibitAnyPolicy or X509_NO_CONSTRAINT
+    inhibit_any: u32,
+}
+
+//
+// Decode a SkipCerts INTEGER, saturating at X509_NO_CONSTRAINT.
+//
+fn skip_certs(obj: &Asn1Object) -> Result<u32, String>
+{
+    let value = obj.to_unsigned()?;
+    if value.len() > 1 {
+        return Ok(X509_NO_CONSTRAINT);
+    }
+    return Ok((value[0] as u32).min(X509_NO_CONSTRAINT));
+}
+
+impl CertPolicies {
+    //
+    // Decode the policy related extensions of a certificate.
+    //
+    fn from_cert(cert: &Certificate) -> Result<CertPolicies, String>
+    {
+        let mut result = CertPolicies {
+            require_explicit: X509_NO_CONSTRAINT,
+            inhibit_mapping: X509_NO_CONSTRAINT,
+            inhibit_any: X509_NO_CONSTRAINT,
+            ..Default::default()
+        };
+        if let Some(ext) = cert.extension(OID_CERTIFICATE_POLICIES) {
+            let mut policies: Vec<String> = Vec::new();
+            let mut parser = Asn1Parser::new(&ext.value).expect(ASN1_SEQUENCE)?.parser();
+            while let Some(info) = parser.next_object()? {
+                let oid = info.parser().expect(ASN1_OID)?.to_oid()?;
+                if policies.contains(&oid) {
+                    return Err(format!("duplicate certificate policy {}", oid));
+                }
+                policies.push(oid);
+            }
+            result.policies = Some(policies);
+        }
+        if let Some(ext) = cert.extension(OID_POLICY_MAPPINGS) {
+            let mut parser = Asn1Parser::new(&ext.value).expect(ASN1_SEQUENCE)?.parser();
+            while let Some(mapping) = parser.next_object()? {
+                let mut p = mapping.parser();
+                let issuer = p.expect(ASN1_OID)?.to_oid()?;
+                let subject = p.expect(ASN1_OID)?.to_oid()?;
+                p.finish()?;
+                if issuer == OID_ANY_POLICY || subject == OID_ANY_POLICY {
+                    return Err("anyPolicy must not be mapped".to_string());
+                }
+                result.mappings.push((issuer, subject));
+            }
+        }
+        if let Some(ext) = cert.extension(OID_POLICY_CONSTRAINTS) {
+            let mut parser = Asn1Parser::new(&ext.value).expect(ASN1_SEQUENCE)?.parser();
+            if let Some(obj) = parser.optional(ASN1_CONTEXT_S_0)? {
+                result.require_explicit = skip_certs(&obj)?;
+            }
+            if let Some(obj) = parser.optional(ASN1_CONTEXT_S_1)? {
+                result.inhibit_mapping = skip_certs(&obj)?;
+            }
+            parser.finish()?;
+        }
+        if let Some(ext) = cert.extension(OID_INHIBIT_ANY_POLICY) {
+            let mut parser = Asn1Parser::new(&ext.value);
+            result.inhibit_any = skip_certs(&parser.expect(ASN1_INTEGER)?)?;
+            parser.finish()?;
+        }
+        return Ok(result);
+    }
+}
+
+impl PolicyTree {
+    //
+    // Create the initial tree with a single anyPolicy node.
+    //
+    fn new() -> PolicyTree
+    {
+        let root = Node { depth: 0, policy: OID_ANY_POLICY.to_string(),
+                          expected: vec![OID_ANY_POLICY.to_string()], parent: None, deleted: false };
+        return PolicyTree { nodes: vec![root] };
+    }
+
+    //
+    // Get the indices of the nodes at a given depth.
+    //
+    fn at_depth(&self, depth: usize) -> Vec<usize>
+    {
+        return (0..self.nodes.len()).filter(|i| {
+            !self.nodes[*i].deleted && self.nodes[*i].depth == depth
+        }).collect();
+    }
+
+    //
+    // Get the indices of the children of a node.
+    //
+    fn children(&self, parent: usize) -> Vec<usize>
+    {
+        return (0..self.nodes.len()).filter(|i| {
+            !self.nodes[*i].deleted && self.nodes[*i].parent == Some(parent)
+        }).collect();
+    }
+
+    //
+    // Add a node below a parent.
+    //
+    fn add(&mut self, parent: usize, policy: &str, expected: Vec<String>)
+    {
+        let depth = self.nodes[parent].depth + 1;
+        self.nodes.push(Node { depth, policy: policy.to_string(), expected,
+                               parent: Some(parent), deleted: false });
+    }
+
+    //
+    // Remove a node together with all its descendants.
+    //
+    fn delete(&mut self, node: usize)
+    {
+        self.nodes[node].deleted = true;
+        for child in self.children(node) {
+            self.delete(child);
+        }
+    }
+
+    //
+    // Remove nodes without children at or above the given depth.
+    //
+    fn prune(&mut self, depth: usize)
+    {
+        for d in (0..=depth).rev() {
+            for i in self.at_depth(d) {
+                if self.children(i).is_empty() {
+                    self.nodes[i].deleted = true;
+                }
+            }
+        }
+    }
+
+    //
+    // Check if the tree is NULL.
+    //
+    fn is_null(&self) -> bool
+    {
+        return self.nodes[0].deleted;
+    }
+}
+
+//
+// Process the policies of a certification path, given in processing order
+// starting with the certificate issued by the trust anchor.  Returns the
+// user-constrained policy set if the path is valid for at least one
+// acceptable policy or if no explicit policy is required.  An empty set of
+// acceptable policies stands for anyPolicy.
+//
+pub fn process_policies(path: &[&Certificate], acceptable: &[String], explicit: bool)
+                       -> Result<Vec<String>, String>
+{
+    let n = path.len();
+    if n == 0 {
+        return Ok(Vec::new());
+    }
+    let mut tree = Some(PolicyTree::new());
+    let mut explicit_policy = if explicit { 0 } else { n + 1 };
+    let mut inhibit_any = n + 1;
+    let mut policy_mapping = n + 1;
+
+    for (k, cert) in path.iter().enumerate() {
+        let i = k + 1;
+        let self_issued = cert.subject == cert.issuer;
+        let info = CertPolicies::from_cert(cert)?;
+
+        // 6.1.3 (d) and (e)
+        match (&info.policies, tree.as_mut()) {
+            (Some(policies), Some(t)) => {
+                let parents = t.at_depth(i - 1);
+                for p in policies.iter().filter(|p| *p != OID_ANY_POLICY) {
+                    let matching: Vec<usize> = parents.iter().copied()
+                        .filter(|j| t.nodes[*j].expected.contains(p)).collect();
+                    if !matching.is_empty() {
+                        for j in matching {
+                            t.add(j, p, vec![p.clone()]);
+                        }
+                    } else if let Some(j) = parents.iter().copied()
+                                                   .find(|j| t.nodes[*j].policy == OID_ANY_POLICY) {
+                        t.add(j, p, vec![p.clone()]);
+                    }
+                }
+                if policies.iter().any(|p| p == OID_ANY_POLICY) &&
+                   (inhibit_any > 0 || (i < n && self_issued)) {
+                    for j in parents {
+                        let existing: Vec<String> = t.children(j).iter()
+                            .map(|c| t.nodes[*c].policy.clone()).collect();
+                        for p in t.nodes[j].expected.clone() {
+                            if !existing.contains(&p) {
+                                t.add(j, &p, vec![p.clone()]);
+                            }
+                        }
+                    }
+                }
+                t.prune(i - 1);
+                if t.is_null() {
+                    tree = None;
+                }
+            }
+            _ => { tree = None; }
+        }
+
+        // 6.1.3 (f)
+        if explicit_policy == 0 && tree.is_none() {
+            return Err(format!("no valid certificate policy at path position {}", i));
+        }
+        if i == n {
+            // 6.1.5 (a) and (b)
+            explicit_policy = explicit_policy.saturating_sub(1);
+            if info.require_explicit == 0 {
+                explicit_policy = 0;
+            }
+            break;
+        }
+
+        // 6.1.4 (a) and (b)
+        if let Some(t) = tree.as_mut() {
+            let mut issuers: Vec<&str> = Vec::new();
+            for (issuer, _) in &info.mappings {
+                if !issuers.contains(&issuer.as_str()) {
+                    issuers.push(issuer);
+                }
+            }
+            for issuer in issuers {
+                let mapped: Vec<String> = info.mappings.iter().filter(|(p, _)| p == issuer)
+                                                              .map(|(_, s)| s.clone()).collect();
+                let nodes = t.at_depth(i);
+                let matching: Vec<usize> = nodes.iter().copied()
+                    .filter(|j| t.nodes[*j].policy == issuer).collect();
+                if policy_mapping > 0 {
+                    if !matching.is_empty() {
+                        for j in matching {
+                            t.nodes[j].expected = mapped.clone();
+                        }
+                    } else if let Some(j) = nodes.iter().copied()
+                                                 .find(|j| t.nodes[*j].policy == OID_ANY_POLICY) {
+                        let parent = t.nodes[j].parent.unwrap();
+                        t.add(parent, issuer, mapped);
+                    }
+                } else {
+                    for j in matching {
+                        t.delete(j);
+                    }
+                    t.prune(i - 1);
+                }
+            }
+            if t.is_null() {
+                tree = None;
+            }
+        }
+
+        // 6.1.4 (h), (i) and (j)
+        if !self_issued {
+            explicit_policy = explicit_policy.saturating_sub(1);
+            policy_mapping = policy_mapping.saturating_sub(1);
+            inhibit_any = inhibit_any.saturating_sub(1);
+        }
+        explicit_policy = explicit_policy.min(info.require_explicit as usize);
+        policy_mapping = policy_mapping.min(info.inhibit_mapping as usize);
+        inhibit_any = inhibit_any.min(info.inhibit_any as usize);
+    }
+
+    // 6.1.5 (g): intersect with the acceptable policies
+    let mut valid: Vec<String> = Vec::new();
+    if let Some(mut t) = tree {
+        let any_acceptable = acceptable.is_empty() || acceptable.iter().any(|p| p == OID_ANY_POLICY);
+        if !any_acceptable {
+            let node_set: Vec<usize> = (1..t.nodes.len()).filter(|j| {
+                let node = &t.nodes[*j];
+                !node.deleted && t.nodes[node.parent.unwrap()].policy == OID_ANY_POLICY
+            }).collect();
+            for j in &node_set {
+                let policy = &t.nodes[*j].policy;
+                if policy != OID_ANY_POLICY && !acceptable.contains(policy) {
+                    t.delete(*j);
+                }
+            }
+            let leaves = t.at_depth(n);
+            if let Some(any) = leaves.iter().copied().find(|j| t.nodes[*j].policy == OID_ANY_POLICY) {
+                let parent = t.nodes[any].parent.unwrap();
+                for p in acceptable {
+                    if !node_set.iter().any(|j| !t.nodes[*j].deleted && &t.nodes[*j].policy == p) {
+                        t.add(parent, p, vec![p.clone()]);
+                    }
+                }
+                t.nodes[any].deleted = true;
+            }
+            t.prune(n.saturating_sub(1));
+        }
+        if !t.is_null() {
+            for j in t.at_depth(n) {
+                if !valid.contains(&t.nodes[j].policy) {
+                    valid.push(t.nodes[j].policy.clone());
+                }
+            }
+        }
+    }
+    if explicit_policy == 0 && valid.is_empty() {
+        return Err("no acceptable certificate policy".to_string());
+    }
+    return Ok(valid);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::eddsa::{EdCurve, EdPrivateKey};
+    use crate::dn::Dn;
+    use crate::extensions::{self as ext, CertPolicy};
+    use crate::keys::PrivateKey;
+    use crate::signature::SignatureScheme;
+    use crate::x509::{Extension, TbsCertificate};
+
+    const P1: &str = "1.3.6.1.4.1.36906.1.1";
+    const P2: &str = "1.3.6.1.4.1.36906.1.2";
+    const P3: &str = "1.3.6.1.4.1.36906.1.3";
+
+    fn policies(oids: &[&str]) -> Extension
+    {
+        let policies: Vec<CertPolicy> = oids.iter().map(|oid| {
+            CertPolicy { oid: oid.to_string(), ..Default::default() }
+        }).collect();
+        return ext::certificate_policies(&policies).unwrap();
+    }
+
+    fn cert(subject: &str, extensions: Vec<Extension>) -> Certificate
+    {
+        let key = PrivateKey::Eddsa(EdPrivateKey::generate(EdCurve::Ed25519));
+        let tbs = TbsCertificate {
+            serial: vec![0x01], issuer: Dn::parse("CN=Issuer").unwrap().to_der(),
+            not_before: 1760000000, not_after: 1790000000,
+            subject: Dn::parse(subject).unwrap().to_der(),
+            public_key: key.public_key(), extensions,
+        };
+        return tbs.sign(&key, SignatureScheme::Ed25519).unwrap();
+    }
+
+    fn strings(oids: &[&str]) -> Vec<String>
+    {
+        return oids.iter().map(|oid| oid.to_string()).collect();
+    }
+
+    #[test]
+    fn policy_intersection()
+    {
+        let ca = cert("CN=CA", vec![policies(&[P1, P2])]);
+        let ee = cert("CN=EE", vec![policies(&[P1, P3])]);
+        assert_eq!(process_policies(&[&ca, &ee], &[], false).unwrap(), strings(&[P1]));
+        assert_eq!(process_policies(&[&ca, &ee], &strings(&[P1]), true).unwrap(), strings(&[P1]));
+        assert_eq!(process_policies(&[&ca, &ee], &strings(&[P2]), true).unwrap_err(),
+                   "no acceptable certificate policy");
+        assert!(process_policies(&[&ca, &ee], &strings(&[P2]), false).unwrap().is_empty());
+    }
+
+    #[test]
+    fn any_policy()
+    {
+        let ca = cert("CN=CA", vec![policies(&[OID_ANY_POLICY])]);
+        let ee = cert("CN=EE", vec![policies(&[P1])]);
+        assert_eq!(process_policies(&[&ca, &ee], &[], false).unwrap(), strings(&[P1]));
+
+        let ee = cert("CN=EE", vec![policies(&[OID_ANY_POLICY])]);
+        assert_eq!(process_policies(&[&ca, &ee], &[], true).unwrap(), strings(&[OID_ANY_POLICY]));
+        assert_eq!(process_policies(&[&ca, &ee], &strings(&[P3]), true).unwrap(), strings(&[P3]));
+
+        // anyPolicy is not expanded below an inhibitAnyPolicy of zero
+        let root = cert("CN=Root", vec![policies(&[OID_ANY_POLICY]), ext::inhibit_any_policy(0)]);
+        let ee = cert("CN=EE", vec![policies(&[P1])]);
+        assert!(process_policies(&[&root, &ca, &ee], &[], false).unwrap().is_empty());
+        let ca = cert("CN=CA", vec![policies(&[P1])]);
+        assert_eq!(process_policies(&[&root, &ca, &ee], &[], false).unwrap(), strings(&[P1]));
+    }
+
+    #[test]
+    fn explicit_policy()
+    {
+        let ca = cert("CN=CA", vec![policies(&[P1])]);
+        let ee = cert("CN=EE", vec![]);
+        assert!(process_policies(&[&ca, &ee], &[], false).unwrap().is_empty());
+        assert_eq!(process_policies(&[&ca, &ee], &[], true).unwrap_err(),
+                   "no valid certificate policy at path position 2");
+
+        let ca = cert("CN=CA", vec![policies(&[P1]),
+                                    ext::policy_constraints(0, X509_NO_CONSTRAINT)]);
+        assert!(process_policies(&[&ca, &ee], &[], false).is_err());
+        let ca = cert("CN=CA", vec![policies(&[P1]),
+                                    ext::policy_constraints(2, X509_NO_CONSTRAINT)]);
+        assert!(process_policies(&[&ca, &ee], &[], false).is_ok());
+        let ca2 = cert("CN=CA2", vec![policies(&[P1])]);
+        assert!(process_policies(&[&ca, &ca2, &ee], &[], false).is_err());
+    }
+
+    #[test]
+    fn policy_mapping()
+    {
+        let mapping = ext::policy_mappings(&[format!("{}:{}", P1, P2)]).unwrap();
+        let ca = cert("CN=CA", vec![policies(&[P1]), mapping.clone()]);
+        let ee = cert("CN=EE", vec![policies(&[P2])]);
+        assert_eq!(process_policies(&[&ca, &ee], &[], true).unwrap(), strings(&[P2]));
+        let ee1 = cert("CN=EE", vec![policies(&[P1])]);
+        assert!(process_policies(&[&ca, &ee1], &[], true).is_err());
+
+        // mapping is inhibited below the root, deleting the mapped policy
+        let root = cert("CN=Root", vec![policies(&[P1]),
+                                        ext::policy_constraints(X509_NO_CONSTRAINT, 0)]);
+        assert!(process_policies(&[&root, &ca, &ee], &[], false).unwrap().is_empty());
+        let root = cert("CN=Root", vec![policies(&[P1]),
+                                        ext::policy_constraints(X509_NO_CONSTRAINT, 1)]);
+        assert_eq!(process_policies(&[&root, &ca, &ee], &[], false).unwrap(), strings(&[P2]));
+    }
+
+    #[test]
+    fn invalid_extensions()
+    {
+        let duplicate = Extension { oid: OID_CERTIFICATE_POLICIES.to_string(), critical: false,
+                                    value: asn1_sequence(&[asn1_sequence(&[asn1_oid(P1)]),
+                                                           asn1_sequence(&[asn1_oid(P1)])]) };
+        let ee = cert("CN=EE", vec![duplicate]);
+        assert_eq!(process_policies(&[&ee], &[], false).unwrap_err(),
+                   format!("duplicate certificate policy {}", P1));
+
+        let any = Extension { oid: OID_POLICY_MAPPINGS.to_string(), critical: true,
+                              value: asn1_sequence(&[asn1_sequence(&[asn1_oid(OID_ANY_POLICY),
+                                                                     asn1_oid(P1)])]) };
+        let ca = cert("CN=CA", vec![policies(&[P1]), any]);
+        let ee = cert("CN=EE", vec![policies(&[P1])]);
+        assert_eq!(process_policies(&[&ca, &ee], &[], false).unwrap_err(),
+                   "anyPolicy must not be mapped");
+        assert!(process_policies(&[], &[], true).unwrap().is_empty());
+    }
+}
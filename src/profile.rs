@@ -0,0 +1,462 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! Certificate profiles loaded with `--profile-file` and selected with
+//! `--profile`.  A profile file contains named sections in strongSwan
+//! settings syntax or as TOML tables, with the long names of command line
+//! options as keys:
+//!
+//! ```text
+//! server {
+//!     lifetime = 730
+//!     flag = serverAuth
+//!     crl = http://crl.example.com/ca.crl
+//!     ocsp = http://ocsp.example.com
+//! }
+//!
+//! [intermediate-ca]
+//! ca = true
+//! pathlen = 0
+//! cert-policy = "1.3.6.1.4.1.36906.1.1"
+//! cps-uri = "http://www.example.com/cps"
+//! ```
+//!
+//! Options accepted multiple times may be repeated or given as a list in
+//! brackets, options without an argument take a boolean.  Any option given
+//! on the command line replaces all values of that option in the profile,
+//! except for options without an argument: these have no negated form on
+//! the command line, so a flag enabled by a profile stays enabled.  Of the
+//! TOML syntax, only tables with single-line basic strings, bare values and
+//! lists thereof are supported; arrays of tables, inline tables and
+//! multi-line strings are rejected.
+
+use std::fs;
+use getopts::Matches;
+use crate::Opt;
+
+/// Options that can't be set by a profile
+const RESERVED: &[&str] = &["help", "profile", "profile-file"];
+
+/// Options grouped with a preceding option, replaced together with it
+const GROUPED: &[(&str, &str)] = &[
+    ("cps-uri",     "cert-policy"),
+    ("user-notice", "cert-policy"),
+    ("crlissuer",   "crl"),
+];
+
+/// A named set of command line option values
+#[derive(Clone, Debug, Default)]
+pub struct Profile {
+    /// name of the profile section
+    pub name: String,
+    /// long option names and values in the order of the file
+    pub settings: Vec<(String, String)>,
+}
+
+//
+// Strip a '#' comment outside of double quotes.
+//
+fn strip_comment(line: &str) -> &str
+{
+    let mut quoted = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '\\' if quoted && !escaped => { escaped = true; continue; }
+            '"' if !escaped => { quoted = !quoted; }
+            '#' if !quoted => { return &line[..i]; }
+            _ => {}
+        }
+        escaped = false;
+    }
+    return line;
+}
+
+//
+// Remove the double quotes of a value, resolving \" and \\ escapes.
+//
+fn unquote(value: &str) -> Result<String, String>
+{
+    let inner = match value.strip_prefix('"') {
+        Some(rest) => {
+            rest.strip_suffix('"').ok_or(format!("unterminated string {}", value))?
+        }
+        None => { return Ok(value.to_string()); }
+    };
+    let mut out = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                match chars.next() {
+                    Some(e) if e == '"' || e == '\\' => { out.push(e); }
+                    _ => { return Err(format!("invalid escape sequence in {}", value)); }
+                }
+            }
+            '"' => { return Err(format!("unexpected quote in {}", value)); }
+            _ => { out.push(c); }
+        }
+    }
+    return Ok(out);
+}
+
+//
+// Parse a single value or a bracketed, comma-separated list of values.
+//
+fn parse_values(value: &str) -> Result<Vec<String>, String>
+{
+    let list = match value.strip_prefix('[') {
+        Some(rest) => { rest.strip_suffix(']').ok_or(format!("unterminated list {}", value))? }
+        None => { return Ok(vec![unquote(value)?]); }
+    };
+    let mut values = Vec::new();
+    let mut quoted = false;
+    let mut escaped = false;
+    let mut start = 0;
+    for (i, c) in list.char_indices() {
+        match c {
+            '\\' if quoted && !escaped => { escaped = true; continue; }
+            '"' if !escaped => { quoted = !quoted; }
+            ',' if !quoted => {
+                values.push(unquote(list[start..i].trim())?);
+                start = i + 1;
+            }
+            _ => {}
+        }
+        escaped = false;
+    }
+    let last = list[start..].trim();
+    if !last.is_empty() {
+        values.push(unquote(last)?);
+    }
+    if values.iter().any(|v| v.is_empty()) {
+        return Err(format!("empty value in list {}", value));
+    }
+    return Ok(values);
+}
+
+//
+// Check a profile or option name.
+//
+fn valid_name(name: &str) -> bool
+{
+    return !name.is_empty() &&
+           name.bytes().all(|c| c.is_ascii_alphanumeric() || c == b'-' || c == b'_' || c == b'.');
+}
+
+//
+// Parse all profiles of a profile file.
+//
+pub fn parse_profiles(text: &str) -> Result<Vec<Profile>, String>
+{
+    let mut profiles: Vec<Profile> = Vec::new();
+    let mut current: Option<Profile> = None;
+    let mut braced = false;
+
+    for (n, line) in text.lines().enumerate() {
+        let lineno = n + 1;
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        let err = |e: String| format!("line {}: {}", lineno, e);
+
+        if line.starts_with("[[") {
+            return Err(err("arrays of tables are not supported".to_string()));
+        }
+        if let Some((_, value)) = line.split_once('=') {
+            let value = value.trim();
+            if value.starts_with('{') {
+                return Err(err("inline tables are not supported".to_string()));
+            }
+            if value.starts_with("\"\"\"") || value.starts_with("'''") {
+                return Err(err("multi-line strings are not supported".to_string()));
+            }
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) &&
+           !line.contains('=') {
+            if braced {
+                return Err(err(format!("table [{}] inside of section", name)));
+            }
+            profiles.extend(current.take());
+            current = Some(Profile { name: unquote(name.trim()).map_err(err)?, ..Default::default() });
+        } else if let Some(name) = line.strip_suffix('{') {
+            if braced {
+                return Err(err("nested sections are not supported".to_string()));
+            }
+            profiles.extend(current.take());
+            current = Some(Profile { name: name.trim().to_string(), ..Default::default() });
+            braced = true;
+        } else if line == "}" {
+            if !braced {
+                return Err(err("unexpected '}'".to_string()));
+            }
+            profiles.extend(current.take());
+            braced = false;
+        } else if let Some((key, value)) = line.split_once('=') {
+            let profile = current.as_mut().ok_or_else(|| err("setting outside of a profile".to_string()))?;
+            let key = key.trim();
+            if !valid_name(key) {
+                return Err(err(format!("invalid option name '{}'", key)));
+            }
+            for value in parse_values(value.trim()).map_err(err)? {
+                profile.settings.push((key.to_string(), value));
+            }
+        } else {
+            return Err(err(format!("syntax error in '{}'", line)));
+        }
+        if let Some(profile) = &current && !valid_name(&profile.name) {
+            return Err(err(format!("invalid profile name '{}'", profile.name)));
+        }
+    }
+    if braced {
+        return Err("missing '}' at end of file".to_string());
+    }
+    profiles.extend(current);
+
+    for (i, profile) in profiles.iter().enumerate() {
+        if profiles[..i].iter().any(|p| p.name == profile.name) {
+            return Err(format!("profile '{}' defined multiple times", profile.name));
+        }
+    }
+    return Ok(profiles);
+}
+
+//
+// Load a named profile from a profile file.
+//
+pub fn load_profile(path: &str, name: &str) -> Result<Profile, String>
+{
+    let text = fs::read_to_string(path)
+                   .map_err(|e| format!("reading profile file '{}' failed: {}", path, e))?;
+    let profiles = parse_profiles(&text).map_err(|e| format!("{}: {}", path, e))?;
+    match profiles.into_iter().find(|p| p.name == name) {
+        Some(profile) => { Ok(profile) }
+        None => { Err(format!("profile '{}' not found in '{}'", name, path)) }
+    }
+}
+
+//
+// Convert the settings of a profile into command line arguments, omitting
+// the options given on the command line.
+//
+fn settings_args(profile: &Profile, options: &[Opt], matches: &Matches) -> Result<Vec<String>, String>
+{
+    let overridden = |long: &str| -> bool {
+        if matches.opt_present(long) {
+            return true;
+        }
+        return GROUPED.iter().any(|(member, leader)| {
+            *member == long && options.iter().any(|o| o.long == *leader) && matches.opt_present(leader)
+        });
+    };
+
+    let mut args: Vec<String> = Vec::new();
+    for (key, value) in &profile.settings {
+        let opt = options.iter().find(|o| o.long == key)
+                         .ok_or(format!("option '{}' in profile '{}' not supported by this command",
+                                        key, profile.name))?;
+        if RESERVED.contains(&opt.long) {
+            return Err(format!("option '{}' not allowed in profile '{}'", key, profile.name));
+        }
+        if opt.arg == 1 && profile.settings.iter().filter(|(k, _)| k == key).count() > 1 {
+            return Err(format!("option '{}' set multiple times in profile '{}'", key, profile.name));
+        }
+        if overridden(opt.long) {
+            continue;
+        }
+        if opt.arg == 0 {
+            match value.to_ascii_lowercase().as_str() {
+                "yes" | "true" => { args.push(format!("--{}", opt.long)); }
+                "no" | "false" => {}
+                _ => {
+                    return Err(format!("invalid boolean '{}' for option '{}' in profile '{}'",
+                                       value, key, profile.name));
+                }
+            }
+        } else {
+            args.push(format!("--{}={}", opt.long, value));
+        }
+    }
+    return Ok(args);
+}
+
+//
+// Get the command line arguments of the profile selected with --profile
+// from the --profile-file, to be prepended to the actual arguments of a
+// command accepting these options.
+//
+pub fn profile_args(options: &[Opt], matches: &Matches) -> Result<Vec<String>, String>
+{
+    match (matches.opt_str("profile-file"), matches.opt_str("profile")) {
+        (Some(path), Some(name)) => { settings_args(&load_profile(&path, &name)?, options, matches) }
+        (Some(_), None) => { Err("--profile-file requires a --profile name".to_string()) }
+        (None, Some(_)) => { Err("--profile requires a --profile-file".to_string()) }
+        (None, None) => { Ok(Vec::new()) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROFILES: &str = r#"
+# strongSwan settings syntax
+server {
+    lifetime = 730
+    flag = [serverAuth, "clientAuth"]   # list
+    crl = http://crl.example.com/ca.crl
+    crlissuer = "C=CH, CN=CRL Issuer"
+}
+
+[intermediate-ca]
+ca = true
+pathlen = 0
+cert-policy = "1.3.6.1.4.1.36906.1.1"
+cps-uri = "http://www.example.com/cps#top"
+user-notice = "say \"hello\" \\ bye"
+"#;
+
+    const OPTIONS: &[Opt] = &[
+        Opt { long: "help",        short: "h", arg: 0, descr: "" },
+        Opt { long: "lifetime",    short: "l", arg: 1, descr: "" },
+        Opt { long: "flag",        short: "e", arg: 2, descr: "" },
+        Opt { long: "crl",         short: "u", arg: 2, descr: "" },
+        Opt { long: "crlissuer",   short: "I", arg: 2, descr: "" },
+        Opt { long: "ca",          short: "b", arg: 0, descr: "" },
+        Opt { long: "pathlen",     short: "p", arg: 1, descr: "" },
+        Opt { long: "cert-policy", short: "P", arg: 2, descr: "" },
+        Opt { long: "cps-uri",     short: "C", arg: 2, descr: "" },
+        Opt { long: "user-notice", short: "U", arg: 2, descr: "" },
+        Opt { long: "profile",      short: "", arg: 1, descr: "" },
+        Opt { long: "profile-file", short: "", arg: 1, descr: "" },
+    ];
+
+    fn matches(args: &[&str]) -> Matches
+    {
+        let mut opts = getopts::Options::new();
+        for opt in OPTIONS {
+            match opt.arg {
+                0 => { opts.optflag(opt.short, opt.long, ""); }
+                1 => { opts.optopt(opt.short, opt.long, "", ""); }
+                _ => { opts.optmulti(opt.short, opt.long, "", ""); }
+            }
+        }
+        return opts.parse(args).unwrap();
+    }
+
+    fn profile(name: &str, settings: &[(&str, &str)]) -> Profile
+    {
+        let settings = settings.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        return Profile { name: name.to_string(), settings };
+    }
+
+    #[test]
+    fn parse_both_syntaxes()
+    {
+        let profiles = parse_profiles(PROFILES).unwrap();
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].name, "server");
+        assert_eq!(profiles[0].settings, profile("", &[
+            ("lifetime", "730"), ("flag", "serverAuth"), ("flag", "clientAuth"),
+            ("crl", "http://crl.example.com/ca.crl"), ("crlissuer", "C=CH, CN=CRL Issuer"),
+        ]).settings);
+        assert_eq!(profiles[1].name, "intermediate-ca");
+        assert_eq!(profiles[1].settings[3], ("cps-uri".to_string(),
+                                             "http://www.example.com/cps#top".to_string()));
+        assert_eq!(profiles[1].settings[4].1, "say \"hello\" \\ bye");
+        assert!(parse_profiles("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn syntax_errors()
+    {
+        for (text, error) in [
+            ("lifetime = 1", "line 1: setting outside of a profile"),
+            ("a {\nb {", "line 2: nested sections are not supported"),
+            ("a {\n[b]", "line 2: table [b] inside of section"),
+            ("}", "line 1: unexpected '}'"),
+            ("a {\nlifetime = 1", "missing '}' at end of file"),
+            ("[a]\nlifetime", "line 2: syntax error in 'lifetime'"),
+            ("[a b]", "line 1: invalid profile name 'a b'"),
+            ("[a]\nlife time = 1", "line 2: invalid option name 'life time'"),
+            ("[a]\ncrl = \"http://", "line 2: unterminated string \"http://"),
+            ("[a]\ncrl = \"a\\b\"", "line 2: invalid escape sequence in \"a\\b\""),
+            ("[a]\nflag = [a, b", "line 2: unterminated list [a, b"),
+            ("[a]\nflag = [a, , b]", "line 2: empty value in list [a, , b]"),
+            ("[a]\n[b]\n[a]", "profile 'a' defined multiple times"),
+            ("[a]\n[[b]]", "line 2: arrays of tables are not supported"),
+            ("[[a]]\nca = true", "line 1: arrays of tables are not supported"),
+            ("[a]\nsan = { dns = \"a\" }", "line 2: inline tables are not supported"),
+            ("[a]\nsan = {", "line 2: inline tables are not supported"),
+            ("[a]\n\ndn = \"\"\"\nCN=a\n\"\"\"", "line 3: multi-line strings are not supported"),
+            ("[a]\ndn = '''CN=a'''", "line 2: multi-line strings are not supported"),
+        ] {
+            assert_eq!(parse_profiles(text).unwrap_err(), error, "{}", text);
+        }
+    }
+
+    #[test]
+    fn profile_settings()
+    {
+        let profiles = parse_profiles(PROFILES).unwrap();
+        let args = settings_args(&profiles[0], OPTIONS, &matches(&[])).unwrap();
+        assert_eq!(args, vec!["--lifetime=730", "--flag=serverAuth", "--flag=clientAuth",
+                              "--crl=http://crl.example.com/ca.crl",
+                              "--crlissuer=C=CH, CN=CRL Issuer"]);
+        let args = settings_args(&profiles[1], OPTIONS, &matches(&[])).unwrap();
+        assert_eq!(args[..2], ["--ca", "--pathlen=0"]);
+
+        // command line options replace the profile values, grouped ones too
+        let args = settings_args(&profiles[0], OPTIONS,
+                                 &matches(&["-l", "10", "--crl", "http://other"])).unwrap();
+        assert_eq!(args, vec!["--flag=serverAuth", "--flag=clientAuth"]);
+        let args = settings_args(&profiles[1], OPTIONS, &matches(&["-P", "1.2.3"])).unwrap();
+        assert_eq!(args, vec!["--ca", "--pathlen=0"]);
+
+        let args = settings_args(&profile("p", &[("ca", "no")]), OPTIONS, &matches(&[])).unwrap();
+        assert!(args.is_empty());
+        for (settings, error) in [
+            (vec![("ca", "maybe")], "invalid boolean 'maybe' for option 'ca' in profile 'p'"),
+            (vec![("lifetime", "1"), ("lifetime", "2")],
+             "option 'lifetime' set multiple times in profile 'p'"),
+            (vec![("serial", "01")], "option 'serial' in profile 'p' not supported by this command"),
+            (vec![("profile", "q")], "option 'profile' not allowed in profile 'p'"),
+        ] {
+            assert_eq!(settings_args(&profile("p", &settings), OPTIONS, &matches(&[])).unwrap_err(),
+                       error);
+        }
+    }
+
+    #[test]
+    fn profile_file()
+    {
+        let path = std::env::temp_dir().join(format!("pki-profiles-{}.conf", std::process::id()));
+        let path = path.to_str().unwrap();
+        fs::write(path, PROFILES).unwrap();
+
+        let args = profile_args(OPTIONS, &matches(&["--profile-file", path,
+                                                    "--profile", "intermediate-ca"])).unwrap();
+        assert_eq!(args[..3], ["--ca", "--pathlen=0", "--cert-policy=1.3.6.1.4.1.36906.1.1"]);
+        assert_eq!(load_profile(path, "client").unwrap_err(),
+                   format!("profile 'client' not found in '{}'", path));
+        fs::remove_file(path).unwrap();
+
+        assert!(profile_args(OPTIONS, &matches(&[])).unwrap().is_empty());
+        assert_eq!(profile_args(OPTIONS, &matches(&["--profile", "server"])).unwrap_err(),
+                   "--profile requires a --profile-file");
+        assert_eq!(profile_args(OPTIONS, &matches(&["--profile-file", path])).unwrap_err(),
+                   "--profile-file requires a --profile name");
+        assert!(load_profile(path, "server").unwrap_err().starts_with("reading profile file"));
+    }
+}
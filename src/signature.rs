@@ -0,0 +1,375 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! Signature schemes for certificates, CRLs and certificate requests and
+//! their AlgorithmIdentifier encodings.
+
+use std::fmt;
+use crate::asn1::*;
+use crate::oid::*;
+use crate::keys::{KeyType, parse_algorithm_identifier};
+use crate::crypto::hash::HashAlgorithm;
+use crate::crypto::mldsa::MlDsaParams;
+use crate::crypto::slhdsa::SlhDsaParams;
+use crate::crypto::composite::{CompositeParams, Traditional};
+
+/// Signature schemes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignatureScheme {
+    RsaPkcs1(HashAlgorithm),
+    RsaPss(HashAlgorithm),
+    Ecdsa(HashAlgorithm),
+    Ed25519,
+    Ed448,
+    MlDsa(MlDsaParams),
+    SlhDsa(SlhDsaParams),
+    Composite(CompositeParams),
+}
+
+// signature algorithms identified by a parameterless OID
+const SIGNATURE_OIDS: &[(&str, SignatureScheme)] = &[
+    (OID_MD5_WITH_RSA,        SignatureScheme::RsaPkcs1(HashAlgorithm::Md5)),
+    (OID_SHA1_WITH_RSA,       SignatureScheme::RsaPkcs1(HashAlgorithm::Sha1)),
+    (OID_SHA224_WITH_RSA,     SignatureScheme::RsaPkcs1(HashAlgorithm::Sha224)),
+    (OID_SHA256_WITH_RSA,     SignatureScheme::RsaPkcs1(HashAlgorithm::Sha256)),
+    (OID_SHA384_WITH_RSA,     SignatureScheme::RsaPkcs1(HashAlgorithm::Sha384)),
+    (OID_SHA512_WITH_RSA,     SignatureScheme::RsaPkcs1(HashAlgorithm::Sha512)),
+    (OID_SHA3_224_WITH_RSA,   SignatureScheme::RsaPkcs1(HashAlgorithm::Sha3_224)),
+    (OID_SHA3_256_WITH_RSA,   SignatureScheme::RsaPkcs1(HashAlgorithm::Sha3_256)),
+    (OID_SHA3_384_WITH_RSA,   SignatureScheme::RsaPkcs1(HashAlgorithm::Sha3_384)),
+    (OID_SHA3_512_WITH_RSA,   SignatureScheme::RsaPkcs1(HashAlgorithm::Sha3_512)),
+    (OID_ECDSA_WITH_SHA1,     SignatureScheme::Ecdsa(HashAlgorithm::Sha1)),
+    (OID_ECDSA_WITH_SHA224,   SignatureScheme::Ecdsa(HashAlgorithm::Sha224)),
+    (OID_ECDSA_WITH_SHA256,   SignatureScheme::Ecdsa(HashAlgorithm::Sha256)),
+    (OID_ECDSA_WITH_SHA384,   SignatureScheme::Ecdsa(HashAlgorithm::Sha384)),
+    (OID_ECDSA_WITH_SHA512,   SignatureScheme::Ecdsa(HashAlgorithm::Sha512)),
+    (OID_ECDSA_WITH_SHA3_224, SignatureScheme::Ecdsa(HashAlgorithm::Sha3_224)),
+    (OID_ECDSA_WITH_SHA3_256, SignatureScheme::Ecdsa(HashAlgorithm::Sha3_256)),
+    (OID_ECDSA_WITH_SHA3_384, SignatureScheme::Ecdsa(HashAlgorithm::Sha3_384)),
+    (OID_ECDSA_WITH_SHA3_512, SignatureScheme::Ecdsa(HashAlgorithm::Sha3_512)),
+    (OID_ED25519,             SignatureScheme::Ed25519),
+    (OID_ED448,               SignatureScheme::Ed448),
+    (OID_ML_DSA_44,           SignatureScheme::MlDsa(MlDsaParams::MlDsa44)),
+    (OID_ML_DSA_65,           SignatureScheme::MlDsa(MlDsaParams::MlDsa65)),
+    (OID_ML_DSA_87,           SignatureScheme::MlDsa(MlDsaParams::MlDsa87)),
+];
+
+impl SignatureScheme {
+    //
+    // Select the signature scheme for a key of the given type and size,
+    // using an explicit digest or a key-specific default.
+    //
+    pub fn select(key_type: KeyType, bits: usize, digest: Option<HashAlgorithm>, pss: bool)
+                  -> Result<SignatureScheme, String>
+    {
+        let scheme = match key_type {
+            KeyType::Rsa => {
+                let hash = digest.unwrap_or(HashAlgorithm::Sha256);
+                if pss {
+                    if hash == HashAlgorithm::Md5 {
+                        return Err("md5 is not supported with RSASSA-PSS".to_string());
+                    }
+                    return Ok(SignatureScheme::RsaPss(hash));
+                }
+                SignatureScheme::RsaPkcs1(hash)
+            }
+            KeyType::Ecdsa => {
+                let hash = digest.unwrap_or(match bits {
+                    256 => { HashAlgorithm::Sha256 }
+                    384 => { HashAlgorithm::Sha384 }
+                    _   => { HashAlgorithm::Sha512 }
+                });
+                SignatureScheme::Ecdsa(hash)
+            }
+            KeyType::Ed25519 | KeyType::Ed448 => {
+                if let Some(hash) = digest {
+                    return Err(format!("digest {} is not supported with {} keys",
+                                       hash.name(), key_type));
+                }
+                if key_type == KeyType::Ed25519 {
+                    SignatureScheme::Ed25519
+                } else {
+                    SignatureScheme::Ed448
+                }
+            }
+            KeyType::MlDsa44 | KeyType::MlDsa65 | KeyType::MlDsa87 => {
+                if let Some(hash) = digest {
+                    return Err(format!("digest {} is not supported with {} keys",
+                                       hash.name(), key_type));
+                }
+                SignatureScheme::MlDsa(match key_type {
+                    KeyType::MlDsa44 => { MlDsaParams::MlDsa44 }
+                    KeyType::MlDsa65 => { MlDsaParams::MlDsa65 }
+                    _                => { MlDsaParams::MlDsa87 }
+                })
+            }
+            KeyType::MlKem512 | KeyType::MlKem768 | KeyType::MlKem1024 => {
+                return Err(format!("{} keys can't create signatures", key_type));
+            }
+            KeyType::SlhDsa(params) => {
+                if let Some(hash) = digest {
+                    return Err(format!("digest {} is not supported with {} keys",
+                                       hash.name(), key_type));
+                }
+                return Ok(SignatureScheme::SlhDsa(params));
+            }
+            KeyType::Composite(params) => {
+                // digest and padding are fixed by the composite algorithm
+                if let Some(hash) = digest {
+                    return Err(format!("digest {} is not supported with {} keys",
+                                       hash.name(), key_type));
+                }
+                if pss && let Traditional::RsaPkcs1(..) = params.traditional() {
+                    return Err(format!("{} keys use RSASSA-PKCS1-v1_5 padding", key_type));
+                }
+                return Ok(SignatureScheme::Composite(params));
+            }
+        };
+        if !SIGNATURE_OIDS.iter().any(|(_, s)| *s == scheme) {
+            return Err(format!("no signature scheme for {} keys with {}",
+                               key_type, digest.map_or("", |h| h.name())));
+        }
+        return Ok(scheme);
+    }
+
+    //
+    // Key type able to create signatures with this scheme.
+    //
+    pub fn key_type(&self) -> KeyType
+    {
+        match self {
+            SignatureScheme::RsaPkcs1(_) |
+            SignatureScheme::RsaPss(_)    => { KeyType::Rsa }
+            SignatureScheme::Ecdsa(_)     => { KeyType::Ecdsa }
+            SignatureScheme::Ed25519      => { KeyType::Ed25519 }
+            SignatureScheme::Ed448        => { KeyType::Ed448 }
+            SignatureScheme::MlDsa(p)     => { (*p).into() }
+            SignatureScheme::SlhDsa(p)    => { KeyType::SlhDsa(*p) }
+            SignatureScheme::Composite(p) => { KeyType::Composite(*p) }
+        }
+    }
+
+    pub fn oid(&self) -> &'static str
+    {
+        match self {
+            SignatureScheme::RsaPss(_)    => { return OID_RSASSA_PSS; }
+            SignatureScheme::SlhDsa(p)    => { return p.oid(); }
+            SignatureScheme::Composite(p) => { return p.oid(); }
+            _ => {}
+        }
+        return SIGNATURE_OIDS.iter().find(|(_, s)| s == self)
+                             .map(|(oid, _)| *oid)
+                             .expect("signature scheme with OID");
+    }
+
+    //
+    // Encode as AlgorithmIdentifier. RSASSA-PSS parameters are included
+    // with defaults omitted (RFC 4055), EdDSA, ECDSA, ML-DSA, SLH-DSA and
+    // composite parameters are absent (RFC 8410, RFC 5758, RFC 9881,
+    // RFC 9909) and PKCS#1 parameters are NULL.
+    //
+    pub fn to_algorithm_identifier(&self) -> Vec<u8>
+    {
+        match self {
+            SignatureScheme::RsaPkcs1(_) => {
+                asn1_algorithm_identifier(self.oid(), Some(&asn1_null()))
+            }
+            SignatureScheme::RsaPss(hash) => {
+                let mut params = Vec::new();
+                if *hash != HashAlgorithm::Sha1 {
+                    let hash_alg = asn1_algorithm_identifier(hash.oid(), Some(&asn1_null()));
+                    params.push(asn1_wrap(ASN1_CONTEXT_C_0, &[&hash_alg]));
+                    params.push(asn1_wrap(ASN1_CONTEXT_C_1,
+                                &[&asn1_algorithm_identifier(OID_MGF1, Some(&hash_alg))]));
+                    params.push(asn1_wrap(ASN1_CONTEXT_C_2,
+                                &[&asn1_integer_from_u64(hash.digest_len() as u64)]));
+                }
+                asn1_algorithm_identifier(OID_RSASSA_PSS, Some(&asn1_sequence(&params)))
+            }
+            _ => { asn1_algorithm_identifier(self.oid(), None) }
+        }
+    }
+
+    //
+    // Decode a signature AlgorithmIdentifier.
+    //
+    pub fn from_algorithm_identifier(obj: &Asn1Object) -> Result<SignatureScheme, String>
+    {
+        let (oid, params) = parse_algorithm_identifier(obj)?;
+
+        if oid == OID_RSASSA_PSS {
+            let params = params.ok_or("missing RSASSA-PSS parameters")?;
+            return parse_pss_params(&params);
+        }
+        if let Some(slhdsa) = SlhDsaParams::from_oid(&oid) {
+            if params.is_some() {
+                return Err(format!("invalid parameters for {}", oid_display(&oid)));
+            }
+            return Ok(SignatureScheme::SlhDsa(slhdsa));
+        }
+        if let Some(composite) = CompositeParams::from_oid(&oid) {
+            if params.is_some() {
+                return Err(format!("invalid parameters for {}", oid_display(&oid)));
+            }
+            return Ok(SignatureScheme::Composite(composite));
+        }
+        let scheme = SIGNATURE_OIDS.iter().find(|(o, _)| *o == oid)
+                                   .map(|(_, s)| *s)
+                                   .ok_or(format!("unsupported signature algorithm {}",
+                                                  oid_display(&oid)))?;
+        let null_ok = matches!(scheme, SignatureScheme::RsaPkcs1(_));
+        match params {
+            None => {}
+            Some(p) if null_ok && p.tag == ASN1_NULL => {}
+            Some(_) => {
+                return Err(format!("invalid parameters for {}", oid_display(&oid)));
+            }
+        }
+        return Ok(scheme);
+    }
+}
+
+//
+// Parse the --digest and --rsa-padding values shared by the commands
+// creating signatures, returning the digest, if any, and whether
+// RSASSA-PSS padding was requested.
+//
+pub fn parse_signature_options(digest: Option<&str>, padding: Option<&str>)
+                               -> Result<(Option<HashAlgorithm>, bool), String>
+{
+    let digest = match digest {
+        Some(name) => {
+            Some(HashAlgorithm::parse(name).ok_or(format!("invalid digest '{}'", name))?)
+        }
+        None => { None }
+    };
+    let pss = match padding {
+        Some("pss")   => { true }
+        Some("pkcs1") | None => { false }
+        Some(name) => { return Err(format!("invalid RSA padding '{}'", name)); }
+    };
+    return Ok((digest, pss));
+}
+
+//
+// Parse a hash AlgorithmIdentifier with absent or NULL parameters.
+//
+fn parse_hash_algorithm(obj: &Asn1Object) -> Result<HashAlgorithm, String>
+{
+    let (oid, params) = parse_algorithm_identifier(obj)?;
+    if let Some(p) = params && p.tag != ASN1_NULL {
+        return Err("invalid hash algorithm parameters".to_string());
+    }
+    return HashAlgorithm::from_oid(&oid)
+           .ok_or(format!("unsupported hash algorithm {}", oid_display(&oid)));
+}
+
+//
+// Parse RSASSA-PSS-params, supporting MGF1 with the message hash and a
+// salt of the hash length only.
+//
+fn parse_pss_params(params: &Asn1Object) -> Result<SignatureScheme, String>
+{
+    if params.tag != ASN1_SEQUENCE {
+        return Err("invalid RSASSA-PSS parameters".to_string());
+    }
+    let mut parser = params.parser();
+    let mut hash = HashAlgorithm::Sha1;
+    let mut mgf_hash = HashAlgorithm::Sha1;
+    let mut salt_len: u64 = 20;
+
+    if let Some(obj) = parser.optional(ASN1_CONTEXT_C_0)? {
+        hash = parse_hash_algorithm(&obj.parser().expect(ASN1_SEQUENCE)?)?;
+    }
+    if let Some(obj) = parser.optional(ASN1_CONTEXT_C_1)? {
+        let (oid, mgf_params) = parse_algorithm_identifier(&obj.parser().expect(ASN1_SEQUENCE)?)?;
+        if oid != OID_MGF1 {
+            return Err(format!("unsupported mask generation function {}", oid_display(&oid)));
+        }
+        match mgf_params {
+            Some(p) if p.tag == ASN1_SEQUENCE => { mgf_hash = parse_hash_algorithm(&p)?; }
+            _ => { return Err("invalid MGF1 parameters".to_string()); }
+        }
+    }
+    if let Some(obj) = parser.optional(ASN1_CONTEXT_C_2)? {
+        salt_len = obj.parser().expect(ASN1_INTEGER)?.to_u64()?;
+    }
+    if let Some(obj) = parser.optional(ASN1_CONTEXT_C_3)? &&
+       obj.parser().expect(ASN1_INTEGER)?.to_u64()? != 1 {
+        return Err("unsupported RSASSA-PSS trailer field".to_string());
+    }
+    parser.finish()?;
+
+    if mgf_hash != hash || salt_len != hash.digest_len() as u64 || hash == HashAlgorithm::Md5 {
+        return Err("unsupported RSASSA-PSS parameters".to_string());
+    }
+    return Ok(SignatureScheme::RsaPss(hash));
+}
+
+impl fmt::Display for SignatureScheme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self {
+            SignatureScheme::RsaPss(hash) => { write!(f, "RSASSA-PSS with {}", hash.name()) }
+            _ => { write!(f, "{}", oid_display(self.oid())) }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::composite::CompositeParams;
+
+    #[test]
+    fn composite_digest_and_padding()
+    {
+        let pss = KeyType::Composite(CompositeParams::MlDsa44Rsa2048Pss);
+        let pkcs15 = KeyType::Composite(CompositeParams::MlDsa44Rsa2048Pkcs15);
+
+        assert_eq!(SignatureScheme::select(pss, 128, None, true).unwrap(),
+                   SignatureScheme::Composite(CompositeParams::MlDsa44Rsa2048Pss));
+        assert_eq!(SignatureScheme::select(pkcs15, 128, None, false).unwrap(),
+                   SignatureScheme::Composite(CompositeParams::MlDsa44Rsa2048Pkcs15));
+        assert!(SignatureScheme::select(pss, 128, Some(HashAlgorithm::Sha256), false).is_err());
+        assert!(SignatureScheme::select(pkcs15, 128, None, true).is_err());
+    }
+
+    #[test]
+    fn algorithm_identifiers()
+    {
+        let schemes = [
+            SignatureScheme::RsaPkcs1(HashAlgorithm::Sha256),
+            SignatureScheme::RsaPss(HashAlgorithm::Sha1),
+            SignatureScheme::RsaPss(HashAlgorithm::Sha384),
+            SignatureScheme::Ecdsa(HashAlgorithm::Sha3_256),
+            SignatureScheme::Ed448,
+            SignatureScheme::MlDsa(MlDsaParams::MlDsa65),
+            SignatureScheme::Composite(CompositeParams::MlDsa87EcdsaP521),
+        ];
+        for scheme in schemes {
+            let der = scheme.to_algorithm_identifier();
+            let parsed = SignatureScheme::from_algorithm_identifier(&asn1_parse(&der).unwrap());
+            assert_eq!(parsed.unwrap(), scheme);
+        }
+    }
+
+    #[test]
+    fn signature_options()
+    {
+        assert_eq!(parse_signature_options(Some("sha384"), Some("pss")).unwrap(),
+                   (Some(HashAlgorithm::Sha384), true));
+        assert_eq!(parse_signature_options(None, None).unwrap(), (None, false));
+        assert!(parse_signature_options(Some("sha3"), None).is_err());
+        assert!(parse_signature_options(None, Some("oaep")).is_err());
+    }
+}
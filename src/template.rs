@@ -0,0 +1,329 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! Certificate templates collecting the certificate contents shared by
+//! `pki --self` and `pki --issue` from the command line.
+
+use getopts::Matches;
+use crate::addrblock::parse_block;
+use crate::extensions::*;
+use crate::keys::PublicKey;
+use crate::oid::*;
+use crate::validity::Validity;
+use crate::x509::{Extension, parse_serial, random_serial};
+use crate::X509_NO_CONSTRAINT;
+
+/// Certificate contents requested on the command line
+#[derive(Clone, Debug)]
+pub struct CertTemplate {
+    /// subject distinguished name
+    pub dn: Option<String>,
+    /// subjectAltNames
+    pub san: Vec<String>,
+    /// serialNumber INTEGER magnitude
+    pub serial: Vec<u8>,
+    /// start of the validity period
+    pub not_before: i64,
+    /// end of the validity period
+    pub not_after: i64,
+    /// CA basicConstraint
+    pub ca: bool,
+    /// pathLenConstraint or X509_NO_CONSTRAINT
+    pub pathlen: u32,
+    /// --flag values
+    pub flags: Vec<String>,
+    /// CRL distribution points
+    pub crl_points: Vec<CrlDistPoint>,
+    /// OCSP AuthorityInfoAccess URIs
+    pub ocsp_uris: Vec<String>,
+    /// CA issuers AuthorityInfoAccess URIs
+    pub ca_issuers: Vec<String>,
+    /// RFC 3779 IP address prefixes and ranges
+    pub addrblocks: Vec<String>,
+    /// permitted NameConstraints
+    pub permitted: Vec<String>,
+    /// excluded NameConstraints
+    pub excluded: Vec<String>,
+    /// certificatePolicies with their qualifiers
+    pub policies: Vec<CertPolicy>,
+    /// issuer-oid:subject-oid policyMappings
+    pub mappings: Vec<String>,
+    /// requireExplicitPolicy or X509_NO_CONSTRAINT
+    pub require_explicit: u32,
+    /// inhibitPolicyMapping or X509_NO_CONSTRAINT
+    pub inhibit_mapping: u32,
+    /// inhibitAnyPolicy or X509_NO_CONSTRAINT
+    pub inhibit_any: u32,
+    /// custom extensions and the critical test extension, replacing or
+    /// adding to the generated ones
+    pub custom: Vec<Extension>,
+}
+
+//
+// Parse an optional numeric constraint option.
+//
+fn parse_constraint(matches: &Matches, name: &str) -> Result<u32, String>
+{
+    match matches.opt_str(name) {
+        Some(string) => {
+            match string.parse::<u32>() {
+                Ok(value) if value < X509_NO_CONSTRAINT => { Ok(value) }
+                _ => { Err(format!("invalid constraint '{}'", string)) }
+            }
+        }
+        None => { Ok(X509_NO_CONSTRAINT) }
+    }
+}
+
+//
+// Group --cps-uri and --user-notice qualifiers with the preceding
+// --cert-policy.
+//
+fn parse_policies(matches: &Matches) -> Result<Vec<CertPolicy>, String>
+{
+    let mut policies: Vec<(usize, CertPolicy)> = matches.opt_strs_pos("P").into_iter()
+        .map(|(pos, oid)| (pos, CertPolicy { oid, ..Default::default() }))
+        .collect();
+
+    for (name, long) in [("C", "--cps-uri"), ("U", "--user-notice")] {
+        for (pos, value) in matches.opt_strs_pos(name) {
+            let policy = match policies.iter_mut().rev().find(|(p, _)| *p < pos) {
+                Some((_, policy)) => { policy }
+                None => { return Err(format!("{} requires a preceding --cert-policy", long)); }
+            };
+            if name == "C" {
+                policy.cps_uris.push(value);
+            } else {
+                policy.user_notices.push(value);
+            }
+        }
+    }
+    return Ok(policies.into_iter().map(|(_, policy)| policy).collect());
+}
+
+//
+// Group --crlissuer names with the preceding --crl distribution point.
+//
+pub fn parse_crl_points(matches: &Matches) -> Result<Vec<CrlDistPoint>, String>
+{
+    let mut points: Vec<(usize, CrlDistPoint)> = matches.opt_strs_pos("u").into_iter()
+        .map(|(pos, uri)| (pos, CrlDistPoint { uri, ..Default::default() }))
+        .collect();
+
+    for (pos, issuer) in matches.opt_strs_pos("I") {
+        match points.iter_mut().rev().find(|(p, _)| *p < pos) {
+            Some((_, point)) => { point.issuers.push(issuer); }
+            None => { return Err("--crlissuer requires a preceding --crl".to_string()); }
+        }
+    }
+    return Ok(points.into_iter().map(|(_, point)| point).collect());
+}
+
+impl CertTemplate {
+    //
+    // Collect the certificate contents from the command line options.
+    //
+    pub fn from_matches(matches: &Matches) -> Result<CertTemplate, String>
+    {
+        let validity = Validity::from_matches(matches, 24 * 60 * 60, 1095)?;
+
+        let serial = match matches.opt_str("s") {
+            Some(hex) => { parse_serial(&hex)? }
+            None => { random_serial() }
+        };
+
+        let flags = matches.opt_strs("e");
+        parse_flags(&flags)?;
+
+        let custom = custom_extensions(&matches.opt_strs("V"), matches.opt_str("X").as_deref())?;
+
+        let addrblocks = matches.opt_strs("B");
+        for block in &addrblocks {
+            parse_block(block)?;
+        }
+
+        return Ok(CertTemplate {
+            dn: matches.opt_str("d"),
+            san: matches.opt_strs("a"),
+            serial,
+            not_before: validity.not_before,
+            not_after: validity.not_after,
+            ca: matches.opt_present("b"),
+            pathlen: parse_constraint(matches, "p")?,
+            flags,
+            crl_points: Vec::new(),
+            ocsp_uris: matches.opt_strs("o"),
+            ca_issuers: Vec::new(),
+            addrblocks,
+            permitted: matches.opt_strs("n"),
+            excluded: matches.opt_strs("N"),
+            policies: parse_policies(matches)?,
+            mappings: matches.opt_strs("M"),
+            require_explicit: parse_constraint(matches, "E")?,
+            inhibit_mapping: parse_constraint(matches, "H")?,
+            inhibit_any: parse_constraint(matches, "A")?,
+            custom,
+        });
+    }
+
+    //
+    // Build the certificate extensions for a subject public key, taking the
+    // subjectAltNames from the requested extensions unless --san is given and
+    // adding the requested extendedKeyUsage purposes to the --flag ones.
+    //
+    pub fn extensions(&self, public_key: &PublicKey, authority_keyid: &[u8],
+                      requested: &[Extension]) -> Result<Vec<Extension>, String>
+    {
+        let (mut purposes, bits) = parse_flags(&self.flags)?;
+        if let Some(eku) = requested.iter().find(|e| e.oid == OID_EXTENDED_KEY_USAGE) {
+            for purpose in parse_extended_key_usage(eku)? {
+                if !purposes.contains(&purpose) {
+                    purposes.push(purpose);
+                }
+            }
+        }
+        let usage = derive_key_usage(public_key.key_type(), self.ca, &purposes, bits)?;
+
+        let mut extensions: Vec<Extension> = Vec::new();
+        if self.ca {
+            extensions.push(basic_constraints(self.ca, self.pathlen));
+        }
+        extensions.push(key_usage(usage));
+        extensions.push(subject_key_identifier(public_key));
+        extensions.push(authority_key_identifier(authority_keyid));
+        if !self.san.is_empty() {
+            extensions.push(subject_alt_name(&self.san)?);
+        } else if let Some(san) = requested.iter().find(|e| e.oid == OID_SUBJECT_ALT_NAME) {
+            extensions.push(san.clone());
+        }
+        if !purposes.is_empty() {
+            extensions.push(extended_key_usage(&purposes)?);
+        }
+        if !self.crl_points.is_empty() {
+            extensions.push(crl_distribution_points(&self.crl_points)?);
+        }
+        if !self.ocsp_uris.is_empty() || !self.ca_issuers.is_empty() {
+            extensions.push(authority_info_access(&self.ocsp_uris, &self.ca_issuers)?);
+        }
+        if !self.addrblocks.is_empty() {
+            extensions.push(ip_addr_blocks(&self.addrblocks)?);
+        }
+        if !self.permitted.is_empty() || !self.excluded.is_empty() {
+            extensions.push(name_constraints(&self.permitted, &self.excluded)?);
+        }
+        if !self.policies.is_empty() {
+            extensions.push(certificate_policies(&self.policies)?);
+        }
+        if !self.mappings.is_empty() {
+            extensions.push(policy_mappings(&self.mappings)?);
+        }
+        if self.require_explicit != X509_NO_CONSTRAINT ||
+           self.inhibit_mapping != X509_NO_CONSTRAINT {
+            extensions.push(policy_constraints(self.require_explicit, self.inhibit_mapping));
+        }
+        if self.inhibit_any != X509_NO_CONSTRAINT {
+            extensions.push(inhibit_any_policy(self.inhibit_any));
+        }
+        merge_extensions(&mut extensions, self.custom.clone())?;
+        return Ok(extensions);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::eddsa::{EdCurve, EdPrivateKey};
+    use crate::asn1::*;
+    use crate::general_name::parse_general_name;
+    use crate::keys::PrivateKey;
+
+    //
+    // Parse the arguments with the template options of pki --issue.
+    //
+    fn matches(args: &[&str]) -> Matches
+    {
+        let mut opts = getopts::Options::new();
+        for (short, long) in [("d", "dn"), ("l", "lifetime"), ("F", "not-before"),
+                              ("T", "not-after"), ("D", "dateform"), ("s", "serial"),
+                              ("p", "pathlen"), ("E", "policy-explicit"),
+                              ("H", "policy-inhibit"), ("A", "policy-any"), ("X", "critical")] {
+            opts.optopt(short, long, "", "");
+        }
+        for (short, long) in [("a", "san"), ("e", "flag"), ("u", "crl"), ("I", "crlissuer"),
+                              ("o", "ocsp"), ("B", "addrblock"), ("n", "nc-permitted"),
+                              ("N", "nc-excluded"), ("P", "cert-policy"), ("C", "cps-uri"),
+                              ("U", "user-notice"), ("M", "policy-mapping"), ("V", "ext")] {
+            opts.optmulti(short, long, "", "");
+        }
+        opts.optflag("b", "ca", "");
+        return opts.parse(args).unwrap();
+    }
+
+    #[test]
+    fn crl_issuer_grouping()
+    {
+        let matches = matches(&["--crl", "http://a.example/ca.crl", "--crlissuer", "CN=Issuer A",
+                                "--crl", "ldap://b.example", "--ca",
+                                "--crl", "http://c.example/ca.crl", "--flag", "serverAuth",
+                                "-I", "dns:c.example", "--crlissuer", "C=CH, CN=Issuer C"]);
+        let points = parse_crl_points(&matches).unwrap();
+        let expected = [
+            ("http://a.example/ca.crl", vec!["CN=Issuer A"]),
+            ("ldap://b.example",        vec![]),
+            ("http://c.example/ca.crl", vec!["dns:c.example", "C=CH, CN=Issuer C"]),
+        ];
+        assert_eq!(points.len(), expected.len());
+        for (point, (uri, issuers)) in points.iter().zip(expected) {
+            assert_eq!(point.uri, uri);
+            assert_eq!(point.issuers, issuers);
+        }
+
+        // the issuers end up in the cRLIssuer field of their distribution point
+        let mut template = CertTemplate::from_matches(&matches).unwrap();
+        template.crl_points = points;
+        let key = PrivateKey::Eddsa(EdPrivateKey::generate(EdCurve::Ed25519)).public_key();
+        let extensions = template.extensions(&key, &[0x01], &[]).unwrap();
+        let ext = extensions.iter().find(|e| e.oid == OID_CRL_DISTRIBUTION_POINTS).unwrap();
+
+        let point = |uri: &str, issuers: &[&str]| {
+            let name = parse_general_name(&format!("uri:{}", uri)).unwrap();
+            let mut dp = vec![asn1_wrap(ASN1_CONTEXT_C_0,
+                                        &[&asn1_wrap(ASN1_CONTEXT_C_0, &[&name])])];
+            if !issuers.is_empty() {
+                let names: Vec<Vec<u8>> = issuers.iter()
+                                                 .map(|n| parse_general_name(n).unwrap()).collect();
+                let parts: Vec<&[u8]> = names.iter().map(|n| n.as_slice()).collect();
+                dp.push(asn1_wrap(ASN1_CONTEXT_C_2, &parts));
+            }
+            asn1_sequence(&dp)
+        };
+        assert_eq!(ext.value, asn1_sequence(&[
+            point("http://a.example/ca.crl", &["CN=Issuer A"]),
+            point("ldap://b.example", &[]),
+            point("http://c.example/ca.crl", &["dns:c.example", "C=CH, CN=Issuer C"]),
+        ]));
+    }
+
+    #[test]
+    fn crl_issuer_without_crl()
+    {
+        assert_eq!(parse_crl_points(&matches(&["--crlissuer", "CN=Issuer", "--crl", "http://a"]))
+                       .unwrap_err(), "--crlissuer requires a preceding --crl");
+        assert!(parse_crl_points(&matches(&[])).unwrap().is_empty());
+
+        let matches = matches(&["--crl", "http://a.example", "--crlissuer", "CN=Issuer, X=y"]);
+        let mut template = CertTemplate::from_matches(&matches).unwrap();
+        template.crl_points = parse_crl_points(&matches).unwrap();
+        let key = PrivateKey::Eddsa(EdPrivateKey::generate(EdCurve::Ed25519)).public_key();
+        assert!(template.extensions(&key, &[0x01], &[]).unwrap_err().starts_with("invalid CRL issuer"));
+    }
+}
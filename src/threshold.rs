@@ -0,0 +1,291 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! Threshold RSA signing of certificates and CRLs.  The first shareholder
+//! encodes the tbsCertificate or tbsCertList for PKCS#1 v1.5 or PSS and
+//! signs it with a single RSA key share, further shareholders sign the very
+//! same encoded message with their share, and the partial signatures are
+//! finally combined into the signed certificate or CRL.  Partial signatures
+//! are exchanged as
+//!
+//! ```text
+//! RSAPartialSignature ::= SEQUENCE {
+//!     version             INTEGER (0),
+//!     tbsData             SEQUENCE,             -- tbsCertificate or tbsCertList
+//!     signatureAlgorithm  AlgorithmIdentifier,
+//!     encodedMessage      OCTET STRING,         -- EM signed by all shares
+//!     modulus             INTEGER,              -- n
+//!     publicExponent      INTEGER,              -- e
+//!     shares              INTEGER,              -- total number of shares l
+//!     threshold           INTEGER,              -- shares required to sign k
+//!     index               INTEGER,              -- index i of the signing share
+//!     partialSignature    INTEGER               -- x^(2 l! s_i) mod n
+//! }
+//! ```
+
+use crate::asn1::*;
+use crate::crl::Crl;
+use crate::crypto::bigint::BigUint;
+use crate::crypto::rsa::{RsaPublicKey, emsa_pkcs1_encode, emsa_pss_encode, emsa_pss_verify};
+use crate::crypto::rsa_threshold::{RsaKeyShare, RsaPartialSignature, RSA_MAX_SHARES,
+                                   combine_partial_signatures};
+use crate::keys::PrivateKey;
+use crate::pem::CredType;
+use crate::signature::SignatureScheme;
+use crate::x509::Certificate;
+
+/// Partial signature over a tbsCertificate or tbsCertList
+#[derive(Clone, Debug)]
+pub struct PartialSignature {
+    /// DER encoded tbsCertificate or tbsCertList
+    pub tbs: Vec<u8>,
+    /// RSA signature scheme
+    pub scheme: SignatureScheme,
+    /// encoded message signed by all shares
+    pub em: Vec<u8>,
+    /// public key of the threshold RSA key
+    pub key: RsaPublicKey,
+    /// total number of shares
+    pub shares: u32,
+    /// number of shares required to sign
+    pub threshold: u32,
+    /// partial signature of a single share
+    pub partial: RsaPartialSignature,
+}
+
+//
+// Encode the to-be-signed data as message representative of the scheme.
+//
+fn encode_message(key: &RsaPublicKey, scheme: SignatureScheme, tbs: &[u8])
+                  -> Result<Vec<u8>, String>
+{
+    match scheme {
+        SignatureScheme::RsaPkcs1(hash) => { emsa_pkcs1_encode(hash, tbs, key.n.bits().div_ceil(8)) }
+        SignatureScheme::RsaPss(hash) => { emsa_pss_encode(hash, tbs, key.n.bits() - 1) }
+        _ => { Err(format!("signature scheme {} not supported by RSA key shares", scheme)) }
+    }
+}
+
+//
+// Check that an encoded message has been derived from the to-be-signed
+// data, so that no share signs anything but the data it is shown.
+//
+fn check_message(key: &RsaPublicKey, scheme: SignatureScheme, tbs: &[u8], em: &[u8])
+                 -> Result<(), String>
+{
+    let valid = match scheme {
+        SignatureScheme::RsaPkcs1(hash) => {
+            emsa_pkcs1_encode(hash, tbs, key.n.bits().div_ceil(8))? == em
+        }
+        SignatureScheme::RsaPss(hash) => { emsa_pss_verify(hash, tbs, em, key.n.bits() - 1) }
+        _ => { false }
+    };
+    if !valid {
+        return Err("encoded message does not match the to-be-signed data".to_string());
+    }
+    return Ok(());
+}
+
+impl PartialSignature {
+    //
+    // Encode the to-be-signed data and create the first partial signature
+    // with a single RSA key share.
+    //
+    pub fn new(key: &PrivateKey, scheme: SignatureScheme, tbs: Vec<u8>)
+               -> Result<PartialSignature, String>
+    {
+        let PrivateKey::RsaShare(share) = key else {
+            return Err("partial signatures require a single RSA key share".to_string());
+        };
+        let public = share.public_key();
+        let em = encode_message(&public, scheme, &tbs)?;
+        return Ok(PartialSignature {
+            partial: share.sign_partial(&em), tbs, scheme, em, key: public,
+            shares: share.shares, threshold: share.threshold,
+        });
+    }
+
+    //
+    // Create the partial signature of another share over the same encoded
+    // message.
+    //
+    pub fn cosign(&self, share: &RsaKeyShare) -> Result<PartialSignature, String>
+    {
+        if share.public_key() != self.key || share.shares != self.shares ||
+           share.threshold != self.threshold {
+            return Err("RSA key share does not belong to the signing key".to_string());
+        }
+        if share.index == self.partial.index {
+            return Err(format!("partial signature of key share {} already present", share.index));
+        }
+        check_message(&self.key, self.scheme, &self.tbs, &self.em)?;
+        return Ok(PartialSignature { partial: share.sign_partial(&self.em), ..self.clone() });
+    }
+
+    //
+    // Encode as RSAPartialSignature.
+    //
+    pub fn to_der(&self) -> Vec<u8>
+    {
+        return asn1_sequence(&[
+            asn1_integer_from_u64(0),
+            self.tbs.clone(),
+            self.scheme.to_algorithm_identifier(),
+            asn1_octet_string(&self.em),
+            asn1_integer(&self.key.n.to_bytes_be()),
+            asn1_integer(&self.key.e.to_bytes_be()),
+            asn1_integer_from_u64(self.shares as u64),
+            asn1_integer_from_u64(self.threshold as u64),
+            asn1_integer_from_u64(self.partial.index as u64),
+            asn1_integer(&self.partial.value.to_bytes_be()),
+        ]);
+    }
+
+    //
+    // Decode an RSAPartialSignature.
+    //
+    pub fn from_der(der: &[u8]) -> Result<PartialSignature, String>
+    {
+        let obj = asn1_parse(der)?;
+        if obj.tag != ASN1_SEQUENCE {
+            return Err("not an RSAPartialSignature".to_string());
+        }
+        let mut parser = obj.parser();
+        if parser.expect(ASN1_INTEGER)?.to_u64()? != 0 {
+            return Err("unsupported RSAPartialSignature version".to_string());
+        }
+        let tbs = parser.expect(ASN1_SEQUENCE)?.raw.to_vec();
+        let scheme = SignatureScheme::from_algorithm_identifier(&parser.expect(ASN1_SEQUENCE)?)?;
+        let em = parser.expect(ASN1_OCTET_STRING)?.content.to_vec();
+        let n = BigUint::from_bytes_be(parser.expect(ASN1_INTEGER)?.to_unsigned()?);
+        let e = BigUint::from_bytes_be(parser.expect(ASN1_INTEGER)?.to_unsigned()?);
+        let shares = parser.expect(ASN1_INTEGER)?.to_u64()?;
+        let threshold = parser.expect(ASN1_INTEGER)?.to_u64()?;
+        let index = parser.expect(ASN1_INTEGER)?.to_u64()?;
+        let value = BigUint::from_bytes_be(parser.expect(ASN1_INTEGER)?.to_unsigned()?);
+        parser.finish()?;
+
+        if shares == 0 || shares > RSA_MAX_SHARES as u64 || threshold == 0 ||
+           threshold > shares || index == 0 || index > shares {
+            return Err("invalid RSA partial signature parameters".to_string());
+        }
+        let key = RsaPublicKey { n, e };
+        key.validate()?;
+        return Ok(PartialSignature {
+            tbs, scheme, em, key, shares: shares as u32, threshold: threshold as u32,
+            partial: RsaPartialSignature { index: index as u32, value },
+        });
+    }
+}
+
+//
+// Combine the partial signatures of at least the threshold number of shares
+// over the same encoded message into a signed certificate or CRL.
+//
+pub fn combine_partials(partials: &[PartialSignature]) -> Result<(Vec<u8>, CredType), String>
+{
+    let first = partials.first().ok_or("no partial signatures")?;
+    for partial in partials {
+        if partial.key != first.key || partial.shares != first.shares ||
+           partial.threshold != first.threshold {
+            return Err("partial signatures created with different keys".to_string());
+        }
+        if partial.tbs != first.tbs || partial.scheme != first.scheme || partial.em != first.em {
+            return Err("partial signatures over different data".to_string());
+        }
+    }
+    check_message(&first.key, first.scheme, &first.tbs, &first.em)?;
+
+    let values: Vec<RsaPartialSignature> = partials.iter().map(|p| p.partial.clone()).collect();
+    let signature = combine_partial_signatures(&first.key, first.shares, first.threshold,
+                                               &first.em, &values)?;
+    let der = asn1_sequence(&[first.tbs.clone(), first.scheme.to_algorithm_identifier(),
+                              asn1_bitstring(&signature)]);
+    if Certificate::from_der(&der).is_ok() {
+        return Ok((der, CredType::Certificate));
+    }
+    Crl::from_der(&der).map_err(|_| "data is neither a certificate nor a CRL".to_string())?;
+    return Ok((der, CredType::Crl));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crl::TbsCertList;
+    use crate::crypto::hash::HashAlgorithm;
+    use crate::crypto::rsa::RSA_MIN_BITS;
+    use crate::crypto::rsa_threshold::generate_shares;
+    use crate::dn::Dn;
+    use crate::keys::PublicKey;
+    use crate::x509::TbsCertificate;
+
+    #[test]
+    fn threshold_certificate()
+    {
+        let (public, shares) = generate_shares(RSA_MIN_BITS, 3, 2).unwrap();
+        let public_key = PublicKey::Rsa(public.clone());
+        let name = Dn::parse("C=CH, O=strongSwan, CN=Threshold CA").unwrap().to_der();
+        let tbs = TbsCertificate {
+            serial: vec![0x01], issuer: name.clone(), not_before: 1760000000,
+            not_after: 1860000000, subject: name.clone(), public_key: public_key.clone(),
+            extensions: Vec::new(),
+        };
+
+        for scheme in [SignatureScheme::RsaPkcs1(HashAlgorithm::Sha256),
+                       SignatureScheme::RsaPss(HashAlgorithm::Sha384)] {
+            let key = PrivateKey::RsaShare(shares[2].clone());
+            let first = PartialSignature::new(&key, scheme, tbs.encode(scheme).unwrap()).unwrap();
+            let first = PartialSignature::from_der(&first.to_der()).unwrap();
+            assert_eq!(first.partial.index, 3);
+            assert!(combine_partials(std::slice::from_ref(&first)).is_err());
+            assert!(first.cosign(&shares[2]).is_err());
+
+            let second = first.cosign(&shares[0]).unwrap();
+            let (der, cred_type) = combine_partials(&[first.clone(), second.clone()]).unwrap();
+            assert_eq!(cred_type, CredType::Certificate);
+            let cert = Certificate::from_der(&der).unwrap();
+            assert_eq!(cert.scheme, scheme);
+            assert!(cert.verify_signature(&public_key));
+
+            // shares refuse to sign an encoded message not matching the data
+            let mut forged = first.clone();
+            forged.tbs = TbsCertificate { serial: vec![0x02], ..tbs.clone() }.encode(scheme).unwrap();
+            assert!(forged.cosign(&shares[1]).is_err());
+            assert!(combine_partials(&[forged, second]).is_err());
+        }
+
+        let (_, other) = generate_shares(RSA_MIN_BITS, 3, 2).unwrap();
+        let scheme = SignatureScheme::RsaPkcs1(HashAlgorithm::Sha256);
+        let key = PrivateKey::RsaShare(shares[0].clone());
+        let first = PartialSignature::new(&key, scheme, tbs.encode(scheme).unwrap()).unwrap();
+        assert!(first.cosign(&other[1]).is_err());
+        let scheme = SignatureScheme::Ecdsa(HashAlgorithm::Sha256);
+        assert!(PartialSignature::new(&key, scheme, tbs.encode(scheme).unwrap()).is_err());
+    }
+
+    #[test]
+    fn threshold_crl()
+    {
+        let (public, shares) = generate_shares(RSA_MIN_BITS, 2, 2).unwrap();
+        let scheme = SignatureScheme::RsaPkcs1(HashAlgorithm::Sha256);
+        let tbs = TbsCertList {
+            issuer: Dn::parse("CN=Threshold CA").unwrap().to_der(), this_update: 1760000000,
+            next_update: 1760604800, revoked: Vec::new(), extensions: Vec::new(),
+        };
+        let key = PrivateKey::RsaShare(shares[0].clone());
+        let first = PartialSignature::new(&key, scheme, tbs.encode(scheme).unwrap()).unwrap();
+        let second = first.cosign(&shares[1]).unwrap();
+        let (der, cred_type) = combine_partials(&[second, first]).unwrap();
+        assert_eq!(cred_type, CredType::Crl);
+        assert!(Crl::from_der(&der).unwrap().verify_signature(&PublicKey::Rsa(public)));
+    }
+}
@@ -0,0 +1,347 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! Validity periods of certificates, attribute certificates and CRLs as
+//! given by the `--not-before`/`--this-update`, `--not-after`/
+//! `--next-update`, `--lifetime` and `--dateform` options.  Without a
+//! `--dateform`, times are accepted as ISO 8601 with or without a timezone
+//! (UTC is assumed if missing), as a date only, as Unix timestamps marked
+//! by a leading `@` or as compact `YYYYMMDDHHMMSSZ` GeneralizedTime.  In any case, `never` denotes
+//! the 99991231235959Z "no well-defined expiration date" value (RFC 5280,
+//! 4.1.2.5), and offsets like `+90d` or `-1h` are relative to now for the
+//! start time and relative to the start for the end time.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use getopts::Matches;
+use crate::asn1::*;
+
+/// 9999-12-31T23:59:59Z, encoded as 99991231235959Z
+pub const NO_EXPIRY: i64 = 253402300799;
+
+/// 0000-01-01T00:00:00Z, the earliest GeneralizedTime
+const MIN_TIME: i64 = -62167219200;
+
+/// ISO 8601 formats with a timezone
+const ZONED_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S%.f%:z",
+    "%Y-%m-%dT%H:%M:%S%.f%z",
+    "%Y-%m-%d %H:%M:%S%.f%:z",
+    "%Y-%m-%d %H:%M:%S%.f%z",
+    "%Y-%m-%dT%H:%M%:z",
+    "%Y-%m-%dT%H:%M%z",
+];
+
+/// ISO 8601 formats without a timezone, interpreted as UTC
+const LOCAL_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+];
+
+/// A validity period as seconds since the epoch
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Validity {
+    /// notBefore or thisUpdate
+    pub not_before: i64,
+    /// notAfter or nextUpdate
+    pub not_after: i64,
+}
+
+//
+// Parse a relative offset like "+90d" or "-1h" with an optional unit of
+// s, m, h, d or w, seconds by default.
+//
+fn parse_offset(value: &str) -> Option<Result<i64, String>>
+{
+    let sign: i64 = match value.as_bytes().first() {
+        Some(b'+') => { 1 }
+        Some(b'-') => { -1 }
+        _ => { return None; }
+    };
+    let (digits, unit) = match value[1..].find(|c: char| !c.is_ascii_digit()) {
+        Some(pos) => { value[1..].split_at(pos) }
+        None => { (&value[1..], "s") }
+    };
+    let scale: i64 = match unit {
+        "s" => { 1 }
+        "m" => { 60 }
+        "h" => { 60 * 60 }
+        "d" => { 24 * 60 * 60 }
+        "w" => { 7 * 24 * 60 * 60 }
+        _ => { return Some(Err(format!("invalid time offset '{}'", value))); }
+    };
+    let offset = digits.parse::<i64>().ok().and_then(|n| n.checked_mul(scale));
+    return Some(offset.map(|n| sign * n).ok_or(format!("invalid time offset '{}'", value)));
+}
+
+//
+// Parse a time in one of the supported formats, or with an explicit
+// strptime(3) format.  Offsets, relative to the given base time, and
+// "never" are accepted with any format.
+//
+pub fn parse_time(value: &str, dateform: Option<&str>, base: i64) -> Result<i64, String>
+{
+    let value = value.trim();
+    let time = if let Some(offset) = parse_offset(value) {
+        base.checked_add(offset?).ok_or(format!("invalid time offset '{}'", value))?
+    } else if value.eq_ignore_ascii_case("never") {
+        NO_EXPIRY
+    } else if let Some(form) = dateform {
+        match DateTime::parse_from_str(value, form) {
+            Ok(dt) => { dt.timestamp() }
+            Err(_) => {
+                match NaiveDateTime::parse_from_str(value, form) {
+                    Ok(dt) => { dt.and_utc().timestamp() }
+                    Err(e) => {
+                        NaiveDate::parse_from_str(value, form)
+                            .map_err(|_| format!("invalid date '{}' for format '{}': {}", value, form, e))?
+                            .and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp()
+                    }
+                }
+            }
+        }
+    } else if let Some(seconds) = value.strip_prefix('@') {
+        let digits = seconds.strip_prefix('-').unwrap_or(seconds);
+        if digits.is_empty() || !digits.bytes().all(|c| c.is_ascii_digit()) {
+            return Err(format!("invalid timestamp '{}'", value));
+        }
+        seconds.parse::<i64>().map_err(|_| format!("invalid timestamp '{}'", value))?
+    } else if !value.is_empty() && value.bytes().all(|c| c.is_ascii_digit()) {
+        // 20301231 would otherwise become a timestamp in August 1970
+        return Err(format!("ambiguous date '{}', use '@{}' for a Unix timestamp", value, value));
+    } else if value.len() == 15 && value.ends_with('Z') && value[..14].bytes().all(|c| c.is_ascii_digit()) {
+        NaiveDateTime::parse_from_str(&value[..14], "%Y%m%d%H%M%S")
+            .map_err(|_| format!("invalid date '{}'", value))?
+            .and_utc().timestamp()
+    } else if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        dt.timestamp()
+    } else if let Some(dt) = ZONED_FORMATS.iter()
+                                          .find_map(|f| DateTime::parse_from_str(value, f).ok()) {
+        dt.timestamp()
+    } else if let Some(dt) = LOCAL_FORMATS.iter()
+                                          .find_map(|f| NaiveDateTime::parse_from_str(value, f).ok()) {
+        dt.and_utc().timestamp()
+    } else if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        date.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp()
+    } else {
+        return Err(format!("invalid date '{}'", value));
+    };
+
+    if !(MIN_TIME..=NO_EXPIRY).contains(&time) {
+        return Err(format!("date '{}' out of range", value));
+    }
+    return Ok(time);
+}
+
+//
+// Format a time as ISO 8601 in UTC.
+//
+pub fn time_string(time: i64) -> String
+{
+    match DateTime::<Utc>::from_timestamp(time, 0) {
+        Some(dt) => { dt.format("%Y-%m-%dT%H:%M:%SZ").to_string() }
+        None => { time.to_string() }
+    }
+}
+
+//
+// Encode a validity time as UTCTime through 2049 and as GeneralizedTime
+// before 1950 and from 2050 on (RFC 5280, 4.1.2.5).
+//
+pub fn encode_time(time: i64) -> Result<Vec<u8>, String>
+{
+    // 1950-01-01T00:00:00Z and 2050-01-01T00:00:00Z
+    let tag = if (-631152000..2524608000).contains(&time) {
+        ASN1_UTCTIME
+    } else {
+        ASN1_GENERALIZEDTIME
+    };
+    return asn1_from_time(time, tag);
+}
+
+impl Validity {
+    //
+    // Determine the validity period from the start and end times, falling
+    // back to now and the lifetime in seconds, respectively.
+    //
+    pub fn new(not_before: Option<&str>, not_after: Option<&str>, dateform: Option<&str>,
+               lifetime: i64) -> Result<Validity, String>
+    {
+        let now = Utc::now().timestamp();
+        let not_before = match not_before {
+            Some(value) => { parse_time(value, dateform, now)? }
+            None => { now }
+        };
+        let not_after = match not_after {
+            Some(value) => { parse_time(value, dateform, not_before)? }
+            None => { not_before.saturating_add(lifetime).min(NO_EXPIRY) }
+        };
+        if not_after < not_before {
+            return Err(format!("end time {} is earlier than start time {}",
+                               time_string(not_after), time_string(not_before)));
+        }
+        return Ok(Validity { not_before, not_after });
+    }
+
+    //
+    // Determine the validity period from the --not-before, --not-after,
+    // --dateform and --lifetime options, the latter given in the unit of
+    // seconds with a default if missing.
+    //
+    pub fn from_matches(matches: &Matches, unit: i64, default: i64) -> Result<Validity, String>
+    {
+        let lifetime = match matches.opt_str("l") {
+            Some(string) => {
+                match string.parse::<i64>().ok().and_then(|n| n.checked_mul(unit)) {
+                    Some(lifetime) if lifetime > 0 => { lifetime }
+                    _ => { return Err(format!("invalid lifetime '{}'", string)); }
+                }
+            }
+            None => { default * unit }
+        };
+        return Validity::new(matches.opt_str("F").as_deref(), matches.opt_str("T").as_deref(),
+                             matches.opt_str("D").as_deref(), lifetime);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex;
+
+    // 2025-10-09T08:53:20Z
+    const NOW: i64 = 1760000000;
+    const DAY: i64 = 24 * 60 * 60;
+
+    #[test]
+    fn time_formats()
+    {
+        for value in ["2025-10-09T08:53:20Z", "2025-10-09T10:53:20+02:00", "2025-10-09T10:53:20+0200",
+                      "2025-10-09 08:53:20.000Z", "2025-10-09T08:53:20", "2025-10-09 08:53:20",
+                      "20251009085320Z", "@1760000000", " @1760000000 "] {
+            assert_eq!(parse_time(value, None, 0), Ok(NOW), "{}", value);
+        }
+        assert_eq!(parse_time("2025-10-09T08:53", None, 0), Ok(NOW - 20));
+        assert_eq!(parse_time("2025-10-09", None, 0), Ok(NOW - 8 * 3600 - 53 * 60 - 20));
+        assert_eq!(parse_time("2060-01-01", None, 0), Ok(2840140800));
+
+        assert_eq!(parse_time("never", None, 0), Ok(NO_EXPIRY));
+        assert_eq!(parse_time("NEVER", Some("%d.%m.%Y"), 0), Ok(NO_EXPIRY));
+        assert_eq!(parse_time("99991231235959Z", None, 0), Ok(NO_EXPIRY));
+
+        assert_eq!(parse_time("2025-13-01", None, 0).unwrap_err(), "invalid date '2025-13-01'");
+        assert_eq!(parse_time("20251309085320Z", None, 0).unwrap_err(),
+                   "invalid date '20251309085320Z'");
+        assert!(parse_time("", None, 0).is_err());
+        assert!(parse_time("yesterday", None, 0).is_err());
+        assert_eq!(parse_time("@253402300800", None, 0).unwrap_err(),
+                   "date '@253402300800' out of range");
+        assert_eq!(parse_time("@-631152000", None, 0), Ok(-631152000));
+        assert_eq!(parse_time("@0", None, 0), Ok(0));
+
+        // digits only are neither dates nor timestamps
+        for value in ["20301231", "20301231235959", "1760000000", "0"] {
+            assert_eq!(parse_time(value, None, 0).unwrap_err(),
+                       format!("ambiguous date '{}', use '@{}' for a Unix timestamp", value, value));
+        }
+        for value in ["@", "@-", "@+1760000000", "@ 1760000000", "@1760000000Z",
+                      "@99999999999999999999"] {
+            assert_eq!(parse_time(value, None, 0).unwrap_err(),
+                       format!("invalid timestamp '{}'", value), "{}", value);
+        }
+    }
+
+    #[test]
+    fn explicit_format()
+    {
+        assert_eq!(parse_time("09.10.2025 08:53:20", Some("%d.%m.%Y %H:%M:%S"), 0), Ok(NOW));
+        assert_eq!(parse_time("09.10.2025 10:53:20 +0200", Some("%d.%m.%Y %H:%M:%S %z"), 0),
+                   Ok(NOW));
+        assert_eq!(parse_time("09.10.2025", Some("%d.%m.%Y"), 0), Ok(NOW - 32000));
+        assert!(parse_time("2025-10-09", Some("%d.%m.%Y"), 0).unwrap_err()
+                    .starts_with("invalid date '2025-10-09' for format '%d.%m.%Y'"));
+    }
+
+    #[test]
+    fn offsets()
+    {
+        assert_eq!(parse_time("+90d", None, NOW), Ok(NOW + 90 * DAY));
+        assert_eq!(parse_time("-1h", None, NOW), Ok(NOW - 3600));
+        assert_eq!(parse_time("+2w", Some("%d.%m.%Y"), NOW), Ok(NOW + 14 * DAY));
+        assert_eq!(parse_time("+30", None, NOW), Ok(NOW + 30));
+        assert_eq!(parse_time("+5m", None, NOW), Ok(NOW + 300));
+        assert_eq!(parse_time("+1y", None, NOW).unwrap_err(), "invalid time offset '+1y'");
+        assert_eq!(parse_time("+d", None, NOW).unwrap_err(), "invalid time offset '+d'");
+        assert!(parse_time(&format!("+{}w", i64::MAX), None, NOW).is_err());
+        assert!(parse_time("+1d", None, NO_EXPIRY).is_err());
+    }
+
+    #[test]
+    fn time_encoding()
+    {
+        assert_eq!(encode_time(NOW), Ok(hex("170d 3235313030393038353332305a")));
+        assert_eq!(encode_time(2524607999), Ok(hex("170d 3439313233313233353935395a")));
+        assert_eq!(encode_time(2524608000), Ok(hex("180f 32303530303130313030303030305a")));
+        assert_eq!(encode_time(-631152000), Ok(hex("170d 3530303130313030303030305a")));
+        assert_eq!(encode_time(-631152001), Ok(hex("180f 31393439313233313233353935395a")));
+        assert_eq!(encode_time(NO_EXPIRY), Ok(hex("180f 39393939313233313233353935395a")));
+        assert!(encode_time(NO_EXPIRY + 1).is_err());
+        assert!(encode_time(i64::MIN).is_err());
+        assert_eq!(time_string(NOW), "2025-10-09T08:53:20Z");
+    }
+
+    #[test]
+    fn validity_period()
+    {
+        let validity = Validity::new(Some("2025-10-09T08:53:20Z"), None, None, 365 * DAY).unwrap();
+        assert_eq!(validity, Validity { not_before: NOW, not_after: NOW + 365 * DAY });
+
+        let validity = Validity::new(Some("2025-10-09T08:53:20Z"), Some("+30d"), None, DAY).unwrap();
+        assert_eq!(validity.not_after, NOW + 30 * DAY);
+        let validity = Validity::new(Some("9999-12-31"), None, None, 365 * DAY).unwrap();
+        assert_eq!(validity.not_after, NO_EXPIRY);
+        let validity = Validity::new(None, Some("never"), None, DAY).unwrap();
+        assert_eq!(validity.not_after, NO_EXPIRY);
+
+        let validity = Validity::new(None, None, None, DAY).unwrap();
+        assert!((validity.not_before - Utc::now().timestamp()).abs() < 60);
+        assert_eq!(validity.not_after - validity.not_before, DAY);
+
+        assert_eq!(Validity::new(Some("2025-10-09T08:53:20Z"), Some("-1s"), None, DAY).unwrap_err(),
+                   "end time 2025-10-09T08:53:19Z is earlier than start time 2025-10-09T08:53:20Z");
+    }
+
+    #[test]
+    fn validity_options()
+    {
+        let mut opts = getopts::Options::new();
+        opts.optopt("l", "lifetime", "", "");
+        opts.optopt("F", "not-before", "", "");
+        opts.optopt("T", "not-after", "", "");
+        opts.optopt("D", "dateform", "", "");
+        let parse = |args: &[&str]| Validity::from_matches(&opts.parse(args).unwrap(), DAY, 1095);
+
+        let validity = parse(&["-F", "09.10.2025", "-D", "%d.%m.%Y"]).unwrap();
+        assert_eq!(validity.not_before, NOW - 32000);
+        assert_eq!(validity.not_after, NOW - 32000 + 1095 * DAY);
+
+        let validity = parse(&["--not-before", "2025-10-09T08:53:20Z", "--lifetime", "30"]).unwrap();
+        assert_eq!(validity.not_after, NOW + 30 * DAY);
+        let validity = parse(&["-F", "2025-10-09T08:53:20Z", "-l", "30", "-T", "+1d"]).unwrap();
+        assert_eq!(validity.not_after, NOW + DAY);
+
+        assert_eq!(parse(&["-l", "0"]).unwrap_err(), "invalid lifetime '0'");
+        assert_eq!(parse(&["-l", "ten"]).unwrap_err(), "invalid lifetime 'ten'");
+        assert!(parse(&["-l", &i64::MAX.to_string()]).is_err());
+    }
+}
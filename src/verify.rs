@@ -0,0 +1,675 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! Certification path building and validation (RFC 5280, section 6).  A
+//! chain is built from the end entity certificate up to a self-signed
+//! trusted root and then processed from the root downwards, checking
+//! signatures, lifetimes, CA constraints, name constraints, certificate
+//! policies and the RFC 3779 address blocks.  The constraints of an issuer
+//! are also checked before it issues a new certificate.
+
+use chrono::DateTime;
+use crate::addrblock::AddrBlocks;
+use crate::asn1::*;
+use crate::dn::Dn;
+use crate::extensions::{KU_CRL_SIGN, KU_KEY_CERT_SIGN};
+use crate::name_constraints::{NameConstraints, subject_names};
+use crate::oid::*;
+use crate::policy::process_policies;
+use crate::x509::{Certificate, Extension, TbsCertificate};
+use crate::X509_NO_CONSTRAINT;
+
+/// Maximum number of certificates in a trust chain
+pub const MAX_TRUSTCHAIN_LEN: usize = 8;
+
+/// Extensions processed by the path validation, all others must not be critical
+const SUPPORTED_EXTENSIONS: &[&str] = &[
+    OID_BASIC_CONSTRAINTS,
+    OID_KEY_USAGE,
+    OID_EXTENDED_KEY_USAGE,
+    OID_SUBJECT_ALT_NAME,
+    OID_SUBJECT_KEY_ID,
+    OID_AUTHORITY_KEY_ID,
+    OID_IP_ADDR_BLOCKS,
+    OID_NAME_CONSTRAINTS,
+    OID_CERTIFICATE_POLICIES,
+    OID_POLICY_MAPPINGS,
+    OID_POLICY_CONSTRAINTS,
+    OID_INHIBIT_ANY_POLICY,
+];
+
+//
+// Format the subject distinguished name of a certificate.
+//
+pub fn subject_name(cert: &Certificate) -> String
+{
+    match Dn::from_der(&cert.subject) {
+        Ok(dn) => { dn.to_string() }
+        Err(_) => { "(invalid subject)".to_string() }
+    }
+}
+
+//
+// Format a certificate time.
+//
+fn time_string(time: i64) -> String
+{
+    match DateTime::from_timestamp(time, 0) {
+        Some(dt) => { dt.format("%b %d %H:%M:%S %Y UTC").to_string() }
+        None => { time.to_string() }
+    }
+}
+
+//
+// Check if a certificate is self-signed.
+//
+pub fn is_self_signed(cert: &Certificate) -> bool
+{
+    return cert.subject == cert.issuer && cert.verify_signature(&cert.public_key);
+}
+
+//
+// Decode a basicConstraints extension into the cA flag and the
+// pathLenConstraint.
+//
+fn decode_basic_constraints(ext: Option<&Extension>) -> Result<(bool, u32), String>
+{
+    let ext = match ext {
+        Some(ext) => { ext }
+        None => { return Ok((false, X509_NO_CONSTRAINT)); }
+    };
+    let mut parser = Asn1Parser::new(&ext.value).expect(ASN1_SEQUENCE)?.parser();
+    let ca = match parser.optional(ASN1_BOOLEAN)? {
+        Some(obj) => { obj.to_bool()? }
+        None => { false }
+    };
+    let pathlen = match parser.optional(ASN1_INTEGER)? {
+        Some(obj) => { obj.to_u64()?.min(X509_NO_CONSTRAINT as u64) as u32 }
+        None => { X509_NO_CONSTRAINT }
+    };
+    parser.finish()?;
+    return Ok((ca, pathlen));
+}
+
+//
+// Decode the basicConstraints extension of a certificate.
+//
+fn basic_constraints(cert: &Certificate) -> Result<(bool, u32), String>
+{
+    return decode_basic_constraints(cert.extension(OID_BASIC_CONSTRAINTS));
+}
+
+//
+// Decode the keyUsage extension, if present.
+//
+fn key_usage(cert: &Certificate) -> Result<Option<u32>, String>
+{
+    match cert.extension(OID_KEY_USAGE) {
+        Some(ext) => {
+            let mut parser = Asn1Parser::new(&ext.value);
+            let usage = parser.expect(ASN1_BIT_STRING)?.to_named_bits()?;
+            parser.finish()?;
+            Ok(Some(usage))
+        }
+        None => { Ok(None) }
+    }
+}
+
+//
+// Decode the sbgp-ipAddrBlock extension, if present.
+//
+fn addr_blocks(cert: &Certificate) -> Result<Option<AddrBlocks>, String>
+{
+    match cert.extension(OID_IP_ADDR_BLOCKS) {
+        Some(ext) => { AddrBlocks::from_der(&ext.value).map(Some) }
+        None => { Ok(None) }
+    }
+}
+
+//
+// Decode the nameConstraints extension, if present.
+//
+fn name_constraints(cert: &Certificate) -> Result<Option<NameConstraints>, String>
+{
+    match cert.extension(OID_NAME_CONSTRAINTS) {
+        Some(ext) => {
+            NameConstraints::from_der(&ext.value).map(Some)
+                .map_err(|e| format!("invalid nameConstraints in \"{}\": {}", subject_name(cert), e))
+        }
+        None => { Ok(None) }
+    }
+}
+
+//
+// Check that the certificate is valid at the given time.
+//
+fn check_lifetime(cert: &Certificate, now: i64) -> Result<(), String>
+{
+    if now < cert.not_before {
+        return Err(format!("certificate \"{}\" not valid before {}",
+                           subject_name(cert), time_string(cert.not_before)));
+    }
+    if now > cert.not_after {
+        return Err(format!("certificate \"{}\" expired on {}",
+                           subject_name(cert), time_string(cert.not_after)));
+    }
+    return Ok(());
+}
+
+//
+// Check that a certificate is allowed to issue certificates, with the given
+// number of intermediate CA certificates following it.
+//
+fn check_issuer(issuer: &Certificate, intermediates: u32) -> Result<(), String>
+{
+    let name = subject_name(issuer);
+    let (ca, pathlen) = basic_constraints(issuer)
+                            .map_err(|e| format!("invalid basicConstraints in \"{}\": {}", name, e))?;
+    if !ca {
+        return Err(format!("issuer certificate \"{}\" is not a CA certificate", name));
+    }
+    let usage = key_usage(issuer).map_err(|e| format!("invalid keyUsage in \"{}\": {}", name, e))?;
+    if let Some(usage) = usage && usage & KU_KEY_CERT_SIGN == 0 {
+        return Err(format!("issuer certificate \"{}\" lacks the keyCertSign usage", name));
+    }
+    if pathlen != X509_NO_CONSTRAINT && intermediates > pathlen {
+        return Err(format!("path length of {} exceeds the pathLenConstraint {} of \"{}\"",
+                           intermediates, pathlen, name));
+    }
+    return Ok(());
+}
+
+//
+// Reject critical extensions not processed by the path validation.
+//
+fn check_critical(cert: &Certificate) -> Result<(), String>
+{
+    for ext in &cert.extensions {
+        if ext.critical && !SUPPORTED_EXTENSIONS.contains(&ext.oid.as_str()) {
+            return Err(format!("certificate \"{}\" contains unsupported critical extension {}",
+                               subject_name(cert), oid_display(&ext.oid)));
+        }
+    }
+    return Ok(());
+}
+
+//
+// Build a trust chain from a certificate up to a self-signed root among the
+// trusted CA certificates.  The certificate comes first and the root last.
+//
+pub fn build_chain(cert: &Certificate, cacerts: &[Certificate]) -> Result<Vec<Certificate>, String>
+{
+    let mut chain: Vec<Certificate> = vec![cert.clone()];
+    loop {
+        let current = chain.last().unwrap();
+        if is_self_signed(current) {
+            if !cacerts.iter().any(|ca| ca.der == current.der) {
+                return Err(format!("self-signed certificate \"{}\" is not trusted",
+                                   subject_name(current)));
+            }
+            return Ok(chain);
+        }
+        if chain.len() >= MAX_TRUSTCHAIN_LEN {
+            return Err(format!("maximum trust chain length of {} exceeded", MAX_TRUSTCHAIN_LEN));
+        }
+        let issuer = cacerts.iter().find(|ca| {
+            ca.subject == current.issuer && current.verify_signature(&ca.public_key) &&
+            !chain.iter().any(|c| c.der == ca.der)
+        });
+        match issuer {
+            Some(issuer) => { chain.push(issuer.clone()); }
+            None => {
+                return Err(format!("no trusted issuer found for \"{}\"", subject_name(current)));
+            }
+        }
+    }
+}
+
+//
+// Validate a trust chain as returned by build_chain at the given time,
+// processing the certificates from the root down to the end entity.  If
+// acceptable policies are given, the chain must be valid for one of them.
+// Returns the valid certificate policies of the chain.
+//
+pub fn verify_chain(chain: &[Certificate], now: i64, acceptable: &[String])
+                   -> Result<Vec<String>, String>
+{
+    let root = chain.last().ok_or("empty trust chain")?;
+    check_lifetime(root, now)?;
+    check_critical(root)?;
+
+    let mut resources = addr_blocks(root)
+                            .map_err(|e| format!("invalid ipAddrBlocks in \"{}\": {}",
+                                                 subject_name(root), e))?;
+    if let Some(blocks) = &resources && blocks.families.iter().any(|f| f.ranges.is_none()) {
+        return Err(format!("root certificate \"{}\" inherits address blocks", subject_name(root)));
+    }
+
+    // the constraints of all CA certificates above, including the root
+    let mut constraints: Vec<NameConstraints> = name_constraints(root)?.into_iter().collect();
+
+    for i in (0..chain.len() - 1).rev() {
+        let (cert, issuer) = (&chain[i], &chain[i + 1]);
+        let name = subject_name(cert);
+
+        if cert.issuer != issuer.subject || !cert.verify_signature(&issuer.public_key) {
+            return Err(format!("signature of \"{}\" not verified by \"{}\"",
+                               name, subject_name(issuer)));
+        }
+        check_lifetime(cert, now)?;
+
+        // count the non-self-issued intermediate CA certificates below the issuer
+        let intermediates = chain[1..=i].iter().filter(|c| c.subject != c.issuer).count();
+        check_issuer(issuer, intermediates as u32)?;
+        check_critical(cert)?;
+
+        // self-issued intermediate certificates are exempt (RFC 5280, 6.1.3)
+        if i == 0 || cert.subject != cert.issuer {
+            let names = subject_names(&cert.subject, &cert.extensions)
+                            .map_err(|e| format!("invalid names in \"{}\": {}", name, e))?;
+            for nc in &constraints {
+                nc.check(&names).map_err(|e| format!("certificate \"{}\": {}", name, e))?;
+            }
+        }
+        constraints.extend(name_constraints(cert)?);
+
+        resources = match addr_blocks(cert).map_err(|e| format!("invalid ipAddrBlocks in \"{}\": {}",
+                                                                  name, e))? {
+            Some(blocks) => {
+                let resolved = blocks.resolve(resources.as_ref())
+                                     .map_err(|e| format!("certificate \"{}\": {}", name, e))?;
+                Some(resolved)
+            }
+            None => { None }
+        };
+    }
+
+    let path: Vec<&Certificate> = chain[..chain.len() - 1].iter().rev().collect();
+    return process_policies(&path, acceptable, !acceptable.is_empty());
+}
+
+//
+// Check a certificate to be issued against the constraints of its issuer
+// at the given time, returning all violations found.
+//
+pub fn check_issuance(issuer: &Certificate, tbs: &TbsCertificate, now: i64) -> Vec<String>
+{
+    let mut violations: Vec<String> = Vec::new();
+    let name = subject_name(issuer);
+
+    let bc = tbs.extensions.iter().find(|e| e.oid == OID_BASIC_CONSTRAINTS);
+    let (ca, pathlen) = decode_basic_constraints(bc).unwrap_or((false, X509_NO_CONSTRAINT));
+    let self_issued = tbs.subject == issuer.subject;
+
+    match basic_constraints(issuer) {
+        Ok((false, _)) => {
+            violations.push(format!("issuer certificate \"{}\" is not a CA certificate", name));
+        }
+        Ok((true, issuer_pathlen)) if ca && !self_issued && issuer_pathlen != X509_NO_CONSTRAINT => {
+            if issuer_pathlen == 0 {
+                violations.push(format!("pathLenConstraint 0 of \"{}\" forbids issuing CA certificates",
+                                        name));
+            } else if pathlen != X509_NO_CONSTRAINT && pathlen >= issuer_pathlen {
+                violations.push(format!("pathLenConstraint {} must be less than the pathLenConstraint {} of \"{}\"",
+                                        pathlen, issuer_pathlen, name));
+            }
+        }
+        Ok(_) => {}
+        Err(e) => {
+            violations.push(format!("invalid basicConstraints in \"{}\": {}", name, e));
+        }
+    }
+    match key_usage(issuer) {
+        Ok(Some(usage)) if usage & KU_KEY_CERT_SIGN == 0 => {
+            violations.push(format!("issuer certificate \"{}\" lacks the keyCertSign usage", name));
+        }
+        Ok(_) => {}
+        Err(e) => {
+            violations.push(format!("invalid keyUsage in \"{}\": {}", name, e));
+        }
+    }
+
+    if let Err(e) = check_lifetime(issuer, now) {
+        violations.push(e);
+    }
+    if tbs.not_before < issuer.not_before {
+        violations.push(format!("notBefore {} precedes the notBefore {} of \"{}\"",
+                                time_string(tbs.not_before), time_string(issuer.not_before), name));
+    }
+    if tbs.not_after > issuer.not_after {
+        violations.push(format!("notAfter {} exceeds the notAfter {} of \"{}\"",
+                                time_string(tbs.not_after), time_string(issuer.not_after), name));
+    }
+
+    // self-issued CA certificates are exempt (RFC 5280, 6.1.3)
+    if !(ca && self_issued) {
+        let result = name_constraints(issuer).and_then(|nc| {
+            match nc {
+                Some(nc) => {
+                    let names = subject_names(&tbs.subject, &tbs.extensions)?;
+                    nc.check(&names).map_err(|e| format!("nameConstraints of \"{}\": {}", name, e))
+                }
+                None => { Ok(()) }
+            }
+        });
+        if let Err(e) = result {
+            violations.push(e);
+        }
+    }
+    return violations;
+}
+
+//
+// Check the constraints of an issuer signing a CRL at the given time,
+// returning all violations found.
+//
+pub fn check_crl_issuance(issuer: &Certificate, now: i64) -> Vec<String>
+{
+    let mut violations: Vec<String> = Vec::new();
+    let name = subject_name(issuer);
+
+    match basic_constraints(issuer) {
+        Ok((false, _)) => {
+            violations.push(format!("issuer certificate \"{}\" is not a CA certificate", name));
+        }
+        Ok(_) => {}
+        Err(e) => {
+            violations.push(format!("invalid basicConstraints in \"{}\": {}", name, e));
+        }
+    }
+    match key_usage(issuer) {
+        Ok(Some(usage)) if usage & KU_CRL_SIGN == 0 => {
+            violations.push(format!("issuer certificate \"{}\" lacks the cRLSign usage", name));
+        }
+        Ok(_) => {}
+        Err(e) => {
+            violations.push(format!("invalid keyUsage in \"{}\": {}", name, e));
+        }
+    }
+    if let Err(e) = check_lifetime(issuer, now) {
+        violations.push(e);
+    }
+    return violations;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::addrblock::{AFI_IPV4, AddrFamily};
+    use crate::crypto::eddsa::{EdCurve, EdPrivateKey};
+    use crate::extensions::{self as ext, CertPolicy};
+    use crate::keys::PrivateKey;
+    use crate::signature::SignatureScheme;
+
+    const DAY: i64 = 24 * 60 * 60;
+    const NOW: i64 = 1760000000;
+
+    fn tbs_certificate(subject: &str, issuer: &str, key: &PrivateKey, lifetime: i64,
+                       extensions: Vec<Extension>) -> TbsCertificate
+    {
+        return TbsCertificate { serial: vec![0x01], issuer: Dn::parse(issuer).unwrap().to_der(),
+                                not_before: NOW - DAY, not_after: NOW + lifetime * DAY,
+                                subject: Dn::parse(subject).unwrap().to_der(),
+                                public_key: key.public_key(), extensions };
+    }
+
+    fn ca_certificate(extensions: Vec<Extension>) -> Certificate
+    {
+        let key = PrivateKey::Eddsa(EdPrivateKey::generate(EdCurve::Ed25519));
+        return tbs_certificate("C=CH, CN=Root CA", "C=CH, CN=Root CA", &key, 365, extensions)
+                   .sign(&key, SignatureScheme::Ed25519).unwrap();
+    }
+
+    fn end_entity(lifetime: i64, extensions: Vec<Extension>) -> TbsCertificate
+    {
+        let key = PrivateKey::Eddsa(EdPrivateKey::generate(EdCurve::Ed25519));
+        return tbs_certificate("C=CH, CN=host", "C=CH, CN=Root CA", &key, lifetime, extensions);
+    }
+
+    #[test]
+    fn issuance_within_constraints()
+    {
+        let ca = ca_certificate(vec![ext::basic_constraints(true, X509_NO_CONSTRAINT),
+                                     ext::key_usage(KU_KEY_CERT_SIGN | KU_CRL_SIGN)]);
+        assert!(check_issuance(&ca, &end_entity(30, vec![]), NOW).is_empty());
+        assert!(check_crl_issuance(&ca, NOW).is_empty());
+    }
+
+    #[test]
+    fn issuance_lifetime()
+    {
+        let ca = ca_certificate(vec![ext::basic_constraints(true, X509_NO_CONSTRAINT)]);
+
+        let violations = check_issuance(&ca, &end_entity(366, vec![]), NOW);
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].starts_with("notAfter"), "{}", violations[0]);
+
+        let mut tbs = end_entity(30, vec![]);
+        tbs.not_before = ca.not_before - 1;
+        let violations = check_issuance(&ca, &tbs, NOW);
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].starts_with("notBefore"), "{}", violations[0]);
+
+        let violations = check_issuance(&ca, &end_entity(30, vec![]), NOW + 400 * DAY);
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].contains("expired"), "{}", violations[0]);
+        assert_eq!(check_crl_issuance(&ca, NOW + 400 * DAY).len(), 1);
+    }
+
+    #[test]
+    fn issuance_path_length()
+    {
+        let sub_ca = vec![ext::basic_constraints(true, 1)];
+
+        let ca = ca_certificate(vec![ext::basic_constraints(true, 0)]);
+        let violations = check_issuance(&ca, &end_entity(30, sub_ca.clone()), NOW);
+        assert_eq!(violations, vec!["pathLenConstraint 0 of \"C=CH, CN=Root CA\" forbids issuing CA certificates"]);
+        assert!(check_issuance(&ca, &end_entity(30, vec![]), NOW).is_empty());
+
+        let ca = ca_certificate(vec![ext::basic_constraints(true, 1)]);
+        let violations = check_issuance(&ca, &end_entity(30, sub_ca), NOW);
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].starts_with("pathLenConstraint 1 must be less"), "{}", violations[0]);
+        assert!(check_issuance(&ca, &end_entity(30, vec![ext::basic_constraints(true, 0)]), NOW).is_empty());
+    }
+
+    #[test]
+    fn issuer_not_a_ca()
+    {
+        let ca = ca_certificate(vec![]);
+        let violations = check_issuance(&ca, &end_entity(30, vec![]), NOW);
+        assert_eq!(violations, vec!["issuer certificate \"C=CH, CN=Root CA\" is not a CA certificate"]);
+        assert_eq!(check_crl_issuance(&ca, NOW), violations);
+    }
+
+    #[test]
+    fn issuer_key_usage()
+    {
+        let ca = ca_certificate(vec![ext::basic_constraints(true, X509_NO_CONSTRAINT),
+                                     ext::key_usage(KU_CRL_SIGN)]);
+        assert_eq!(check_issuance(&ca, &end_entity(30, vec![]), NOW),
+                   vec!["issuer certificate \"C=CH, CN=Root CA\" lacks the keyCertSign usage"]);
+        assert!(check_crl_issuance(&ca, NOW).is_empty());
+
+        let ca = ca_certificate(vec![ext::basic_constraints(true, X509_NO_CONSTRAINT),
+                                     ext::key_usage(KU_KEY_CERT_SIGN)]);
+        assert!(check_issuance(&ca, &end_entity(30, vec![]), NOW).is_empty());
+        assert_eq!(check_crl_issuance(&ca, NOW),
+                   vec!["issuer certificate \"C=CH, CN=Root CA\" lacks the cRLSign usage"]);
+    }
+
+    fn issue(subject: &str, key: &PrivateKey, issuer: Option<(&Certificate, &PrivateKey)>,
+             extensions: Vec<Extension>) -> Certificate
+    {
+        let mut tbs = tbs_certificate(subject, subject, key, 365, extensions);
+        match issuer {
+            Some((cert, issuer_key)) => {
+                tbs.issuer = cert.subject.clone();
+                tbs.sign(issuer_key, SignatureScheme::Ed25519).unwrap()
+            }
+            None => { tbs.sign(key, SignatureScheme::Ed25519).unwrap() }
+        }
+    }
+
+    fn key() -> PrivateKey
+    {
+        return PrivateKey::Eddsa(EdPrivateKey::generate(EdCurve::Ed25519));
+    }
+
+    fn strings(values: &[&str]) -> Vec<String>
+    {
+        return values.iter().map(|v| v.to_string()).collect();
+    }
+
+    //
+    // Issue a chain of root, intermediate and end entity certificates with
+    // the given additional extensions each.
+    //
+    fn chain(root: Vec<Extension>, sub: Vec<Extension>, ee: Vec<Extension>) -> Vec<Certificate>
+    {
+        fn ca(mut extensions: Vec<Extension>) -> Vec<Extension>
+        {
+            if !extensions.iter().any(|e| e.oid == OID_BASIC_CONSTRAINTS) {
+                extensions.insert(0, ext::basic_constraints(true, X509_NO_CONSTRAINT));
+            }
+            return extensions;
+        }
+
+        let (root_key, sub_key) = (key(), key());
+        let root = issue("C=CH, CN=Root CA", &root_key, None, ca(root));
+        let sub = issue("C=CH, CN=Sub CA", &sub_key, Some((&root, &root_key)), ca(sub));
+        let ee = issue("C=CH, CN=host", &key(), Some((&sub, &sub_key)), ee);
+        return vec![ee, sub, root];
+    }
+
+    #[test]
+    fn trust_chain()
+    {
+        let certs = chain(vec![], vec![], vec![]);
+        let (ee, sub, root) = (&certs[0], &certs[1], &certs[2]);
+
+        let built = build_chain(ee, &[root.clone(), sub.clone()]).unwrap();
+        assert_eq!(built.iter().map(subject_name).collect::<Vec<_>>(),
+                   vec!["C=CH, CN=host", "C=CH, CN=Sub CA", "C=CH, CN=Root CA"]);
+        assert!(verify_chain(&built, NOW, &[]).unwrap().is_empty());
+        assert_eq!(build_chain(root, &certs[2..]).unwrap().len(), 1);
+
+        assert_eq!(build_chain(ee, &certs[1..2]).unwrap_err(),
+                   "no trusted issuer found for \"C=CH, CN=Sub CA\"");
+        let other = chain(vec![], vec![], vec![]);
+        assert_eq!(build_chain(&other[2], &certs[2..]).unwrap_err(),
+                   "self-signed certificate \"C=CH, CN=Root CA\" is not trusted");
+
+        assert!(verify_chain(&built, NOW + 400 * DAY, &[]).unwrap_err().contains("expired on"));
+        assert!(verify_chain(&built, NOW - 2 * DAY, &[]).unwrap_err().contains("not valid before"));
+        let forged = vec![ee.clone(), other[1].clone(), other[2].clone()];
+        assert_eq!(verify_chain(&forged, NOW, &[]).unwrap_err(),
+                   "signature of \"C=CH, CN=host\" not verified by \"C=CH, CN=Sub CA\"");
+    }
+
+    #[test]
+    fn chain_issuer_constraints()
+    {
+        let certs = chain(vec![ext::basic_constraints(true, 0)], vec![], vec![]);
+        assert_eq!(verify_chain(&certs, NOW, &[]).unwrap_err(),
+                   "path length of 1 exceeds the pathLenConstraint 0 of \"C=CH, CN=Root CA\"");
+
+        let certs = chain(vec![], vec![ext::key_usage(KU_CRL_SIGN)], vec![]);
+        assert_eq!(verify_chain(&certs, NOW, &[]).unwrap_err(),
+                   "issuer certificate \"C=CH, CN=Sub CA\" lacks the keyCertSign usage");
+
+        let critical = ext::critical_extension("1.3.6.1.4.1.36906.99").unwrap();
+        let certs = chain(vec![], vec![], vec![critical.clone()]);
+        assert_eq!(verify_chain(&certs, NOW, &[]).unwrap_err(),
+                   "certificate \"C=CH, CN=host\" contains unsupported critical extension \
+                    1.3.6.1.4.1.36906.99");
+
+        // the trust anchor is no exception, even as the only certificate
+        let certs = chain(vec![critical], vec![], vec![]);
+        for path in [&certs[..], &certs[2..]] {
+            assert_eq!(verify_chain(path, NOW, &[]).unwrap_err(),
+                       "certificate \"C=CH, CN=Root CA\" contains unsupported critical extension \
+                        1.3.6.1.4.1.36906.99");
+        }
+    }
+
+    #[test]
+    fn chain_address_blocks()
+    {
+        let blocks = |b: &[&str]| ext::ip_addr_blocks(&strings(b)).unwrap();
+        let inherit = Extension { oid: OID_IP_ADDR_BLOCKS.to_string(), critical: true,
+                                  value: AddrBlocks { families: vec![AddrFamily {
+                                      afi: AFI_IPV4, safi: None, ranges: None,
+                                  }] }.encode() };
+
+        let certs = chain(vec![blocks(&["10.0.0.0/8"])], vec![blocks(&["10.1.0.0/16"])],
+                          vec![blocks(&["10.1.2.0/24"])]);
+        assert!(verify_chain(&certs, NOW, &[]).is_ok());
+        let certs = chain(vec![blocks(&["10.0.0.0/8"])], vec![inherit.clone()],
+                          vec![blocks(&["10.2.0.0/16"])]);
+        assert!(verify_chain(&certs, NOW, &[]).is_ok());
+
+        let certs = chain(vec![blocks(&["10.0.0.0/8"])], vec![blocks(&["10.1.0.0/16"])],
+                          vec![blocks(&["10.2.0.0/24"])]);
+        assert_eq!(verify_chain(&certs, NOW, &[]).unwrap_err(),
+                   "certificate \"C=CH, CN=host\": address block 10.2.0.0/24 not contained in \
+                    issuer's");
+        let certs = chain(vec![blocks(&["10.0.0.0/8"])], vec![], vec![blocks(&["10.1.0.0/16"])]);
+        assert_eq!(verify_chain(&certs, NOW, &[]).unwrap_err(),
+                   "certificate \"C=CH, CN=host\": issuer holds no IPv4 address blocks");
+        let certs = chain(vec![inherit], vec![], vec![]);
+        assert_eq!(verify_chain(&certs, NOW, &[]).unwrap_err(),
+                   "root certificate \"C=CH, CN=Root CA\" inherits address blocks");
+    }
+
+    #[test]
+    fn chain_name_constraints()
+    {
+        let nc = ext::name_constraints(&strings(&["example.com", "C=CH"]),
+                                       &strings(&["secret.example.com"])).unwrap();
+        let san = |names: &[&str]| ext::subject_alt_name(&strings(names)).unwrap();
+
+        let certs = chain(vec![nc.clone()], vec![], vec![san(&["www.example.com"])]);
+        assert!(verify_chain(&certs, NOW, &[]).is_ok());
+        let certs = chain(vec![], vec![nc.clone()], vec![san(&["www.example.org"])]);
+        assert!(verify_chain(&certs, NOW, &[]).unwrap_err()
+                    .starts_with("certificate \"C=CH, CN=host\": "));
+        let certs = chain(vec![nc], vec![], vec![san(&["www.secret.example.com"])]);
+        assert!(verify_chain(&certs, NOW, &[]).is_err());
+
+        // the constraints apply to the subject DN of the intermediate CA too
+        let nc = ext::name_constraints(&strings(&["C=DE"]), &[]).unwrap();
+        let certs = chain(vec![nc], vec![], vec![]);
+        assert!(verify_chain(&certs, NOW, &[]).unwrap_err()
+                    .starts_with("certificate \"C=CH, CN=Sub CA\": "));
+    }
+
+    #[test]
+    fn chain_policies()
+    {
+        let policy = |oid: &str| {
+            ext::certificate_policies(&[CertPolicy { oid: oid.to_string(), ..Default::default() }])
+                .unwrap()
+        };
+        let p1 = "1.3.6.1.4.1.36906.1.1";
+
+        let certs = chain(vec![], vec![policy(OID_ANY_POLICY)], vec![policy(p1)]);
+        assert_eq!(verify_chain(&certs, NOW, &[]).unwrap(), strings(&[p1]));
+        assert_eq!(verify_chain(&certs, NOW, &strings(&[p1])).unwrap(), strings(&[p1]));
+        assert_eq!(verify_chain(&certs, NOW, &strings(&["1.3.6.1.4.1.36906.1.2"])).unwrap_err(),
+                   "no acceptable certificate policy");
+
+        let certs = chain(vec![], vec![policy(p1)], vec![]);
+        assert!(verify_chain(&certs, NOW, &[]).unwrap().is_empty());
+        assert!(verify_chain(&certs, NOW, &strings(&[p1])).is_err());
+    }
+}
@@ -0,0 +1,387 @@
+// Copyright 2026 Andreas Steffen
+//
+// This program is free software; you can redistribute it and/or modify it
+// under the terms of the GNU General Public License as published by the
+// Free Software Foundation; either version 2 of the License, or (at your
+// option) any later version.  See <http://www.fsf.org/copyleft/gpl.txt>.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+// or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU General Public License
+// for more details.
+
+//! X.509 certificates (RFC 5280).
+
+use crate::asn1::*;
+use crate::crypto::rng::rng_bytes;
+use crate::keys::{PrivateKey, PublicKey};
+use crate::oid::*;
+use crate::signature::SignatureScheme;
+use crate::validity::encode_time;
+
+/// A certificate or CRL extension
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Extension {
+    /// extension OID in dotted notation
+    pub oid: String,
+    /// criticality flag
+    pub critical: bool,
+    /// DER encoded extension value
+    pub value: Vec<u8>,
+}
+
+/// The fields of a certificate to be signed
+#[derive(Clone, Debug)]
+pub struct TbsCertificate {
+    /// serialNumber INTEGER magnitude
+    pub serial: Vec<u8>,
+    /// DER encoded issuer Name
+    pub issuer: Vec<u8>,
+    /// start of the validity period
+    pub not_before: i64,
+    /// end of the validity period
+    pub not_after: i64,
+    /// DER encoded subject Name
+    pub subject: Vec<u8>,
+    /// subject public key
+    pub public_key: PublicKey,
+    /// certificate extensions
+    pub extensions: Vec<Extension>,
+}
+
+/// A decoded X.509 certificate
+#[derive(Clone, Debug)]
+pub struct Certificate {
+    /// complete DER encoding
+    pub der: Vec<u8>,
+    /// DER encoded tbsCertificate
+    pub tbs: Vec<u8>,
+    /// X.509 version, 1..3
+    pub version: u8,
+    /// serialNumber INTEGER content octets
+    pub serial: Vec<u8>,
+    /// DER encoded issuer Name
+    pub issuer: Vec<u8>,
+    /// start of the validity period
+    pub not_before: i64,
+    /// end of the validity period
+    pub not_after: i64,
+    /// DER encoded subject Name
+    pub subject: Vec<u8>,
+    /// subject public key
+    pub public_key: PublicKey,
+    /// certificate extensions
+    pub extensions: Vec<Extension>,
+    /// outer signature algorithm
+    pub scheme: SignatureScheme,
+    /// signature value
+    pub signature: Vec<u8>,
+}
+
+//
+// Parse the Extensions SEQUENCE, rejecting duplicates (RFC 5280, 4.2).
+//
+pub fn parse_extensions(obj: &Asn1Object) -> Result<Vec<Extension>, String>
+{
+    let mut extensions: Vec<Extension> = Vec::new();
+    let mut parser = obj.parser();
+    while let Some(ext) = parser.next_object()? {
+        if ext.tag != ASN1_SEQUENCE {
+            return Err("invalid Extension".to_string());
+        }
+        let mut p = ext.parser();
+        let oid = p.expect(ASN1_OID)?.to_oid()?;
+        let critical = match p.optional(ASN1_BOOLEAN)? {
+            Some(b) => { b.to_bool()? }
+            None => { false }
+        };
+        let value = p.expect(ASN1_OCTET_STRING)?.content.to_vec();
+        p.finish()?;
+        if extensions.iter().any(|e| e.oid == oid) {
+            return Err(format!("duplicate extension {}", oid_display(&oid)));
+        }
+        extensions.push(Extension { oid, critical, value });
+    }
+    return Ok(extensions);
+}
+
+impl Extension {
+    //
+    // Encode as an Extension SEQUENCE, omitting the DEFAULT FALSE critical flag.
+    //
+    pub fn encode(&self) -> Result<Vec<u8>, String>
+    {
+        let mut items = vec![asn1_simple_object(ASN1_OID, &asn1_oid_from_string(&self.oid)?)];
+        if self.critical {
+            items.push(asn1_boolean(true));
+        }
+        items.push(asn1_octet_string(&self.value));
+        return Ok(asn1_sequence(&items));
+    }
+}
+
+/// Maximum length of an encoded serialNumber (RFC 5280, 4.1.2.2)
+pub const MAX_SERIAL_LEN: usize = 20;
+
+//
+// Parse a serial number given in hex as the content octets of a positive
+// INTEGER of at most 20 octets and return its magnitude.
+//
+pub fn parse_serial(hex: &str) -> Result<Vec<u8>, String>
+{
+    if hex.is_empty() || !hex.bytes().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("invalid serial number '{}': not a hex string", hex));
+    }
+    if hex.len() % 2 == 1 {
+        return Err(format!("invalid serial number '{}': odd number of hex digits", hex));
+    }
+    let octets: Vec<u8> = (0..hex.len()).step_by(2)
+                                        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+                                        .collect();
+    if octets[0] & 0x80 != 0 {
+        return Err(format!("invalid serial number '{}': negative INTEGER, prepend 00", hex));
+    }
+    if octets.len() > MAX_SERIAL_LEN {
+        return Err(format!("invalid serial number '{}': exceeds {} octets", hex, MAX_SERIAL_LEN));
+    }
+    let skip = octets.iter().take_while(|b| **b == 0).count();
+    if skip == octets.len() {
+        return Err(format!("invalid serial number '{}': must be positive", hex));
+    }
+    return Ok(octets[skip..].to_vec());
+}
+
+//
+// Generate a random positive 16 octet serial number with 126 bits from the
+// CSPRNG, exceeding the 64 bits required by the CA/Browser Forum.
+//
+pub fn random_serial() -> Vec<u8>
+{
+    let mut serial = rng_bytes(16);
+    serial[0] = (serial[0] & 0x7F) | 0x40;
+    return serial;
+}
+
+impl TbsCertificate {
+    //
+    // Encode as an X.509v3 tbsCertificate.
+    //
+    pub fn encode(&self, scheme: SignatureScheme) -> Result<Vec<u8>, String>
+    {
+        let version = asn1_wrap(ASN1_CONTEXT_C_0, &[&asn1_integer_from_u64(2)]);
+        let validity = asn1_sequence(&[encode_time(self.not_before)?, encode_time(self.not_after)?]);
+        let mut items = vec![version, asn1_integer(&self.serial), scheme.to_algorithm_identifier(),
+                             self.issuer.clone(), validity, self.subject.clone(),
+                             self.public_key.to_spki()];
+        if !self.extensions.is_empty() {
+            let exts = self.extensions.iter().map(|e| e.encode()).collect::<Result<Vec<_>, _>>()?;
+            items.push(asn1_wrap(ASN1_CONTEXT_C_3, &[&asn1_sequence(&exts)]));
+        }
+        return Ok(asn1_sequence(&items));
+    }
+
+    //
+    // Sign with the issuer's private key and return the decoded certificate.
+    //
+    pub fn sign(&self, key: &PrivateKey, scheme: SignatureScheme) -> Result<Certificate, String>
+    {
+        let tbs = self.encode(scheme)?;
+        let signature = key.sign(scheme, &tbs)?;
+        let der = asn1_sequence(&[tbs, scheme.to_algorithm_identifier(), asn1_bitstring(&signature)]);
+        return Certificate::from_der(&der);
+    }
+}
+
+impl Certificate {
+    //
+    // Decode a DER encoded certificate.
+    //
+    pub fn from_der(der: &[u8]) -> Result<Certificate, String>
+    {
+        let obj = asn1_parse(der)?;
+        if obj.tag != ASN1_SEQUENCE {
+            return Err("not a Certificate".to_string());
+        }
+        let mut parser = obj.parser();
+        let tbs = parser.expect(ASN1_SEQUENCE)?;
+        let scheme = SignatureScheme::from_algorithm_identifier(&parser.expect(ASN1_SEQUENCE)?)?;
+        let signature = parser.expect(ASN1_BIT_STRING)?.to_bitstring_octets()?.to_vec();
+        parser.finish()?;
+
+        let mut p = tbs.parser();
+        let version = match p.optional(ASN1_CONTEXT_C_0)? {
+            Some(v) => { v.parser().expect(ASN1_INTEGER)?.to_u64()? + 1 }
+            None => { 1 }
+        };
+        if version > 3 {
+            return Err(format!("unsupported X.509 version {}", version));
+        }
+        let serial = p.expect(ASN1_INTEGER)?.integer_bytes()?.to_vec();
+        let inner = SignatureScheme::from_algorithm_identifier(&p.expect(ASN1_SEQUENCE)?)?;
+        if inner != scheme {
+            return Err("inconsistent signature algorithms".to_string());
+        }
+        let issuer = p.expect(ASN1_SEQUENCE)?.raw.to_vec();
+        let validity = p.expect(ASN1_SEQUENCE)?;
+        let mut v = validity.parser();
+        let not_before = v.next_object()?.ok_or("missing notBefore")?.to_time()?;
+        let not_after = v.next_object()?.ok_or("missing notAfter")?.to_time()?;
+        v.finish()?;
+        let subject = p.expect(ASN1_SEQUENCE)?.raw.to_vec();
+        let public_key = PublicKey::from_spki(p.expect(ASN1_SEQUENCE)?.raw)?;
+
+        // skip the obsolete issuerUniqueID and subjectUniqueID
+        p.optional(ASN1_CONTEXT_S_1)?;
+        p.optional(ASN1_CONTEXT_S_2)?;
+        let extensions = match p.optional(ASN1_CONTEXT_C_3)? {
+            Some(exts) => {
+                if version != 3 {
+                    return Err("extensions require an X.509v3 certificate".to_string());
+                }
+                parse_extensions(&exts.parser().expect(ASN1_SEQUENCE)?)?
+            }
+            None => { Vec::new() }
+        };
+        p.finish()?;
+
+        return Ok(Certificate {
+            der: der.to_vec(), tbs: tbs.raw.to_vec(), version: version as u8, serial,
+            issuer, not_before, not_after, subject, public_key, extensions, scheme, signature,
+        });
+    }
+
+    //
+    // Look up an extension by its OID.
+    //
+    pub fn extension(&self, oid: &str) -> Option<&Extension>
+    {
+        return self.extensions.iter().find(|e| e.oid == oid);
+    }
+
+    //
+    // Get the keyIdentifier of the certificate, preferring its
+    // subjectKeyIdentifier over the one derived from its public key.
+    //
+    pub fn key_identifier(&self) -> Result<Vec<u8>, String>
+    {
+        match self.extension(OID_SUBJECT_KEY_ID) {
+            Some(ext) => {
+                let mut parser = Asn1Parser::new(&ext.value);
+                let keyid = parser.expect(ASN1_OCTET_STRING)?;
+                parser.finish()?;
+                Ok(keyid.content.to_vec())
+            }
+            None => { Ok(self.public_key.subject_key_id()) }
+        }
+    }
+
+    //
+    // Encode as IssuerAndSerialNumber (RFC 5652).
+    //
+    pub fn issuer_and_serial(&self) -> Vec<u8>
+    {
+        return asn1_wrap(ASN1_SEQUENCE, &[&self.issuer,
+                                          &asn1_simple_object(ASN1_INTEGER, &self.serial)]);
+    }
+
+    //
+    // Check the certificate signature with the issuer's public key.
+    //
+    pub fn verify_signature(&self, issuer: &PublicKey) -> bool
+    {
+        return issuer.verify(self.scheme, &self.tbs, &self.signature);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::X509_NO_CONSTRAINT;
+    use crate::crypto::ec::{Curve, EcPrivateKey};
+    use crate::crypto::eddsa::{EdCurve, EdPrivateKey};
+    use crate::crypto::hash::HashAlgorithm;
+    use crate::dn::Dn;
+    use crate::extensions as ext;
+
+    fn tbs_certificate(key: &PrivateKey) -> TbsCertificate
+    {
+        let subject = Dn::parse("C=CH, O=strongSwan, CN=host").unwrap().to_der();
+        return TbsCertificate {
+            serial: vec![0x00, 0x80], issuer: Dn::parse("C=CH, CN=Root CA").unwrap().to_der(),
+            not_before: 1760000000, not_after: 2556143999, subject,
+            public_key: key.public_key(),
+            extensions: vec![
+                ext::basic_constraints(false, X509_NO_CONSTRAINT),
+                ext::key_usage(ext::KU_DIGITAL_SIGNATURE),
+                ext::subject_alt_name(&["host.example.com".to_string()]).unwrap(),
+            ],
+        };
+    }
+
+    #[test]
+    fn certificate_encoding()
+    {
+        let issuer = PrivateKey::Ecdsa(EcPrivateKey::generate(Curve::P256));
+        let subject = PrivateKey::Eddsa(EdPrivateKey::generate(EdCurve::Ed25519));
+        let tbs = tbs_certificate(&subject);
+        let scheme = SignatureScheme::Ecdsa(HashAlgorithm::Sha256);
+
+        let cert = tbs.sign(&issuer, scheme).unwrap();
+        assert_eq!(cert.tbs, tbs.encode(scheme).unwrap());
+        assert_eq!(cert.version, 3);
+        assert_eq!(cert.serial, vec![0x00, 0x80]);
+        assert_eq!((&cert.issuer, &cert.subject), (&tbs.issuer, &tbs.subject));
+        assert_eq!((cert.not_before, cert.not_after), (tbs.not_before, tbs.not_after));
+        assert_eq!(cert.public_key, subject.public_key());
+        assert_eq!(cert.extensions, tbs.extensions);
+        assert_eq!(cert.scheme, scheme);
+        assert!(cert.verify_signature(&issuer.public_key()));
+        assert!(!cert.verify_signature(&subject.public_key()));
+
+        let decoded = Certificate::from_der(&cert.der).unwrap();
+        assert_eq!(decoded.der, cert.der);
+        assert_eq!(decoded.extension(OID_KEY_USAGE), tbs.extensions.get(1));
+        assert!(decoded.extension(OID_SUBJECT_KEY_ID).is_none());
+        assert_eq!(decoded.key_identifier().unwrap(), subject.public_key().subject_key_id());
+
+        let mut tampered = cert.der.clone();
+        let pos = tampered.windows(4).position(|w| w == b"host").unwrap();
+        tampered[pos] = b'H';
+        assert!(!Certificate::from_der(&tampered).unwrap().verify_signature(&issuer.public_key()));
+        assert!(Certificate::from_der(&cert.der[..cert.der.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn extension_encoding()
+    {
+        let ext = Extension { oid: OID_KEY_USAGE.to_string(), critical: false,
+                              value: vec![0x03, 0x02, 0x07, 0x80] };
+        assert_eq!(ext.encode().unwrap(), crate::hex("300b0603551d0f040403020780"));
+        let critical = Extension { critical: true, ..ext.clone() };
+        assert_eq!(critical.encode().unwrap(), crate::hex("300e0603551d0f0101ff040403020780"));
+
+        let exts = asn1_sequence(&[critical.encode().unwrap(), ext.encode().unwrap()]);
+        assert_eq!(parse_extensions(&asn1_parse(&exts).unwrap()).unwrap_err(),
+                   "duplicate extension keyUsage");
+        let exts = asn1_sequence(&[critical.encode().unwrap()]);
+        assert_eq!(parse_extensions(&asn1_parse(&exts).unwrap()).unwrap(), vec![critical]);
+    }
+
+    #[test]
+    fn serial_numbers()
+    {
+        assert_eq!(parse_serial("01").unwrap(), vec![0x01]);
+        assert_eq!(parse_serial("007f").unwrap(), vec![0x7f]);
+        assert!(parse_serial("").is_err());
+        assert!(parse_serial("00").is_err());
+        assert!(parse_serial("zz").is_err());
+        assert!(parse_serial("123").is_err());
+        assert!(parse_serial("80").is_err());
+        assert!(parse_serial(&"ff".repeat(MAX_SERIAL_LEN)).is_err());
+        assert_eq!(parse_serial(&"7f".repeat(MAX_SERIAL_LEN)).unwrap().len(), MAX_SERIAL_LEN);
+
+        let serial = random_serial();
+        assert_eq!(serial.len(), 16);
+        assert_eq!(serial[0] & 0xc0, 0x40);
+    }
+}